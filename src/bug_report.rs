@@ -229,6 +229,9 @@ fn get_config_path(shell: &str) -> Option<PathBuf> {
             "tcsh" => Some(".tcshrc"),
             "xonsh" => Some(".xonshrc"),
             "cmd" => Some("AppData/Local/clink/starship.lua"),
+            "osh" => Some(".config/oils/oshrc"),
+            "ysh" => Some(".config/oils/yshrc"),
+            "murex" => Some(".murex_profile"),
             _ => None,
         }
         .map(|path| home_dir.join(path))
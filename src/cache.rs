@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsStr;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+
+use crate::context::Context;
+use crate::modules::{agenda, ci_status, cpu_usage, custom, git_branch, pull_request};
+use crate::utils::prompt_budget;
+use crate::utils::{CommandOutput, create_command};
+
+/// The file in the cache directory holding the output of version probes
+const VERSION_CACHE_FILE: &str = "version_cache.json";
+
+/// Whether caches are kept in memory across prompts, see `keep_in_memory`
+static IN_MEMORY: AtomicBool = AtomicBool::new(false);
+
+/// The version cache kept in memory by long-running processes
+static SHARED_VERSION_CACHE: Mutex<Option<Arc<VersionCache>>> = Mutex::new(None);
+
+/// The arguments that make a command print the version of a tool, when passed first
+const VERSION_ARGS: &[&str] = &[
+    "--version",
+    "-version",
+    "--numeric-version",
+    "-Xinternalversion",
+    "-v",
+    "-V",
+];
+
+/// How long a background refresh is waited for before another one is started
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long an expired entry is kept in a cache file, to be shown while it is refreshed
+const STALE_ENTRY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Serializes the updates of the cache files by the modules computed in parallel
+static CACHE_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Tells apart the temporary files written by the threads of the process, see `write_atomic`
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A value cached by a module, until it expires
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CacheEntry<T> {
+    pub expires_at: SystemTime,
+    pub value: T,
+}
+
+impl<T> CacheEntry<T> {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= SystemTime::now()
+    }
+}
+
+/// Returns the directory starship keeps its caches in, `STARSHIP_CACHE` or `~/.cache/starship`
+pub fn cache_dir(context: &Context) -> Option<PathBuf> {
+    context
+        .get_env_os("STARSHIP_CACHE")
+        .map(PathBuf::from)
+        .or_else(|| {
+            context
+                .get_home()
+                .map(|home| home.join(".cache").join("starship"))
+        })
+}
+
+/// Removes the cached version probes, module and custom command output, CPU times, calendar
+/// events, CI statuses and what a detached HEAD is based on
+pub fn clear(context: &Context) {
+    let Some(dir) = cache_dir(context) else {
+        eprintln!("Unable to find the cache directory");
+        process::exit(1);
+    };
+
+    for file in [
+        VERSION_CACHE_FILE,
+        prompt_budget::CACHE_FILE,
+        cpu_usage::CACHE_FILE,
+        agenda::CACHE_FILE,
+        custom::CACHE_FILE,
+        ci_status::CACHE_FILE,
+        pull_request::CACHE_FILE,
+        git_branch::CACHE_FILE,
+    ] {
+        let path = dir.join(file);
+        match fs::remove_file(&path) {
+            Ok(()) => println!("Removed {}", path.display()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => {
+                eprintln!("Unable to remove {}: {error}", path.display());
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Starts a detached `starship module` process rendering `module` with `refresh_var` set, to
+/// update its cached output for the next prompt, unless one is already running. The lock file
+/// is to be removed by the process once the cache is updated.
+pub fn spawn_refresh(context: &Context, module: &str, lock_path: &Path, refresh_var: &str) {
+    let is_running = fs::metadata(lock_path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| {
+            modified
+                .elapsed()
+                .is_ok_and(|elapsed| elapsed < REFRESH_TIMEOUT)
+        });
+    if is_running {
+        log::trace!("The output of module `{module}` is already being refreshed");
+        return;
+    }
+    if let Err(error) = fs::write(lock_path, "") {
+        log::debug!("Unable to create {lock_path:?}: {error}");
+        return;
+    }
+
+    // Tests run in the test binary, which can't render the module
+    if cfg!(test) {
+        return;
+    }
+
+    let spawned = env::current_exe()
+        .and_then(create_command)
+        .and_then(|mut cmd| {
+            cmd.arg("module")
+                .arg(module)
+                .arg("--path")
+                .arg(&context.current_dir)
+                .arg("--logical-path")
+                .arg(&context.logical_dir)
+                .env(refresh_var, "1")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+
+            // Keep the process running when the shell interrupts the jobs in the foreground
+            #[cfg(unix)]
+            std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+
+            cmd.spawn()
+        });
+    if let Err(error) = spawned {
+        log::warn!("Unable to refresh the output of module `{module}`: {error}");
+        let _ = fs::remove_file(lock_path);
+    }
+}
+
+/// Returns the entry cached under `key` in the cache file at `path`
+pub fn read_entry<T: DeserializeOwned>(path: &Path, key: &str) -> Option<CacheEntry<T>> {
+    let entry = {
+        let _lock = CACHE_FILE_LOCK.lock();
+        read_entries(path).remove(key)?
+    };
+    Some(CacheEntry {
+        expires_at: entry.expires_at,
+        value: serde_json::from_value(entry.value).ok()?,
+    })
+}
+
+/// Caches `value` under `key` for `ttl` in the cache file at `path`, and removes the entries
+/// that expired long ago
+pub fn save_entry<T: Serialize + ?Sized>(path: &Path, key: &str, value: &T, ttl: Duration) {
+    let value = match serde_json::to_value(value) {
+        Ok(value) => value,
+        Err(error) => {
+            log::debug!("Unable to serialize the value cached under {key:?}: {error}");
+            return;
+        }
+    };
+
+    let _lock = CACHE_FILE_LOCK.lock();
+    let saved = update_file(path, |entries: &mut HashMap<String, CacheEntry<Value>>| {
+        let now = SystemTime::now();
+        entries.retain(|_, entry| entry.expires_at + STALE_ENTRY_TTL > now);
+        entries.insert(
+            key.to_string(),
+            CacheEntry {
+                expires_at: now + ttl,
+                value,
+            },
+        );
+    });
+    if let Err(error) = saved {
+        log::debug!("Unable to save the cache to {path:?}: {error}");
+    }
+}
+
+/// The file that exists while the value `module` caches under `key` is refreshed
+pub fn refresh_lock_path(dir: &Path, module: &str, key: &str) -> PathBuf {
+    let hash = crate::utils::encode_to_hex(&Sha1::digest(key.as_bytes()));
+    dir.join(format!("{module}_refresh_{}.lock", &hash[..16]))
+}
+
+fn read_entries(path: &Path) -> HashMap<String, CacheEntry<Value>> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Applies `update` to the content of the cache file at `path` and saves it. The file is re-read
+/// under an advisory lock, so the entries saved by other starship processes in the meantime are
+/// kept rather than overwritten.
+fn update_file<T>(path: &Path, update: impl FnOnce(&mut T)) -> Result<(), String>
+where
+    T: Default + DeserializeOwned + Serialize,
+{
+    let _lock = lock_file(path);
+    let mut content = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    update(&mut content);
+    let content = serde_json::to_string(&content).map_err(|error| error.to_string())?;
+    write_atomic(path, &content).map_err(|error| error.to_string())
+}
+
+/// Takes the advisory lock on the lock file next to the cache file at `path`, which is held
+/// until the returned file is dropped. The file is updated without it if it can't be taken.
+fn lock_file(path: &Path) -> Option<File> {
+    let mut name = path.file_name()?.to_os_string();
+    name.push(".lock");
+    let lock_path = path.with_file_name(name);
+    let locked = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .and_then(|file| file.lock().map(|()| file));
+    match locked {
+        Ok(file) => Some(file),
+        Err(error) => {
+            log::debug!("Unable to lock {lock_path:?}: {error}");
+            None
+        }
+    }
+}
+
+/// Writes `content` to a temporary file in the same directory, then renames it to `path`, so
+/// that the file is never seen half written by another starship process reading it
+pub fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    let count = TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    name.push(format!(".{}.{count}.tmp", process::id()));
+    let temp_path = path.with_file_name(name);
+
+    let written = fs::write(&temp_path, content).and_then(|()| fs::rename(&temp_path, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written
+}
+
+/// Whether a command only prints the version of a tool, so its output can be cached
+pub fn is_version_probe<T: AsRef<OsStr>>(args: &[T]) -> bool {
+    args.first()
+        .and_then(|arg| arg.as_ref().to_str())
+        .is_some_and(|arg| VERSION_ARGS.contains(&arg))
+}
+
+/// The output of a version probe, along with the binary it came from
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Entry {
+    modified: SystemTime,
+    size: u64,
+    cached_at: SystemTime,
+    stdout: String,
+    stderr: String,
+}
+
+/// Keeps the version cache and the opened git repositories in memory, so they are loaded once
+/// for all the prompts rendered by the process.
+/// This is only worth it for `starship daemon`, which renders many prompts.
+pub fn keep_in_memory() {
+    IN_MEMORY.store(true, Ordering::Relaxed);
+}
+
+/// Whether caches are kept in memory across prompts
+pub fn in_memory() -> bool {
+    IN_MEMORY.load(Ordering::Relaxed)
+}
+
+/// Caches the output of version probes such as `node --version` for `version_cache_ttl`
+/// seconds. The output is cached per directory, as shims select the version of a tool from the
+/// project, and until the binary is modified, which is detected from its modification time and
+/// size.
+pub struct VersionCache {
+    path: PathBuf,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl VersionCache {
+    /// Loads the cache, if `version_cache_ttl` is set, or reuses the one kept in memory
+    pub fn new(context: &Context) -> Option<Arc<Self>> {
+        let ttl = context.root_config.version_cache_ttl;
+        if ttl == 0 {
+            return None;
+        }
+
+        let path = cache_dir(context)?.join(VERSION_CACHE_FILE);
+        let ttl = Duration::from_secs(ttl);
+        if !in_memory() {
+            return Some(Arc::new(Self::load(path, ttl)));
+        }
+
+        let mut shared = SHARED_VERSION_CACHE.lock();
+        if let Some(cache) = shared
+            .as_ref()
+            .filter(|cache| cache.path == path && cache.ttl == ttl)
+        {
+            return Some(Arc::clone(cache));
+        }
+        let cache = Arc::new(Self::load(path, ttl));
+        *shared = Some(Arc::clone(&cache));
+        Some(cache)
+    }
+
+    fn load(path: PathBuf, ttl: Duration) -> Self {
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            ttl,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// Returns the cached output of the command, if its binary hasn't changed since
+    pub fn get(&self, cmd: &Command) -> Option<CommandOutput> {
+        let (modified, size) = binary_metadata(cmd)?;
+        let entries = self.entries.lock();
+        let entry = entries.get(&key(cmd))?;
+        let fresh = entry.cached_at.elapsed().is_ok_and(|age| age < self.ttl);
+        if !fresh || entry.modified != modified || entry.size != size {
+            return None;
+        }
+
+        log::trace!("Using the cached output of {cmd:?}");
+        Some(CommandOutput {
+            stdout: entry.stdout.clone(),
+            stderr: entry.stderr.clone(),
+        })
+    }
+
+    /// Caches the output of the command and saves the cache
+    pub fn insert(&self, cmd: &Command, output: &CommandOutput) {
+        let Some((modified, size)) = binary_metadata(cmd) else {
+            return;
+        };
+
+        let key = key(cmd);
+        let entry = Entry {
+            modified,
+            size,
+            cached_at: SystemTime::now(),
+            stdout: output.stdout.clone(),
+            stderr: output.stderr.clone(),
+        };
+        let is_fresh = |_: &String, entry: &mut Entry| {
+            entry.cached_at.elapsed().is_ok_and(|age| age < self.ttl)
+        };
+
+        {
+            let mut entries = self.entries.lock();
+            entries.insert(key.clone(), entry.clone());
+            entries.retain(is_fresh);
+        }
+
+        // Other starship processes may have cached the output of other commands since the cache
+        // was loaded, so only this entry is added to the saved ones
+        let saved = update_file(&self.path, |entries: &mut HashMap<String, Entry>| {
+            entries.insert(key, entry);
+            entries.retain(is_fresh);
+        });
+        if let Err(error) = saved {
+            log::debug!(
+                "Unable to save the version cache to {:?}: {error}",
+                self.path
+            );
+        }
+    }
+}
+
+/// The full path of the binary, the arguments of the command and the directory it runs in
+fn key(cmd: &Command) -> String {
+    let mut key = cmd.get_program().to_string_lossy().into_owned();
+    for arg in cmd.get_args() {
+        key.push(' ');
+        key.push_str(&arg.to_string_lossy());
+    }
+    if let Some(dir) = cmd.get_current_dir() {
+        key.push_str(" in ");
+        key.push_str(&dir.to_string_lossy());
+    }
+    key
+}
+
+fn binary_metadata(cmd: &Command) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(cmd.get_program()).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    #[test]
+    fn version_probes() {
+        assert!(is_version_probe(&["--version"]));
+        assert!(is_version_probe(&["-V"]));
+        assert!(!is_version_probe(&["version", "--short"]));
+        assert!(!is_version_probe(&["version-name"]));
+        assert!(!is_version_probe(&["branch", "--version"]));
+        assert!(!is_version_probe::<&str>(&[]));
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(VersionCache::new(&default_context()).is_none());
+    }
+
+    #[test]
+    fn caches_until_binary_changes() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let binary = dir.path().join("tool");
+        fs::write(&binary, "v1")?;
+        let mut context = default_context().set_config(toml::toml! {
+            version_cache_ttl = 60
+        });
+        context
+            .env
+            .insert("STARSHIP_CACHE", dir.path().to_string_lossy().to_string());
+
+        #[allow(clippy::disallowed_methods)]
+        let mut cmd = Command::new(&binary);
+        cmd.arg("--version").current_dir(dir.path());
+        let output = CommandOutput {
+            stdout: "1.0.0\n".to_string(),
+            stderr: String::new(),
+        };
+        VersionCache::new(&context).unwrap().insert(&cmd, &output);
+
+        // The cache is saved and read back by the next prompt
+        let cache = VersionCache::new(&context).unwrap();
+        assert_eq!(cache.get(&cmd), Some(output));
+
+        // Shims select the version from the directory they run in
+        #[allow(clippy::disallowed_methods)]
+        let mut other_dir_cmd = Command::new(&binary);
+        other_dir_cmd.arg("--version").current_dir(env::temp_dir());
+        assert_eq!(cache.get(&other_dir_cmd), None);
+
+        fs::write(&binary, "v2 is bigger")?;
+        assert_eq!(cache.get(&cmd), None);
+        dir.close()
+    }
+
+    #[test]
+    fn version_cache_keeps_entries_of_other_processes() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context().set_config(toml::toml! {
+            version_cache_ttl = 60
+        });
+        context
+            .env
+            .insert("STARSHIP_CACHE", dir.path().to_string_lossy().to_string());
+        let output = CommandOutput {
+            stdout: "1.0.0\n".to_string(),
+            stderr: String::new(),
+        };
+
+        // Two prompts load the cache before either of them saves it
+        let first = VersionCache::new(&context).unwrap();
+        let second = VersionCache::new(&context).unwrap();
+        let mut cmds = Vec::new();
+        for (cache, name) in [(&first, "node"), (&second, "python")] {
+            let binary = dir.path().join(name);
+            fs::write(&binary, name)?;
+            #[allow(clippy::disallowed_methods)]
+            let mut cmd = Command::new(&binary);
+            cmd.arg("--version");
+            cache.insert(&cmd, &output);
+            cmds.push(cmd);
+        }
+
+        let cache = VersionCache::new(&context).unwrap();
+        for cmd in &cmds {
+            assert_eq!(cache.get(cmd), Some(output.clone()));
+        }
+        dir.close()
+    }
+
+    #[test]
+    fn entries_saved_in_parallel_are_all_kept() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.json");
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let path = &path;
+                scope.spawn(move || save_entry(path, &i.to_string(), &i, Duration::from_secs(60)));
+            }
+        });
+
+        for i in 0..8 {
+            let entry = read_entry::<i32>(&path, &i.to_string()).unwrap();
+            assert_eq!(entry.value, i);
+        }
+        // Only the cache file and its lock file are left
+        let mut files = fs::read_dir(dir.path())?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect::<io::Result<Vec<_>>>()?;
+        files.sort();
+        assert_eq!(files, ["test.json", "test.json.lock"]);
+        dir.close()
+    }
+}
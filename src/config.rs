@@ -13,9 +13,43 @@ use std::clone::Clone;
 use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
 
 use toml::Value;
 
+static PARSE_CACHE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The last parsed content of each config file, kept by long-running processes
+static PARSE_CACHE: OnceLock<Mutex<HashMap<PathBuf, (String, toml::Table)>>> = OnceLock::new();
+
+/// Keeps the parsed config files in memory, to only parse them again once they change.
+/// This is only worth it for `starship daemon`, which renders many prompts.
+pub fn enable_parse_cache() {
+    PARSE_CACHE_ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn parse_config_file(path: &Path, content: &str) -> Result<toml::Table, toml::de::Error> {
+    if !PARSE_CACHE_ENABLED.load(Ordering::Relaxed) {
+        return toml::from_str(content);
+    }
+
+    let cache = PARSE_CACHE.get_or_init(Default::default);
+    if let Some((cached_content, parsed)) = cache.lock().unwrap().get(path)
+        && cached_content == content
+    {
+        return Ok(parsed.clone());
+    }
+
+    let parsed: toml::Table = toml::from_str(content)?;
+    cache
+        .lock()
+        .unwrap()
+        .insert(path.to_owned(), (content.to_string(), parsed.clone()));
+    Ok(parsed)
+}
+
 /// Root config of a module.
 pub trait ModuleConfig<'a, E>
 where
@@ -76,6 +110,31 @@ pub enum Either<A, B> {
     Second(B),
 }
 
+/// A style used by a module when the value it is based on is within a range,
+/// e.g. `{ min = 10000, style = "bold red" }` for the duration of `cmd_duration`.
+///
+/// `min` is inclusive and `max` is exclusive, so consecutive rules don't overlap.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct StyleRule<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    pub style: &'a str,
+}
+
+impl StyleRule<'_> {
+    pub fn matches(&self, value: f64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value < max)
+    }
+}
+
 /// A wrapper around `Vec<T>` that implements `ModuleConfig`, and either
 /// accepts a value of type `T` or a list of values of type `T`.
 #[derive(Clone, Default, Serialize)]
@@ -116,8 +175,65 @@ where
     }
 }
 
+/// The option of a module that replaces its `format` below `width_threshold`
+pub const NARROW_FORMAT_KEY: &str = "format_narrow";
+
+/// Moves the `format_narrow` options of the (nested) tables in `narrow` to `format`,
+/// removing them from `wide`. Returns whether any table had such an option.
+fn use_narrow_formats(wide: &mut toml::Table, narrow: &mut toml::Table) -> bool {
+    let mut found = false;
+    for (key, narrow_value) in narrow.iter_mut() {
+        let Value::Table(narrow_table) = narrow_value else {
+            continue;
+        };
+        let Some(Value::Table(wide_table)) = wide.get_mut(key) else {
+            continue;
+        };
+
+        if let Some(format) = narrow_table.remove(NARROW_FORMAT_KEY) {
+            wide_table.remove(NARROW_FORMAT_KEY);
+            narrow_table.insert("format".to_string(), format);
+            found = true;
+        }
+        found |= use_narrow_formats(wide_table, narrow_table);
+    }
+    found
+}
+
+/// The prefix of the environment variables overriding a config key, followed by the path of
+/// the key separated by `__`, e.g. `STARSHIP_CONFIG_OVERRIDE__aws__symbol`
+pub const CONFIG_OVERRIDE_PREFIX: &str = "STARSHIP_CONFIG_OVERRIDE__";
+
+/// The name of the config file that is merged over the global config for the directory it is in
+pub const PROJECT_CONFIG_FILE: &str = ".starship.toml";
+
+/// Returns the table at `path` within `table`, creating missing tables along the way
+fn table_at_path<'a>(table: &'a mut toml::Table, path: &[&str]) -> Option<&'a mut toml::Table> {
+    path.iter().try_fold(table, |table, key| {
+        table
+            .entry(*key)
+            .or_insert_with(|| Value::Table(toml::Table::new()))
+            .as_table_mut()
+    })
+}
+
+/// Merges `overlay` into `base`, replacing its values, except for tables which are merged
+/// recursively.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 /// Root config of starship.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct StarshipConfig {
     pub config: Option<toml::Table>,
 }
@@ -132,14 +248,98 @@ impl StarshipConfig {
             .unwrap_or_default()
     }
 
+    /// Removes the `format_narrow` option from the module configs, returning a copy of the
+    /// config in which it replaces `format`, or `None` if no module defines one.
+    pub fn split_narrow_formats(&mut self) -> Option<Self> {
+        let config = self.config.as_mut()?;
+        let mut narrow_config = config.clone();
+        if !use_narrow_formats(config, &mut narrow_config) {
+            return None;
+        }
+
+        Some(Self {
+            config: Some(narrow_config),
+        })
+    }
+
+    /// Merges the `.starship.toml` file of the nearest ancestor of `dir` that has one over
+    /// the config, provided that the ancestor is within one of the `trusted_paths`.
+    pub fn merge_project_config(&mut self, dir: &Path) {
+        let Some(project_dir) = dir
+            .ancestors()
+            .find(|ancestor| ancestor.join(PROJECT_CONFIG_FILE).is_file())
+        else {
+            return;
+        };
+
+        let is_trusted = self
+            .get_config(&["trusted_paths"])
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .any(|trusted| project_dir.starts_with(Context::expand_tilde(PathBuf::from(trusted))));
+        let project_config_path = project_dir.join(PROJECT_CONFIG_FILE);
+        if !is_trusted {
+            log::warn!(
+                "Ignoring untrusted config file {project_config_path:?}, run `starship config trust {}` to use it",
+                project_dir.display()
+            );
+            return;
+        }
+
+        if let Some(project_config) = Self::config_from_file(Some(project_config_path.as_os_str()))
+        {
+            merge_tables(self.config.get_or_insert_default(), project_config);
+        }
+    }
+
+    /// Sets the config keys named by `vars`, which are pairs of environment variable names made
+    /// up of `CONFIG_OVERRIDE_PREFIX` and the path of a key, and values.
+    ///
+    /// Values are parsed as TOML values, falling back to plain strings.
+    pub fn apply_env_overrides(&mut self, vars: Vec<(String, String)>) {
+        for (name, value) in vars {
+            let Some(path) = name.strip_prefix(CONFIG_OVERRIDE_PREFIX) else {
+                continue;
+            };
+            let keys: Vec<&str> = path.split("__").collect();
+            let Some((last_key, parent_keys)) = keys.split_last() else {
+                continue;
+            };
+            if keys.iter().any(|key| key.is_empty()) {
+                log::warn!("Invalid config override {name}: empty keys are not supported");
+                continue;
+            }
+
+            let Some(table) = table_at_path(self.config.get_or_insert_default(), parent_keys)
+            else {
+                log::warn!("Invalid config override {name}: {path} is not within a table");
+                continue;
+            };
+
+            let value = match value.parse::<Value>() {
+                Ok(value) => value,
+                Err(_) => Value::String(value),
+            };
+            log::debug!("Overriding config key {path:?} with {value:?}");
+            table.insert((*last_key).to_string(), value);
+        }
+    }
+
     /// Create a config from a starship configuration file
     fn config_from_file(config_file_path: Option<&OsStr>) -> Option<toml::Table> {
         let toml_content = Self::read_config_content_as_str(config_file_path)?;
+        let path = Path::new(config_file_path?);
 
-        match toml::from_str(&toml_content) {
+        match parse_config_file(path, &toml_content) {
             Ok(parsed) => {
                 log::debug!("Config parsed: {:?}", &parsed);
-                Some(parsed)
+                Some(Self::merge_includes(
+                    parsed,
+                    path,
+                    &mut vec![dunce::canonicalize(path).unwrap_or_else(|_| path.to_owned())],
+                ))
             }
             Err(error) => {
                 log::error!("Unable to parse the config file: {error}");
@@ -148,6 +348,53 @@ impl StarshipConfig {
         }
     }
 
+    /// Merges the config files listed in the `include` option of `config` in order over
+    /// `config` itself, so later files override the tables and keys of earlier ones.
+    ///
+    /// Relative paths are resolved against the directory of the including file at `path`.
+    /// `parents` holds the canonical paths of the files being merged, to skip cyclic includes.
+    fn merge_includes(config: toml::Table, path: &Path, parents: &mut Vec<PathBuf>) -> toml::Table {
+        let Some(includes) = config.get("include").and_then(Value::as_array) else {
+            return config;
+        };
+
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let includes = includes.clone();
+        let mut merged = config;
+        for include in &includes {
+            let Some(include) = include.as_str() else {
+                log::warn!("Invalid config include: {include}");
+                continue;
+            };
+            let include_path = dir.join(Context::expand_tilde(PathBuf::from(include)));
+            let canonical_path =
+                dunce::canonicalize(&include_path).unwrap_or_else(|_| include_path.clone());
+            if parents.contains(&canonical_path) {
+                log::warn!("Config file {include_path:?} includes itself");
+                continue;
+            }
+
+            let Some(content) = Self::read_config_content_as_str(Some(include_path.as_os_str()))
+            else {
+                continue;
+            };
+            let included = match parse_config_file(&include_path, &content) {
+                Ok(included) => included,
+                Err(error) => {
+                    log::error!("Unable to parse the config file {include_path:?}: {error}");
+                    continue;
+                }
+            };
+
+            parents.push(canonical_path);
+            let included = Self::merge_includes(included, &include_path, parents);
+            parents.pop();
+            merge_tables(&mut merged, included);
+        }
+
+        merged
+    }
+
     pub fn read_config_content_as_str(config_file_path: Option<&OsStr>) -> Option<String> {
         if config_file_path.is_none() {
             log::debug!(
@@ -306,6 +553,49 @@ impl Style {
         current
     }
 
+    /// Returns a style string that `parse_style_string` parses into the same style
+    pub fn to_style_string(&self) -> String {
+        let style = &self.style;
+        let mut tokens: Vec<String> = style_attributes(style)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        let colors = [
+            ("fg", self.fg, style.foreground),
+            ("bg", self.bg, style.background),
+        ];
+        for (prefix, prev_color, color) in colors {
+            match (prev_color, color) {
+                (Some(PrevColor::Fg), _) => tokens.push(format!("{prefix}:prev_fg")),
+                (Some(PrevColor::Bg), _) => tokens.push(format!("{prefix}:prev_bg")),
+                (None, Some(color)) => {
+                    if let Some(color) = color_to_string(color) {
+                        tokens.push(format!("{prefix}:{color}"));
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+        tokens.join(" ")
+    }
+
+    /// Returns the style with the background of `other`, unless it has a background of its own
+    pub fn with_background_if_empty(&self, other: &Self) -> Self {
+        if self.style.background.is_some() || self.bg.is_some() {
+            return *self;
+        }
+
+        Self {
+            style: nu_ansi_term::Style {
+                background: other.style.background,
+                ..self.style
+            },
+            bg: other.bg,
+            ..*self
+        }
+    }
+
     fn map_style<F>(&self, f: F) -> Self
     where
         F: FnOnce(&nu_ansi_term::Style) -> nu_ansi_term::Style,
@@ -404,10 +694,7 @@ pub fn parse_style_string(style_string: &str, context: Option<&Context>) -> Opti
                         let parsed = parse_color_string(
                             color_string,
                             context.and_then(|x| {
-                                get_palette(
-                                    &x.root_config.palettes,
-                                    x.root_config.palette.as_deref(),
-                                )
+                                get_palette(&x.root_config.palettes, x.palette_name())
                             }),
                         );
                         // bg + invalid color = reset the background to default.
@@ -431,11 +718,21 @@ pub fn parse_style_string(style_string: &str, context: Option<&Context>) -> Opti
         })
 }
 
+/// Semantic color slots referenced by the default styles of built-in modules, with the
+/// colors they fall back to when the active palette doesn't define them.
+const SEMANTIC_COLORS: &[(&str, &str)] = &[
+    ("primary", "cyan"),
+    ("success", "green"),
+    ("warning", "yellow"),
+    ("error", "red"),
+    ("vcs", "purple"),
+];
+
 /** Parse a string that represents a color setting, returning None if this fails
  There are three valid color formats:
   - #RRGGBB      (a hash followed by an RGB hex)
   - u8           (a number from 0-255, representing an ANSI color)
-  - colstring    (one of the 16 predefined color strings or a custom user-defined color)
+  - colstring    (one of the 16 predefined color strings, a semantic slot or a custom user-defined color)
 */
 fn parse_color_string(
     color_string: &str,
@@ -468,6 +765,15 @@ fn parse_color_string(
         return parse_color_string(palette_color, None);
     }
 
+    // Fall back to the default color of a semantic slot not defined in the palette
+    if let Some((_, slot_color)) = SEMANTIC_COLORS
+        .iter()
+        .find(|(slot, _)| *slot == color_string)
+    {
+        log::trace!("Read semantic color string: {color_string} defaulting to {slot_color}");
+        return parse_color_string(slot_color, None);
+    }
+
     // Check for any predefined color strings
     // There are no predefined enums for bright colors, so we use Color::Fixed
     let predefined_color = match color_string.to_lowercase().as_str() {
@@ -498,6 +804,50 @@ fn parse_color_string(
     predefined_color
 }
 
+/// Returns the names of the attributes of a style, as used in style strings
+pub fn style_attributes(style: &nu_ansi_term::Style) -> Vec<&'static str> {
+    [
+        (style.is_bold, "bold"),
+        (style.is_dimmed, "dimmed"),
+        (style.is_italic, "italic"),
+        (style.is_underline, "underline"),
+        (style.is_blink, "blink"),
+        (style.is_reverse, "inverted"),
+        (style.is_hidden, "hidden"),
+        (style.is_strikethrough, "strikethrough"),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, name)| name)
+    .collect()
+}
+
+/// Returns the color string of a color, or `None` for the default color of the terminal
+pub fn color_to_string(color: Color) -> Option<String> {
+    let name = match color {
+        Color::Black => "black",
+        Color::Red => "red",
+        Color::Green => "green",
+        Color::Yellow => "yellow",
+        Color::Blue => "blue",
+        Color::Purple | Color::Magenta => "purple",
+        Color::Cyan => "cyan",
+        Color::White => "white",
+        Color::DarkGray => "bright-black",
+        Color::LightRed => "bright-red",
+        Color::LightGreen => "bright-green",
+        Color::LightYellow => "bright-yellow",
+        Color::LightBlue => "bright-blue",
+        Color::LightPurple | Color::LightMagenta => "bright-purple",
+        Color::LightCyan => "bright-cyan",
+        Color::LightGray => "bright-white",
+        Color::Fixed(number) => return Some(number.to_string()),
+        Color::Rgb(r, g, b) => return Some(format!("#{r:02x}{g:02x}{b:02x}")),
+        Color::Default => return None,
+    };
+    Some(name.to_string())
+}
+
 fn get_palette<'a>(
     palettes: &'a HashMap<String, Palette>,
     palette_name: Option<&str>,
@@ -692,6 +1042,24 @@ mod tests {
         assert_eq!(<i64>::from_config(&config).unwrap(), 42);
     }
 
+    #[test]
+    fn test_to_style_string() {
+        for style in [
+            "",
+            "bold italic fg:red bg:#1e1e2e",
+            "dimmed fg:bright-black",
+            "underline fg:214 bg:prev_fg",
+            "inverted strikethrough fg:prev_bg",
+        ] {
+            let parsed = parse_style_string(style, None).unwrap();
+            assert_eq!(parsed.to_style_string(), style);
+            assert_eq!(
+                parse_style_string(&parsed.to_style_string(), None),
+                Some(parsed)
+            );
+        }
+    }
+
     #[test]
     fn test_from_style() {
         let config = Value::from("red bold");
@@ -1015,6 +1383,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn table_get_semantic_colors() {
+        let mut palette = Palette::new();
+        palette.insert("vcs".to_string(), "#af8700".to_string());
+
+        assert_eq!(
+            parse_color_string("vcs", Some(&palette)),
+            Some(Color::Rgb(0xaf, 0x87, 0x00))
+        );
+        assert_eq!(parse_color_string("vcs", None), Some(Color::Purple));
+        assert_eq!(parse_color_string("warning", None), Some(Color::Yellow));
+    }
+
+    #[test]
+    fn table_get_inherited_palette() {
+        let config = toml::toml! {
+            palette = "dark"
+
+            [palettes.base]
+            primary = "blue"
+            vcs = "123"
+
+            [palettes.dark]
+            inherits = "base"
+            primary = "#ABCDEF"
+
+            [palettes.loop]
+            inherits = "loop"
+            primary = "red"
+        };
+        let root_config =
+            crate::configs::StarshipRootConfig::load(&config).with_inherited_palettes();
+
+        let dark = get_palette(&root_config.palettes, Some("dark")).unwrap();
+        assert_eq!(dark.get("primary").unwrap(), "#ABCDEF");
+        assert_eq!(dark.get("vcs").unwrap(), "123");
+        assert!(dark.get("inherits").is_none());
+
+        let looping = get_palette(&root_config.palettes, Some("loop")).unwrap();
+        assert_eq!(looping.get("primary").unwrap(), "red");
+    }
+
     #[test]
     fn table_get_palette() {
         // Test retrieving color palette by name
@@ -1059,4 +1469,160 @@ mod tests {
             "if the platform doesn't have utils::home_dir(), it should return None"
         );
     }
+
+    #[test]
+    fn read_config_with_includes() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("team"))?;
+        std::fs::write(
+            dir.path().join("team/base.toml"),
+            "include = ['colors.toml']\nadd_newline = false\n[character]\nsuccess_symbol = '>'\nerror_symbol = 'x'\n",
+        )?;
+        std::fs::write(
+            dir.path().join("team/colors.toml"),
+            "[character]\nsuccess_symbol = '$'\nformat = '$symbol'\n",
+        )?;
+        std::fs::write(
+            dir.path().join("personal.toml"),
+            "[character]\nerror_symbol = '!'\n",
+        )?;
+        let config_path = dir.path().join("starship.toml");
+        std::fs::write(
+            &config_path,
+            "include = ['team/base.toml', 'personal.toml', 'starship.toml', 'missing.toml']\n[directory]\ndisabled = true\n",
+        )?;
+
+        let config = StarshipConfig::initialize(Some(config_path.as_os_str()));
+
+        assert_eq!(
+            config.get_config(&["add_newline"]),
+            Some(&Value::Boolean(false))
+        );
+        assert_eq!(
+            config.get_config(&["directory", "disabled"]),
+            Some(&Value::Boolean(true))
+        );
+        let character = config.get_module_config("character").unwrap();
+        assert_eq!(character["format"].as_str(), Some("$symbol"));
+        assert_eq!(character["success_symbol"].as_str(), Some("$"));
+        assert_eq!(character["error_symbol"].as_str(), Some("!"));
+        dir.close()
+    }
+
+    #[test]
+    fn read_config_with_cyclic_includes() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("team"))?;
+        std::fs::write(
+            dir.path().join("team/base.toml"),
+            "include = ['../starship.toml']
+[character]
+format = 'base'
+",
+        )?;
+        let config_path = dir.path().join("starship.toml");
+        std::fs::write(
+            &config_path,
+            "include = ['team/base.toml']
+[character]
+format = 'main'
+success_symbol = '>'
+",
+        )?;
+
+        let config = StarshipConfig::initialize(Some(config_path.as_os_str()));
+
+        let character = config.get_module_config("character").unwrap();
+        assert_eq!(character["format"].as_str(), Some("base"));
+        assert_eq!(character["success_symbol"].as_str(), Some(">"));
+        dir.close()
+    }
+
+    #[test]
+    fn merge_trusted_project_config() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let project_dir = dir.path().join("project");
+        std::fs::create_dir_all(project_dir.join("src"))?;
+        std::fs::write(
+            project_dir.join(PROJECT_CONFIG_FILE),
+            "[character]\nformat = 'project'\n",
+        )?;
+        let global_config = toml::toml! {
+            [character]
+            format = "global"
+            success_symbol = ">"
+        };
+
+        let mut config = StarshipConfig {
+            config: Some(global_config.clone()),
+        };
+        config.merge_project_config(&project_dir.join("src"));
+        let character = config.get_module_config("character").unwrap();
+        assert_eq!(character["format"].as_str(), Some("global"));
+
+        let mut trusted_config = global_config;
+        trusted_config.insert(
+            "trusted_paths".to_string(),
+            Value::Array(vec![dir.path().to_string_lossy().as_ref().into()]),
+        );
+        let mut config = StarshipConfig {
+            config: Some(trusted_config),
+        };
+        config.merge_project_config(&project_dir.join("src"));
+        let character = config.get_module_config("character").unwrap();
+        assert_eq!(character["format"].as_str(), Some("project"));
+        assert_eq!(character["success_symbol"].as_str(), Some(">"));
+        dir.close()
+    }
+
+    #[test]
+    fn apply_env_overrides() {
+        let mut config = StarshipConfig {
+            config: Some(toml::toml! {
+                add_newline = true
+                [aws]
+                format = "on $symbol"
+            }),
+        };
+
+        config.apply_env_overrides(vec![
+            (
+                "STARSHIP_CONFIG_OVERRIDE__add_newline".to_string(),
+                "false".to_string(),
+            ),
+            (
+                "STARSHIP_CONFIG_OVERRIDE__aws__symbol".to_string(),
+                "X".to_string(),
+            ),
+            (
+                "STARSHIP_CONFIG_OVERRIDE__git_status__ahead".to_string(),
+                "'⇡'".to_string(),
+            ),
+            (
+                "STARSHIP_CONFIG_OVERRIDE__add_newline__nested".to_string(),
+                "1".to_string(),
+            ),
+            (
+                "STARSHIP_CONFIG_OVERRIDE__aws__".to_string(),
+                "1".to_string(),
+            ),
+        ]);
+
+        assert_eq!(
+            config.get_config(&["add_newline"]),
+            Some(&Value::Boolean(false))
+        );
+        assert_eq!(
+            config.get_config(&["aws", "format"]),
+            Some(&Value::String("on $symbol".to_string()))
+        );
+        assert_eq!(
+            config.get_config(&["aws", "symbol"]),
+            Some(&Value::String("X".to_string()))
+        );
+        assert_eq!(
+            config.get_config(&["git_status", "ahead"]),
+            Some(&Value::String("⇡".to_string()))
+        );
+    }
 }
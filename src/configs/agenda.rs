@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct AgendaConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub path: &'a str,
+    pub horizon: u64,
+    pub time_format: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for AgendaConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "[$symbol$title in $countdown]($style) ",
+            symbol: "📅 ",
+            style: "bold purple",
+            path: "",
+            horizon: 60,
+            time_format: "%H:%M",
+            disabled: true,
+        }
+    }
+}
@@ -45,6 +45,12 @@ pub struct AwsConfig<'a> {
     pub profile_aliases: HashMap<String, &'a str>,
     /// The symbol displayed when the temporary credentials have expired.
     pub expiration_symbol: &'a str,
+    /// How many seconds before the temporary credentials expire the module switches to `expiration_warning_style`. 0 disables the warning.
+    pub expiration_warning_threshold: u64,
+    /// The style for the module when the temporary credentials are about to expire. Uses `style` if empty.
+    pub expiration_warning_style: &'a str,
+    /// The style for the module when the temporary credentials have expired. Uses `style` if empty.
+    pub expiration_style: &'a str,
     /// If true displays info even if `credentials`, `credential_process` or `sso_start_url` have not been setup.
     pub force_display: bool,
 }
@@ -59,6 +65,9 @@ impl Default for AwsConfig<'_> {
             region_aliases: HashMap::new(),
             profile_aliases: HashMap::new(),
             expiration_symbol: "X",
+            expiration_warning_threshold: 0,
+            expiration_warning_style: "",
+            expiration_style: "",
             force_display: false,
         }
     }
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct AzureDevOpsConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for AzureDevOpsConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "in [$symbol$organization/$project]($style) ",
+            symbol: "󰠅 ",
+            style: "blue bold",
+            disabled: false,
+        }
+    }
+}
@@ -17,6 +17,9 @@ pub struct BatteryConfig<'a> {
     pub display: Vec<BatteryDisplayConfig<'a>>,
     pub disabled: bool,
     pub format: &'a str,
+    pub aggregation: &'a str,
+    pub power_draw_threshold: f64,
+    pub time_remaining_threshold: i64,
 }
 
 impl Default for BatteryConfig<'_> {
@@ -30,6 +33,9 @@ impl Default for BatteryConfig<'_> {
             format: "[$symbol$percentage]($style) ",
             display: vec![BatteryDisplayConfig::default()],
             disabled: false,
+            aggregation: "sum",
+            power_draw_threshold: 0.0,
+            time_remaining_threshold: 0,
         }
     }
 }
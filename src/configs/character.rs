@@ -23,8 +23,8 @@ impl Default for CharacterConfig<'_> {
     fn default() -> Self {
         Self {
             format: "$symbol ",
-            success_symbol: "[❯](bold green)",
-            error_symbol: "[❯](bold red)",
+            success_symbol: "[❯](bold success)",
+            error_symbol: "[❯](bold error)",
             vimcmd_symbol: "[❮](bold green)",
             vimcmd_visual_symbol: "[❮](bold yellow)",
             vimcmd_replace_symbol: "[❮](bold purple)",
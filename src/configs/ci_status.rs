@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct CiStatusConfig<'a> {
+    pub format: &'a str,
+    pub success_symbol: &'a str,
+    pub failure_symbol: &'a str,
+    pub pending_symbol: &'a str,
+    pub cancelled_symbol: &'a str,
+    pub success_style: &'a str,
+    pub failure_style: &'a str,
+    pub pending_style: &'a str,
+    pub cancelled_style: &'a str,
+    pub provider: &'a str,
+    pub hosts: Vec<&'a str>,
+    pub cache_ttl: u64,
+    pub timeout: u64,
+    pub disabled: bool,
+}
+
+impl Default for CiStatusConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "[CI $symbol]($style) ",
+            success_symbol: "✓",
+            failure_symbol: "✗",
+            pending_symbol: "●",
+            cancelled_symbol: "⊘",
+            success_style: "bold green",
+            failure_style: "bold red",
+            pending_style: "bold yellow",
+            cancelled_style: "bold bright-black",
+            provider: "",
+            hosts: vec![],
+            cache_ttl: 60,
+            timeout: 5000,
+            disabled: true,
+        }
+    }
+}
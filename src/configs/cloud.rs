@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct CloudConfig<'a> {
+    pub format: &'a str,
+    pub style: &'a str,
+    pub order: Vec<&'a str>,
+    pub disabled: bool,
+}
+
+impl Default for CloudConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "on [$symbol($name )(\\($region\\) )]($style)",
+            style: "bold yellow",
+            order: vec!["aws", "gcloud", "azure", "openstack"],
+            disabled: false,
+        }
+    }
+}
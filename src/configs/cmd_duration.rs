@@ -1,3 +1,4 @@
+use crate::config::StyleRule;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -11,13 +12,37 @@ pub struct CmdDurationConfig<'a> {
     pub min_time: i64,
     pub format: &'a str,
     pub style: &'a str,
+    #[serde(borrow)]
+    pub style_rules: Vec<StyleRule<'a>>,
     pub show_milliseconds: bool,
+    pub ignore_commands: Vec<&'a str>,
     pub disabled: bool,
     pub show_notifications: bool,
     pub min_time_to_notify: i64,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notification_timeout: Option<u32>,
+    #[serde(borrow)]
+    pub notify_rules: Vec<NotifyRule<'a>>,
+    pub suppress_when_focused: bool,
+    pub show_command_in_notifications: bool,
+}
+
+/// Changes when and how the end of commands matching `command_pattern`, a regular expression,
+/// is notified. The first matching rule is used.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct NotifyRule<'a> {
+    pub command_pattern: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_duration: Option<i64>,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    pub urgency: Option<&'a str>,
 }
 
 impl Default for CmdDurationConfig<'_> {
@@ -26,11 +51,16 @@ impl Default for CmdDurationConfig<'_> {
             min_time: 2_000,
             format: "took [$duration]($style) ",
             show_milliseconds: false,
-            style: "yellow bold",
+            ignore_commands: vec![],
+            style: "warning bold",
+            style_rules: vec![],
             disabled: false,
             show_notifications: false,
             min_time_to_notify: 45_000,
             notification_timeout: None,
+            notify_rules: vec![],
+            suppress_when_focused: false,
+            show_command_in_notifications: false,
         }
     }
 }
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct CmsConfig<'a> {
+    pub format: &'a str,
+    pub version_format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for CmsConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "via [$symbol$name( $version)]($style) ",
+            version_format: "v${raw}",
+            symbol: "🌐 ",
+            style: "bold blue",
+            disabled: false,
+        }
+    }
+}
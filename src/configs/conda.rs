@@ -1,3 +1,4 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -11,6 +12,7 @@ pub struct CondaConfig<'a> {
     pub truncation_length: usize,
     pub format: &'a str,
     pub symbol: &'a str,
+    pub manager_symbols: IndexMap<String, &'a str>,
     pub style: &'a str,
     pub ignore_base: bool,
     pub detect_env_vars: Vec<&'a str>,
@@ -23,6 +25,7 @@ impl Default for CondaConfig<'_> {
             truncation_length: 1,
             format: "via [$symbol$environment]($style) ",
             symbol: "🅒 ",
+            manager_symbols: IndexMap::new(),
             style: "green bold",
             ignore_base: true,
             detect_env_vars: vec!["!PIXI_ENVIRONMENT_NAME"],
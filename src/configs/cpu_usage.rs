@@ -0,0 +1,32 @@
+use crate::config::StyleRule;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct CpuUsageConfig<'a> {
+    pub threshold: i64,
+    pub format: &'a str,
+    pub style: &'a str,
+    #[serde(borrow)]
+    pub style_rules: Vec<StyleRule<'a>>,
+    pub symbol: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for CpuUsageConfig<'_> {
+    fn default() -> Self {
+        Self {
+            threshold: 75,
+            format: "via $symbol[$cpu_pct]($style) ",
+            style: "white bold dimmed",
+            style_rules: vec![],
+            symbol: "🧮 ",
+            disabled: true,
+        }
+    }
+}
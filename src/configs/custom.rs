@@ -1,5 +1,7 @@
 use crate::config::{Either, VecOr};
 
+use indexmap::IndexMap;
+
 use serde::{self, Deserialize, Serialize};
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -15,6 +17,8 @@ pub struct CustomConfig<'a> {
     pub command: &'a str,
     pub when: Either<bool, &'a str>,
     pub require_repo: bool,
+    pub require_env_vars: Vec<&'a str>,
+    pub env_matches: IndexMap<String, &'a str>,
     pub shell: VecOr<&'a str>,
     pub description: &'a str,
     pub style: &'a str,
@@ -31,6 +35,19 @@ pub struct CustomConfig<'a> {
     pub use_stdin: Option<bool>,
     pub ignore_timeout: bool,
     pub unsafe_no_escape: bool,
+    pub format_output: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<&'a str>,
+    pub cache_key: Vec<&'a str>,
+    #[serde(rename = "async")]
+    pub run_async: bool,
+    pub async_placeholder: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_file: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_regex: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<&'a str>,
 }
 
 impl Default for CustomConfig<'_> {
@@ -41,6 +58,8 @@ impl Default for CustomConfig<'_> {
             command: "",
             when: Either::First(false),
             require_repo: false,
+            require_env_vars: Vec::default(),
+            env_matches: IndexMap::default(),
             shell: VecOr::default(),
             description: "<custom config>",
             style: "green bold",
@@ -52,6 +71,14 @@ impl Default for CustomConfig<'_> {
             use_stdin: None,
             ignore_timeout: false,
             unsafe_no_escape: false,
+            format_output: "text",
+            cache_ttl: None,
+            cache_key: Vec::default(),
+            run_async: false,
+            async_placeholder: "",
+            from_file: None,
+            parse_regex: None,
+            group: None,
         }
     }
 }
@@ -60,7 +60,7 @@ impl Default for DirectoryConfig<'_> {
             substitutions: Either::First(vec![]),
             format: "[$path]($style)[$read_only]($read_only_style) ",
             repo_root_format: "[$before_root_path]($before_repo_root_style)[$repo_root]($repo_root_style)[$path]($style)[$read_only]($read_only_style) ",
-            style: "cyan bold",
+            style: "primary bold",
             repo_root_style: None,
             before_repo_root_style: None,
             disabled: false,
@@ -0,0 +1,32 @@
+use crate::config::StyleRule;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct DiskUsageConfig<'a> {
+    pub threshold: i64,
+    pub format: &'a str,
+    pub style: &'a str,
+    #[serde(borrow)]
+    pub style_rules: Vec<StyleRule<'a>>,
+    pub symbol: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for DiskUsageConfig<'_> {
+    fn default() -> Self {
+        Self {
+            threshold: 90,
+            format: "with $symbol[$free free]($style) ",
+            style: "bold yellow",
+            style_rules: vec![],
+            symbol: "💾 ",
+            disabled: true,
+        }
+    }
+}
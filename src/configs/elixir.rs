@@ -12,6 +12,7 @@ pub struct ElixirConfig<'a> {
     pub version_format: &'a str,
     pub symbol: &'a str,
     pub style: &'a str,
+    pub prod_style: &'a str,
     pub disabled: bool,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
@@ -25,6 +26,7 @@ impl Default for ElixirConfig<'_> {
             version_format: "v${raw}",
             symbol: "💧 ",
             style: "bold purple",
+            prod_style: "bold red",
             disabled: false,
             detect_extensions: vec![],
             detect_files: vec!["mix.exs"],
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct EmbeddedConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub disabled: bool,
+    pub detect_extensions: Vec<&'a str>,
+    pub detect_files: Vec<&'a str>,
+    pub detect_folders: Vec<&'a str>,
+}
+
+impl Default for EmbeddedConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "for [$symbol($chip )(\\($target\\) )]($style)",
+            symbol: "📟 ",
+            style: "bold cyan",
+            disabled: false,
+            detect_extensions: vec![],
+            detect_files: vec!["Embed.toml", ".embed.toml", "memory.x"],
+            detect_folders: vec![".cargo"],
+        }
+    }
+}
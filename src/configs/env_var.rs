@@ -1,5 +1,10 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+/// The options of `env_var` that are tables, which aren't `env_var` modules of their own
+pub const TABLE_OPTIONS: &[&str] = &["value_aliases"];
+
 #[derive(Clone, Deserialize, Serialize)]
 #[cfg_attr(
     feature = "config-schema",
@@ -17,6 +22,9 @@ pub struct EnvVarConfig<'a> {
     pub format: &'a str,
     pub disabled: bool,
     pub description: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_regex: Option<&'a str>,
+    pub value_aliases: HashMap<String, &'a str>,
 }
 
 impl Default for EnvVarConfig<'_> {
@@ -29,6 +37,8 @@ impl Default for EnvVarConfig<'_> {
             format: "with [$symbol$env_value]($style) ",
             disabled: false,
             description: "<env_var module>",
+            parse_regex: None,
+            value_aliases: HashMap::new(),
         }
     }
 }
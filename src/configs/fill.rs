@@ -10,6 +10,8 @@ use serde::{Deserialize, Serialize};
 pub struct FillConfig<'a> {
     pub style: &'a str,
     pub symbol: &'a str,
+    pub min_width: usize,
+    pub max_width: usize,
     pub disabled: bool,
 }
 
@@ -18,6 +20,8 @@ impl Default for FillConfig<'_> {
         Self {
             style: "bold black",
             symbol: ".",
+            min_width: 0,
+            max_width: 0,
             disabled: false,
         }
     }
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct FirebaseConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub project_aliases: HashMap<String, &'a str>,
+    pub disabled: bool,
+}
+
+impl Default for FirebaseConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "on [$symbol$project( \\($alias\\))]($style) ",
+            symbol: "󰥧 ",
+            style: "bold yellow",
+            project_aliases: HashMap::new(),
+            disabled: false,
+        }
+    }
+}
@@ -21,7 +21,7 @@ impl Default for FossilBranchConfig<'_> {
         Self {
             format: "on [$symbol$branch]($style) ",
             symbol: " ",
-            style: "bold purple",
+            style: "bold vcs",
             truncation_length: i64::MAX,
             truncation_symbol: "…",
             disabled: true,
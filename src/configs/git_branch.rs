@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 #[serde(default)]
 pub struct GitBranchConfig<'a> {
     pub format: &'a str,
+    pub detached_format: Option<&'a str>,
     pub symbol: &'a str,
     pub style: &'a str,
     pub truncation_length: i64,
@@ -24,8 +25,9 @@ impl Default for GitBranchConfig<'_> {
     fn default() -> Self {
         Self {
             format: "on [$symbol$branch(:$remote_branch)]($style) ",
+            detached_format: None,
             symbol: " ",
-            style: "bold purple",
+            style: "bold vcs",
             truncation_length: i64::MAX,
             truncation_symbol: "…",
             only_attached: false,
@@ -30,7 +30,7 @@ impl Default for GitStateConfig<'_> {
             bisect: "BISECTING",
             am: "AM",
             am_or_rebase: "AM/REBASE",
-            style: "bold yellow",
+            style: "bold warning",
             format: "\\([$state( $progress_current/$progress_total)]($style)\\) ",
             disabled: false,
         }
@@ -16,12 +16,16 @@ pub struct GitStatusConfig<'a> {
     pub up_to_date: &'a str,
     pub diverged: &'a str,
     pub conflicted: &'a str,
+    pub conflicted_added: &'a str,
+    pub conflicted_deleted: &'a str,
+    pub conflicted_modified: &'a str,
     pub deleted: &'a str,
     pub renamed: &'a str,
     pub modified: &'a str,
     pub staged: &'a str,
     pub untracked: &'a str,
     pub typechanged: &'a str,
+    pub type_changed: &'a str,
     pub worktree_added: &'a str,
     pub worktree_deleted: &'a str,
     pub worktree_modified: &'a str,
@@ -30,6 +34,7 @@ pub struct GitStatusConfig<'a> {
     pub index_deleted: &'a str,
     pub index_modified: &'a str,
     pub index_typechanged: &'a str,
+    pub count_format: &'a str,
     pub ignore_submodules: bool,
     pub disabled: bool,
     pub use_git_executable: bool,
@@ -48,12 +53,16 @@ impl Default for GitStatusConfig<'_> {
             up_to_date: "",
             diverged: "⇕",
             conflicted: "=",
+            conflicted_added: "",
+            conflicted_deleted: "",
+            conflicted_modified: "",
             deleted: "✘",
             renamed: "»",
             modified: "!",
             staged: "+",
             untracked: "?",
             typechanged: "",
+            type_changed: "",
             worktree_added: "",
             worktree_deleted: "",
             worktree_modified: "",
@@ -62,6 +71,7 @@ impl Default for GitStatusConfig<'_> {
             index_deleted: "",
             index_modified: "",
             index_typechanged: "",
+            count_format: "symbols",
             ignore_submodules: false,
             disabled: false,
             use_git_executable: false,
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct GodotConfig<'a> {
+    pub format: &'a str,
+    pub version_format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub disabled: bool,
+    pub detect_extensions: Vec<&'a str>,
+    pub detect_files: Vec<&'a str>,
+    pub detect_folders: Vec<&'a str>,
+}
+
+impl Default for GodotConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "via [$symbol($engine_version )(\\($version\\) )]($style)",
+            version_format: "v${raw}",
+            symbol: "🤖 ",
+            style: "bold #478CBF",
+            disabled: false,
+            detect_extensions: vec![],
+            detect_files: vec!["project.godot"],
+            detect_folders: vec![],
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct GpgConfig<'a> {
+    pub format: &'a str,
+    pub unavailable_format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub unavailable_style: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for GpgConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "[$symbol]($style) ",
+            unavailable_format: "[$symbol!]($style) ",
+            symbol: "󰌾 ",
+            style: "bold green",
+            unavailable_style: "bold red",
+            disabled: true,
+        }
+    }
+}
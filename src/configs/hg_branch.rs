@@ -20,7 +20,7 @@ impl Default for HgBranchConfig<'_> {
     fn default() -> Self {
         Self {
             symbol: " ",
-            style: "bold purple",
+            style: "bold vcs",
             format: "on [$symbol$branch(:$topic)]($style) ",
             truncation_length: i64::MAX,
             truncation_symbol: "…",
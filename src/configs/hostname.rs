@@ -15,6 +15,10 @@ pub struct HostnameConfig<'a> {
     pub detect_env_vars: Vec<&'a str>,
     pub format: &'a str,
     pub style: &'a str,
+    pub ssh_format: Option<&'a str>,
+    pub ssh_style: Option<&'a str>,
+    pub tmux_ssh_format: Option<&'a str>,
+    pub tmux_ssh_style: Option<&'a str>,
     pub disabled: bool,
     pub aliases: IndexMap<String, &'a str>,
 }
@@ -28,6 +32,10 @@ impl Default for HostnameConfig<'_> {
             detect_env_vars: vec![],
             format: "[$ssh_symbol$hostname]($style) in ",
             style: "green dimmed bold",
+            ssh_format: None,
+            ssh_style: None,
+            tmux_ssh_format: None,
+            tmux_ssh_style: None,
             disabled: false,
             aliases: IndexMap::new(),
         }
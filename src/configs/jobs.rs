@@ -14,6 +14,8 @@ pub struct JobsConfig<'a> {
     pub format: &'a str,
     pub symbol: &'a str,
     pub style: &'a str,
+    pub max_names: usize,
+    pub names_truncation_symbol: &'a str,
     pub disabled: bool,
 }
 
@@ -26,6 +28,8 @@ impl Default for JobsConfig<'_> {
             format: "[$symbol$number]($style) ",
             symbol: "✦",
             style: "bold blue",
+            max_names: 3,
+            names_truncation_symbol: "…",
             disabled: false,
         }
     }
@@ -0,0 +1,36 @@
+use crate::config::StyleRule;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct LoadAvgConfig<'a> {
+    pub threshold: f64,
+    pub averages: Vec<&'a str>,
+    pub normalize: bool,
+    pub format: &'a str,
+    pub style: &'a str,
+    #[serde(borrow)]
+    pub style_rules: Vec<StyleRule<'a>>,
+    pub symbol: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for LoadAvgConfig<'_> {
+    fn default() -> Self {
+        Self {
+            threshold: 1.0,
+            averages: vec!["1", "5", "15"],
+            normalize: true,
+            format: "via $symbol[$load]($style) ",
+            style: "white bold dimmed",
+            style_rules: vec![],
+            symbol: "📈 ",
+            disabled: true,
+        }
+    }
+}
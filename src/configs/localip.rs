@@ -11,6 +11,11 @@ pub struct LocalipConfig<'a> {
     pub ssh_only: bool,
     pub format: &'a str,
     pub style: &'a str,
+    pub interfaces: Vec<&'a str>,
+    pub prefer_ipv6: bool,
+    pub exclude_link_local: bool,
+    pub max_addresses: usize,
+    pub separator: &'a str,
     pub disabled: bool,
 }
 
@@ -18,8 +23,13 @@ impl Default for LocalipConfig<'_> {
     fn default() -> Self {
         Self {
             ssh_only: true,
-            format: "[$localipv4]($style) ",
+            format: "[$localip]($style) ",
             style: "yellow bold",
+            interfaces: vec![],
+            prefer_ipv6: false,
+            exclude_link_local: true,
+            max_addresses: 1,
+            separator: " ",
             disabled: true,
         }
     }
@@ -1,3 +1,4 @@
+use crate::config::StyleRule;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -11,6 +12,8 @@ pub struct MemoryConfig<'a> {
     pub threshold: i64,
     pub format: &'a str,
     pub style: &'a str,
+    #[serde(borrow)]
+    pub style_rules: Vec<StyleRule<'a>>,
     pub symbol: &'a str,
     pub disabled: bool,
 }
@@ -21,6 +24,7 @@ impl Default for MemoryConfig<'_> {
             threshold: 75,
             format: "via $symbol[$ram( | $swap)]($style) ",
             style: "white bold dimmed",
+            style_rules: vec![],
             symbol: "🐏 ",
             disabled: true,
         }
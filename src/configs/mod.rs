@@ -1,23 +1,29 @@
 use indexmap::IndexMap;
 use serde::{self, Deserialize, Serialize};
 
+pub mod agenda;
 pub mod aws;
 pub mod azure;
+pub mod azure_devops;
 pub mod battery;
 pub mod buf;
 pub mod bun;
 pub mod c;
 pub mod cc;
 pub mod character;
+pub mod ci_status;
 pub mod claude_context;
 pub mod claude_cost;
 pub mod claude_model;
+pub mod cloud;
 pub mod cmake;
 pub mod cmd_duration;
+pub mod cms;
 pub mod cobol;
 pub mod conda;
 pub mod container;
 pub mod cpp;
+pub mod cpu_usage;
 pub mod crystal;
 pub mod custom;
 pub mod daml;
@@ -25,14 +31,17 @@ pub mod dart;
 pub mod deno;
 pub mod directory;
 pub mod direnv;
+pub mod disk_usage;
 pub mod docker_context;
 pub mod dotnet;
 pub mod elixir;
 pub mod elm;
+pub mod embedded;
 pub mod env_var;
 pub mod erlang;
 pub mod fennel;
 pub mod fill;
+pub mod firebase;
 pub mod fortran;
 pub mod fossil_branch;
 pub mod fossil_metrics;
@@ -44,6 +53,8 @@ pub mod git_state;
 pub mod git_status;
 pub mod gleam;
 pub mod go;
+pub mod godot;
+pub mod gpg;
 pub mod gradle;
 pub mod guix_shell;
 pub mod haskell;
@@ -58,6 +69,7 @@ pub mod julia;
 pub mod kotlin;
 pub mod kubernetes;
 pub mod line_break;
+pub mod load_avg;
 pub mod localip;
 pub mod lua;
 pub mod maven;
@@ -66,7 +78,9 @@ pub mod meson;
 pub mod mise;
 pub mod mojo;
 pub mod nats;
+pub mod netlify;
 pub mod netns;
+pub mod network;
 pub mod nim;
 pub mod nix_shell;
 pub mod nodejs;
@@ -80,33 +94,44 @@ pub mod perl;
 pub mod php;
 pub mod pijul_channel;
 pub mod pixi;
+pub mod platformio;
+pub mod pull_request;
 pub mod pulumi;
 pub mod purescript;
 pub mod python;
 pub mod quarto;
 pub mod raku;
 pub mod red;
+pub mod release_pending;
 pub mod rlang;
 pub mod ruby;
 pub mod rust;
 pub mod scala;
+pub mod secrets_guard;
 pub mod shell;
 pub mod shlvl;
 pub mod singularity;
 pub mod solidity;
 pub mod spack;
+pub mod ssh_agent;
 mod starship_root;
 pub mod status;
 pub mod sudo;
+pub mod supabase;
 pub mod swift;
+pub mod temperature;
 pub mod terraform;
 pub mod time;
+pub mod timer;
 pub mod typst;
+pub mod unity;
 pub mod username;
 pub mod v;
 pub mod vagrant;
 pub mod vcs;
 pub mod vcsh;
+pub mod vercel;
+pub mod wsl;
 pub mod xmake;
 pub mod zig;
 
@@ -128,10 +153,14 @@ pub struct FullConfig<'a> {
     root: StarshipRootConfig,
     // modules
     #[serde(borrow)]
+    agenda: agenda::AgendaConfig<'a>,
+    #[serde(borrow)]
     aws: aws::AwsConfig<'a>,
     #[serde(borrow)]
     azure: azure::AzureConfig<'a>,
     #[serde(borrow)]
+    azure_devops: azure_devops::AzureDevOpsConfig<'a>,
+    #[serde(borrow)]
     battery: battery::BatteryConfig<'a>,
     #[serde(borrow)]
     buf: buf::BufConfig<'a>,
@@ -142,16 +171,22 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     character: character::CharacterConfig<'a>,
     #[serde(borrow)]
+    ci_status: ci_status::CiStatusConfig<'a>,
+    #[serde(borrow)]
     claude_context: claude_context::ClaudeContextConfig<'a>,
     #[serde(borrow)]
     claude_cost: claude_cost::ClaudeCostConfig<'a>,
     #[serde(borrow)]
     claude_model: claude_model::ClaudeModelConfig<'a>,
     #[serde(borrow)]
+    cloud: cloud::CloudConfig<'a>,
+    #[serde(borrow)]
     cmake: cmake::CMakeConfig<'a>,
     #[serde(borrow)]
     cmd_duration: cmd_duration::CmdDurationConfig<'a>,
     #[serde(borrow)]
+    cms: cms::CmsConfig<'a>,
+    #[serde(borrow)]
     cobol: cobol::CobolConfig<'a>,
     #[serde(borrow)]
     conda: conda::CondaConfig<'a>,
@@ -160,6 +195,8 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     cpp: cpp::CppConfig<'a>,
     #[serde(borrow)]
+    cpu_usage: cpu_usage::CpuUsageConfig<'a>,
+    #[serde(borrow)]
     crystal: crystal::CrystalConfig<'a>,
     #[serde(borrow)]
     daml: daml::DamlConfig<'a>,
@@ -172,6 +209,8 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     direnv: direnv::DirenvConfig<'a>,
     #[serde(borrow)]
+    disk_usage: disk_usage::DiskUsageConfig<'a>,
+    #[serde(borrow)]
     docker_context: docker_context::DockerContextConfig<'a>,
     #[serde(borrow)]
     dotnet: dotnet::DotnetConfig<'a>,
@@ -182,12 +221,16 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     env_var: IndexMap<String, env_var::EnvVarConfig<'a>>,
     #[serde(borrow)]
+    embedded: embedded::EmbeddedConfig<'a>,
+    #[serde(borrow)]
     erlang: erlang::ErlangConfig<'a>,
     #[serde(borrow)]
     fennel: fennel::FennelConfig<'a>,
     #[serde(borrow)]
     fill: fill::FillConfig<'a>,
     #[serde(borrow)]
+    firebase: firebase::FirebaseConfig<'a>,
+    #[serde(borrow)]
     fortran: fortran::FortranConfig<'a>,
     #[serde(borrow)]
     fossil_branch: fossil_branch::FossilBranchConfig<'a>,
@@ -208,8 +251,12 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     gleam: gleam::GleamConfig<'a>,
     #[serde(borrow)]
+    godot: godot::GodotConfig<'a>,
+    #[serde(borrow)]
     golang: go::GoConfig<'a>,
     #[serde(borrow)]
+    gpg: gpg::GpgConfig<'a>,
+    #[serde(borrow)]
     gradle: gradle::GradleConfig<'a>,
     #[serde(borrow)]
     guix_shell: guix_shell::GuixShellConfig<'a>,
@@ -237,6 +284,8 @@ pub struct FullConfig<'a> {
     kubernetes: kubernetes::KubernetesConfig<'a>,
     line_break: line_break::LineBreakConfig,
     #[serde(borrow)]
+    load_avg: load_avg::LoadAvgConfig<'a>,
+    #[serde(borrow)]
     localip: localip::LocalipConfig<'a>,
     #[serde(borrow)]
     lua: lua::LuaConfig<'a>,
@@ -253,8 +302,12 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     nats: nats::NatsConfig<'a>,
     #[serde(borrow)]
+    netlify: netlify::NetlifyConfig<'a>,
+    #[serde(borrow)]
     netns: netns::NetnsConfig<'a>,
     #[serde(borrow)]
+    network: network::NetworkConfig<'a>,
+    #[serde(borrow)]
     nim: nim::NimConfig<'a>,
     #[serde(borrow)]
     nix_shell: nix_shell::NixShellConfig<'a>,
@@ -281,6 +334,10 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     pixi: pixi::PixiConfig<'a>,
     #[serde(borrow)]
+    platformio: platformio::PlatformioConfig<'a>,
+    #[serde(borrow)]
+    pull_request: pull_request::PullRequestConfig<'a>,
+    #[serde(borrow)]
     pulumi: pulumi::PulumiConfig<'a>,
     #[serde(borrow)]
     purescript: purescript::PureScriptConfig<'a>,
@@ -293,6 +350,8 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     red: red::RedConfig<'a>,
     #[serde(borrow)]
+    release_pending: release_pending::ReleasePendingConfig<'a>,
+    #[serde(borrow)]
     rlang: rlang::RLangConfig<'a>,
     #[serde(borrow)]
     ruby: ruby::RubyConfig<'a>,
@@ -301,6 +360,8 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     scala: scala::ScalaConfig<'a>,
     #[serde(borrow)]
+    secrets_guard: secrets_guard::SecretsGuardConfig<'a>,
+    #[serde(borrow)]
     shell: shell::ShellConfig<'a>,
     #[serde(borrow)]
     shlvl: shlvl::ShLvlConfig<'a>,
@@ -311,18 +372,28 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     spack: spack::SpackConfig<'a>,
     #[serde(borrow)]
+    ssh_agent: ssh_agent::SshAgentConfig<'a>,
+    #[serde(borrow)]
     status: status::StatusConfig<'a>,
     #[serde(borrow)]
     sudo: sudo::SudoConfig<'a>,
     #[serde(borrow)]
+    supabase: supabase::SupabaseConfig<'a>,
+    #[serde(borrow)]
     swift: swift::SwiftConfig<'a>,
     #[serde(borrow)]
+    temperature: temperature::TemperatureConfig<'a>,
+    #[serde(borrow)]
     terraform: terraform::TerraformConfig<'a>,
     #[serde(borrow)]
     time: time::TimeConfig<'a>,
     #[serde(borrow)]
+    timer: timer::TimerConfig<'a>,
+    #[serde(borrow)]
     typst: typst::TypstConfig<'a>,
     #[serde(borrow)]
+    unity: unity::UnityConfig<'a>,
+    #[serde(borrow)]
     username: username::UsernameConfig<'a>,
     #[serde(borrow)]
     vagrant: vagrant::VagrantConfig<'a>,
@@ -331,8 +402,12 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     vcsh: vcsh::VcshConfig<'a>,
     #[serde(borrow)]
+    vercel: vercel::VercelConfig<'a>,
+    #[serde(borrow)]
     vlang: v::VConfig<'a>,
     #[serde(borrow)]
+    wsl: wsl::WslConfig<'a>,
+    #[serde(borrow)]
     xmake: xmake::XMakeConfig<'a>,
     #[serde(borrow)]
     zig: zig::ZigConfig<'a>,
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct NetlifyConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub site_aliases: HashMap<String, &'a str>,
+    pub disabled: bool,
+}
+
+impl Default for NetlifyConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "on [$symbol$site]($style) ",
+            symbol: "◈ ",
+            style: "bold cyan",
+            site_aliases: HashMap::new(),
+            disabled: false,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct NetworkConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub vpn_symbol: &'a str,
+    pub style: &'a str,
+    pub vpn_interfaces: Vec<&'a str>,
+    pub show_when: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for NetworkConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "on [$symbol($interface)( $vpn_symbol$vpn)]($style) ",
+            symbol: "🌐 ",
+            vpn_symbol: "🔒 ",
+            style: "bold blue",
+            vpn_interfaces: vec!["^wg", "^tun", "^tailscale", "^ppp"],
+            show_when: "always",
+            disabled: true,
+        }
+    }
+}
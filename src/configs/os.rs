@@ -12,13 +12,38 @@ use serde::{Deserialize, Serialize};
 pub struct OSConfig<'a> {
     pub format: &'a str,
     pub style: &'a str,
-    pub symbols: IndexMap<Type, &'a str>,
+    pub symbols: IndexMap<OSSymbolKey, &'a str>,
     pub disabled: bool,
 }
 
+/// A key of `symbols`: the type of an operating system, or the `ID` of a distribution in
+/// `/etc/os-release`, for distributions without a type of their own
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum OSSymbolKey {
+    Type(Type),
+    Id(String),
+}
+
+// Keys of tables are always strings in the schema
+#[cfg(feature = "config-schema")]
+impl schemars::JsonSchema for OSSymbolKey {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "OSSymbolKey".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        String::json_schema(generator)
+    }
+}
+
 impl<'a> OSConfig<'a> {
     pub fn get_symbol(&self, key: Type) -> Option<&'a str> {
-        self.symbols.get(&key).copied()
+        self.symbols.get(&OSSymbolKey::Type(key)).copied()
+    }
+
+    pub fn get_id_symbol(&self, id: &str) -> Option<&'a str> {
+        self.symbols.get(&OSSymbolKey::Id(id.to_string())).copied()
     }
 }
 
@@ -94,7 +119,10 @@ impl Default for OSConfig<'_> {
                 //sabayon =>    " ",
                 //slackware =>  " ",
                 //solaris =>    " ",
-            },
+            }
+            .into_iter()
+            .map(|(os_type, symbol)| (OSSymbolKey::Type(os_type), symbol))
+            .collect(),
             disabled: true,
         }
     }
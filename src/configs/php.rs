@@ -13,6 +13,7 @@ pub struct PhpConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    pub not_capable_style: &'a str,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -26,6 +27,7 @@ impl Default for PhpConfig<'_> {
             symbol: "🐘 ",
             style: "147 bold",
             disabled: false,
+            not_capable_style: "bold red",
             detect_extensions: vec!["php"],
             detect_files: vec!["composer.json", ".php-version"],
             detect_folders: vec![],
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct PullRequestConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub draft_style: &'a str,
+    pub approved_symbol: &'a str,
+    pub changes_requested_symbol: &'a str,
+    pub review_required_symbol: &'a str,
+    pub provider: &'a str,
+    pub hosts: Vec<&'a str>,
+    pub cache_ttl: u64,
+    pub timeout: u64,
+    pub disabled: bool,
+}
+
+impl Default for PullRequestConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "[$symbol#$number( $review_symbol)]($style) ",
+            symbol: " ",
+            style: "bold purple",
+            draft_style: "bright-black",
+            approved_symbol: "✓",
+            changes_requested_symbol: "✗",
+            review_required_symbol: "●",
+            provider: "",
+            hosts: vec![],
+            cache_ttl: 300,
+            timeout: 5000,
+            disabled: true,
+        }
+    }
+}
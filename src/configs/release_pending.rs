@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct ReleasePendingConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub changeset_folder: &'a str,
+    pub changelog_files: Vec<&'a str>,
+    pub unreleased_heading: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for ReleasePendingConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "[$symbol$count]($style) ",
+            symbol: "🦋 ",
+            style: "bold purple",
+            changeset_folder: ".changeset",
+            changelog_files: vec!["CHANGELOG.md"],
+            unreleased_heading: "Unreleased",
+            disabled: true,
+        }
+    }
+}
@@ -12,6 +12,8 @@ pub struct RLangConfig<'a> {
     pub version_format: &'a str,
     pub style: &'a str,
     pub symbol: &'a str,
+    pub renv_synced: &'a str,
+    pub renv_outdated: &'a str,
     pub disabled: bool,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
@@ -25,9 +27,11 @@ impl Default for RLangConfig<'_> {
             version_format: "v${raw}",
             style: "blue bold",
             symbol: "📐 ",
+            renv_synced: "renv",
+            renv_outdated: "renv*",
             disabled: false,
             detect_extensions: vec!["R", "Rd", "Rmd", "Rproj", "Rsx"],
-            detect_files: vec!["DESCRIPTION"],
+            detect_files: vec!["DESCRIPTION", "renv.lock"],
             detect_folders: vec![".Rproj.user"],
         }
     }
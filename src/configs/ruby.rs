@@ -13,6 +13,7 @@ pub struct RubyConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    pub not_capable_style: &'a str,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -27,6 +28,7 @@ impl Default for RubyConfig<'_> {
             symbol: "💎 ",
             style: "bold red",
             disabled: false,
+            not_capable_style: "bold yellow",
             detect_extensions: vec!["rb"],
             detect_files: vec!["Gemfile", ".ruby-version"],
             detect_folders: vec![],
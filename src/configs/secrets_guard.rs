@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct SecretsGuardConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub variables: Vec<&'a str>,
+    pub disabled: bool,
+}
+
+impl Default for SecretsGuardConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "[$symbol$count]($style) ",
+            symbol: "🔑 ",
+            style: "bold red",
+            variables: vec![
+                "^AWS_SECRET_ACCESS_KEY$",
+                "^AWS_SESSION_TOKEN$",
+                "^GH_TOKEN$",
+                "^GITHUB_TOKEN$",
+                "^GITLAB_TOKEN$",
+                "^NPM_TOKEN$",
+                "_API_KEY$",
+                "_SECRET$",
+            ],
+            disabled: true,
+        }
+    }
+}
@@ -21,7 +21,12 @@ pub struct ShellConfig<'a> {
     pub nu_indicator: &'a str,
     pub xonsh_indicator: &'a str,
     pub cmd_indicator: &'a str,
+    pub osh_indicator: &'a str,
+    pub ysh_indicator: &'a str,
+    pub murex_indicator: &'a str,
     pub unknown_indicator: &'a str,
+    pub login_indicator: &'a str,
+    pub non_interactive_indicator: &'a str,
     pub style: &'a str,
     pub disabled: bool,
 }
@@ -41,7 +46,12 @@ impl Default for ShellConfig<'_> {
             nu_indicator: "nu",
             xonsh_indicator: "xsh",
             cmd_indicator: "cmd",
+            osh_indicator: "osh",
+            ysh_indicator: "ysh",
+            murex_indicator: "mx",
             unknown_indicator: "",
+            login_indicator: "login",
+            non_interactive_indicator: "non-interactive",
             style: "white bold",
             disabled: true,
         }
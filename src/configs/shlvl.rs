@@ -13,6 +13,7 @@ pub struct ShLvlConfig<'a> {
     pub symbol: &'a str,
     pub repeat: bool,
     pub repeat_offset: u64,
+    pub auto_baseline: bool,
     pub style: &'a str,
     pub disabled: bool,
 }
@@ -25,6 +26,7 @@ impl Default for ShLvlConfig<'_> {
             symbol: "↕️  ", // extra space for emoji
             repeat: false,
             repeat_offset: 0,
+            auto_baseline: false,
             style: "bold yellow",
             disabled: true,
         }
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct SshAgentConfig<'a> {
+    pub format: &'a str,
+    pub unreachable_format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub empty_style: &'a str,
+    pub unreachable_style: &'a str,
+    pub timeout: u64,
+    pub disabled: bool,
+}
+
+impl Default for SshAgentConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "[$symbol$count]($style) ",
+            unreachable_format: "[$symbol!]($style) ",
+            symbol: "󰌆 ",
+            style: "bold green",
+            empty_style: "bold yellow",
+            unreachable_style: "bold red",
+            timeout: 100,
+            disabled: true,
+        }
+    }
+}
@@ -2,13 +2,56 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-pub fn default_profiles() -> IndexMap<String, String> {
+pub fn default_profiles() -> IndexMap<String, Profile> {
     IndexMap::from_iter([(
         "claude-code".to_string(),
-        "$claude_model$git_branch$claude_context$claude_cost".to_string(),
+        Profile::Format("$claude_model$git_branch$claude_context$claude_cost".to_string()),
     )])
 }
 
+/// A named prompt layout, printed with `starship prompt --profile` or used in place of the
+/// prompt with `STARSHIP_PROFILE`. Either the format of the left prompt, or a table replacing
+/// `format` and `right_format`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "config-schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum Profile {
+    Format(String),
+    Layout(ProfileLayout),
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileLayout {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right_format: Option<String>,
+}
+
+impl Profile {
+    /// The format of the left prompt, if the profile replaces it
+    pub fn format(&self) -> Option<&str> {
+        match self {
+            Self::Format(format) => Some(format),
+            Self::Layout(layout) => layout.format.as_deref(),
+        }
+    }
+
+    /// The format of the right prompt, if the profile replaces it
+    pub fn right_format(&self) -> Option<&str> {
+        match self {
+            Self::Format(_) => None,
+            Self::Layout(layout) => layout.right_format.as_deref(),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[cfg_attr(
     feature = "config-schema",
@@ -19,25 +62,105 @@ pub fn default_profiles() -> IndexMap<String, String> {
 pub struct StarshipRootConfig {
     #[serde(rename = "$schema")]
     schema: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trusted_paths: Vec<String>,
     pub format: String,
     pub right_format: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub format_lines: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub right_format_lines: Vec<String>,
+    pub bash_right_prompt: bool,
+    pub width_threshold: usize,
+    pub max_prompt_width: usize,
     pub continuation_prompt: String,
     pub scan_timeout: u64,
     pub command_timeout: u64,
+    pub prompt_timeout_strategy: String,
+    pub prompt_timeout_placeholder: String,
+    pub prompt_timeout_symbol: String,
+    pub version_cache_ttl: u64,
+    pub async_modules: Vec<String>,
+    pub custom_order: Vec<String>,
+    pub groups: HashMap<String, GroupConfig>,
+    pub segment_separators: SegmentSeparators,
     pub add_newline: bool,
+    pub prompt_marks: bool,
+    pub force_plain: bool,
     pub follow_symlinks: bool,
+    pub redact: RedactConfig,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub palette: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub palette_light: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub palette_dark: Option<String>,
     pub palettes: HashMap<String, Palette>,
     #[serde(rename = "profiles")]
     #[cfg_attr(feature = "config-schema", schemars(default = "default_profiles"))]
-    pub user_profiles: IndexMap<String, String>,
+    pub user_profiles: IndexMap<String, Profile>,
     #[serde(skip)]
-    pub internal_profiles: IndexMap<String, String>,
+    pub internal_profiles: IndexMap<String, Profile>,
+}
+
+/// Hides the values of sensitive variables, e.g. while sharing the screen. The variables
+/// are set with `redact_variables` in the table of each module.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct RedactConfig {
+    pub enabled: bool,
+    pub placeholder: String,
+    pub fake_names: bool,
+}
+
+impl Default for RedactConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            placeholder: "•••".to_string(),
+            fake_names: false,
+        }
+    }
+}
+
+/// The options of a group of modules in the format, e.g. `(group:cloud $aws$gcloud)`.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct GroupConfig {
+    pub style: String,
+}
+
+/// The glyphs inserted between adjacent modules with different backgrounds, e.g. for
+/// powerline-style prompts. `left` is used in the left prompt, `right` in the right prompt.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct SegmentSeparators {
+    pub left: String,
+    pub right: String,
 }
 
 pub type Palette = HashMap<String, String>;
 
+/// The key of a palette naming the palette it extends.
+const INHERITS_KEY: &str = "inherits";
+
 // List of default prompt order
 // NOTE: If this const value is changed then Default prompt order subheading inside
 // prompt heading of config docs needs to be updated according to changes made here.
@@ -58,11 +181,17 @@ pub const PROMPT_ORDER: &[&str] = &[
     "git_state",
     "git_metrics",
     "git_status",
+    "ci_status",
+    "pull_request",
+    "azure_devops",
+    "gpg",
     "hg_branch",
     "hg_state",
     "pijul_channel",
     "docker_context",
     "package",
+    "release_pending",
+    "cms",
     // ↓ Toolchain version modules ↓
     // (Let's keep these sorted alphabetically)
     "bun",
@@ -76,10 +205,12 @@ pub const PROMPT_ORDER: &[&str] = &[
     "dotnet",
     "elixir",
     "elm",
+    "embedded",
     "erlang",
     "fennel",
     "fortran",
     "gleam",
+    "godot",
     "golang",
     "gradle",
     "haskell",
@@ -98,6 +229,7 @@ pub const PROMPT_ORDER: &[&str] = &[
     "opa",
     "perl",
     "php",
+    "platformio",
     "pulumi",
     "purescript",
     "python",
@@ -112,6 +244,7 @@ pub const PROMPT_ORDER: &[&str] = &[
     "swift",
     "terraform",
     "typst",
+    "unity",
     "vlang",
     "vagrant",
     "xmake",
@@ -124,9 +257,18 @@ pub const PROMPT_ORDER: &[&str] = &[
     "pixi",
     "meson",
     "spack",
+    "cpu_usage",
+    "load_avg",
     "memory_usage",
+    "disk_usage",
+    #[cfg(feature = "temperature")]
+    "temperature",
     "aws",
     "gcloud",
+    "firebase",
+    "supabase",
+    "vercel",
+    "netlify",
     "openstack",
     "azure",
     "direnv",
@@ -134,6 +276,8 @@ pub const PROMPT_ORDER: &[&str] = &[
     "mise",
     "crystal",
     "custom",
+    "secrets_guard",
+    "ssh_agent",
     "sudo",
     "cmd_duration",
     "line_break",
@@ -141,9 +285,13 @@ pub const PROMPT_ORDER: &[&str] = &[
     #[cfg(feature = "battery")]
     "battery",
     "time",
+    "timer",
+    "agenda",
     "status",
     "container",
+    "wsl",
     "netns",
+    "network",
     "os",
     "shell",
     "character",
@@ -154,17 +302,129 @@ impl Default for StarshipRootConfig {
     fn default() -> Self {
         Self {
             schema: "https://starship.rs/config-schema.json".to_string(),
+            include: vec![],
+            trusted_paths: vec![],
             format: "$all".to_string(),
             right_format: String::new(),
+            format_lines: vec![],
+            right_format_lines: vec![],
+            bash_right_prompt: true,
+            width_threshold: 0,
+            max_prompt_width: 0,
             continuation_prompt: "[∙](bright-black) ".to_string(),
             user_profiles: IndexMap::new(),
             internal_profiles: default_profiles(),
             scan_timeout: 30,
             command_timeout: 500,
+            prompt_timeout_strategy: "wait".to_string(),
+            prompt_timeout_placeholder: "[$cached](dimmed) ".to_string(),
+            prompt_timeout_symbol: "[⧗ ](bold yellow)".to_string(),
+            version_cache_ttl: 0,
+            async_modules: vec![],
+            custom_order: vec![],
+            groups: HashMap::default(),
+            segment_separators: SegmentSeparators::default(),
             add_newline: true,
+            prompt_marks: false,
+            force_plain: false,
             follow_symlinks: true,
+            redact: RedactConfig::default(),
             palette: None,
+            palette_light: None,
+            palette_dark: None,
             palettes: HashMap::default(),
         }
     }
 }
+
+impl StarshipRootConfig {
+    /// Replaces `format` and `right_format` with the prompt made up of `format_lines`,
+    /// if it is set.
+    ///
+    /// The right prompt of each line but the last is aligned to the right with `$fill`,
+    /// while the one of the last line becomes the actual right prompt.
+    #[must_use]
+    pub fn with_format_lines(mut self) -> Self {
+        if self.format_lines.is_empty() {
+            if !self.right_format_lines.is_empty() {
+                log::warn!("`right_format_lines` is ignored without `format_lines`");
+            }
+            return self;
+        }
+
+        let last_line = self.format_lines.len() - 1;
+        if self.right_format_lines.len() > self.format_lines.len() {
+            log::warn!(
+                "`right_format_lines` has more lines than `format_lines`, ignoring the rest"
+            );
+        }
+
+        self.format = self
+            .format_lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| match self.right_format_lines.get(index) {
+                Some(right) if index != last_line && !right.is_empty() => {
+                    format!("{line}${{fill}}{right}")
+                }
+                _ => line.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Some(right) = self.right_format_lines.get(last_line) {
+            self.right_format.clone_from(right);
+        }
+
+        self
+    }
+
+    /// Returns the profile named `name`, preferring the user's profiles over the built-in ones
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.user_profiles
+            .get(name)
+            .or_else(|| self.internal_profiles.get(name))
+    }
+
+    /// Merges the colors of the palettes named by the `inherits` key of each palette into it.
+    ///
+    /// Colors defined in a palette take precedence over the inherited ones.
+    #[must_use]
+    pub fn with_inherited_palettes(mut self) -> Self {
+        let resolved = self
+            .palettes
+            .keys()
+            .map(|name| (name.clone(), resolve_palette(&self.palettes, name)))
+            .collect();
+        self.palettes = resolved;
+        self
+    }
+}
+
+/// Returns the colors of the palette named `name`, including the ones it inherits.
+fn resolve_palette(palettes: &HashMap<String, Palette>, name: &str) -> Palette {
+    let mut chain = vec![name];
+    while let Some(parent) = palettes
+        .get(*chain.last().unwrap())
+        .and_then(|palette| palette.get(INHERITS_KEY))
+    {
+        if chain.contains(&parent.as_str()) {
+            log::warn!("Color palette {name:?} inherits from itself through {parent:?}");
+            break;
+        }
+        if !palettes.contains_key(parent) {
+            log::warn!("Could not find color palette {parent:?} inherited by {name:?}");
+            break;
+        }
+        chain.push(parent);
+    }
+
+    chain
+        .iter()
+        .rev()
+        .filter_map(|name| palettes.get(*name))
+        .flatten()
+        .filter(|(key, _)| *key != INHERITS_KEY)
+        .map(|(key, color)| (key.clone(), color.clone()))
+        .collect()
+}
@@ -1,3 +1,4 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -15,6 +16,8 @@ pub struct StatusConfig<'a> {
     pub not_found_symbol: &'a str,
     pub sigint_symbol: &'a str,
     pub signal_symbol: &'a str,
+    pub code_symbols: IndexMap<String, &'a str>,
+    pub descriptions: IndexMap<String, &'a str>,
     pub style: &'a str,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub success_style: Option<&'a str>,
@@ -27,6 +30,8 @@ pub struct StatusConfig<'a> {
     pub pipestatus_format: &'a str,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pipestatus_segment_format: Option<&'a str>,
+    pub max_pipe_elements: usize,
+    pub pipestatus_truncation_symbol: &'a str,
     pub disabled: bool,
 }
 
@@ -40,7 +45,9 @@ impl Default for StatusConfig<'_> {
             not_found_symbol: "🔍",
             sigint_symbol: "🧱",
             signal_symbol: "⚡",
-            style: "bold red",
+            code_symbols: IndexMap::new(),
+            descriptions: IndexMap::new(),
+            style: "bold error",
             success_style: None,
             failure_style: None,
             map_symbol: false,
@@ -49,6 +56,8 @@ impl Default for StatusConfig<'_> {
             pipestatus_separator: "|",
             pipestatus_format: "\\[$pipestatus\\] => [$symbol$common_meaning$signal_name$maybe_int]($style) ",
             pipestatus_segment_format: None,
+            max_pipe_elements: 0,
+            pipestatus_truncation_symbol: "…",
             disabled: true,
         }
     }
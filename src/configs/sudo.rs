@@ -12,6 +12,7 @@ pub struct SudoConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub allow_windows: bool,
+    pub timestamp_timeout: u64,
     pub disabled: bool,
 }
 
@@ -22,6 +23,7 @@ impl Default for SudoConfig<'_> {
             symbol: "🧙 ",
             style: "bold blue",
             allow_windows: false,
+            timestamp_timeout: 15,
             disabled: true,
         }
     }
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct SupabaseConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub linked_style: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for SupabaseConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "via [$symbol$mode( $project_ref)]($style) ",
+            symbol: "󱐋 ",
+            style: "bold green",
+            linked_style: "bold yellow",
+            disabled: false,
+        }
+    }
+}
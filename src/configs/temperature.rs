@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct TemperatureConfig<'a> {
+    pub threshold: f64,
+    pub sensors: Vec<&'a str>,
+    pub format: &'a str,
+    pub style: &'a str,
+    pub symbol: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for TemperatureConfig<'_> {
+    fn default() -> Self {
+        Self {
+            threshold: 80.0,
+            sensors: vec![],
+            format: "[$symbol$temperature]($style) ",
+            style: "bold red",
+            symbol: "🔥 ",
+            disabled: true,
+        }
+    }
+}
@@ -16,6 +16,32 @@ pub struct TimeConfig<'a> {
     pub disabled: bool,
     pub utc_time_offset: &'a str,
     pub time_range: &'a str,
+    #[serde(borrow)]
+    pub zones: Vec<TimeZoneConfig<'a>>,
+    pub world_clock_separator: &'a str,
+}
+
+/// A time zone shown by `$time_<alias>` and `$world_clock`, e.g.
+/// `{ zone = "America/New_York", alias = "NYC" }`
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct TimeZoneConfig<'a> {
+    pub zone: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<&'a str>,
+}
+
+impl<'a> TimeZoneConfig<'a> {
+    /// The alias of the zone, or else the last part of its name, e.g. `New_York`
+    pub fn alias(&self) -> &'a str {
+        self.alias
+            .unwrap_or_else(|| self.zone.rsplit('/').next().unwrap_or(self.zone))
+    }
 }
 
 impl Default for TimeConfig<'_> {
@@ -28,6 +54,8 @@ impl Default for TimeConfig<'_> {
             disabled: true,
             utc_time_offset: "local",
             time_range: "-",
+            zones: vec![],
+            world_clock_separator: " | ",
         }
     }
 }
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct TimerConfig<'a> {
+    pub format: &'a str,
+    pub expired_format: &'a str,
+    pub symbol: &'a str,
+    pub expired_symbol: &'a str,
+    pub style: &'a str,
+    pub expired_style: &'a str,
+    pub expired_timeout: u64,
+    pub disabled: bool,
+}
+
+impl Default for TimerConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "[$symbol$remaining( $label)]($style) ",
+            expired_format: "[$symbol$label]($style) ",
+            symbol: "🍅 ",
+            expired_symbol: "⏰ ",
+            style: "bold red",
+            expired_style: "bold green",
+            expired_timeout: 30,
+            disabled: false,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct UnityConfig<'a> {
+    pub format: &'a str,
+    pub version_format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub disabled: bool,
+    pub not_capable_style: &'a str,
+    pub detect_extensions: Vec<&'a str>,
+    pub detect_files: Vec<&'a str>,
+    pub detect_folders: Vec<&'a str>,
+}
+
+impl Default for UnityConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "via [$symbol($version )(\\(hub $hub_version\\) )]($style)",
+            version_format: "v${raw}",
+            symbol: "🎮 ",
+            style: "bold white",
+            disabled: false,
+            not_capable_style: "bold yellow",
+            detect_extensions: vec![],
+            detect_files: vec![],
+            detect_folders: vec!["ProjectSettings"],
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub struct UsernameConfig<'a> {
     pub format: &'a str,
     pub style_root: &'a str,
     pub style_user: &'a str,
+    pub style_real_user: &'a str,
     pub show_always: bool,
     pub disabled: bool,
     pub aliases: IndexMap<String, &'a str>,
@@ -22,9 +23,10 @@ impl Default for UsernameConfig<'_> {
     fn default() -> Self {
         Self {
             detect_env_vars: vec![],
-            format: "[$user]($style) in ",
+            format: "([$real_user→]($style_real_user))[$user]($style) in ",
             style_root: "red bold",
             style_user: "yellow bold",
+            style_real_user: "yellow bold",
             show_always: false,
             disabled: false,
             aliases: IndexMap::new(),
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct WslConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub windows_path_symbol: &'a str,
+    pub windows_path_style: &'a str,
+    pub disabled: bool,
+}
+
+impl Default for WslConfig<'_> {
+    fn default() -> Self {
+        Self {
+            format: "[$symbol$distro on WSL$version( $windows_path)]($style) ",
+            symbol: "🐧 ",
+            style: "bold blue",
+            windows_path_symbol: "⚠ ",
+            windows_path_style: "bold yellow",
+            disabled: true,
+        }
+    }
+}
@@ -0,0 +1,771 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::process;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use toml_edit::{DocumentMut, Item};
+
+use crate::config::ModuleConfig;
+use crate::configs::{FullConfig, PROMPT_ORDER};
+use crate::configure;
+use crate::context::{Context, Shell};
+use crate::print;
+
+const HELP: &str = "↑/↓ select  space toggle  J/K move  s symbol  y style  w save  q quit";
+
+/// A key pressed in the configurator
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Enter,
+    Escape,
+    Backspace,
+    Interrupt,
+    Char(char),
+}
+
+/// What to do after a key press
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Continue,
+    Save,
+    Quit,
+}
+
+/// An option of the selected module being edited, with its new value
+struct Edit {
+    key: &'static str,
+    value: String,
+}
+
+/// The state of the configurator: the edited config file and the modules of its format
+struct Configurator<'a> {
+    context: Context<'a>,
+    doc: DocumentMut,
+    /// The config with the default values of all options filled in
+    config: toml::Value,
+    modules: Vec<String>,
+    selected: usize,
+    edit: Option<Edit>,
+    message: Option<String>,
+    modified: bool,
+    confirm_quit: bool,
+    previews: HashMap<String, Option<String>>,
+}
+
+/// Lets the user toggle, reorder and restyle the modules of the prompt in a terminal UI,
+/// with a live preview, and writes the result to the config file
+pub fn run(mut context: Context) {
+    let doc = configure::get_configuration_edit(&context);
+    context.shell = Shell::Unknown;
+
+    let mut configurator = Configurator::new(context, doc);
+    match terminal::run(&mut configurator) {
+        Ok(Action::Save) => {
+            configure::write_configuration(&configurator.context, &configurator.doc);
+        }
+        Ok(_) => {}
+        Err(error) => {
+            eprintln!("Unable to run the configurator: {error}");
+            process::exit(1);
+        }
+    }
+}
+
+impl<'a> Configurator<'a> {
+    fn new(mut context: Context<'a>, doc: DocumentMut) -> Self {
+        // The prompt is rendered on every key press, so only the edited modules are rendered again
+        context.keep_module_output();
+        let mut configurator = Self {
+            context,
+            doc,
+            config: toml::Value::Table(toml::Table::new()),
+            modules: Vec::new(),
+            selected: 0,
+            edit: None,
+            message: None,
+            modified: false,
+            confirm_quit: false,
+            previews: HashMap::new(),
+        };
+        configurator.reload();
+        configurator
+    }
+
+    /// Loads the edited config into the context, for the previews
+    fn reload(&mut self) {
+        let config: toml::Table = match toml::from_str(&self.doc.to_string()) {
+            Ok(config) => config,
+            Err(error) => {
+                self.message = Some(format!("Unable to parse the configuration: {error}"));
+                return;
+            }
+        };
+
+        self.config = toml::Value::try_from(FullConfig::load(&config))
+            .unwrap_or_else(|_| toml::Value::Table(toml::Table::new()));
+        self.context.update_config(config);
+        self.modules = format_modules(&self.format());
+        self.selected = self.selected.min(self.modules.len().saturating_sub(1));
+    }
+
+    /// The root format, with `$all` expanded into the modules it stands for
+    fn format(&self) -> String {
+        let get = |key| self.config.get(key).and_then(toml::Value::as_str);
+        expand_all(
+            get("format").unwrap_or("$all"),
+            get("right_format").unwrap_or(""),
+        )
+    }
+
+    fn selected_module(&self) -> Option<&str> {
+        self.modules.get(self.selected).map(String::as_str)
+    }
+
+    /// Returns an option of a module, following the dots of names such as `custom.foo`
+    fn option(&self, module: &str, key: &str) -> Option<&toml::Value> {
+        module
+            .split('.')
+            .try_fold(&self.config, |config, name| config.get(name))?
+            .get(key)
+    }
+
+    fn handle_key(&mut self, key: Key) -> Action {
+        if key == Key::Interrupt {
+            return Action::Quit;
+        }
+
+        if let Some(edit) = &mut self.edit {
+            match key {
+                Key::Enter => {
+                    if let Some(edit) = self.edit.take() {
+                        self.set_option(edit.key, toml_edit::Value::from(edit.value));
+                    }
+                }
+                Key::Escape => self.edit = None,
+                Key::Backspace => {
+                    edit.value.pop();
+                }
+                Key::Char(c) => edit.value.push(c),
+                _ => {}
+            }
+            return Action::Continue;
+        }
+
+        let confirm_quit = std::mem::take(&mut self.confirm_quit);
+        self.message = None;
+        match key {
+            Key::Up | Key::Char('k') => self.selected = self.selected.saturating_sub(1),
+            Key::Down | Key::Char('j') => {
+                self.selected = (self.selected + 1).min(self.modules.len().saturating_sub(1));
+            }
+            Key::Char(' ') => self.toggle_disabled(),
+            Key::Char('K') => self.move_selected(false),
+            Key::Char('J') => self.move_selected(true),
+            Key::Char('s') => self.start_edit("symbol"),
+            Key::Char('y') => self.start_edit("style"),
+            Key::Char('w') => return Action::Save,
+            Key::Char('q') | Key::Escape if self.modified && !confirm_quit => {
+                self.confirm_quit = true;
+                self.message = Some("Unsaved changes, press q again to quit".to_string());
+            }
+            Key::Char('q') | Key::Escape => return Action::Quit,
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn toggle_disabled(&mut self) {
+        let Some(module) = self.selected_module() else {
+            return;
+        };
+        match self
+            .option(module, "disabled")
+            .and_then(toml::Value::as_bool)
+        {
+            Some(disabled) => self.set_option("disabled", toml_edit::Value::from(!disabled)),
+            None => self.message = Some(format!("`{module}` can not be disabled")),
+        }
+    }
+
+    fn start_edit(&mut self, key: &'static str) {
+        let Some(module) = self.selected_module() else {
+            return;
+        };
+        match self.option(module, key).and_then(toml::Value::as_str) {
+            Some(value) => {
+                self.edit = Some(Edit {
+                    key,
+                    value: value.to_string(),
+                });
+            }
+            None => self.message = Some(format!("`{module}` has no {key} option")),
+        }
+    }
+
+    /// Sets an option of the selected module, unless it makes the config invalid
+    fn set_option(&mut self, key: &str, value: toml_edit::Value) {
+        let Some(module) = self.selected_module() else {
+            return;
+        };
+        let mut doc = self.doc.clone();
+        let name = format!("{module}.{key}");
+        let result = configure::handle_update_configuration(&mut doc, &name, &value.to_string());
+
+        // Render the module again with its new options
+        let module = module.to_string();
+        self.context.forget_module_output(&module);
+        self.previews.remove(&module);
+        self.apply(doc, result);
+    }
+
+    /// Swaps the selected module with the next or previous one in the format
+    fn move_selected(&mut self, down: bool) {
+        let Some(target) = (if down {
+            self.selected.checked_add(1)
+        } else {
+            self.selected.checked_sub(1)
+        }) else {
+            return;
+        };
+        let (Some(module), Some(other)) =
+            (self.modules.get(self.selected), self.modules.get(target))
+        else {
+            return;
+        };
+        let Some(format) = swap_modules(&self.format(), module, other) else {
+            return;
+        };
+
+        let mut doc = self.doc.clone();
+        let mut value = format_value(&format);
+        if let Some(old) = doc.get("format").and_then(Item::as_value) {
+            *value.decor_mut() = old.decor().clone();
+        }
+        doc.insert("format", Item::Value(value));
+
+        self.selected = target;
+        self.apply(doc, Ok(()));
+    }
+
+    /// Replaces the edited config with `doc`, unless the edit failed or made the config invalid
+    fn apply(&mut self, doc: DocumentMut, result: Result<(), String>) {
+        let result = result.and_then(|()| {
+            match (
+                configure::validate_configuration(&self.doc),
+                configure::validate_configuration(&doc),
+            ) {
+                (Ok(()), Err(error)) => Err(format!("Invalid configuration: {error}")),
+                _ => Ok(()),
+            }
+        });
+
+        match result {
+            Ok(()) => {
+                self.doc = doc;
+                self.modified = true;
+                self.reload();
+            }
+            Err(error) => self.message = Some(error),
+        }
+    }
+
+    fn preview(&mut self, module: &str) -> Option<String> {
+        if !self.previews.contains_key(module) {
+            let preview =
+                print::get_module(module, &self.context).map(|preview| preview.replace('\n', "↵"));
+            self.previews.insert(module.to_string(), preview);
+        }
+        self.previews.get(module).cloned().flatten()
+    }
+
+    /// Renders the screen, with lines separated by `\r\n` as the terminal is in raw mode
+    fn render(&mut self, height: usize) -> String {
+        let mut lines = vec![
+            format!("\x1b[1mstarship configurator\x1b[0m  {HELP}"),
+            String::new(),
+        ];
+
+        let prompt = print::get_prompt(&self.context);
+        lines.extend(prompt.trim_start_matches('\n').lines().map(str::to_string));
+        lines.push(format!("\x1b[0m{}", "─".repeat(self.context.width)));
+
+        // Leave room for the status line
+        let rows = height.saturating_sub(lines.len() + 2).max(1);
+        let start = self
+            .selected
+            .saturating_sub(rows / 2)
+            .min(self.modules.len().saturating_sub(rows));
+        let name_width = self.modules.iter().map(String::len).max().unwrap_or(0);
+
+        for (index, module) in self
+            .modules
+            .clone()
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(rows)
+        {
+            let enabled = match self
+                .option(module, "disabled")
+                .and_then(toml::Value::as_bool)
+            {
+                Some(true) => ' ',
+                Some(false) => 'x',
+                None => '-',
+            };
+            let name = if index == self.selected {
+                format!("\x1b[7m{module:name_width$}\x1b[0m")
+            } else {
+                format!("{module:name_width$}")
+            };
+            let preview = if enabled == ' ' {
+                String::new()
+            } else {
+                self.preview(module).unwrap_or_default()
+            };
+            lines.push(format!("[{enabled}] {name}  {preview}\x1b[0m"));
+        }
+
+        lines.push(String::new());
+        lines.push(match (&self.edit, &self.message) {
+            (Some(edit), _) => format!(
+                "{} {}: {}█",
+                self.selected_module().unwrap_or_default(),
+                edit.key,
+                edit.value
+            ),
+            (None, Some(message)) => message.clone(),
+            (None, None) if self.modified => "Modified, press w to save".to_string(),
+            (None, None) => String::new(),
+        });
+
+        lines.join("\r\n")
+    }
+}
+
+/// Matches the variables of a format string, skipping escaped characters
+fn variable_regex() -> &'static Regex {
+    static VARIABLE: OnceLock<Regex> = OnceLock::new();
+    VARIABLE.get_or_init(|| Regex::new(r"\\.|\$\{?(\w+(?:\.[\w-]+)?)\}?").unwrap())
+}
+
+/// Returns the variables of a format string with their positions
+fn variables(format: &str) -> Vec<(Range<usize>, &str)> {
+    variable_regex()
+        .captures_iter(format)
+        .filter_map(|captures| {
+            let name = captures.get(1)?;
+            Some((captures.get(0)?.range(), name.as_str()))
+        })
+        .collect()
+}
+
+/// Returns the modules of a format string, in order
+fn format_modules(format: &str) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    variables(format)
+        .into_iter()
+        .filter(|(_, name)| seen.insert(*name))
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Replaces `$all` with the modules it stands for, i.e. the ones not used in either format
+fn expand_all(format: &str, right_format: &str) -> String {
+    let Some((range, _)) = variables(format)
+        .into_iter()
+        .find(|(_, name)| *name == "all")
+    else {
+        return format.to_string();
+    };
+
+    let used: BTreeSet<_> = variables(format)
+        .into_iter()
+        .chain(variables(right_format))
+        .map(|(_, name)| name)
+        .collect();
+    let all = PROMPT_ORDER
+        .iter()
+        .filter(|module| !used.contains(*module))
+        .fold(String::new(), |mut output, module| {
+            let _ = write!(output, "${module}");
+            output
+        });
+
+    format!("{}{all}{}", &format[..range.start], &format[range.end..])
+}
+
+/// Swaps the first occurrences of two modules in a format string
+fn swap_modules(format: &str, first: &str, second: &str) -> Option<String> {
+    let variables = variables(format);
+    let find = |module| {
+        variables
+            .iter()
+            .find(|(_, name)| *name == module)
+            .map(|(range, _)| range.clone())
+    };
+    let (mut a, mut b) = (find(first)?, find(second)?);
+    if a.start > b.start {
+        std::mem::swap(&mut a, &mut b);
+    }
+
+    Some(format!(
+        "{}{}{}{}{}",
+        &format[..a.start],
+        &format[b.clone()],
+        &format[a.end..b.start],
+        &format[a],
+        &format[b.end..]
+    ))
+}
+
+/// Writes a format string with one module per line, like the default format in the docs
+fn format_value(format: &str) -> toml_edit::Value {
+    if format.contains(['\\', '"']) {
+        return format.into();
+    }
+
+    let mut repr = String::from("\"\"\"\n");
+    let mut last = 0;
+    for (range, _) in variables(format) {
+        if range.start > 0 {
+            repr.push_str(&format[last..range.start]);
+            repr.push_str("\\\n");
+            last = range.start;
+        }
+    }
+    repr.push_str(&format[last..]);
+    repr.push_str("\"\"\"");
+
+    repr.parse().unwrap_or_else(|_| format.into())
+}
+
+/// Parses the bytes read from the terminal into keys
+fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let text = String::from_utf8_lossy(bytes);
+    let mut chars = text.chars().peekable();
+    let mut keys = Vec::new();
+
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '\x1b' if matches!(chars.peek(), Some('[' | 'O')) => {
+                chars.next();
+                // Skip the parameters of the sequence, e.g. the modifiers in `\x1b[1;5A`
+                while chars.next_if(|c| c.is_ascii_digit() || *c == ';').is_some() {}
+                match chars.next() {
+                    Some('A') => Key::Up,
+                    Some('B') => Key::Down,
+                    _ => continue,
+                }
+            }
+            '\x1b' => Key::Escape,
+            '\r' | '\n' => Key::Enter,
+            '\x7f' | '\x08' => Key::Backspace,
+            '\x03' | '\x04' => Key::Interrupt,
+            c if c.is_control() => continue,
+            c => Key::Char(c),
+        };
+        keys.push(key);
+    }
+
+    keys
+}
+
+#[cfg(unix)]
+mod terminal {
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, Read, Write};
+
+    use nix::sys::termios::{SetArg, Termios, cfmakeraw, tcgetattr, tcsetattr};
+    use terminal_size::{Height, Width, terminal_size_of};
+
+    use super::{Action, Configurator, parse_keys};
+
+    /// Switches the terminal to raw mode on an alternate screen, restoring it when dropped
+    struct RawTerminal {
+        tty: File,
+        original: Termios,
+    }
+
+    impl RawTerminal {
+        fn new() -> io::Result<Self> {
+            let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+            let original = tcgetattr(&tty)?;
+            let mut raw = original.clone();
+            cfmakeraw(&mut raw);
+            tcsetattr(&tty, SetArg::TCSANOW, &raw)?;
+
+            // Use the alternate screen, hide the cursor and disable line wrapping
+            tty.write_all(b"\x1b[?1049h\x1b[?25l\x1b[?7l")?;
+            Ok(Self { tty, original })
+        }
+    }
+
+    impl Drop for RawTerminal {
+        fn drop(&mut self) {
+            let _ = self.tty.write_all(b"\x1b[?7h\x1b[?25h\x1b[?1049l");
+            if let Err(error) = tcsetattr(&self.tty, SetArg::TCSANOW, &self.original) {
+                log::warn!("Unable to restore the terminal mode: {error}");
+            }
+        }
+    }
+
+    pub fn run(configurator: &mut Configurator) -> io::Result<Action> {
+        let mut terminal = RawTerminal::new()?;
+
+        loop {
+            let (Width(width), Height(height)) =
+                terminal_size_of(&terminal.tty).unwrap_or((Width(80), Height(24)));
+            configurator.context.width = usize::from(width);
+            let screen = configurator.render(usize::from(height));
+            write!(terminal.tty, "\x1b[H\x1b[2J{screen}")?;
+            terminal.tty.flush()?;
+
+            let mut buf = [0; 64];
+            let n = terminal.tty.read(&mut buf)?;
+            if n == 0 {
+                return Ok(Action::Quit);
+            }
+            for key in parse_keys(&buf[..n]) {
+                match configurator.handle_key(key) {
+                    Action::Continue => {}
+                    action => return Ok(action),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod terminal {
+    use std::io;
+
+    use super::{Action, Configurator};
+
+    pub fn run(_configurator: &mut Configurator) -> io::Result<Action> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the configurator is not supported on Windows, edit the configuration file or use \
+             `starship config <name> <value>` instead",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::test::default_context;
+
+    fn configurator(config: &str) -> Configurator<'static> {
+        let doc = config.parse::<DocumentMut>().unwrap();
+        let context = default_context().set_config(toml::from_str(config).unwrap());
+        Configurator::new(context, doc)
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(
+            parse_keys(b"\x1b[A\x1b[1;2Bj \x1b\r\x7f\xc3\xa9\x03"),
+            vec![
+                Key::Up,
+                Key::Down,
+                Key::Char('j'),
+                Key::Char(' '),
+                Key::Escape,
+                Key::Enter,
+                Key::Backspace,
+                Key::Char('é'),
+                Key::Interrupt,
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_all() {
+        let expanded = expand_all("$directory$all", "$time");
+        assert!(expanded.starts_with("$directory$username$hostname"));
+        assert_eq!(expanded.matches("$directory").count(), 1);
+        assert!(!expanded.split('$').any(|module| module == "time"));
+        assert!(!expanded.contains("$all"));
+        assert_eq!(expand_all("$character", ""), "$character");
+    }
+
+    #[test]
+    fn swaps_modules() {
+        assert_eq!(
+            swap_modules("[$git_branch](red)$git ${custom.foo}", "git", "git_branch"),
+            Some("[$git](red)$git_branch ${custom.foo}".to_string())
+        );
+        assert_eq!(
+            swap_modules("$a \\$b $c", "c", "a"),
+            Some("$c \\$b $a".to_string())
+        );
+        assert_eq!(swap_modules("$a", "a", "b"), None);
+    }
+
+    #[test]
+    fn formats_one_module_per_line() {
+        let value = format_value("$directory$git_branch\n$character");
+        assert_eq!(
+            value.to_string(),
+            "\"\"\"\n$directory\\\n$git_branch\n\\\n$character\"\"\""
+        );
+        assert_eq!(value.as_str(), Some("$directory$git_branch\n$character"));
+    }
+
+    #[test]
+    fn lists_format_modules() {
+        let configurator = configurator("format = '$directory$git_branch$directory$character'");
+        assert_eq!(
+            configurator.modules,
+            vec!["directory", "git_branch", "character"]
+        );
+    }
+
+    #[test]
+    fn toggles_disabled() {
+        let mut configurator = configurator(
+            "format = '$directory$character'\n[character] # comment\ndisabled = false\n",
+        );
+        configurator.handle_key(Key::Down);
+        configurator.handle_key(Key::Char(' '));
+        assert_eq!(
+            configurator.doc.to_string(),
+            "format = '$directory$character'\n[character] # comment\ndisabled = true\n"
+        );
+
+        configurator.handle_key(Key::Up);
+        configurator.handle_key(Key::Char(' '));
+        assert_eq!(
+            configurator.doc["directory"]["disabled"].as_bool(),
+            Some(true)
+        );
+        assert_eq!(configurator.handle_key(Key::Char('w')), Action::Save);
+    }
+
+    #[test]
+    fn moves_modules() {
+        let mut configurator =
+            configurator("format = '$directory$git_branch$character' # comment\n");
+        configurator.handle_key(Key::Char('J'));
+        assert_eq!(
+            configurator.doc.to_string(),
+            "format = \"\"\"\n$git_branch\\\n$directory\\\n$character\"\"\" # comment\n"
+        );
+        assert_eq!(
+            configurator.modules,
+            vec!["git_branch", "directory", "character"]
+        );
+        assert_eq!(configurator.selected_module(), Some("directory"));
+
+        configurator.handle_key(Key::Char('J'));
+        configurator.handle_key(Key::Char('J'));
+        assert_eq!(
+            configurator.modules,
+            vec!["git_branch", "character", "directory"]
+        );
+    }
+
+    #[test]
+    fn edits_options() {
+        let mut configurator = configurator("format = '$directory'\n");
+        configurator.handle_key(Key::Char('y'));
+        for _ in "primary bold".chars() {
+            configurator.handle_key(Key::Backspace);
+        }
+        for c in "red".chars() {
+            configurator.handle_key(Key::Char(c));
+        }
+        configurator.handle_key(Key::Enter);
+        assert_eq!(configurator.doc["directory"]["style"].as_str(), Some("red"));
+
+        configurator.handle_key(Key::Char('s'));
+        assert!(configurator.edit.is_none());
+        assert_eq!(
+            configurator.message.as_deref(),
+            Some("`directory` has no symbol option")
+        );
+    }
+
+    #[test]
+    fn confirms_quit_with_unsaved_changes() {
+        let mut configurator = configurator("format = '$character'\n");
+        assert_eq!(configurator.handle_key(Key::Char('q')), Action::Quit);
+
+        configurator.handle_key(Key::Char(' '));
+        assert_eq!(configurator.handle_key(Key::Char('q')), Action::Continue);
+        assert_eq!(configurator.handle_key(Key::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn options_of_dotted_modules() {
+        let mut configurator = configurator(
+            "format = '${custom.foo}${env_var.FOO}'\n[custom.foo]\ncommand = 'echo foo'\n\
+             [env_var.FOO]\nsymbol = 'F '\n",
+        );
+        assert_eq!(
+            configurator.option("env_var.FOO", "symbol"),
+            Some(&toml::Value::from("F "))
+        );
+
+        configurator.handle_key(Key::Char(' '));
+        assert_eq!(
+            configurator.doc["custom"]["foo"]["disabled"].as_bool(),
+            Some(true)
+        );
+        configurator.handle_key(Key::Down);
+        configurator.handle_key(Key::Char(' '));
+        assert_eq!(
+            configurator.doc["env_var"]["FOO"]["disabled"].as_bool(),
+            Some(true)
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn only_renders_edited_modules_again() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let runs = dir.path().join("runs");
+        let config = format!(
+            "format = '${{custom.foo}}$character'\n[custom.foo]\n\
+             command = 'echo run >> {}; echo foo'\nwhen = true\nshell = ['sh']\n",
+            runs.display()
+        );
+        let mut configurator = configurator(&config);
+        configurator.context.current_dir = dir.path().to_path_buf();
+        let count_runs = || fs::read_to_string(&runs).map(|runs| runs.lines().count());
+
+        // The module is rendered for the prompt and for its preview
+        configurator.render(24);
+        assert_eq!(count_runs()?, 2);
+
+        // Editing another module doesn't render it again
+        configurator.handle_key(Key::Down);
+        configurator.handle_key(Key::Char(' '));
+        configurator.render(24);
+        assert_eq!(count_runs()?, 2);
+
+        configurator.handle_key(Key::Up);
+        configurator.handle_key(Key::Char('s'));
+        configurator.handle_key(Key::Char('x'));
+        configurator.handle_key(Key::Enter);
+        configurator.render(24);
+        assert_eq!(count_runs()?, 4);
+        dir.close()
+    }
+
+    #[test]
+    fn renders_previews() {
+        let mut configurator = configurator("format = '$character'\n[character]\nformat = '>'\n");
+        configurator.context.width = 20;
+        let screen = configurator.render(24);
+        assert!(screen.contains("\r\n>\r\n"));
+        assert!(screen.contains("[x] \x1b[7mcharacter\x1b[0m  >"));
+    }
+}
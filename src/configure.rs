@@ -8,9 +8,12 @@ use crate::config::StarshipConfig;
 use crate::configs::PROMPT_ORDER;
 use crate::context::Context;
 use crate::utils;
+use crate::utils::serde::ValueDeserializer;
+use serde::Deserialize;
 use std::fs::File;
 use std::io::Write;
-use toml_edit::DocumentMut;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, TableLike};
 
 #[cfg(not(windows))]
 const STD_EDITOR: &str = "vi";
@@ -19,8 +22,53 @@ const STD_EDITOR: &str = "notepad.exe";
 
 pub fn update_configuration(context: &Context, name: &str, value: &str) {
     let mut doc = get_configuration_edit(context);
+    let old_doc = doc.clone();
 
-    match handle_update_configuration(&mut doc, name, value) {
+    match handle_update_configuration(&mut doc, name, value)
+        .and_then(|()| validate_edit(&old_doc, &doc))
+    {
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+        _ => write_configuration(context, &doc),
+    }
+}
+
+/// Prints the value of the config key `name`, or its default value if it isn't set
+pub fn print_configuration_value(context: &Context, name: &str) {
+    let user_config = get_configuration(context);
+    let config = crate::configs::FullConfig::load(&user_config);
+    // Convert back to Value because toml can't serialize FullConfig directly
+    let config = toml::Value::try_from(config).unwrap();
+
+    match handle_get_configuration(&config, name) {
+        Ok(value) => println!("{value}"),
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn handle_get_configuration(config: &toml::Value, name: &str) -> Result<String, String> {
+    let value = name
+        .split('.')
+        .try_fold(config, |value, key| value.get(key))
+        .ok_or_else(|| format!("Given config key '{name}' does not exist"))?;
+
+    Ok(match value {
+        toml::Value::String(value) => value.clone(),
+        toml::Value::Table(_) => toml::to_string_pretty(value).unwrap_or_default(),
+        value => value.to_string(),
+    })
+}
+
+/// Removes the config key `name` from the config file, restoring its default value
+pub fn unset_configuration(context: &Context, name: &str) {
+    let mut doc = get_configuration_edit(context);
+
+    match handle_unset_configuration(&mut doc, name) {
         Err(e) => {
             eprintln!("{e}");
             process::exit(1);
@@ -29,7 +77,47 @@ pub fn update_configuration(context: &Context, name: &str, value: &str) {
     }
 }
 
-fn handle_update_configuration(
+fn handle_unset_configuration(doc: &mut DocumentMut, name: &str) -> Result<(), String> {
+    let (parents, key) = name
+        .rsplit_once('.')
+        .map_or((None, name), |(parents, key)| (Some(parents), key));
+
+    let table = parents
+        .into_iter()
+        .flat_map(|parents| parents.split('.'))
+        .try_fold(
+            doc.as_table_mut() as &mut dyn toml_edit::TableLike,
+            |table, key| table.get_mut(key)?.as_table_like_mut(),
+        )
+        .ok_or_else(|| format!("Given config key '{name}' is not set in config file"))?;
+
+    table
+        .remove(key)
+        .map(|_| ())
+        .ok_or_else(|| format!("Given config key '{name}' is not set in config file"))
+}
+
+/// Checks that `doc` can be loaded into the config structs, unless `old_doc`,
+/// the config before the edit, could not be loaded either.
+fn validate_edit(old_doc: &DocumentMut, doc: &DocumentMut) -> Result<(), String> {
+    match (validate_configuration(old_doc), validate_configuration(doc)) {
+        (Ok(()), Err(e)) => Err(format!("Invalid configuration: {e}")),
+        (Err(_), Err(e)) => {
+            eprintln!("Warning: the configuration was already invalid: {e}");
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn validate_configuration(doc: &DocumentMut) -> Result<(), String> {
+    let config: toml::Table = toml::from_str(&doc.to_string()).map_err(|e| e.to_string())?;
+    crate::configs::FullConfig::deserialize(ValueDeserializer::new(&config))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) fn handle_update_configuration(
     doc: &mut DocumentMut,
     name: &str,
     value: &str,
@@ -223,6 +311,259 @@ fn handle_toggle_configuration(doc: &mut DocumentMut, name: &str, key: &str) ->
     Ok(())
 }
 
+/// An option that was renamed or moved, from the path `from` to the path `to`.
+/// A `*` matches any key, e.g. the name of a custom module.
+pub struct Migration {
+    pub from: &'static str,
+    pub to: &'static str,
+}
+
+/// The options that were renamed or moved, in the order they should be migrated
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: "character.vicmd_symbol",
+        to: "character.vimcmd_symbol",
+    },
+    Migration {
+        from: "custom.*.files",
+        to: "custom.*.detect_files",
+    },
+    Migration {
+        from: "custom.*.extensions",
+        to: "custom.*.detect_extensions",
+    },
+    Migration {
+        from: "custom.*.directories",
+        to: "custom.*.detect_folders",
+    },
+    Migration {
+        from: "fennel.detect_extentions",
+        to: "fennel.detect_extensions",
+    },
+];
+
+impl Migration {
+    /// Returns the path `path` is migrated to, if it matches `from`
+    pub fn migrate_path(&self, path: &[&str]) -> Option<Vec<String>> {
+        let from: Vec<&str> = self.from.split('.').collect();
+        if from.len() != path.len()
+            || from
+                .iter()
+                .zip(path)
+                .any(|(pattern, key)| *pattern != "*" && pattern != key)
+        {
+            return None;
+        }
+
+        let mut wildcards = path
+            .iter()
+            .zip(&from)
+            .filter(|(_, pattern)| **pattern == "*")
+            .map(|(key, _)| *key);
+        Some(
+            self.to
+                .split('.')
+                .map(|key| match key {
+                    "*" => wildcards.next().unwrap_or(key).to_string(),
+                    key => key.to_string(),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Rewrites the renamed and moved options of the config file, keeping its comments
+pub fn migrate_configuration(context: &Context) {
+    let mut doc = get_configuration_edit(context);
+
+    let changes = handle_migrate_configuration(&mut doc);
+    if changes.is_empty() {
+        println!("The configuration is up to date");
+        return;
+    }
+
+    for change in &changes {
+        println!("{change}");
+    }
+    write_configuration(context, &doc);
+}
+
+fn handle_migrate_configuration(doc: &mut DocumentMut) -> Vec<String> {
+    let mut changes = Vec::new();
+    for migration in MIGRATIONS {
+        let mut paths = Vec::new();
+        collect_paths(doc.as_table(), &mut Vec::new(), &mut paths);
+
+        for path in paths {
+            let path: Vec<&str> = path.iter().map(String::as_str).collect();
+            let Some(new_path) = migration.migrate_path(&path) else {
+                continue;
+            };
+            let old_name = path.join(".");
+            let new_name = new_path.join(".");
+
+            match move_option(doc.as_table_mut(), &path, &new_path) {
+                Ok(()) => changes.push(format!("Renamed `{old_name}` to `{new_name}`")),
+                Err(e) => changes.push(format!("Skipped `{old_name}`: {e}")),
+            }
+        }
+    }
+    changes
+}
+
+/// Collects the paths of all the keys in `table`
+fn collect_paths(
+    table: &dyn toml_edit::TableLike,
+    path: &mut Vec<String>,
+    paths: &mut Vec<Vec<String>>,
+) {
+    for (key, item) in table.iter() {
+        path.push(key.to_string());
+        paths.push(path.clone());
+        if let Some(table) = item.as_table_like() {
+            collect_paths(table, path, paths);
+        }
+        path.pop();
+    }
+}
+
+/// Moves the item at `from` to `to`, keeping the comments around its key
+fn move_option(doc: &mut toml_edit::Table, from: &[&str], to: &[String]) -> Result<(), String> {
+    fn table_at<'a>(
+        table: &'a mut dyn toml_edit::TableLike,
+        path: &[&str],
+        create: bool,
+    ) -> Option<&'a mut dyn toml_edit::TableLike> {
+        path.iter().try_fold(table, |table, key| {
+            let item = if create {
+                table.entry(key).or_insert_with(toml_edit::table)
+            } else {
+                table.get_mut(key)?
+            };
+            item.as_table_like_mut()
+        })
+    }
+
+    let (new_key, new_parents) = to.split_last().ok_or("empty path")?;
+    let new_parents: Vec<&str> = new_parents.iter().map(String::as_str).collect();
+    if table_at(doc, &new_parents, false).is_some_and(|table| table.contains_key(new_key)) {
+        return Err(format!("`{}` is already set", to.join(".")));
+    }
+
+    let (old_key, old_parents) = from.split_last().ok_or("empty path")?;
+    let old_table = table_at(doc, old_parents, false).ok_or("not a table")?;
+    let decor = old_table
+        .key(old_key)
+        .map(|key| key.leaf_decor().clone())
+        .unwrap_or_default();
+    let item = old_table.remove(old_key).ok_or("not set")?;
+
+    let new_table = table_at(doc, &new_parents, true)
+        .ok_or_else(|| format!("`{}` is not a table", new_parents.join(".")))?;
+    new_table
+        .entry_format(&toml_edit::Key::new(new_key.as_str()).with_leaf_decor(decor))
+        .or_insert(item);
+    Ok(())
+}
+
+/// Adds `path` to the `trusted_paths` of the config, which allows the `.starship.toml`
+/// files within it to override the config
+pub fn trust_path(context: &Context, path: &Path) {
+    let path = match dunce::canonicalize(path) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Unable to trust {}: {e}", path.display());
+            process::exit(1);
+        }
+    };
+
+    let mut doc = get_configuration_edit(context);
+    match handle_trust_path(&mut doc, &path.to_string_lossy()) {
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+        _ => write_configuration(context, &doc),
+    }
+}
+
+fn handle_trust_path(doc: &mut DocumentMut, path: &str) -> Result<(), String> {
+    let trusted_paths = doc
+        .as_table_mut()
+        .entry("trusted_paths")
+        .or_insert_with(|| toml_edit::value(toml_edit::Array::new()))
+        .as_array_mut()
+        .ok_or("`trusted_paths` must be an array")?;
+
+    if !trusted_paths
+        .iter()
+        .any(|trusted| trusted.as_str() == Some(path))
+    {
+        trusted_paths.push(path);
+    }
+    Ok(())
+}
+
+/// Merges the preset into the config file, keeping the options and comments it does not set
+pub fn apply_preset(context: &Context, preset: &DocumentMut) {
+    let mut doc = get_configuration_edit(context);
+    merge_edits(doc.as_table_mut(), preset.as_table());
+    write_configuration(context, &doc);
+}
+
+/// Merges the tables of `overlay` into `base` recursively, replacing its other values
+pub fn merge_edits(base: &mut dyn TableLike, overlay: &dyn TableLike) {
+    for (key, item) in overlay.iter() {
+        let mut new_item = item.clone();
+        clear_positions(&mut new_item);
+
+        match base.get_mut(key) {
+            Some(existing) => {
+                if let (Some(existing), Some(item)) =
+                    (existing.as_table_like_mut(), item.as_table_like())
+                {
+                    merge_edits(existing, item);
+                } else {
+                    *existing = new_item;
+                }
+            }
+            None => {
+                let Some((key, _)) = overlay.get_key_value(key) else {
+                    continue;
+                };
+                // Separate the new table from the ones before it
+                if let Item::Table(table) = &mut new_item
+                    && table
+                        .decor()
+                        .prefix()
+                        .and_then(|prefix| prefix.as_str())
+                        .is_none_or(str::is_empty)
+                {
+                    table.decor_mut().set_prefix("\n");
+                }
+                base.entry_format(key).or_insert(new_item);
+            }
+        }
+    }
+}
+
+/// Clears the positions the tables of `item` had in their own document, so that they are
+/// written after the tables of the document they are merged into
+fn clear_positions(item: &mut Item) {
+    let tables: Vec<&mut toml_edit::Table> = match item {
+        Item::Table(table) => vec![table],
+        Item::ArrayOfTables(array) => array.iter_mut().collect(),
+        _ => return,
+    };
+
+    for table in tables {
+        table.set_position(None);
+        for (_, item) in table.iter_mut() {
+            clear_positions(item);
+        }
+    }
+}
+
 pub fn get_configuration(context: &Context) -> toml::Table {
     let starship_config = StarshipConfig::initialize(context.get_config_path_os().as_deref());
 
@@ -499,6 +840,187 @@ mod tests {
         assert!(handle_toggle_configuration(&mut doc, "", "disabled").is_err());
     }
 
+    #[test]
+    fn test_get_config() {
+        let config = toml::toml! {
+            [directory]
+            style = "bold cyan"
+            truncation_length = 3
+        };
+        let config = toml::Value::Table(config);
+
+        assert_eq!(
+            handle_get_configuration(&config, "directory.style"),
+            Ok("bold cyan".to_string())
+        );
+        assert_eq!(
+            handle_get_configuration(&config, "directory.truncation_length"),
+            Ok("3".to_string())
+        );
+        assert!(handle_get_configuration(&config, "directory.unknown").is_err());
+    }
+
+    #[test]
+    fn test_unset_config() {
+        let mut doc = create_doc();
+
+        handle_unset_configuration(&mut doc, "status.disabled").unwrap();
+
+        let new_config = concat!(
+            " # comment\n",
+            "  [status] # comment\n",
+            "# comment\n",
+            "\n"
+        );
+        assert_eq!(doc.to_string(), new_config);
+        assert!(handle_unset_configuration(&mut doc, "status.disabled").is_err());
+        assert!(handle_unset_configuration(&mut doc, "character.format").is_err());
+    }
+
+    #[test]
+    fn test_set_config_validation() {
+        let doc = create_doc();
+
+        let mut valid_doc = doc.clone();
+        handle_update_configuration(&mut valid_doc, "directory.truncation_length", "5").unwrap();
+        assert!(validate_edit(&doc, &valid_doc).is_ok());
+
+        let mut narrow_doc = doc.clone();
+        handle_update_configuration(&mut narrow_doc, "character.format_narrow", "x").unwrap();
+        assert!(validate_edit(&doc, &narrow_doc).is_ok());
+
+        let mut wrong_type_doc = doc.clone();
+        handle_update_configuration(&mut wrong_type_doc, "directory.truncation_length", "five")
+            .unwrap();
+        assert!(validate_edit(&doc, &wrong_type_doc).is_err());
+
+        let mut unknown_key_doc = doc.clone();
+        handle_update_configuration(&mut unknown_key_doc, "directory.truncation_lenght", "5")
+            .unwrap();
+        assert!(validate_edit(&doc, &unknown_key_doc).is_err());
+    }
+
+    #[test]
+    fn test_migrate_configuration() {
+        let mut doc = concat!(
+            "# my prompt\n",
+            "[character]\n",
+            "# vim mode\n",
+            "vicmd_symbol = '[V](green)' # comment\n",
+            "\n",
+            "[custom.foo]\n",
+            "files = ['foo.toml']\n",
+            "extensions = ['foo']\n",
+        )
+        .parse::<DocumentMut>()
+        .unwrap();
+
+        let changes = handle_migrate_configuration(&mut doc);
+
+        assert_eq!(
+            changes,
+            vec![
+                "Renamed `character.vicmd_symbol` to `character.vimcmd_symbol`",
+                "Renamed `custom.foo.files` to `custom.foo.detect_files`",
+                "Renamed `custom.foo.extensions` to `custom.foo.detect_extensions`",
+            ]
+        );
+        assert_eq!(
+            doc.to_string(),
+            concat!(
+                "# my prompt\n",
+                "[character]\n",
+                "# vim mode\n",
+                "vimcmd_symbol = '[V](green)' # comment\n",
+                "\n",
+                "[custom.foo]\n",
+                "detect_files = ['foo.toml']\n",
+                "detect_extensions = ['foo']\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_migrate_configuration_keeps_existing_option() {
+        let mut doc = "[character]\nvicmd_symbol = '<'\nvimcmd_symbol = 'V'\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+
+        let changes = handle_migrate_configuration(&mut doc);
+
+        assert_eq!(
+            changes,
+            vec!["Skipped `character.vicmd_symbol`: `character.vimcmd_symbol` is already set"]
+        );
+        assert_eq!(
+            doc.to_string(),
+            "[character]\nvicmd_symbol = '<'\nvimcmd_symbol = 'V'\n"
+        );
+    }
+
+    #[test]
+    fn test_migrate_configuration_up_to_date() {
+        let mut doc = create_doc();
+        assert!(handle_migrate_configuration(&mut doc).is_empty());
+    }
+
+    #[test]
+    fn test_merge_edits() {
+        let mut doc = concat!(
+            "# my prompt\n",
+            "format = '$all'\n",
+            "\n",
+            "[directory] # comment\n",
+            "truncation_length = 5\n",
+            "style = 'red'\n",
+        )
+        .parse::<DocumentMut>()
+        .unwrap();
+        let preset = concat!(
+            "[aws]\n",
+            "symbol = 'aws '\n",
+            "\n",
+            "[directory]\n",
+            "style = 'blue'\n",
+        )
+        .parse::<DocumentMut>()
+        .unwrap();
+
+        merge_edits(doc.as_table_mut(), preset.as_table());
+
+        assert_eq!(
+            doc.to_string(),
+            concat!(
+                "# my prompt\n",
+                "format = '$all'\n",
+                "\n",
+                "[directory] # comment\n",
+                "truncation_length = 5\n",
+                "style = 'blue'\n",
+                "\n",
+                "[aws]\n",
+                "symbol = 'aws '\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_trust_path() {
+        let mut doc = create_doc();
+
+        handle_trust_path(&mut doc, "/home/user/monorepo").unwrap();
+        handle_trust_path(&mut doc, "/home/user/work").unwrap();
+        handle_trust_path(&mut doc, "/home/user/monorepo").unwrap();
+
+        let trusted_paths: Vec<_> = doc["trusted_paths"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(toml_edit::Value::as_str)
+            .collect();
+        assert_eq!(trusted_paths, ["/home/user/monorepo", "/home/user/work"]);
+    }
+
     #[test]
     fn test_update_config_wrong_type() {
         let mut doc = create_doc();
@@ -1,19 +1,23 @@
-use crate::config::{ModuleConfig, StarshipConfig};
+use crate::cache::{self, VersionCache};
+use crate::config::{CONFIG_OVERRIDE_PREFIX, ModuleConfig, StarshipConfig};
 use crate::configs::StarshipRootConfig;
 use crate::module::Module;
+use crate::segment::Segment;
+use crate::utils::background::{self, Background};
+use crate::utils::prompt_budget::PromptBudget;
+use crate::utils::timing::{self, Step};
 use crate::utils::{CommandOutput, PathExt, create_command, exec_timeout, read_file};
 
 use crate::modules;
 use crate::utils;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use gix::{
     Repository, ThreadSafeRepository,
     sec::{self as git_sec, trust::DefaultForLevel},
     state as git_state,
 };
-#[cfg(test)]
-use std::collections::HashMap;
-use std::collections::HashSet;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::fmt::Debug;
@@ -25,7 +29,7 @@ use std::str::FromStr;
 use std::string::String;
 use std::sync::{Arc, OnceLock, mpsc};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use terminal_size::terminal_size;
 
 pub use crate::utils::env::Env;
@@ -40,6 +44,10 @@ pub struct Context<'a> {
     /// The deserialized configuration map from the user's `starship.toml` file.
     pub config: StarshipConfig,
 
+    /// The configuration map in which `format_narrow` replaces `format` of the modules,
+    /// if any module defines one.
+    narrow_config: Option<StarshipConfig>,
+
     /// The current working directory that starship is being called in.
     pub current_dir: PathBuf,
 
@@ -51,12 +59,25 @@ pub struct Context<'a> {
     /// A struct containing directory contents in a lookup-optimized format.
     dir_contents: OnceLock<Result<DirContents, std::io::Error>>,
 
+    /// The detected brightness of the terminal background
+    background: OnceLock<Option<Background>>,
+
     /// Properties to provide to modules.
     pub properties: Properties,
 
     /// Private field to store Git information for modules who need it
     repo: OnceLock<Result<Repo, Box<gix::discover::Error>>>,
 
+    /// The cached output of version probes, if enabled
+    version_cache: OnceLock<Option<Arc<VersionCache>>>,
+
+    /// The time budget of the prompt, if `prompt_timeout_strategy` is `degrade`
+    prompt_budget: OnceLock<Option<PromptBudget>>,
+
+    /// The output of the modules, kept across the prompts rendered with the context once
+    /// `keep_module_output` is called
+    module_output: Option<Mutex<HashMap<String, Vec<Segment>>>>,
+
     /// The shell the user is assumed to be running
     pub shell: Shell,
 
@@ -135,7 +156,7 @@ impl<'a> Context<'a> {
         logical_path: PathBuf,
         env: Env<'a>,
     ) -> Self {
-        let config = StarshipConfig::initialize(get_config_path_os(&env).as_deref());
+        let mut config = StarshipConfig::initialize(get_config_path_os(&env).as_deref());
 
         // If the vector is zero-length, we should pretend that we didn't get a
         // pipestatus at all (since this is the input `--pipestatus=""`)
@@ -162,20 +183,31 @@ impl<'a> Context<'a> {
         let current_dir = dunce::canonicalize(&current_dir).unwrap_or(current_dir);
         let logical_dir = logical_path;
 
+        config.merge_project_config(&current_dir);
+        config.apply_env_overrides(env.get_env_with_prefix(CONFIG_OVERRIDE_PREFIX));
+        let narrow_config = config.split_narrow_formats();
+
         let root_config = config
             .config
             .as_ref()
-            .map_or_else(StarshipRootConfig::default, StarshipRootConfig::load);
+            .map_or_else(StarshipRootConfig::default, StarshipRootConfig::load)
+            .with_format_lines()
+            .with_inherited_palettes();
 
         let width = properties.terminal_width;
 
         Self {
             config,
+            narrow_config,
             properties,
             current_dir,
             logical_dir,
             dir_contents: OnceLock::new(),
+            background: OnceLock::new(),
             repo: OnceLock::new(),
+            version_cache: OnceLock::new(),
+            prompt_budget: OnceLock::new(),
+            module_output: None,
             shell,
             target,
             width,
@@ -194,11 +226,19 @@ impl<'a> Context<'a> {
 
     /// Sets the context config, overwriting the existing config
     pub fn set_config(mut self, config: toml::Table) -> Self {
-        self.root_config = StarshipRootConfig::load(&config);
+        self.update_config(config);
+        self
+    }
+
+    /// Replaces the context config in place, e.g. after it was edited
+    pub fn update_config(&mut self, config: toml::Table) {
+        self.root_config = StarshipRootConfig::load(&config)
+            .with_format_lines()
+            .with_inherited_palettes();
         self.config = StarshipConfig {
             config: Some(config),
         };
-        self
+        self.narrow_config = self.config.split_narrow_formats();
     }
 
     /// Sets the Claude Code session data
@@ -233,14 +273,54 @@ impl<'a> Context<'a> {
         dir
     }
 
+    /// Returns true if the terminal is narrower than the configured `width_threshold`.
+    pub fn is_narrow(&self) -> bool {
+        self.width > 0 && self.width < self.root_config.width_threshold
+    }
+
+    /// Returns the config modules should be loaded from, taking `format_narrow` into account
+    /// in narrow terminals.
+    pub fn module_config_source(&self) -> &StarshipConfig {
+        match &self.narrow_config {
+            Some(narrow_config) if self.is_narrow() => narrow_config,
+            _ => &self.config,
+        }
+    }
+
+    /// Returns the name of the palette to use, which is `palette_light` or `palette_dark`
+    /// if the terminal background is detected to be light or dark, or `palette` otherwise.
+    pub fn palette_name(&self) -> Option<&str> {
+        let config = &self.root_config;
+        if config.palette_light.is_none() && config.palette_dark.is_none() {
+            return config.palette.as_deref();
+        }
+
+        let themed_palette = match self.background.get_or_init(|| background::detect(self)) {
+            Some(Background::Light) => config.palette_light.as_deref(),
+            Some(Background::Dark) => config.palette_dark.as_deref(),
+            None => None,
+        };
+        themed_palette.or(config.palette.as_deref())
+    }
+
     /// Create a new module
     pub fn new_module(&self, name: &str) -> Module<'_> {
-        let config = self.config.get_module_config(name);
+        let config = self.module_config_source().get_module_config(name);
         let desc = modules::description(name);
 
         Module::new(name, desc, config)
     }
 
+    /// Whether the prompt is printed without styles, because `force_plain` is set or the
+    /// terminal doesn't want them
+    pub fn is_plain(&self) -> bool {
+        self.root_config.force_plain
+            || self
+                .get_env("NO_COLOR")
+                .is_some_and(|value| !value.is_empty())
+            || self.get_env("TERM").is_some_and(|term| term == "dumb")
+    }
+
     /// Check if `disabled` option of the module is true in configuration file.
     pub fn is_module_disabled_in_config(&self, name: &str) -> bool {
         let config = self.config.get_module_config(name);
@@ -312,6 +392,22 @@ impl<'a> Context<'a> {
         })
     }
 
+    /// The current directory and its parents, up to the root of the repository or, outside of
+    /// one, the home directory. Only the current directory is returned when it is in neither.
+    pub fn project_ancestors(&self) -> impl Iterator<Item = &Path> {
+        let root = match self.get_repo() {
+            Ok(repo) => repo.workdir.clone(),
+            Err(_) => self.get_home(),
+        }
+        .filter(|root| self.current_dir.starts_with(root));
+        let mut reached_root = false;
+        self.current_dir.ancestors().take_while(move |dir| {
+            let take = !reached_root;
+            reached_root = root.as_deref().is_none_or(|root| *dir == root);
+            take
+        })
+    }
+
     /// Begins an ancestor scan at the current directory, see [`ScanAncestors`] for available
     /// methods.
     pub fn begin_ancestor_scan(&'a self) -> ScanAncestors<'a> {
@@ -322,10 +418,57 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// The time budget of the prompt, started when it is first requested
+    pub fn prompt_budget(&self) -> Option<&PromptBudget> {
+        self.prompt_budget
+            .get_or_init(|| PromptBudget::new(self))
+            .as_ref()
+    }
+
+    /// Keeps the output of the modules across the prompts rendered with the context, so that
+    /// only the modules forgotten with `forget_module_output` are rendered again
+    pub fn keep_module_output(&mut self) {
+        self.module_output = Some(Mutex::new(HashMap::new()));
+    }
+
+    /// The output of a module kept from a previous prompt
+    pub fn kept_module_output(&self, module: &str) -> Option<Vec<Segment>> {
+        self.module_output.as_ref()?.lock().get(module).cloned()
+    }
+
+    /// Keeps the output of a module for the next prompts, if the output of modules is kept
+    pub fn record_module_output(&self, module: &str, segments: &[Segment]) {
+        if let Some(output) = &self.module_output {
+            output.lock().insert(module.to_string(), segments.to_vec());
+        }
+    }
+
+    /// Forgets the kept output of a module, and of its group such as `custom` for `custom.foo`
+    pub fn forget_module_output(&self, module: &str) {
+        if let Some(output) = &self.module_output {
+            let mut output = output.lock();
+            output.remove(module);
+            if let Some((group, _)) = module.split_once('.') {
+                output.remove(group);
+            }
+        }
+    }
+
+    /// The command line of the last command, which the init scripts pass in
+    /// `STARSHIP_LAST_COMMAND` rather than with `--last-command`, where other processes can see it
+    pub fn last_command(&self) -> Option<String> {
+        self.properties
+            .last_command
+            .clone()
+            .or_else(|| self.get_env("STARSHIP_LAST_COMMAND"))
+            .filter(|command| !command.is_empty())
+    }
+
     /// Will lazily get repo root and branch when a module requests it.
     pub fn get_repo(&self) -> Result<&Repo, &gix::discover::Error> {
         self.repo
             .get_or_init(|| -> Result<Repo, Box<gix::discover::Error>> {
+                let _step = timing::step(Step::Detection);
                 // custom open options
                 let mut git_open_opts_map =
                     git_sec::trust::Mapping::<gix::open::Options>::default();
@@ -354,7 +497,9 @@ impl<'a> Context<'a> {
                         ..gix::open::Permissions::default_for_level(git_sec::Trust::Full)
                     });
 
-                let shared_repo =
+                let shared_repo = if let Some(repo) = open_repo(&self.current_dir) {
+                    repo
+                } else {
                     match ThreadSafeRepository::discover_with_environment_overrides_opts(
                         &self.current_dir,
                         gix::discover::upwards::Options {
@@ -363,12 +508,16 @@ impl<'a> Context<'a> {
                         },
                         git_open_opts_map,
                     ) {
-                        Ok(repo) => repo,
+                        Ok(repo) => {
+                            keep_repo_open(&self.current_dir, &repo);
+                            repo
+                        }
                         Err(e) => {
                             log::debug!("Failed to find git repo: {e}");
                             return Err(Box::new(e));
                         }
-                    };
+                    }
+                };
 
                 let repository = shared_repo.to_thread_local();
                 log::trace!(
@@ -403,6 +552,7 @@ impl<'a> Context<'a> {
     pub fn dir_contents(&self) -> Result<&DirContents, &std::io::Error> {
         self.dir_contents
             .get_or_init(|| {
+                let _step = timing::step(Step::Detection);
                 let timeout = self.root_config.scan_timeout;
                 DirContents::from_path_with_timeout(
                     &self.current_dir,
@@ -427,6 +577,9 @@ impl<'a> Context<'a> {
             "nu" => Shell::Nu,
             "xonsh" => Shell::Xonsh,
             "cmd" => Shell::Cmd,
+            "osh" => Shell::Osh,
+            "ysh" => Shell::Ysh,
+            "murex" => Shell::Murex,
             _ => Shell::Unknown,
         }
     }
@@ -461,10 +614,33 @@ impl<'a> Context<'a> {
         }
         let mut cmd = create_command(cmd).ok()?;
         cmd.args(args).current_dir(&self.current_dir);
-        exec_timeout(
-            &mut cmd,
-            Duration::from_millis(self.root_config.command_timeout),
-        )
+
+        let version_cache = if cache::is_version_probe(args) {
+            self.version_cache
+                .get_or_init(|| VersionCache::new(self))
+                .as_ref()
+        } else {
+            None
+        };
+        if let Some(output) = version_cache.and_then(|cache| cache.get(&cmd)) {
+            return Some(output);
+        }
+
+        let output = exec_timeout(&mut cmd, self.command_time_limit());
+        if let (Some(cache), Some(output)) = (version_cache, &output) {
+            cache.insert(&cmd, output);
+        }
+        output
+    }
+
+    /// How long a command may run: `command_timeout`, or the time left to compute the prompt
+    /// once its budget was started
+    pub fn command_time_limit(&self) -> Duration {
+        let timeout = Duration::from_millis(self.root_config.command_timeout);
+        match self.prompt_budget.get() {
+            Some(Some(budget)) => timeout.min(budget.remaining()),
+            _ => timeout,
+        }
     }
 
     /// Attempt to execute several commands with `exec_cmd`, return the results of the first that works
@@ -489,6 +665,28 @@ impl<'a> Context<'a> {
     pub fn get_config_path_os(&self) -> Option<OsString> {
         get_config_path_os(&self.env)
     }
+
+    /// Whether the resource was computed, by `prepare` or a module using it
+    #[cfg(test)]
+    pub fn is_prepared(&self, resource: Resource) -> bool {
+        match resource {
+            Resource::Repo => self.repo.get().is_some(),
+            Resource::DirContents => self.dir_contents.get().is_some(),
+        }
+    }
+
+    /// Computes a resource shared between modules, so the modules depending on it don't have to
+    /// wait for it
+    pub fn prepare(&self, resource: Resource) {
+        match resource {
+            Resource::Repo => {
+                let _ = self.get_repo();
+            }
+            Resource::DirContents => {
+                let _ = self.dir_contents();
+            }
+        }
+    }
 }
 
 impl Default for Context<'_> {
@@ -497,6 +695,15 @@ impl Default for Context<'_> {
     }
 }
 
+/// An expensive resource computed once and shared between the modules of a prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    /// The repository of the current directory, see `Context::get_repo`
+    Repo,
+    /// The files of the current directory, see `Context::dir_contents`
+    DirContents,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Detected {
     /// `detect_env_vars` was empty.
@@ -826,6 +1033,7 @@ impl<'a> ScanDir<'a> {
     /// based on the current `PathBuf` check to see
     /// if any of this criteria match or exist and returning a boolean
     pub fn is_match(&self) -> bool {
+        let _step = timing::step(Step::Detection);
         // if there exists a file with a file/folder/ext we've said we don't want,
         // fail the match straight away
         self.dir_contents.has_no_negative_extension(self.extensions)
@@ -865,6 +1073,7 @@ impl<'a> ScanAncestors<'a> {
     ///
     /// The scan does not cross device boundaries.
     pub fn scan(&self) -> Option<PathBuf> {
+        let _step = timing::step(Step::Detection);
         let path = self.path;
         let initial_device_id = path.device_id();
 
@@ -915,6 +1124,49 @@ impl<'a> ScanAncestors<'a> {
     }
 }
 
+/// The git repositories kept open by long-running processes, by the directory they were
+/// discovered from, with the modification time of their config when they were opened
+static OPEN_REPOS: OnceLock<Mutex<HashMap<PathBuf, (ThreadSafeRepository, SystemTime)>>> =
+    OnceLock::new();
+
+/// The modification time of the config of the repository, which changes the options it is
+/// opened with
+fn repo_config_modified(repo: &ThreadSafeRepository) -> Option<SystemTime> {
+    let common_dir = repo.refs.common_dir().unwrap_or_else(|| repo.git_dir());
+    fs::metadata(common_dir.join("config"))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// The repository discovered from `dir` for a previous prompt, if caches are kept in memory
+/// and its config hasn't changed since
+fn open_repo(dir: &Path) -> Option<ThreadSafeRepository> {
+    // The environment can point to another repository for each prompt
+    if !cache::in_memory() || env::var_os("GIT_DIR").is_some() {
+        return None;
+    }
+
+    let repos = OPEN_REPOS.get()?.lock();
+    let (repo, modified) = repos.get(dir)?;
+    (repo_config_modified(repo) == Some(*modified)).then(|| repo.clone())
+}
+
+/// Keeps the repository discovered from `dir` open for the next prompts, if caches are kept in
+/// memory
+fn keep_repo_open(dir: &Path, repo: &ThreadSafeRepository) {
+    if !cache::in_memory() || env::var_os("GIT_DIR").is_some() {
+        return;
+    }
+    let Some(modified) = repo_config_modified(repo) else {
+        return;
+    };
+
+    OPEN_REPOS
+        .get_or_init(Default::default)
+        .lock()
+        .insert(dir.to_owned(), (repo.clone(), modified));
+}
+
 fn get_current_branch(repository: &Repository) -> Option<gix::refs::FullName> {
     repository.head_name().ok()?
 }
@@ -948,6 +1200,9 @@ pub enum Shell {
     Nu,
     Xonsh,
     Cmd,
+    Osh,
+    Ysh,
+    Murex,
     Unknown,
 }
 
@@ -988,9 +1243,20 @@ pub struct Properties {
     /// The number of currently running jobs
     #[clap(short, long, default_value_t, value_parser=parse_i64)]
     pub jobs: i64,
+    /// The commands of the currently running jobs, separated by newlines or given repeatedly
+    #[clap(long, value_delimiter = '\n')]
+    pub job_names: Vec<String>,
     /// The current value of SHLVL, for shells that mis-handle it in $()
     #[clap(long, value_parser=parse_i64)]
     pub shlvl: Option<i64>,
+    /// The phase of a prompt rendered in two phases: 1 leaves out the `async_modules`, which
+    /// are rendered on their own in phase 2
+    #[clap(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub phase: Option<u8>,
+    /// The command line of the last command, for the `ignore_commands` and notification rules of
+    /// `cmd_duration`
+    #[clap(long, visible_alias = "cmd")]
+    pub last_command: Option<String>,
 }
 
 impl Default for Properties {
@@ -1004,11 +1270,56 @@ impl Default for Properties {
             cmd_duration: None,
             keymap: "viins".to_string(),
             jobs: 0,
+            job_names: Vec::new(),
             shlvl: None,
+            phase: None,
+            last_command: None,
         }
     }
 }
 
+/// The arguments of `starship prompt`
+#[derive(Parser, Debug)]
+pub struct PromptArgs {
+    /// Print the right prompt (instead of the standard left prompt)
+    #[clap(long)]
+    pub right: bool,
+    /// Print the prompt with the specified profile name (instead of the standard left prompt)
+    #[clap(long, conflicts_with = "right")]
+    pub profile: Option<String>,
+    /// Print the continuation prompt (instead of the standard left prompt)
+    #[clap(long, conflicts_with = "right", conflicts_with = "profile")]
+    pub continuation: bool,
+    /// Where the prompt is drawn, which decides how it is styled
+    #[clap(long, value_enum, default_value_t)]
+    pub target: OutputTarget,
+    #[clap(flatten)]
+    pub properties: Properties,
+}
+
+impl PromptArgs {
+    /// Returns the properties and the prompt to print
+    pub fn into_parts(self) -> (Properties, Target) {
+        let target = match (self.right, self.profile, self.continuation) {
+            (true, _, _) => Target::Right,
+            (_, Some(profile_name), _) => Target::Profile(profile_name),
+            (_, _, true) => Target::Continuation,
+            (_, _, _) => Target::Main,
+        };
+        (self.properties, target)
+    }
+}
+
+/// Where the output of `starship prompt` is drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputTarget {
+    /// A shell prompt, styled with ANSI escape sequences
+    #[default]
+    Shell,
+    /// A tmux status line, styled with `#[...]` tmux style directives
+    Tmux,
+}
+
 /// Parse String, but treat empty strings as `None`
 fn parse_trim<F: FromStr>(value: &str) -> Option<Result<F, F::Err>> {
     let value = value.trim();
@@ -1048,6 +1359,35 @@ mod tests {
         Ok(dir)
     }
 
+    #[test]
+    fn palette_for_background() {
+        let config = toml::toml! {
+            palette = "default"
+            palette_light = "light"
+            palette_dark = "dark"
+        };
+
+        let mut context = default_context().set_config(config.clone());
+        context.env.insert("STARSHIP_BACKGROUND", "light".into());
+        assert_eq!(context.palette_name(), Some("light"));
+
+        let mut context = default_context().set_config(config.clone());
+        context.env.insert("COLORFGBG", "15;0".into());
+        assert_eq!(context.palette_name(), Some("dark"));
+
+        // As set by the init scripts, from the output of `starship background`
+        let mut context = default_context().set_config(config.clone());
+        context.env.insert("STARSHIP_BACKGROUND", "dark\n".into());
+        assert_eq!(context.palette_name(), Some("dark"));
+
+        let mut context = default_context().set_config(config.clone());
+        context.env.insert("STARSHIP_BACKGROUND", String::new());
+        assert_eq!(context.palette_name(), Some("default"));
+
+        let context = default_context().set_config(config);
+        assert_eq!(context.palette_name(), Some("default"));
+    }
+
     #[test]
     fn test_scan_dir_no_symlinks() -> Result<(), Box<dyn std::error::Error>> {
         #[cfg(not(target_os = "windows"))]
@@ -1337,6 +1677,27 @@ mod tests {
         assert_eq!(expected_logical_dir, context.logical_dir);
     }
 
+    #[test]
+    fn project_ancestors_stop_at_home() -> io::Result<()> {
+        let dir = testdir(&["home/project/src/main.rs"])?;
+        let home = dir.path().join("home");
+        let src = home.join("project/src");
+        let mut context = default_context();
+        context
+            .env
+            .insert("HOME", home.to_string_lossy().to_string());
+
+        context.current_dir.clone_from(&src);
+        let ancestors: Vec<&Path> = context.project_ancestors().collect();
+        assert_eq!(ancestors, vec![&src, &home.join("project"), &home]);
+
+        // Outside of the home directory, only the current directory is searched
+        context.current_dir = dir.path().to_path_buf();
+        let ancestors: Vec<&Path> = context.project_ancestors().collect();
+        assert_eq!(ancestors, vec![dir.path()]);
+        dir.close()
+    }
+
     #[test]
     fn set_config_method_overwrites_constructor() {
         let context = default_context();
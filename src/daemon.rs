@@ -0,0 +1,595 @@
+use std::env;
+use std::io;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::Parser;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+use crate::config;
+use crate::context::{Context, OutputTarget, PromptArgs};
+use crate::print;
+
+/// How long `starship prompt --via-daemon` waits for the daemon before rendering the prompt
+/// itself
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long the daemon waits for a request, or for the shell to read its prompt
+#[cfg(unix)]
+const SERVER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Held while rendering a prompt, which replaces the environment and working directory of the
+/// daemon by those of the shell requesting it
+static RENDER_LOCK: Mutex<()> = Mutex::new(());
+
+/// A prompt to render, sent by `starship prompt --via-daemon` to `starship daemon`
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Request {
+    /// The arguments of `starship prompt`
+    args: Vec<String>,
+    /// The working directory of the shell
+    dir: PathBuf,
+    /// The environment variables of the shell
+    env: Vec<(String, String)>,
+}
+
+impl Request {
+    /// The prompt requested by the arguments, directory and environment of this process
+    fn current() -> Self {
+        Self {
+            args: env::args_os()
+                .skip(2)
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .filter(|arg| arg != "--via-daemon")
+                .collect(),
+            dir: env::current_dir().unwrap_or_default(),
+            env: env::vars_os()
+                .filter_map(|(key, value)| {
+                    Some((key.into_string().ok()?, value.into_string().ok()?))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The socket the daemon listens on, `STARSHIP_DAEMON_SOCKET` or by default `daemon.sock` in the
+/// cache directory, or the named pipe `\\.\pipe\starship-daemon-<user>` on Windows
+fn socket_path() -> PathBuf {
+    env::var_os("STARSHIP_DAEMON_SOCKET")
+        .map(PathBuf::from)
+        .unwrap_or_else(default_socket_path)
+}
+
+#[cfg(not(windows))]
+fn default_socket_path() -> PathBuf {
+    crate::logger::get_log_dir().join("daemon.sock")
+}
+
+#[cfg(windows)]
+fn default_socket_path() -> PathBuf {
+    let user = env::var("USERNAME").unwrap_or_default();
+    PathBuf::from(format!(r"\\.\pipe\starship-daemon-{user}"))
+}
+
+/// Asks the daemon for the prompt of this process, returning `None` if it isn't running so the
+/// prompt can be rendered in-process instead
+pub fn request_prompt() -> Option<String> {
+    let path = socket_path();
+    match send(&path, &Request::current()) {
+        Ok(prompt) if !prompt.is_empty() => Some(prompt),
+        Ok(_) => {
+            log::debug!("The daemon on {path:?} returned an empty prompt");
+            None
+        }
+        Err(error) => {
+            log::debug!("Unable to reach the daemon on {path:?}: {error}");
+            None
+        }
+    }
+}
+
+/// Renders the prompts requested on the socket until killed, keeping the parsed config files,
+/// opened git repositories and version cache in memory
+pub fn run() {
+    config::enable_parse_cache();
+    cache::keep_in_memory();
+    serve(&socket_path());
+}
+
+/// Renders a prompt in the directory and environment of the shell requesting it. Requests are
+/// read and answered concurrently, but prompts are rendered one at a time, so no prompt sees the
+/// environment of another.
+fn render(request: Request) -> String {
+    let args = iter::once("starship prompt".to_string()).chain(request.args);
+    let args = match PromptArgs::try_parse_from(args) {
+        Ok(args) => args,
+        Err(error) => {
+            log::warn!("Invalid prompt arguments: {error}");
+            return String::new();
+        }
+    };
+
+    let _lock = RENDER_LOCK.lock();
+    set_environment(&request.env, &request.dir);
+    let output = args.target;
+    let (properties, target) = args.into_parts();
+    std::panic::catch_unwind(|| {
+        let context = Context::new(properties, target);
+        match output {
+            OutputTarget::Shell => print::get_prompt(&context),
+            OutputTarget::Tmux => print::get_tmux_prompt(context),
+        }
+    })
+    .unwrap_or_else(|_| {
+        log::error!("Rendering the prompt panicked");
+        String::new()
+    })
+}
+
+/// Replaces the environment and working directory of the daemon by those of the shell
+#[allow(clippy::disallowed_methods)]
+fn set_environment(vars: &[(String, String)], dir: &Path) {
+    let stale: Vec<_> = env::vars_os()
+        .map(|(key, _)| key)
+        .filter(|key| !vars.iter().any(|(name, _)| key == name.as_str()))
+        .collect();
+
+    // SAFETY: Prompts are rendered one at a time under `RENDER_LOCK`, and the threads rendering
+    // the previous prompt are done with it, so no other thread reads the environment meanwhile.
+    unsafe {
+        for key in stale {
+            env::remove_var(key);
+        }
+        for (key, value) in vars {
+            env::set_var(key, value);
+        }
+    }
+
+    if let Err(error) = env::set_current_dir(dir) {
+        log::warn!("Unable to change to directory {dir:?}: {error}");
+    }
+}
+
+/// Reads a request, which ends with its JSON value rather than the end of the stream, as the
+/// client can't close its end of a named pipe while waiting for the prompt
+fn read_request(stream: impl io::Read) -> io::Result<Request> {
+    serde_json::Deserializer::from_reader(stream)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| {
+            Err(serde_json::Error::io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "empty prompt request",
+            )))
+        })
+        .map_err(io::Error::from)
+}
+
+#[cfg(unix)]
+fn send(path: &Path, request: &Request) -> io::Result<String> {
+    use std::io::Read;
+    use std::net::Shutdown;
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(path)?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    serde_json::to_writer(&mut stream, request)?;
+    stream.shutdown(Shutdown::Write)?;
+
+    let mut prompt = String::new();
+    stream.read_to_string(&mut prompt)?;
+    Ok(prompt)
+}
+
+#[cfg(unix)]
+fn serve(path: &Path) {
+    use std::os::unix::net::UnixStream;
+    use std::process;
+
+    if UnixStream::connect(path).is_ok() {
+        eprintln!("starship daemon is already running on {}", path.display());
+        process::exit(1);
+    }
+
+    let listener = match bind(path) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Unable to listen on {}: {error}", path.display());
+            process::exit(1);
+        }
+    };
+
+    println!("Listening on {}", path.display());
+    accept(&listener, render);
+}
+
+/// Listens on the socket at `path`, which only the user may connect to, as the prompts run in
+/// their environment
+#[cfg(unix)]
+fn bind(path: &Path) -> io::Result<std::os::unix::net::UnixListener> {
+    use nix::sys::stat::{Mode, umask};
+    use std::fs;
+    use std::os::unix::net::UnixListener;
+
+    // Remove the socket of a daemon that was killed
+    let _ = fs::remove_file(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // The socket is created with the permissions left by the umask, so it is never accessible
+    // to other users, even until its permissions could be changed
+    let umask_before = umask(Mode::from_bits_truncate(0o077));
+    let listener = UnixListener::bind(path);
+    umask(umask_before);
+    listener
+}
+
+/// Answers each request on its own thread, so a shell that is slow to send its request or to
+/// read its prompt doesn't hold up the others
+#[cfg(unix)]
+fn accept(
+    listener: &std::os::unix::net::UnixListener,
+    render: impl Fn(Request) -> String + Copy + Send + 'static,
+) {
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                log::warn!("Unable to accept a prompt request: {error}");
+                continue;
+            }
+        };
+        std::thread::spawn(move || {
+            if let Err(error) = handle(&mut stream, render) {
+                log::warn!("Unable to answer a prompt request: {error}");
+            }
+        });
+    }
+}
+
+#[cfg(unix)]
+fn handle(
+    stream: &mut std::os::unix::net::UnixStream,
+    render: impl FnOnce(Request) -> String,
+) -> io::Result<()> {
+    use std::io::Write;
+
+    stream.set_read_timeout(Some(SERVER_TIMEOUT))?;
+    stream.set_write_timeout(Some(SERVER_TIMEOUT))?;
+    let request = read_request(&mut *stream)?;
+    stream.write_all(render(request).as_bytes())
+}
+
+#[cfg(windows)]
+fn send(path: &Path, request: &Request) -> io::Result<String> {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::sync::mpsc;
+
+    let mut pipe = OpenOptions::new().read(true).write(true).open(path)?;
+    let request = serde_json::to_vec(request)?;
+
+    // Named pipes opened as files have no timeouts, so the daemon is waited for on another
+    // thread, which is left behind if it doesn't answer in time
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let prompt = pipe.write_all(&request).and_then(|()| {
+            let mut prompt = String::new();
+            pipe.read_to_string(&mut prompt).map(|_| prompt)
+        });
+        let _ = sender.send(prompt);
+    });
+    receiver.recv_timeout(CLIENT_TIMEOUT).unwrap_or_else(|_| {
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "the daemon didn't answer in time",
+        ))
+    })
+}
+
+#[cfg(windows)]
+fn serve(path: &Path) {
+    use std::process;
+
+    // Creating the first instance of the pipe fails if another daemon already created it
+    let pipe = match create_pipe(path, true) {
+        Ok(pipe) => pipe,
+        Err(error) => {
+            eprintln!(
+                "Unable to listen on {}, is starship daemon already running? {error}",
+                path.display()
+            );
+            process::exit(1);
+        }
+    };
+
+    println!("Listening on {}", path.display());
+    accept(pipe, path, render);
+}
+
+/// Creates an instance of the named pipe at `path`, which only the user may connect to, as the
+/// prompts run in their environment. Each client connects to an instance of its own.
+#[cfg(windows)]
+fn create_pipe(path: &Path, first: bool) -> io::Result<std::fs::File> {
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::{FromRawHandle, OwnedHandle};
+    use windows::Win32::Foundation::{HLOCAL, LocalFree};
+    use windows::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+    };
+    use windows::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+    use windows::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX};
+    use windows::Win32::System::Pipes::{
+        CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE,
+        PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+    use windows::core::{PCWSTR, w};
+
+    // Only grants access to the owner of the pipe, the user running the daemon
+    let mut descriptor = PSECURITY_DESCRIPTOR(std::ptr::null_mut());
+    unsafe {
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            w!("D:P(A;;GA;;;OW)"),
+            SDDL_REVISION_1,
+            &raw mut descriptor,
+            None,
+        )
+    }?;
+    let attributes = SECURITY_ATTRIBUTES {
+        nLength: u32::try_from(size_of::<SECURITY_ATTRIBUTES>()).unwrap_or_default(),
+        lpSecurityDescriptor: descriptor.0,
+        bInheritHandle: false.into(),
+    };
+
+    let name: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let open_mode = if first {
+        PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE
+    } else {
+        PIPE_ACCESS_DUPLEX
+    };
+    let handle = unsafe {
+        CreateNamedPipeW(
+            PCWSTR(name.as_ptr()),
+            open_mode,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_UNLIMITED_INSTANCES,
+            4096,
+            4096,
+            0,
+            Some(&raw const attributes),
+        )
+    };
+    let error = io::Error::last_os_error();
+    unsafe { LocalFree(Some(HLOCAL(descriptor.0))) };
+
+    if handle.is_invalid() {
+        return Err(error);
+    }
+    // SAFETY: The handle was just created and is owned by nothing else
+    Ok(std::fs::File::from(unsafe {
+        OwnedHandle::from_raw_handle(handle.0)
+    }))
+}
+
+/// Waits for a client to connect to the instance of the pipe
+#[cfg(windows)]
+fn connect(pipe: &std::fs::File) -> io::Result<()> {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::{ERROR_PIPE_CONNECTED, HANDLE};
+    use windows::Win32::System::Pipes::ConnectNamedPipe;
+
+    match unsafe { ConnectNamedPipe(HANDLE(pipe.as_raw_handle()), None) } {
+        // The client connected before it was waited for
+        Err(error) if error.code() == ERROR_PIPE_CONNECTED.to_hresult() => Ok(()),
+        result => result.map_err(io::Error::from),
+    }
+}
+
+/// Answers each request on its own thread, so a shell that is slow to send its request or to
+/// read its prompt doesn't hold up the others. Once a client connects to the instance of the
+/// pipe, the next client is waited for on a new instance.
+#[cfg(windows)]
+fn accept(
+    mut pipe: std::fs::File,
+    path: &Path,
+    render: impl Fn(Request) -> String + Copy + Send + 'static,
+) {
+    loop {
+        let connected = connect(&pipe);
+        let next = match create_pipe(path, false) {
+            Ok(next) => next,
+            Err(error) => {
+                log::error!("Unable to listen on {}: {error}", path.display());
+                return;
+            }
+        };
+        let mut stream = std::mem::replace(&mut pipe, next);
+
+        if let Err(error) = connected {
+            log::warn!("Unable to accept a prompt request: {error}");
+            continue;
+        }
+        std::thread::spawn(move || {
+            if let Err(error) = handle(&mut stream, render) {
+                log::warn!("Unable to answer a prompt request: {error}");
+            }
+        });
+    }
+}
+
+#[cfg(windows)]
+fn handle(stream: &mut std::fs::File, render: impl FnOnce(Request) -> String) -> io::Result<()> {
+    use std::io::Write;
+
+    let request = read_request(&mut *stream)?;
+    stream.write_all(render(request).as_bytes())?;
+    // Waits for the client to read the prompt before the pipe is closed
+    stream.sync_all()
+}
+
+#[cfg(not(any(unix, windows)))]
+fn send(_path: &Path, _request: &Request) -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "starship daemon is only supported on unix-like systems and Windows",
+    ))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn serve(_path: &Path) {
+    eprintln!("starship daemon is only supported on unix-like systems and Windows");
+    std::process::exit(1);
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::thread;
+
+    #[test]
+    fn request_round_trip() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("daemon.sock");
+        let listener = UnixListener::bind(&path)?;
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept()?;
+            handle(&mut stream, |request| {
+                format!("{} in {}", request.args.join(" "), request.dir.display())
+            })
+        });
+
+        let request = Request {
+            args: vec!["--right".to_string(), "--status=1".to_string()],
+            dir: PathBuf::from("/src"),
+            env: vec![("TERM".to_string(), "xterm".to_string())],
+        };
+        assert_eq!(send(&path, &request)?, "--right --status=1 in /src");
+        server.join().unwrap()?;
+        dir.close()
+    }
+
+    #[test]
+    fn stalled_request_does_not_block_others() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("daemon.sock");
+        let listener = bind(&path)?;
+        thread::spawn(move || accept(&listener, |request| request.args.join(" ")));
+
+        // Connects without ever sending a request
+        let _stalled = UnixStream::connect(&path)?;
+        let request = Request {
+            args: vec!["--right".to_string()],
+            dir: PathBuf::from("/"),
+            env: Vec::new(),
+        };
+        assert_eq!(send(&path, &request)?, "--right");
+        dir.close()
+    }
+
+    #[test]
+    fn socket_is_private() -> io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("daemon.sock");
+        let _listener = bind(&path)?;
+        let mode = std::fs::metadata(&path)?.permissions().mode();
+        assert_eq!(mode & 0o077, 0);
+        dir.close()
+    }
+
+    #[test]
+    fn request_ends_with_its_value() -> io::Result<()> {
+        let request = Request {
+            args: vec!["--right".to_string()],
+            dir: PathBuf::from("/"),
+            env: Vec::new(),
+        };
+        // The client of a named pipe keeps its end open to read the prompt
+        let mut input = serde_json::to_vec(&request)?;
+        input.extend_from_slice(b"not read");
+        assert_eq!(read_request(input.as_slice())?, request);
+        assert!(read_request([].as_slice()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let request = Request {
+            args: vec!["--right".to_string(), "--continuation".to_string()],
+            dir: PathBuf::from("/"),
+            env: Vec::new(),
+        };
+        assert_eq!(render(request), "");
+    }
+
+    #[test]
+    fn missing_daemon() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let request = Request {
+            args: Vec::new(),
+            dir: PathBuf::from("/"),
+            env: Vec::new(),
+        };
+        assert!(send(&dir.path().join("daemon.sock"), &request).is_err());
+        dir.close()
+    }
+}
+
+#[cfg(all(test, windows))]
+mod pipe_tests {
+    use super::*;
+    use std::thread;
+
+    fn pipe_path(name: &str) -> PathBuf {
+        PathBuf::from(format!(
+            r"\\.\pipe\starship-test-{name}-{}",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn request_round_trip() -> io::Result<()> {
+        let path = pipe_path("round-trip");
+        let pipe = create_pipe(&path, true)?;
+        let accept_path = path.clone();
+        thread::spawn(move || {
+            accept(pipe, &accept_path, |request| {
+                format!("{} in {}", request.args.join(" "), request.dir.display())
+            });
+        });
+
+        let request = Request {
+            args: vec!["--right".to_string(), "--status=1".to_string()],
+            dir: PathBuf::from(r"C:\src"),
+            env: vec![("TERM".to_string(), "xterm".to_string())],
+        };
+        assert_eq!(send(&path, &request)?, r"--right --status=1 in C:\src");
+        assert_eq!(send(&path, &request)?, r"--right --status=1 in C:\src");
+        Ok(())
+    }
+
+    #[test]
+    fn single_daemon() -> io::Result<()> {
+        let path = pipe_path("single");
+        let _pipe = create_pipe(&path, true)?;
+        assert!(create_pipe(&path, true).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_daemon() {
+        let request = Request {
+            args: Vec::new(),
+            dir: PathBuf::from(r"C:\"),
+            env: Vec::new(),
+        };
+        assert!(send(&pipe_path("missing"), &request).is_err());
+    }
+}
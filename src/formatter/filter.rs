@@ -0,0 +1,318 @@
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::model::{Align, Filter, Layout};
+use super::string_formatter::StringFormatterError;
+use crate::config::Style;
+use crate::segment::Segment;
+
+/// Applies the filters to the segments of a variable, in order. The segments keep their
+/// styles, padding is added in `style`, the style of the surrounding text.
+pub fn apply_filters(
+    segments: Vec<Segment>,
+    filters: &[Filter],
+    style: Option<Style>,
+) -> Result<Vec<Segment>, StringFormatterError> {
+    filters.iter().try_fold(segments, |segments, filter| {
+        apply_filter(segments, filter, style)
+    })
+}
+
+fn apply_filter(
+    segments: Vec<Segment>,
+    filter: &Filter,
+    style: Option<Style>,
+) -> Result<Vec<Segment>, StringFormatterError> {
+    let args = filter.args.iter().map(Cow::as_ref).collect::<Vec<_>>();
+
+    match (filter.name.as_ref(), args.as_slice()) {
+        ("upper", []) => Ok(map_text(segments, str::to_uppercase)),
+        ("lower", []) => Ok(map_text(segments, str::to_lowercase)),
+        ("truncate", [length]) => Ok(truncate(segments, parse_length(filter, length)?, "…")),
+        ("truncate", [length, symbol]) => {
+            Ok(truncate(segments, parse_length(filter, length)?, symbol))
+        }
+        ("replace", [from]) => Ok(map_text(segments, |text| text.replace(from, ""))),
+        ("replace", [from, to]) => Ok(map_text(segments, |text| text.replace(from, to))),
+        ("pad", [width]) => Ok(pad(
+            segments,
+            parse_length(filter, width)?,
+            Align::Left,
+            style,
+        )),
+        ("pad", [width, align]) => {
+            let align = Align::from_name(align).ok_or_else(|| {
+                StringFormatterError::Custom(format!(
+                    "Invalid alignment `{align}` for filter `pad`, expected `left`, `right` or `center`"
+                ))
+            })?;
+            Ok(pad(segments, parse_length(filter, width)?, align, style))
+        }
+        (name, _) => Err(StringFormatterError::Custom(format!(
+            "Invalid number of arguments for filter `{name}`"
+        ))),
+    }
+}
+
+fn parse_length(filter: &Filter, length: &str) -> Result<usize, StringFormatterError> {
+    length.parse().map_err(|_| {
+        StringFormatterError::Custom(format!(
+            "Invalid length `{length}` for filter `{}`",
+            filter.name
+        ))
+    })
+}
+
+/// Lays out the segments to the width given by the layout, truncating them with `…` if
+/// they are too wide and padding them with spaces in `style` if they are too narrow
+pub fn apply_layout(segments: Vec<Segment>, layout: &Layout, style: Option<Style>) -> Vec<Segment> {
+    let segments = match layout.max_width {
+        Some(max_width) if text_width(&segments) > max_width => {
+            let symbol = "…";
+            cut(
+                segments,
+                max_width.saturating_sub(symbol.width()),
+                UnicodeWidthStr::width,
+                symbol,
+            )
+        }
+        _ => segments,
+    };
+
+    match layout.min_width {
+        Some(min_width) => pad(segments, min_width, layout.align, style),
+        None => segments,
+    }
+}
+
+fn map_text(mut segments: Vec<Segment>, f: impl Fn(&str) -> String) -> Vec<Segment> {
+    for segment in &mut segments {
+        segment.map_text(&f);
+    }
+    segments
+}
+
+/// The width of the text of the segments
+fn text_width(segments: &[Segment]) -> usize {
+    segments
+        .iter()
+        .filter(|segment| matches!(segment, Segment::Text(_)))
+        .map(|segment| segment.value().width())
+        .sum()
+}
+
+/// Truncates the segments to the given number of graphemes, appending the symbol if they were
+/// truncated
+fn truncate(segments: Vec<Segment>, length: usize, symbol: &str) -> Vec<Segment> {
+    cut(segments, length, |_| 1, symbol)
+}
+
+/// Keeps the graphemes of the segments while their size, as given by `size`, adds up to at most
+/// `limit`. The segment the first grapheme that doesn't fit is in ends with the symbol instead,
+/// in its own style, and the segments after it are dropped.
+fn cut(
+    segments: Vec<Segment>,
+    limit: usize,
+    size: impl Fn(&str) -> usize,
+    symbol: &str,
+) -> Vec<Segment> {
+    let mut total = 0;
+    let mut kept = Vec::new();
+
+    for mut segment in segments {
+        if !matches!(segment, Segment::Text(_)) {
+            kept.push(segment);
+            continue;
+        }
+
+        let mut end = 0;
+        let mut overflows = false;
+        for grapheme in segment.value().graphemes(true) {
+            total += size(grapheme);
+            if total > limit {
+                overflows = true;
+                break;
+            }
+            end += grapheme.len();
+        }
+
+        if overflows {
+            segment.map_text(|text| format!("{}{symbol}", &text[..end]));
+            kept.push(segment);
+            break;
+        }
+        kept.push(segment);
+    }
+
+    kept
+}
+
+/// Pads the segments with spaces in `style` until they are at least as wide as the given width
+fn pad(
+    mut segments: Vec<Segment>,
+    width: usize,
+    align: Align,
+    style: Option<Style>,
+) -> Vec<Segment> {
+    let padding = width.saturating_sub(text_width(&segments));
+    let (left, right) = match align {
+        Align::Left => (0, padding),
+        Align::Right => (padding, 0),
+        Align::Center => (padding / 2, padding - padding / 2),
+    };
+
+    if left > 0 {
+        segments.splice(0..0, Segment::from_text(style, " ".repeat(left)));
+    }
+    if right > 0 {
+        segments.extend(Segment::from_text(style, " ".repeat(right)));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nu_ansi_term::Color;
+
+    fn filter<'a>(name: &'a str, args: &[&'a str]) -> Filter<'a> {
+        Filter {
+            name: name.into(),
+            args: args.iter().map(|&arg| arg.into()).collect(),
+        }
+    }
+
+    fn text(segments: &[Segment]) -> String {
+        segments.iter().map(Segment::value).collect()
+    }
+
+    fn filtered(value: &str, filters: &[Filter]) -> Result<String, StringFormatterError> {
+        apply_filters(Segment::from_text(None, value), filters, None)
+            .map(|segments| text(&segments))
+    }
+
+    fn laid_out(value: &str, layout: Layout) -> String {
+        text(&apply_layout(
+            Segment::from_text(None, value),
+            &layout,
+            None,
+        ))
+    }
+
+    fn padded(value: &str, width: usize, align: Align) -> String {
+        text(&pad(Segment::from_text(None, value), width, align, None))
+    }
+
+    #[test]
+    fn test_case_filters() {
+        let filters = [filter("upper", &[])];
+        assert_eq!(filtered("Main", &filters), Ok("MAIN".to_string()));
+        let filters = [filter("lower", &[])];
+        assert_eq!(filtered("Main", &filters), Ok("main".to_string()));
+    }
+
+    #[test]
+    fn test_truncate() {
+        let truncated = |value, length, symbol| {
+            text(&truncate(Segment::from_text(None, value), length, symbol))
+        };
+        assert_eq!(truncated("feature/login", 7, "…"), "feature…");
+        assert_eq!(truncated("main", 7, "…"), "main");
+        assert_eq!(truncated("🦀🦀🦀", 2, ""), "🦀🦀");
+        let filters = [filter("truncate", &["3", "..."])];
+        assert_eq!(filtered("starship", &filters), Ok("sta...".to_string()));
+    }
+
+    #[test]
+    fn test_replace() {
+        let filters = [filter("replace", &["/", "-"])];
+        assert_eq!(filtered("a/b/c", &filters), Ok("a-b-c".to_string()));
+        let filters = [filter("replace", &["feature/"])];
+        assert_eq!(filtered("feature/x", &filters), Ok("x".to_string()));
+    }
+
+    #[test]
+    fn test_pad() {
+        assert_eq!(padded("ab", 5, Align::Left), "ab   ");
+        assert_eq!(padded("ab", 5, Align::Right), "   ab");
+        assert_eq!(padded("ab", 5, Align::Center), " ab  ");
+        assert_eq!(padded("🦀", 3, Align::Left), "🦀 ");
+        assert_eq!(padded("abcdef", 3, Align::Left), "abcdef");
+    }
+
+    #[test]
+    fn test_layout() {
+        let layout = |min_width, max_width, align| Layout {
+            min_width,
+            max_width,
+            align,
+        };
+
+        assert_eq!(
+            laid_out("abc", layout(Some(5), None, Align::Right)),
+            "  abc"
+        );
+        assert_eq!(
+            laid_out("abcdef", layout(None, Some(4), Align::Left)),
+            "abc…"
+        );
+        assert_eq!(laid_out("abc", layout(None, Some(4), Align::Left)), "abc");
+        assert_eq!(
+            laid_out("ab", layout(Some(4), Some(4), Align::Center)),
+            " ab "
+        );
+        // Wide characters can't be split, so the result may be narrower than requested
+        assert_eq!(
+            laid_out("🦀🦀🦀", layout(Some(4), Some(4), Align::Left)),
+            "🦀… "
+        );
+    }
+
+    #[test]
+    fn test_styled_segments() {
+        let red = Some(Color::Red.normal());
+        let blue = Some(Color::Blue.normal());
+        let green = Some(Color::Green.normal());
+        let mut segments = Segment::from_text(red.map(Into::into), "ab");
+        segments.extend(Segment::from_text(blue.map(Into::into), "cd"));
+        segments.extend(Segment::from_text(red.map(Into::into), "ef"));
+
+        let filters = [filter("truncate", &["3"]), filter("upper", &[])];
+        let result = apply_filters(segments.clone(), &filters, None).unwrap();
+        let values: Vec<_> = result
+            .iter()
+            .map(|segment| (segment.value(), segment.style()))
+            .collect();
+        assert_eq!(values, [("AB", red), ("C…", blue)]);
+
+        let layout = Layout {
+            min_width: Some(8),
+            max_width: None,
+            align: Align::Right,
+        };
+        let result = apply_layout(segments, &layout, green.map(Into::into));
+        let values: Vec<_> = result
+            .iter()
+            .map(|segment| (segment.value(), segment.style()))
+            .collect();
+        assert_eq!(
+            values,
+            [("  ", green), ("ab", red), ("cd", blue), ("ef", red)]
+        );
+    }
+
+    #[test]
+    fn test_chained_filters() {
+        let filters = [filter("truncate", &["4", ""]), filter("upper", &[])];
+        assert_eq!(filtered("starship", &filters), Ok("STAR".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_filters() {
+        assert!(filtered("a", &[filter("truncate", &["many"])]).is_err());
+        assert!(filtered("a", &[filter("upper", &["1"])]).is_err());
+        assert!(filtered("a", &[filter("pad", &["2", "top"])]).is_err());
+    }
+}
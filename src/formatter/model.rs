@@ -21,8 +21,79 @@ pub struct TextGroup<'a> {
 pub enum FormatElement<'a> {
     Text(Cow<'a, str>),
     Variable(Cow<'a, str>),
+    TransformedVariable(TransformedVariable<'a>),
     TextGroup(TextGroup<'a>),
     Conditional(Vec<Self>),
+    Condition(Condition<'a>),
+    Group(Group<'a>),
+}
+
+/// A variable whose value is transformed by filters and laid out to a width before rendering
+#[derive(Clone)]
+pub struct TransformedVariable<'a> {
+    pub name: Cow<'a, str>,
+    pub filters: Vec<Filter<'a>>,
+    pub layout: Layout,
+}
+
+/// The width attributes of a variable, e.g. `${directory:width=40:align=left}`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Layout {
+    pub min_width: Option<usize>,
+    pub max_width: Option<usize>,
+    pub align: Align,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+impl Align {
+    pub fn from_name(align: &str) -> Option<Self> {
+        match align {
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            "center" => Some(Self::Center),
+            _ => None,
+        }
+    }
+}
+
+/// A transformation applied to the value of a variable, e.g. `truncate(12)`
+#[derive(Clone)]
+pub struct Filter<'a> {
+    pub name: Cow<'a, str>,
+    pub args: Vec<Cow<'a, str>>,
+}
+
+/// A format string which only renders if the comparison of a variable with a value holds
+#[derive(Clone)]
+pub struct Condition<'a> {
+    pub variable: Cow<'a, str>,
+    pub operator: Operator,
+    pub operand: Cow<'a, str>,
+    pub format: Vec<FormatElement<'a>>,
+}
+
+/// A conditional format string rendered in the style of the named group
+#[derive(Clone)]
+pub struct Group<'a> {
+    pub name: Cow<'a, str>,
+    pub format: Vec<FormatElement<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    GreaterOrEqual,
+    LessOrEqual,
+    Greater,
+    Less,
 }
 
 #[derive(Clone)]
@@ -39,8 +110,19 @@ impl<'a> VariableHolder<Cow<'a, str>> for FormatElement<'a> {
                 variables.insert(var.clone());
                 variables
             }
+            Self::TransformedVariable(variable) => {
+                let mut variables = BTreeSet::new();
+                variables.insert(variable.name.clone());
+                variables
+            }
             Self::TextGroup(textgroup) => textgroup.format.get_variables(),
             Self::Conditional(format) => format.get_variables(),
+            Self::Group(group) => group.format.get_variables(),
+            Self::Condition(condition) => {
+                let mut variables = condition.format.get_variables();
+                variables.insert(condition.variable.clone());
+                variables
+            }
             _ => Default::default(),
         }
     }
@@ -98,6 +180,14 @@ impl<'a> StyleVariableHolder<Cow<'a, str>> for Vec<FormatElement<'a>> {
                 acc.extend(format.get_style_variables());
                 acc
             }
+            FormatElement::Condition(condition) => {
+                acc.extend(condition.format.get_style_variables());
+                acc
+            }
+            FormatElement::Group(group) => {
+                acc.extend(group.format.get_style_variables());
+                acc
+            }
             _ => acc,
         })
     }
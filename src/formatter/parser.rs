@@ -13,15 +13,101 @@ struct IdentParser;
 fn parse_value(value: Pair<Rule>) -> FormatElement {
     match value.as_rule() {
         Rule::text => FormatElement::Text(parse_text(value).into()),
-        Rule::variable => FormatElement::Variable(parse_variable(value).into()),
+        Rule::variable => {
+            let mut inner_rules = value.into_inner();
+            let name = inner_rules.next().unwrap().as_str().into();
+            let mut filters = Vec::new();
+            let mut layout = Layout::default();
+            for pair in inner_rules {
+                match pair.as_rule() {
+                    Rule::filter => filters.push(parse_filter(pair)),
+                    Rule::width_attribute => {
+                        let mut inner_rules = pair.into_inner();
+                        let name = inner_rules.next().unwrap().as_str();
+                        // The grammar only allows digits, so this can only fail on overflow
+                        let width = inner_rules.next().unwrap().as_str().parse().ok();
+                        match name {
+                            "min_width" => layout.min_width = width,
+                            "max_width" => layout.max_width = width,
+                            _ => {
+                                layout.min_width = width;
+                                layout.max_width = width;
+                            }
+                        }
+                    }
+                    Rule::align_attribute => {
+                        let align = pair.into_inner().next().unwrap().as_str();
+                        layout.align = Align::from_name(align).unwrap();
+                    }
+                    _ => unreachable!(),
+                }
+            }
+
+            if filters.is_empty() && layout == Layout::default() {
+                FormatElement::Variable(name)
+            } else {
+                FormatElement::TransformedVariable(TransformedVariable {
+                    name,
+                    filters,
+                    layout,
+                })
+            }
+        }
         Rule::textgroup => FormatElement::TextGroup(parse_textgroup(value)),
         Rule::conditional => {
             FormatElement::Conditional(parse_format(value.into_inner().next().unwrap()))
         }
+        Rule::condition => FormatElement::Condition(parse_condition(value)),
+        Rule::group => {
+            let mut inner_rules = value.into_inner();
+            let name = inner_rules.next().unwrap().as_str().into();
+            let format = parse_format(inner_rules.next().unwrap());
+            FormatElement::Group(Group { name, format })
+        }
         _ => unreachable!(),
     }
 }
 
+fn parse_filter(filter: Pair<Rule>) -> Filter {
+    let mut inner_rules = filter.into_inner();
+    let name = inner_rules.next().unwrap().as_str();
+
+    Filter {
+        name: name.into(),
+        args: inner_rules.map(|arg| parse_operand(arg).into()).collect(),
+    }
+}
+
+fn parse_operand(operand: Pair<'_, Rule>) -> &str {
+    match operand.as_rule() {
+        Rule::quoted_operand => operand.into_inner().next().unwrap().as_str(),
+        _ => operand.as_str(),
+    }
+}
+
+fn parse_condition(condition: Pair<Rule>) -> Condition {
+    let mut inner_rules = condition.into_inner();
+    let variable = inner_rules.next().unwrap();
+    let operator = inner_rules.next().unwrap();
+    let operand = inner_rules.next().unwrap();
+    let format = inner_rules.next().unwrap();
+
+    Condition {
+        variable: variable.as_str().into(),
+        operator: match operator.as_str() {
+            "==" => Operator::Equal,
+            "!=" => Operator::NotEqual,
+            ">=" => Operator::GreaterOrEqual,
+            "<=" => Operator::LessOrEqual,
+            ">" => Operator::Greater,
+            "<" => Operator::Less,
+            _ => unreachable!(),
+        },
+        operand: parse_operand(operand).into(),
+        format: parse_format(format),
+    }
+}
+
 fn parse_textgroup(textgroup: Pair<Rule>) -> TextGroup {
     let mut inner_rules = textgroup.into_inner();
     let format = inner_rules.next().unwrap();
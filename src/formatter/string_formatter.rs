@@ -5,10 +5,13 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 use std::fmt;
 
-use crate::config::{Style, parse_style_string};
+use crate::config::{Style, StyleRule, parse_style_string};
 use crate::context::{Context, Shell};
 use crate::segment::Segment;
+use crate::utils::redact::{self, Redaction};
+use crate::utils::timing::{self, Step};
 
+use super::filter::{apply_filters, apply_layout};
 use super::model::*;
 use super::parser::{Rule, parse};
 
@@ -20,6 +23,26 @@ enum VariableValue<'a> {
     Meta(Vec<FormatElement<'a>>),
 }
 
+impl VariableValue<'_> {
+    /// Returns the text of the value, or `None` for meta variables
+    fn as_text(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Self::Plain(text) | Self::NoEscapingPlain(text) => Some(Cow::Borrowed(text)),
+            Self::Styled(segments) => Some(segments.iter().map(Segment::value).collect()),
+            Self::Meta(_) => None,
+        }
+    }
+
+    /// Replaces the value with a placeholder if the module being computed hides the variable
+    fn redacted(self, name: &str, redaction: Option<&Redaction>) -> Self {
+        let replacement = redaction.and_then(|redaction| redaction.replace(name, &self.as_text()?));
+        match replacement {
+            Some(text) => Self::Plain(Cow::Owned(text)),
+            None => self,
+        }
+    }
+}
+
 impl Default for VariableValue<'_> {
     fn default() -> Self {
         Self::Plain(Cow::Borrowed(""))
@@ -116,11 +139,16 @@ impl<'a> StringFormatter<'a> {
         T: Into<Cow<'a, str>>,
         M: Fn(&str) -> Option<Result<T, StringFormatterError>> + Sync,
     {
+        let redaction = redact::current();
         self.variables
             .par_iter_mut()
             .filter(|(_, value)| value.is_none())
             .for_each(|(key, value)| {
-                *value = mapper(key).map(|var| var.map(|var| VariableValue::Plain(var.into())));
+                *value = mapper(key).map(|var| {
+                    var.map(|var| {
+                        VariableValue::Plain(var.into()).redacted(key, redaction.as_deref())
+                    })
+                });
             });
         self
     }
@@ -137,12 +165,17 @@ impl<'a> StringFormatter<'a> {
         T: Into<Cow<'a, str>>,
         M: Fn(&str) -> Option<Result<T, StringFormatterError>> + Sync,
     {
+        let redaction = redact::current();
         self.variables
             .par_iter_mut()
             .filter(|(_, value)| value.is_none())
             .for_each(|(key, value)| {
-                *value = mapper(key)
-                    .map(|var| var.map(|var| VariableValue::NoEscapingPlain(var.into())));
+                *value = mapper(key).map(|var| {
+                    var.map(|var| {
+                        VariableValue::NoEscapingPlain(var.into())
+                            .redacted(key, redaction.as_deref())
+                    })
+                });
             });
         self
     }
@@ -200,11 +233,14 @@ impl<'a> StringFormatter<'a> {
     where
         M: Fn(&str) -> Option<Result<Vec<Segment>, StringFormatterError>> + Sync,
     {
+        let redaction = redact::current();
         self.variables
             .par_iter_mut()
             .filter(|(_, value)| value.is_none())
             .for_each(|(key, value)| {
-                *value = mapper(key).map(|var| var.map(VariableValue::Styled));
+                *value = mapper(key).map(|var| {
+                    var.map(|var| VariableValue::Styled(var).redacted(key, redaction.as_deref()))
+                });
             });
         self
     }
@@ -227,6 +263,20 @@ impl<'a> StringFormatter<'a> {
         self
     }
 
+    /// Maps the `style` variable to the style of the last rule matching the value
+    ///
+    /// This should be called **before** `map_style`, so that the style of the module is
+    /// only used when none of the rules match.
+    #[must_use]
+    pub fn map_style_rules(mut self, rules: &[StyleRule<'a>], value: f64) -> Self {
+        if let Some(rule) = rules.iter().rev().find(|rule| rule.matches(value))
+            && let Some(style @ None) = self.style_variables.get_mut("style")
+        {
+            *style = Some(Ok(rule.style.into()));
+        }
+        self
+    }
+
     /// Parse the format string and consume self.
     ///
     /// This method will throw an Error in the following conditions:
@@ -238,6 +288,8 @@ impl<'a> StringFormatter<'a> {
         default_style: Option<Style>,
         context: Option<&Context>,
     ) -> Result<Vec<Segment>, StringFormatterError> {
+        let _step = timing::step(Step::Formatting);
+
         fn parse_textgroup<'a>(
             textgroup: TextGroup<'a>,
             variables: &'a VariableMapType<'a>,
@@ -280,6 +332,14 @@ impl<'a> StringFormatter<'a> {
                 .transpose()
         }
 
+        /// Marks segments as rendered from the variable `name`
+        fn with_variable(mut segments: Vec<Segment>, name: &str) -> Vec<Segment> {
+            for segment in &mut segments {
+                segment.set_variable_if_empty(name);
+            }
+            segments
+        }
+
         fn parse_format<'a>(
             format: Vec<FormatElement<'a>>,
             style: Option<Style>,
@@ -339,49 +399,65 @@ impl<'a> StringFormatter<'a> {
                                     formatter.parse(style, context)
                                 }
                             })
-                            .unwrap_or_else(|| Ok(Vec::new())),
+                            .unwrap_or_else(|| Ok(Vec::new()))
+                            .map(|segments| with_variable(segments, &name)),
+                        FormatElement::TransformedVariable(variable) => {
+                            let Some(value) = variables
+                                .get(variable.name.as_ref())
+                                .expect("Uncached variable found")
+                            else {
+                                return Ok(Vec::new());
+                            };
+
+                            // Filters and layout work on the segments of the variable, which
+                            // keep their styles
+                            let transform = |segments| {
+                                apply_filters(segments, &variable.filters, style)
+                                    .map(|segments| apply_layout(segments, &variable.layout, style))
+                            };
+                            let segments = match value.clone()? {
+                                VariableValue::Plain(text) => {
+                                    let shell = match context {
+                                        None => Shell::Unknown,
+                                        Some(c) => c.shell,
+                                    };
+                                    transform(Segment::from_text(style, text)).map(
+                                        |mut segments| {
+                                            for segment in &mut segments {
+                                                segment.map_text(|text| {
+                                                    shell_prompt_escape(text.to_string(), shell)
+                                                });
+                                            }
+                                            segments
+                                        },
+                                    )
+                                }
+                                VariableValue::NoEscapingPlain(text) => {
+                                    transform(Segment::from_text(style, text))
+                                }
+                                VariableValue::Styled(segments) => transform(
+                                    segments
+                                        .into_iter()
+                                        .map(|mut segment| {
+                                            segment.set_style_if_empty(style);
+                                            segment
+                                        })
+                                        .collect(),
+                                ),
+                                VariableValue::Meta(format) => {
+                                    let formatter = StringFormatter {
+                                        format,
+                                        variables: clone_without_meta(variables),
+                                        style_variables: style_variables.clone(),
+                                    };
+                                    transform(formatter.parse(style, context)?)
+                                }
+                            };
+                            segments.map(|segments| with_variable(segments, &variable.name))
+                        }
                         FormatElement::Conditional(format) => {
                             // Show the conditional format string if all the variables inside are not
                             // none or empty string.
-                            fn should_show_elements<'a>(
-                                format_elements: &[FormatElement],
-                                variables: &'a VariableMapType<'a>,
-                            ) -> bool {
-                                format_elements.get_variables().iter().any(|var| {
-                                    variables
-                                        .get(var.as_ref())
-                                        // false if can't find the variable in format string
-                                        .is_some_and(|map_result| {
-                                            let map_result = map_result.as_ref();
-                                            map_result
-                                                .and_then(|result| result.as_ref().ok())
-                                                // false if the variable is None or Err, or a meta variable
-                                                // that shouldn't show
-                                                .is_some_and(|result| match result {
-                                                    // If the variable is a meta variable, also
-                                                    // check the format string inside it.
-                                                    VariableValue::Meta(meta_elements) => {
-                                                        let meta_variables =
-                                                            clone_without_meta(variables);
-                                                        should_show_elements(
-                                                            meta_elements,
-                                                            &meta_variables,
-                                                        )
-                                                    }
-                                                    VariableValue::Plain(plain_value) => {
-                                                        !plain_value.is_empty()
-                                                    }
-                                                    VariableValue::NoEscapingPlain(
-                                                        no_escaping_plain_value,
-                                                    ) => !no_escaping_plain_value.is_empty(),
-                                                    VariableValue::Styled(segments) => segments
-                                                        .iter()
-                                                        .any(|x| !x.value().is_empty()),
-                                                })
-                                        })
-                                })
-                            }
-
                             let should_show: bool = should_show_elements(&format, variables);
 
                             if should_show {
@@ -390,6 +466,71 @@ impl<'a> StringFormatter<'a> {
                                 Ok(Vec::new())
                             }
                         }
+                        FormatElement::Condition(condition) => {
+                            // Show the format string if the value of the variable satisfies the
+                            // comparison. Missing, failed and meta variables never do.
+                            let holds = variables
+                                .get(condition.variable.as_ref())
+                                .and_then(|value| value.as_ref()?.as_ref().ok())
+                                .and_then(VariableValue::as_text)
+                                .is_some_and(|value| {
+                                    compare(&value, condition.operator, &condition.operand)
+                                });
+
+                            if holds {
+                                parse_format(
+                                    condition.format,
+                                    style,
+                                    variables,
+                                    style_variables,
+                                    context,
+                                )
+                            } else {
+                                Ok(Vec::new())
+                            }
+                        }
+                        FormatElement::Group(group) => {
+                            // Groups are shown like conditional format strings, with `$style`
+                            // set to the style of the group.
+                            if !should_show_elements(&group.format, variables) {
+                                return Ok(Vec::new());
+                            }
+                            let Some(group_style) = context.and_then(|context| {
+                                context.root_config.groups.get(group.name.as_ref())
+                            }) else {
+                                return parse_format(
+                                    group.format,
+                                    style,
+                                    variables,
+                                    style_variables,
+                                    context,
+                                );
+                            };
+
+                            let mut group_style_variables = style_variables.clone();
+                            group_style_variables.insert(
+                                "style".to_string(),
+                                Some(Ok(Cow::Owned(group_style.style.clone()))),
+                            );
+                            let segments = parse_format(
+                                group.format,
+                                style,
+                                variables,
+                                &group_style_variables,
+                                context,
+                            )?;
+                            let Some(background) = parse_style_string(&group_style.style, context)
+                            else {
+                                return Ok(segments);
+                            };
+                            Ok(segments
+                                .into_iter()
+                                .map(|mut segment| {
+                                    segment.set_background_if_empty(background);
+                                    segment
+                                })
+                                .collect())
+                        }
                     }
                 })
                 .collect();
@@ -418,6 +559,41 @@ impl StyleVariableHolder<String> for StringFormatter<'_> {
     }
 }
 
+/// Returns whether a conditional format string is shown, which is the case if any of the
+/// variables inside are not none or empty string.
+fn should_show_elements<'a>(
+    format_elements: &[FormatElement],
+    variables: &'a VariableMapType<'a>,
+) -> bool {
+    format_elements.get_variables().iter().any(|var| {
+        variables
+            .get(var.as_ref())
+            // false if can't find the variable in format string
+            .is_some_and(|map_result| {
+                let map_result = map_result.as_ref();
+                map_result
+                    .and_then(|result| result.as_ref().ok())
+                    // false if the variable is None or Err, or a meta variable
+                    // that shouldn't show
+                    .is_some_and(|result| match result {
+                        // If the variable is a meta variable, also
+                        // check the format string inside it.
+                        VariableValue::Meta(meta_elements) => {
+                            let meta_variables = clone_without_meta(variables);
+                            should_show_elements(meta_elements, &meta_variables)
+                        }
+                        VariableValue::Plain(plain_value) => !plain_value.is_empty(),
+                        VariableValue::NoEscapingPlain(no_escaping_plain_value) => {
+                            !no_escaping_plain_value.is_empty()
+                        }
+                        VariableValue::Styled(segments) => {
+                            segments.iter().any(|x| !x.value().is_empty())
+                        }
+                    })
+            })
+    })
+}
+
 fn clone_without_meta<'a>(variables: &VariableMapType<'a>) -> VariableMapType<'a> {
     variables
         .iter()
@@ -435,6 +611,29 @@ fn clone_without_meta<'a>(variables: &VariableMapType<'a>) -> VariableMapType<'a
         .collect()
 }
 
+/// Compares the value of a variable with an operand.
+///
+/// Both sides are compared as numbers if they can be parsed as such, otherwise
+/// they can only be checked for (in)equality.
+fn compare(value: &str, operator: Operator, operand: &str) -> bool {
+    let ordering = match (value.trim().parse::<f64>(), operand.trim().parse::<f64>()) {
+        (Ok(value), Ok(operand)) => value.partial_cmp(&operand),
+        _ => match operator {
+            Operator::Equal | Operator::NotEqual => Some(value.cmp(operand)),
+            _ => None,
+        },
+    };
+
+    ordering.is_some_and(|ordering| match operator {
+        Operator::Equal => ordering.is_eq(),
+        Operator::NotEqual => ordering.is_ne(),
+        Operator::GreaterOrEqual => ordering.is_ge(),
+        Operator::LessOrEqual => ordering.is_le(),
+        Operator::Greater => ordering.is_gt(),
+        Operator::Less => ordering.is_lt(),
+    })
+}
+
 /// Escape interpretable characters for the shell prompt
 pub fn shell_prompt_escape<T>(text: T, shell: Shell) -> String
 where
@@ -444,7 +643,7 @@ where
     match shell {
         // Bash might interpret backslashes, backticks and $
         // see #658 for more details
-        Shell::Bash => text
+        Shell::Bash | Shell::Osh => text
             .into()
             .replace('\\', r"\\")
             .replace('$', r"\$")
@@ -460,7 +659,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nu_ansi_term::Color;
+    use nu_ansi_term::{Color, Style};
 
     // match_next(result: IterMut<Segment>, value, style)
     macro_rules! match_next {
@@ -653,6 +852,36 @@ mod tests {
         match_next!(result_iter, "$b", None);
     }
 
+    #[test]
+    fn test_segment_variables() {
+        const FORMAT_STR: &str = "on [$symbol$a](red)";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_meta(|var, _| match var {
+                "symbol" => Some("x $a"),
+                _ => None,
+            })
+            .map(|var| match var {
+                "a" => Some(Ok("a")),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let variables: Vec<_> = result
+            .iter()
+            .map(|segment| (segment.value(), segment.variable()))
+            .collect();
+        assert_eq!(
+            variables,
+            vec![
+                ("on ", None),
+                ("x ", Some("symbol")),
+                ("a", Some("a")),
+                ("a", Some("a")),
+            ]
+        );
+    }
+
     #[test]
     fn test_multiple_mapper() {
         const FORMAT_STR: &str = "$a$b$c";
@@ -799,6 +1028,357 @@ mod tests {
         match_next!(result_iter, " ", None);
     }
 
+    #[test]
+    fn test_condition_numeric() {
+        const FORMAT_STR: &str =
+            "(${status > 0})[failed $status](${jobs >= 2})[many jobs](${jobs < 1})[no jobs]";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "status" => Some(Ok("127")),
+                "jobs" => Some(Ok("2")),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "failed ", None);
+        match_next!(result_iter, "127", None);
+        match_next!(result_iter, "many jobs", None);
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_condition_string() {
+        const FORMAT_STR: &str = r#"(${branch == main})[on main ](${branch != 'main'})[elsewhere ](${branch == "dev"})[[dev](red)]"#;
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "branch" => Some(Ok("dev")),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "elsewhere ", None);
+        match_next!(result_iter, "dev", Some(Color::Red.normal()));
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_condition_without_value() {
+        // Missing variables never satisfy a condition, and strings can't be ordered
+        const FORMAT_STR: &str = "(${none != 0})[none](${name > 0})[name]";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "name" => Some(Ok("starship")),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_condition_variable_holder() {
+        const FORMAT_STR: &str = "(${a > 1})[$b [c]($s)]";
+        let expected_variables = vec!["a", "b"].into_iter().map(String::from).collect();
+
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap();
+        assert_eq!(formatter.get_variables(), expected_variables);
+        assert_eq!(
+            formatter.get_style_variables(),
+            vec!["s"].into_iter().map(String::from).collect()
+        );
+    }
+
+    #[test]
+    fn test_group() {
+        const FORMAT_STR: &str = "(group:a [$some]($style) )(group:b [$none]($style) )";
+
+        let context = crate::test::default_context().set_config(toml::toml! {
+            [groups.a]
+            style = "red bg:blue"
+            [groups.b]
+            style = "green"
+        });
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "some" => Some(Ok("$some")),
+                _ => None,
+            });
+        let result = formatter.parse(None, Some(&context)).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "$some", Some(Color::Red.on(Color::Blue)));
+        match_next!(result_iter, " ", Some(Style::new().on(Color::Blue)));
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_group_keeps_background() {
+        const FORMAT_STR: &str = "(group:a [$some](bg:red)$other)";
+
+        let context = crate::test::default_context().set_config(toml::toml! {
+            [groups.a]
+            style = "bg:blue"
+        });
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "some" => Some(Ok("$some")),
+                "other" => Some(Ok("$other")),
+                _ => None,
+            });
+        let result = formatter.parse(None, Some(&context)).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "$some", Some(Style::new().on(Color::Red)));
+        match_next!(result_iter, "$other", Some(Style::new().on(Color::Blue)));
+    }
+
+    #[test]
+    fn test_group_without_config() {
+        const FORMAT_STR: &str = "(group:a [$some]($style))";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "some" => Some(Ok("$some")),
+                _ => None,
+            })
+            .map_style(|var| match var {
+                "style" => Some(Ok("red")),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "$some", Some(Color::Red.into()));
+    }
+
+    #[test]
+    fn test_group_variable_holder() {
+        const FORMAT_STR: &str = "(group:cloud [$a$b]($style) )";
+        let expected_variables = vec!["a", "b"].into_iter().map(String::from).collect();
+
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap();
+        assert_eq!(formatter.get_variables(), expected_variables);
+        assert_eq!(
+            formatter.get_style_variables(),
+            vec!["style"].into_iter().map(String::from).collect()
+        );
+    }
+
+    #[test]
+    fn test_filters() {
+        const FORMAT_STR: &str =
+            r#"$branch|truncate(7)|upper [$branch|replace("/", " - ")](red) $short|pad(4, right)"#;
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "branch" => Some(Ok("feature/login")),
+                "short" => Some(Ok("ab")),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "FEATURE…", None);
+        match_next!(result_iter, " ", None);
+        match_next!(result_iter, "feature - login", Some(Color::Red.normal()));
+        match_next!(result_iter, " ", None);
+        match_next!(result_iter, "  ", None);
+        match_next!(result_iter, "ab", None);
+    }
+
+    #[test]
+    fn test_filter_like_text() {
+        // Only known filters are parsed, anything else after `|` stays text
+        const FORMAT_STR: &str = "$a|$b|lowercase";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "a" => Some(Ok("A")),
+                "b" => Some(Ok("B")),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "A", None);
+        match_next!(result_iter, "|", None);
+        match_next!(result_iter, "B", None);
+        match_next!(result_iter, "|lowercase", None);
+    }
+
+    #[test]
+    fn test_filter_meta_variable() {
+        const FORMAT_STR: &str = "$all|lower";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_meta(|var, _| match var {
+                "all" => Some("[$a](red)-$b"),
+                _ => None,
+            })
+            .map(|var| match var {
+                "a" => Some(Ok("A")),
+                "b" => Some(Ok("B")),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "a", Some(Color::Red.normal()));
+        match_next!(result_iter, "-", None);
+        match_next!(result_iter, "b", None);
+    }
+
+    #[test]
+    fn test_filter_styled_variable() {
+        const FORMAT_STR: &str = "$directory|upper|truncate(4)";
+        let directory_style = Some(Color::Cyan.bold());
+        let read_only_style = Some(Color::Red.normal());
+
+        let mut segments = Segment::from_text(directory_style.map(Into::into), "/tmp");
+        segments.extend(Segment::from_text(read_only_style.map(Into::into), " ro"));
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_variables_to_segments(|variable| match variable {
+                "directory" => Some(Ok(segments.clone())),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "/TMP", directory_style);
+        match_next!(result_iter, "…", read_only_style);
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_layout_attributes() {
+        const FORMAT_STR: &str = "[${dir:width=6:align=right}](red)|${dir:max_width=3}|${env_var.HOME:min_width=4}|${dir:width=8}";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "dir" => Some(Ok("~/src")),
+                "env_var.HOME" => Some(Ok("/h")),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, " ", Some(Color::Red.normal()));
+        match_next!(result_iter, "~/src", Some(Color::Red.normal()));
+        match_next!(result_iter, "|", None);
+        match_next!(result_iter, "~/…", None);
+        match_next!(result_iter, "|", None);
+        match_next!(result_iter, "/h", None);
+        match_next!(result_iter, "  ", None);
+        match_next!(result_iter, "|", None);
+        match_next!(result_iter, "~/src", None);
+        match_next!(result_iter, "   ", None);
+    }
+
+    #[test]
+    fn test_layout_styled_variable() {
+        const FORMAT_STR: &str = "${directory:width=8:align=left}|${directory:max_width=3}";
+        let directory_style = Some(Color::Cyan.bold());
+
+        let segments = Segment::from_text(directory_style.map(Into::into), "/tmp");
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_variables_to_segments(|variable| match variable {
+                "directory" => Some(Ok(segments.clone())),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "/tmp", directory_style);
+        match_next!(result_iter, "    ", None);
+        match_next!(result_iter, "|", None);
+        match_next!(result_iter, "/t…", directory_style);
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_layout_attributes_with_filters() {
+        const FORMAT_STR: &str = "${branch:width=4}|upper";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "branch" => Some(Ok("ab")),
+                _ => None,
+            });
+        assert_eq!(
+            formatter.get_variables(),
+            BTreeSet::from(["branch".to_string()])
+        );
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "AB", None);
+        match_next!(result_iter, "  ", None);
+    }
+
+    #[test]
+    fn test_layout_attribute_error() {
+        assert!(StringFormatter::new("${dir:width=wide}").is_err());
+        assert!(StringFormatter::new("${dir:align=top}").is_err());
+    }
+
+    #[test]
+    fn test_filter_error() {
+        const FORMAT_STR: &str = "$a|truncate(x)";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "a" => Some(Ok("A")),
+                _ => None,
+            });
+        assert!(formatter.parse(None, None).is_err());
+    }
+
+    #[test]
+    fn test_style_rules() {
+        const FORMAT_STR: &str = "[$value]($style)";
+        let rules = [
+            StyleRule {
+                min: Some(10.0),
+                max: None,
+                style: "red",
+            },
+            StyleRule {
+                min: Some(60.0),
+                max: Some(120.0),
+                style: "bold red",
+            },
+        ];
+
+        for (value, expected_style) in [
+            (5.0, Color::Yellow.normal()),
+            (10.0, Color::Red.normal()),
+            (60.0, Color::Red.bold()),
+            (120.0, Color::Red.normal()),
+        ] {
+            let formatter = StringFormatter::new(FORMAT_STR)
+                .unwrap()
+                .map_style_rules(&rules, value)
+                .map_style(|variable| match variable {
+                    "style" => Some(Ok("yellow")),
+                    _ => None,
+                })
+                .map(|variable| match variable {
+                    "value" => Some(Ok(value.to_string())),
+                    _ => None,
+                });
+            let result = formatter.parse(None, None).unwrap();
+            assert_eq!(result[0].style(), Some(expected_style));
+        }
+    }
+
     #[test]
     fn test_variable_holder() {
         const FORMAT_STR: &str = "($a [($b) $c](none $s)) $d [t]($t)";
@@ -1,3 +1,5 @@
+use crate::context::Context;
+use crate::modules::cmd_duration;
 use crate::utils::create_command;
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
@@ -165,7 +167,10 @@ pub fn init_stub(shell_name: &str) -> io::Result<()> {
             r#"eval -- "$({0} init bash --print-full-init)""#,
             starship.sprint_posix()?
         ),
-        "zsh" => print_script(ZSH_INIT, &starship.sprint_posix()?),
+        "zsh" => print_script(
+            &with_last_command(&with_async_init(ZSH_INIT, ZSH_ASYNC_INIT)),
+            &starship.sprint_posix()?,
+        ),
         "fish" => print!(
             // Fish does process substitution with pipes and psub instead of bash syntax
             r"source ({} init fish --print-full-init | psub)",
@@ -190,6 +195,15 @@ pub fn init_stub(shell_name: &str) -> io::Result<()> {
             starship.sprint_posix()?
         ),
         "cmd" => print_script(CMDEXE_INIT, &StarshipPath::init()?.sprint_cmdexe()?),
+        "osh" => print!(
+            r#"eval -- "$({} init osh --print-full-init)""#,
+            starship.sprint_posix()?
+        ),
+        "ysh" => print!(
+            r#"eval "$({} init ysh --print-full-init)""#,
+            starship.sprint_posix()?
+        ),
+        "murex" => print_script(MUREX_INIT, &starship.sprint_posix()?),
         _ => {
             eprintln!(
                 "{shell_basename} is not yet supported by starship.\n\
@@ -204,6 +218,9 @@ pub fn init_stub(shell_name: &str) -> io::Result<()> {
                  * nu\n\
                  * xonsh\n\
                  * cmd\n\
+                 * osh\n\
+                 * ysh\n\
+                 * murex\n\
                  \n\
                  Please open an issue in the starship repo if you would like to \
                  see support for {shell_basename}:\n\
@@ -220,14 +237,26 @@ pub fn init_main(shell_name: &str) -> io::Result<()> {
     let starship_path = StarshipPath::init()?;
 
     match shell_name {
-        "bash" => print_script(BASH_INIT, &starship_path.sprint_posix()?),
-        "zsh" => print_script(ZSH_INIT, &starship_path.sprint_posix()?),
-        "fish" => print_script(FISH_INIT, &starship_path.sprint_posix()?),
-        "powershell" => print_script(PWSH_INIT, &starship_path.sprint_pwsh()?),
+        "bash" => print_script(
+            &with_last_command(BASH_INIT),
+            &starship_path.sprint_posix()?,
+        ),
+        "zsh" => print_script(
+            &with_last_command(&with_async_init(ZSH_INIT, ZSH_ASYNC_INIT)),
+            &starship_path.sprint_posix()?,
+        ),
+        "fish" => print_script(
+            &with_last_command(&with_async_init(FISH_INIT, FISH_ASYNC_INIT)),
+            &starship_path.sprint_posix()?,
+        ),
+        "powershell" => print_script(&with_last_command(PWSH_INIT), &starship_path.sprint_pwsh()?),
         "ion" => print_script(ION_INIT, &starship_path.sprint()?),
         "elvish" => print_script(ELVISH_INIT, &starship_path.sprint_elv()?),
         "tcsh" => print_script(TCSH_INIT, &starship_path.sprint_posix()?),
         "xonsh" => print_script(XONSH_INIT, &starship_path.sprint_posix()?),
+        "osh" => print_script(OSH_INIT, &starship_path.sprint_posix()?),
+        "ysh" => print_script(YSH_INIT, &starship_path.sprint_posix()?),
+        "murex" => print_script(MUREX_INIT, &starship_path.sprint_posix()?),
         _ => {
             println!(
                 "printf \"Shell name detection failed on phase two init.\\n\
@@ -239,6 +268,26 @@ pub fn init_main(shell_name: &str) -> io::Result<()> {
     Ok(())
 }
 
+/// Appends the script rendering the `async_modules` in the background, if any are configured
+fn with_async_init(script: &str, async_script: &str) -> String {
+    if Context::default().root_config.async_modules.is_empty() {
+        script.to_string()
+    } else {
+        format!("{script}{async_script}")
+    }
+}
+
+/// Replaces `::PASS_LAST_COMMAND::` with the command telling whether the command line of the last
+/// command is passed on to starship, which is only done if `cmd_duration` uses it
+fn with_last_command(script: &str) -> String {
+    let pass = if cmd_duration::uses_last_command(&Context::default()) {
+        "true"
+    } else {
+        "false"
+    };
+    script.replace("::PASS_LAST_COMMAND::", pass)
+}
+
 fn print_script(script: &str, path: &str) {
     let script = script.replace("::STARSHIP::", path);
     print!("{script}");
@@ -256,15 +305,24 @@ to avoid the cost of an additional shell fork every shell draw.
 Note that the init scripts are not in their final form--they are processed by
 `starship init` prior to emitting the final form. In this processing, some tokens
 are replaced, e.g. `::STARSHIP::` is replaced by the full path to the
-starship binary.
+starship binary, and `::PASS_LAST_COMMAND::` by `true` or `false`.
+
+LAST COMMAND: The command line of the last command is passed in the environment
+variable STARSHIP_LAST_COMMAND of the prompt, as the arguments of processes can be
+seen by other users. It may hold secrets, so it is only passed at all if the
+configuration of `cmd_duration` uses it.
 */
 
 const BASH_INIT: &str = include_str!("starship.bash");
 
 const ZSH_INIT: &str = include_str!("starship.zsh");
 
+const ZSH_ASYNC_INIT: &str = include_str!("starship_async.zsh");
+
 const FISH_INIT: &str = include_str!("starship.fish");
 
+const FISH_ASYNC_INIT: &str = include_str!("starship_async.fish");
+
 const PWSH_INIT: &str = include_str!("starship.ps1");
 
 const ION_INIT: &str = include_str!("starship.ion");
@@ -279,6 +337,12 @@ const XONSH_INIT: &str = include_str!("starship.xsh");
 
 const CMDEXE_INIT: &str = include_str!("starship.lua");
 
+const OSH_INIT: &str = include_str!("starship.osh");
+
+const YSH_INIT: &str = include_str!("starship.ysh");
+
+const MUREX_INIT: &str = include_str!("starship.mx");
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,17 +70,41 @@ starship_precmd() {
     fi
 
     local -a ARGS=(--terminal-width="${COLUMNS}" --status="${STARSHIP_CMD_STATUS}" --pipestatus="${STARSHIP_PIPE_STATUS[*]}" --jobs="${NUM_JOBS}" --shlvl="${SHLVL}")
+    # Pass the commands of the jobs on for the jobs module
+    if (( NUM_JOBS > 0 )); then
+        ARGS+=( --job-names="$(compgen -A job)")
+    fi
     # Prepare the timer data, if needed.
     if [[ -n "${STARSHIP_START_TIME-}" ]]; then
         STARSHIP_END_TIME=$(::STARSHIP:: time)
         STARSHIP_DURATION=$((STARSHIP_END_TIME - STARSHIP_START_TIME))
         ARGS+=( --cmd-duration="${STARSHIP_DURATION}")
         STARSHIP_START_TIME=""
+        # Pass the command line on to cmd_duration, if it uses it, exported for the prompts only.
+        # It is only the last history entry if it was added to the history, which HISTCONTROL
+        # and HISTIGNORE can prevent, in which case the history number of the next command
+        # stays the same.
+        if ::PASS_LAST_COMMAND:: && [[ ${HISTCMD-} && ${HISTCMD} != "${STARSHIP_HISTCMD-}" ]]; then
+            local LAST_COMMAND
+            LAST_COMMAND="$(HISTTIMEFORMAT='' builtin history 1)"
+            if [[ $LAST_COMMAND =~ ^[[:space:]]*[0-9]+\*?[[:space:]]+(.*)$ ]]; then
+                local -x STARSHIP_LAST_COMMAND="${BASH_REMATCH[1]}"
+            fi
+        fi
+    fi
+    STARSHIP_HISTCMD=${HISTCMD-}
+    # Render the continuation prompt again if the terminal was resized, so `fill` spans its width
+    if [[ "${STARSHIP_PS2_COLUMNS-}" != "${COLUMNS}" ]]; then
+        STARSHIP_PS2_COLUMNS="${COLUMNS}"
+        PS2="$(::STARSHIP:: prompt --continuation --terminal-width="${COLUMNS}")"
     fi
-    PS1="$(::STARSHIP:: prompt "${ARGS[@]}")"
     if [[ ${BLE_ATTACHED-} ]]; then
+        # ble.sh draws the right prompt by itself, so starship doesn't have to
+        PS1="$(STARSHIP_BASH_RIGHT_PROMPT=0 ::STARSHIP:: prompt "${ARGS[@]}")"
         local nlns=${PS1//[!$'\n']}
         bleopt prompt_rps1="$nlns$(::STARSHIP:: prompt --right "${ARGS[@]}")"
+    else
+        PS1="$(::STARSHIP:: prompt "${ARGS[@]}")"
     fi
     STARSHIP_PREEXEC_READY=true  # Signal that we can safely restart the timer
 }
@@ -143,11 +167,37 @@ shopt -s checkwinsize
 STARSHIP_START_TIME=$(::STARSHIP:: time)
 export STARSHIP_SHELL="bash"
 
+# Pass the version of bash and whether it is a login or interactive shell to the shell module
+export STARSHIP_SHELL_VERSION="${BASH_VERSION%%[^0-9.]*}"
+if shopt -q login_shell; then export STARSHIP_SHELL_LOGIN=1; else export STARSHIP_SHELL_LOGIN=0; fi
+if [[ $- == *i* ]]; then export STARSHIP_SHELL_INTERACTIVE=1; else export STARSHIP_SHELL_INTERACTIVE=0; fi
+
+# Count the nested SSH sessions for the hostname module, in variables that ssh forwards like
+# the locale. A connection is only counted once, by the first shell started by it.
+if [[ -n "${SSH_CONNECTION-}" && "${LC_STARSHIP_SSH_CONNECTION-}" != "$SSH_CONNECTION" ]]; then
+    [[ "${LC_STARSHIP_SSH_DEPTH-}" =~ ^[0-9]+$ ]] || LC_STARSHIP_SSH_DEPTH=0
+    export LC_STARSHIP_SSH_DEPTH=$((LC_STARSHIP_SSH_DEPTH + 1))
+    export LC_STARSHIP_SSH_CONNECTION="$SSH_CONNECTION"
+fi
+
+# Record the SHLVL of the first shell in this terminal, or tmux pane, for the shlvl module to
+# only count the shells nested in it. Terminals are told apart by their tty.
+STARSHIP_TTY="$(tty 2>/dev/null)"
+if [[ "${STARSHIP_SHLVL_TTY-}" != "$STARSHIP_TTY" || ! "${STARSHIP_SHLVL_BASE-}" =~ ^[0-9]+$ ]]; then
+    export STARSHIP_SHLVL_BASE="${SHLVL-}"
+    export STARSHIP_SHLVL_TTY="$STARSHIP_TTY"
+fi
+unset STARSHIP_TTY
+
 # Set up the session key that will be used to store logs
 STARSHIP_SESSION_KEY="$RANDOM$RANDOM$RANDOM$RANDOM$RANDOM"; # Random generates a number b/w 0 - 32767
 STARSHIP_SESSION_KEY="${STARSHIP_SESSION_KEY}0000000000000000" # Pad it to 16+ chars.
 export STARSHIP_SESSION_KEY=${STARSHIP_SESSION_KEY:0:16}; # Trim to 16-digits if excess.
 
-# Set the continuation prompt
-PS2="$(::STARSHIP:: prompt --continuation)"
+# Detect the terminal background once per session, for `palette_light` and `palette_dark`
+export STARSHIP_BACKGROUND="$(::STARSHIP:: background)"
+
+# Set the continuation prompt, which is rendered again by starship_precmd if the terminal is resized
+PS2="$(::STARSHIP:: prompt --continuation --terminal-width="${COLUMNS-}")"
+STARSHIP_PS2_COLUMNS="${COLUMNS-}"
 
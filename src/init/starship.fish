@@ -8,6 +8,12 @@ function __starship_set_job_count --description 'Set STARSHIP_JOBS using fish jo
         # Default behavior: count job groups
         set -g STARSHIP_JOBS (jobs -g 2>/dev/null | count)
     end    
+
+    # The commands of the jobs, for the jobs module
+    set -g STARSHIP_JOB_NAMES
+    if test "$STARSHIP_JOBS" -gt 0
+        set STARSHIP_JOB_NAMES --job-names=(jobs --command 2>/dev/null)
+    end
 end
 
 function fish_prompt
@@ -33,12 +39,20 @@ function fish_prompt
             printf \e\[0J
         end
         if type -q starship_transient_prompt_func
-            starship_transient_prompt_func --terminal-width="$COLUMNS" --status=$STARSHIP_CMD_STATUS --pipestatus="$STARSHIP_CMD_PIPESTATUS" --keymap=$STARSHIP_KEYMAP --cmd-duration=$STARSHIP_DURATION --jobs=$STARSHIP_JOBS
+            starship_transient_prompt_func --terminal-width="$COLUMNS" --status=$STARSHIP_CMD_STATUS --pipestatus="$STARSHIP_CMD_PIPESTATUS" --keymap=$STARSHIP_KEYMAP --cmd-duration=$STARSHIP_DURATION --jobs=$STARSHIP_JOBS $STARSHIP_JOB_NAMES
         else
             printf "\e[1;32m❯\e[0m "
         end
     else
-        ::STARSHIP:: prompt --terminal-width="$COLUMNS" --status=$STARSHIP_CMD_STATUS --pipestatus="$STARSHIP_CMD_PIPESTATUS" --keymap=$STARSHIP_KEYMAP --cmd-duration=$STARSHIP_DURATION --jobs=$STARSHIP_JOBS
+        set -l prompt_args --terminal-width="$COLUMNS" --status=$STARSHIP_CMD_STATUS --pipestatus="$STARSHIP_CMD_PIPESTATUS" --keymap=$STARSHIP_KEYMAP --cmd-duration=$STARSHIP_DURATION --jobs=$STARSHIP_JOBS $STARSHIP_JOB_NAMES
+        # The command line, if cmd_duration uses it, which is passed in the environment
+        set -l last_command
+        ::PASS_LAST_COMMAND::; and set last_command $history[1]
+        # Render the `async_modules` in the background, see starship_async.fish
+        if set -q __starship_async
+            STARSHIP_LAST_COMMAND=$last_command __starship_async_render $prompt_args
+        end
+        STARSHIP_LAST_COMMAND=$last_command ::STARSHIP:: prompt $__starship_async $prompt_args
     end
 end
 
@@ -61,12 +75,14 @@ function fish_right_prompt
     if contains -- --final-rendering $argv; or test "$RIGHT_TRANSIENT" = "1"
         set -g RIGHT_TRANSIENT 0
         if type -q starship_transient_rprompt_func
-            starship_transient_rprompt_func --terminal-width="$COLUMNS" --status=$STARSHIP_CMD_STATUS --pipestatus="$STARSHIP_CMD_PIPESTATUS" --keymap=$STARSHIP_KEYMAP --cmd-duration=$STARSHIP_DURATION --jobs=$STARSHIP_JOBS
+            starship_transient_rprompt_func --terminal-width="$COLUMNS" --status=$STARSHIP_CMD_STATUS --pipestatus="$STARSHIP_CMD_PIPESTATUS" --keymap=$STARSHIP_KEYMAP --cmd-duration=$STARSHIP_DURATION --jobs=$STARSHIP_JOBS $STARSHIP_JOB_NAMES
         else
             printf ""
         end
     else
-        ::STARSHIP:: prompt --right --terminal-width="$COLUMNS" --status=$STARSHIP_CMD_STATUS --pipestatus="$STARSHIP_CMD_PIPESTATUS" --keymap=$STARSHIP_KEYMAP --cmd-duration=$STARSHIP_DURATION --jobs=$STARSHIP_JOBS
+        set -l last_command
+        ::PASS_LAST_COMMAND::; and set last_command $history[1]
+        STARSHIP_LAST_COMMAND=$last_command ::STARSHIP:: prompt $__starship_async --right --terminal-width="$COLUMNS" --status=$STARSHIP_CMD_STATUS --pipestatus="$STARSHIP_CMD_PIPESTATUS" --keymap=$STARSHIP_KEYMAP --cmd-duration=$STARSHIP_DURATION --jobs=$STARSHIP_JOBS $STARSHIP_JOB_NAMES
     end
 end
 
@@ -78,6 +94,11 @@ builtin functions -e fish_mode_prompt
 
 set -gx STARSHIP_SHELL "fish"
 
+# Pass the version of fish and whether it is a login or interactive shell to the shell module
+set -gx STARSHIP_SHELL_VERSION $FISH_VERSION
+status is-login; and set -gx STARSHIP_SHELL_LOGIN 1; or set -gx STARSHIP_SHELL_LOGIN 0
+status is-interactive; and set -gx STARSHIP_SHELL_INTERACTIVE 1; or set -gx STARSHIP_SHELL_INTERACTIVE 0
+
 # Transience related functions
 function __starship_reset_transient --on-event fish_postexec
     set -g TRANSIENT 0
@@ -141,6 +162,25 @@ function disable_transience --description 'remove transient prompt keybindings'
     bind --user -M insert -e \r
 end
 
+# Count the nested SSH sessions for the hostname module, in variables that ssh forwards like
+# the locale. A connection is only counted once, by the first shell started by it.
+if set -q SSH_CONNECTION; and test "$LC_STARSHIP_SSH_CONNECTION" != "$SSH_CONNECTION"
+    string match -qr '^[0-9]+$' -- "$LC_STARSHIP_SSH_DEPTH"; or set LC_STARSHIP_SSH_DEPTH 0
+    set -gx LC_STARSHIP_SSH_DEPTH (math $LC_STARSHIP_SSH_DEPTH + 1)
+    set -gx LC_STARSHIP_SSH_CONNECTION "$SSH_CONNECTION"
+end
+
+# Record the SHLVL of the first shell in this terminal, or tmux pane, for the shlvl module to
+# only count the shells nested in it. Terminals are told apart by their tty.
+set -l starship_tty (tty 2>/dev/null)
+if test "$STARSHIP_SHLVL_TTY" != "$starship_tty"; or not string match -qr '^[0-9]+$' -- "$STARSHIP_SHLVL_BASE"
+    set -gx STARSHIP_SHLVL_BASE "$SHLVL"
+    set -gx STARSHIP_SHLVL_TTY "$starship_tty"
+end
+
 # Set up the session key that will be used to store logs
 # We don't use `random [min] [max]` because it is unavailable in older versions of fish shell
 set -gx STARSHIP_SESSION_KEY (string sub -s1 -l16 (random)(random)(random)(random)(random)0000000000000000)
+
+# Detect the terminal background once per session, for `palette_light` and `palette_dark`
+set -gx STARSHIP_BACKGROUND (::STARSHIP:: background)
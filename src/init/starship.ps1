@@ -24,7 +24,7 @@ $null = New-Module starship {
     }
 
     function Invoke-Native {
-        param($Executable, $Arguments)
+        param($Executable, $Arguments, $Environment = @{})
         $startInfo = New-Object System.Diagnostics.ProcessStartInfo -ArgumentList $Executable -Property @{
             StandardOutputEncoding = [System.Text.Encoding]::UTF8;
             RedirectStandardOutput = $true;
@@ -51,6 +51,9 @@ $null = New-Module starship {
             }
             $startInfo.Arguments = $escaped -Join ' ';
         }
+        foreach ($name in $Environment.Keys) {
+            $startInfo.EnvironmentVariables[$name] = $Environment[$name]
+        }
         $process = [System.Diagnostics.Process]::Start($startInfo)
 
         # Read the output and error streams asynchronously
@@ -70,19 +73,32 @@ $null = New-Module starship {
         $stdout.Result;
     }
 
-    function Enable-TransientPrompt {
+    # Writes an OSC 133 mark, which tells the terminal where prompts, commands and their output are
+    function Get-PromptMark {
+        param($Mark)
+        "$([char]0x1B)]133;$Mark$([char]7)"
+    }
+
+    # Binds Enter to redraw the prompt as a transient prompt and to mark the start of the output of the
+    # command, when either is enabled
+    function Set-EnterKeyHandler {
+        if (-not ($script:TransientPromptEnabled -or $script:SemanticPromptEnabled)) {
+            Set-PSReadLineKeyHandler -Key Enter -Function AcceptLine
+            return
+        }
+
         Set-PSReadLineKeyHandler -Key Enter -ScriptBlock {
             $previousOutputEncoding = [Console]::OutputEncoding
             try {
                 $parseErrors = $null
                 [Microsoft.PowerShell.PSConsoleReadLine]::GetBufferState([ref]$null, [ref]$null, [ref]$parseErrors, [ref]$null)
-                if ($parseErrors.Count -eq 0) {
+                if ($script:TransientPromptEnabled -and $parseErrors.Count -eq 0) {
                     $script:TransientPrompt = $true
                     [Console]::OutputEncoding = [Text.Encoding]::UTF8
                     [Microsoft.PowerShell.PSConsoleReadLine]::InvokePrompt()
                 }
             } finally {
-                if ($script:DoesUseLists) {
+                if ($script:TransientPromptEnabled -and $script:DoesUseLists) {
                     # If PSReadline is set to display suggestion list, this workaround is needed to clear the buffer below
                     # before accepting the current commandline. The max amount of items in the list is 10, so 12 lines
                     # are cleared (10 + 1 more for the prompt + 1 more for current commandline).
@@ -90,14 +106,49 @@ $null = New-Module starship {
                     [Microsoft.PowerShell.PSConsoleReadLine]::Undo()
                 }
                 [Microsoft.PowerShell.PSConsoleReadLine]::AcceptLine()
+                if ($script:SemanticPromptEnabled) {
+                    [Console]::Write((Get-PromptMark "C"))
+                }
                 [Console]::OutputEncoding = $previousOutputEncoding
             }
         }
     }
 
+    # Renders the continuation prompt for the width of the terminal, unless it was already rendered for it
+    function Set-ContinuationPrompt {
+        $width = $Host.UI.RawUI.WindowSize.Width
+        if ($script:ContinuationPromptWidth -eq $width) {
+            return
+        }
+        $script:ContinuationPromptWidth = $width
+        Set-PSReadLineOption -ContinuationPrompt (
+            Invoke-Native -Executable ::STARSHIP:: -Arguments @(
+                "prompt",
+                "--continuation",
+                "--terminal-width=$($width)"
+            )
+        )
+    }
+
+    function Enable-TransientPrompt {
+        $script:TransientPromptEnabled = $true
+        Set-EnterKeyHandler
+    }
+
     function Disable-TransientPrompt {
-        Set-PSReadLineKeyHandler -Key Enter -Function AcceptLine
+        $script:TransientPromptEnabled = $false
         $script:TransientPrompt = $false
+        Set-EnterKeyHandler
+    }
+
+    function Enable-SemanticPrompt {
+        $script:SemanticPromptEnabled = $true
+        Set-EnterKeyHandler
+    }
+
+    function Disable-SemanticPrompt {
+        $script:SemanticPromptEnabled = $false
+        Set-EnterKeyHandler
     }
 
     function global:prompt {
@@ -112,7 +163,8 @@ $null = New-Module starship {
         } catch {}
 
         # @ makes sure the result is an array even if single or no values are returned
-        $jobs = @(Get-Job | Where-Object { $_.State -eq 'Running' }).Count
+        $runningJobs = @(Get-Job | Where-Object { $_.State -eq 'Running' })
+        $jobs = $runningJobs.Count
 
         $cwd = Get-Cwd
         $arguments = @(
@@ -122,6 +174,11 @@ $null = New-Module starship {
             "--terminal-width=$($Host.UI.RawUI.WindowSize.Width)",
             "--jobs=$($jobs)"
         )
+        if ($jobs -gt 0) {
+            $arguments += "--job-names=$(($runningJobs | ForEach-Object { $_.Command }) -join "`n")"
+        }
+
+        $environment = @{}
 
         # We start from the premise that the command executed correctly, which covers also the fresh console.
         $lastExitCodeForPrompt = 0
@@ -137,6 +194,11 @@ $null = New-Module starship {
             $duration = [math]::Round(($lastCmd.EndExecutionTime - $lastCmd.StartExecutionTime).TotalMilliseconds)
 
             $arguments += "--cmd-duration=$($duration)"
+            # The command line, if cmd_duration uses it, which is passed in the environment
+            # rather than the arguments, where other processes can see it
+            if ($::PASS_LAST_COMMAND::) {
+                $environment["STARSHIP_LAST_COMMAND"] = $lastCmd.CommandLine
+            }
         }
 
         $arguments += "--status=$($lastExitCodeForPrompt)"
@@ -154,12 +216,26 @@ $null = New-Module starship {
                 "$([char]0x1B)[1;32m❯$([char]0x1B)[0m "
             }
         } else {
-            Invoke-Native -Executable ::STARSHIP:: -Arguments $arguments
+            Invoke-Native -Executable ::STARSHIP:: -Arguments $arguments -Environment $environment
         }
 
+        # Render the continuation prompt again if the terminal was resized, so `fill` spans its width
+        Set-ContinuationPrompt
+
         # Set the number of extra lines in the prompt for PSReadLine prompt redraw.
         Set-PSReadLineOption -ExtraPromptLineCount ($promptText.Split("`n").Length - 1)
 
+        if ($script:SemanticPromptEnabled) {
+            # Mark the end of the previous command, if one was run since the last prompt, and the
+            # start and end of the prompt.
+            $marks = ""
+            if ($lastCmd -and $lastCmd.Id -ne $script:LastHistoryId) {
+                $script:LastHistoryId = $lastCmd.Id
+                $marks += Get-PromptMark "D;$lastExitCodeForPrompt"
+            }
+            $promptText = $marks + (Get-PromptMark "A") + $promptText + (Get-PromptMark "B")
+        }
+
         # Return the prompt
         $promptText
 
@@ -191,6 +267,10 @@ $null = New-Module starship {
     $ENV:VIRTUAL_ENV_DISABLE_PROMPT=1
 
     $script:TransientPrompt = $false
+    $script:TransientPromptEnabled = $false
+    $script:SemanticPromptEnabled = $false
+    $script:LastHistoryId = -1
+    $script:ContinuationPromptWidth = $null
     $script:DoesUseLists = (Get-PSReadLineOption).PredictionViewStyle -eq 'ListView'
 
     if ($PSVersionTable.PSVersion.Major -gt 5) {
@@ -198,17 +278,16 @@ $null = New-Module starship {
     } else {
         $ENV:STARSHIP_SHELL = "powershell"
     }
+    $ENV:STARSHIP_SHELL_VERSION = $PSVersionTable.PSVersion.ToString()
 
     # Set up the session key that will be used to store logs
     $ENV:STARSHIP_SESSION_KEY = -join ((48..57) + (65..90) + (97..122) | Get-Random -Count 16 | ForEach-Object { [char]$_ })
 
+    # Detect the terminal background once per session, for `palette_light` and `palette_dark`
+    $ENV:STARSHIP_BACKGROUND = "$(Invoke-Native -Executable ::STARSHIP:: -Arguments @("background"))".Trim()
+
     # Invoke Starship and set continuation prompt
-    Set-PSReadLineOption -ContinuationPrompt (
-        Invoke-Native -Executable ::STARSHIP:: -Arguments @(
-            "prompt",
-            "--continuation"
-        )
-    )
+    Set-ContinuationPrompt
 
     try {
         # Combine user defined ViModeChangeHandler if it exists
@@ -231,5 +310,7 @@ $null = New-Module starship {
     Export-ModuleMember -Function @(
         "Enable-TransientPrompt"
         "Disable-TransientPrompt"
+        "Enable-SemanticPrompt"
+        "Disable-SemanticPrompt"
     )
 }
@@ -0,0 +1,47 @@
+# Renders the modules listed in `async_modules` in the background once the prompt
+# is drawn without them, and repaints the prompt with their output when they are
+# done. The output is passed back to starship in STARSHIP_ASYNC_SEGMENTS.
+set -g __starship_async --phase=1
+set -g __starship_async_id 0
+# The background render writes its output to this file, then signals the shell with SIGUSR1
+set -g __starship_async_file (command mktemp -t starship_async.XXXXXX)
+
+# Repaints don't start a new prompt, so the modules are only rendered once per prompt
+function __starship_async_new_prompt --on-event fish_prompt
+    set -g __starship_async_pending 1
+    set -e STARSHIP_ASYNC_SEGMENTS
+end
+
+function __starship_async_render --description 'Render the async modules in the background'
+    set -q __starship_async_pending; or return
+    set -e __starship_async_pending
+
+    # The id tells the output of this prompt apart from that of a previous one still running
+    set -g __starship_async_id (math $__starship_async_id + 1)
+    set -l file (string escape -- $__starship_async_file)
+    # The output is moved into place once complete, and dropped if the shell has exited
+    fish --no-config --command "
+        begin
+            echo $__starship_async_id
+            ::STARSHIP:: prompt --phase=2 "(string escape -- $argv | string join " ")"
+        end >$file.$__starship_async_id
+        if kill -0 $fish_pid 2>/dev/null
+            mv $file.$__starship_async_id $file
+            kill -s USR1 $fish_pid
+        else
+            rm -f $file.$__starship_async_id
+        end" &
+    disown
+end
+
+function __starship_async_done --on-signal SIGUSR1
+    set -l result (command cat $__starship_async_file 2>/dev/null)
+    test "$result[1]" = "$__starship_async_id"; or return
+
+    set -gx STARSHIP_ASYNC_SEGMENTS $result[2..-1]
+    commandline -f repaint
+end
+
+function __starship_async_cleanup --on-event fish_exit
+    command rm -f $__starship_async_file $__starship_async_file.*
+end
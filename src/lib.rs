@@ -9,17 +9,22 @@ shadow!(shadow);
 
 // Lib is present to allow for benchmarking
 pub mod bug_report;
+pub mod cache;
 pub mod config;
 pub mod configs;
+pub mod configurator;
 pub mod configure;
 pub mod context;
+pub mod daemon;
 pub mod formatter;
 pub mod init;
+pub mod lint;
 pub mod logger;
 pub mod module;
 mod modules;
 pub mod print;
 mod segment;
+pub mod timer;
 mod utils;
 
 #[cfg(test)]
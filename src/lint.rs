@@ -0,0 +1,364 @@
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::process;
+
+use serde::Deserialize;
+use serde::de::value::Error as ValueError;
+use toml_edit::{Item, TableLike};
+
+use crate::config::{NARROW_FORMAT_KEY, StarshipConfig, parse_style_string};
+use crate::configs::{FullConfig, StarshipRootConfig};
+use crate::configure::MIGRATIONS;
+use crate::context::Context;
+use crate::formatter::StringFormatter;
+use crate::utils::serde::ValueDeserializer;
+
+/// Options that are still supported, but should be replaced, with a hint on how to do so
+const DEPRECATED_OPTIONS: &[(&str, &str)] = &[
+    (
+        "jobs.threshold",
+        "use `symbol_threshold` and `number_threshold` instead",
+    ),
+    (
+        "kubernetes.context_aliases",
+        "use `contexts` with `context_alias` instead",
+    ),
+    (
+        "kubernetes.user_aliases",
+        "use `contexts` with `user_alias` instead",
+    ),
+];
+
+/// A problem found in the config file, at the byte range `span` if it could be located
+#[derive(Debug, PartialEq)]
+struct Diagnostic {
+    span: Option<Range<usize>>,
+    message: String,
+}
+
+/// Checks the config file for problems and prints them, exiting with an error code if any
+/// were found
+pub fn lint_configuration(context: &Context, config_path: Option<OsString>) {
+    let Some(config_path) = config_path.or_else(|| context.get_config_path_os()) else {
+        eprintln!("config path required to lint configuration");
+        process::exit(1);
+    };
+    let path = config_path.to_string_lossy();
+    let Some(content) = StarshipConfig::read_config_content_as_str(Some(&config_path)) else {
+        eprintln!("Unable to read config file {path}");
+        process::exit(1);
+    };
+
+    let diagnostics = lint(&content, context);
+    print!("{}", format_diagnostics(&path, &content, &diagnostics));
+    if !diagnostics.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn lint(content: &str, context: &Context) -> Vec<Diagnostic> {
+    let doc = match toml_edit::Document::parse(content) {
+        Ok(doc) => doc,
+        Err(error) => {
+            return vec![Diagnostic {
+                span: error.span(),
+                message: format!("invalid TOML: {}", error.message()),
+            }];
+        }
+    };
+    let config: toml::Table = match toml::from_str(content) {
+        Ok(config) => config,
+        Err(error) => {
+            return vec![Diagnostic {
+                span: error.span(),
+                message: format!("invalid TOML: {}", error.message()),
+            }];
+        }
+    };
+
+    let mut diagnostics = deserialization_diagnostics(doc.as_table(), config);
+    lint_values(doc.as_table(), &mut vec![], context, &mut diagnostics);
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.as_ref().map(|span| span.start));
+    diagnostics
+}
+
+/// Reports the unknown keys and values of the wrong type, by repeatedly loading the config
+/// strictly and removing the key of each error from it
+fn deserialization_diagnostics(doc: &dyn TableLike, mut config: toml::Table) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    // Every error removes a key, so this always ends
+    while let Err(error) = load_strictly(&config) {
+        let message = error.to_string();
+        let (path, reason) = split_error(&message);
+        let Some((key, parents)) = path.split_last() else {
+            diagnostics.push(Diagnostic {
+                span: None,
+                message: reason,
+            });
+            break;
+        };
+
+        let parent = parents.iter().try_fold(&mut config, |table, key| {
+            table.get_mut(*key)?.as_table_mut()
+        });
+        if parent.and_then(|parent| parent.remove(*key)).is_none() {
+            diagnostics.push(Diagnostic {
+                span: None,
+                message: format!("`{}`: {reason}", path.join(".")),
+            });
+            break;
+        }
+
+        let reason = reason.replace("Unknown key", "unknown key");
+        diagnostics.push(Diagnostic {
+            span: find_span(doc, &path),
+            message: format!("`{}`: {reason}", path.join(".")),
+        });
+    }
+
+    diagnostics
+}
+
+/// Loads the config, failing on unknown keys. Unknown top-level keys are only caught when
+/// loading the root config on its own, as `FullConfig` flattens it.
+fn load_strictly(config: &toml::Table) -> Result<(), ValueError> {
+    FullConfig::deserialize(ValueDeserializer::new(config))?;
+    StarshipRootConfig::deserialize(ValueDeserializer::new(config))?;
+    Ok(())
+}
+
+/// Splits the nested `Error in 'Struct' at 'key': ` prefixes off an error message, returning
+/// the path of keys they make up and the remaining reason
+fn split_error(message: &str) -> (Vec<&str>, String) {
+    let mut path = Vec::new();
+    let mut rest = message;
+    while let Some((key, remaining)) = rest
+        .strip_prefix("Error in '")
+        .and_then(|rest| rest.split_once("' at '"))
+        .and_then(|(_, rest)| rest.split_once("': "))
+    {
+        path.push(key);
+        rest = remaining;
+    }
+    (path, rest.to_string())
+}
+
+/// Returns the span of the key at `path`
+fn find_span(doc: &dyn TableLike, path: &[&str]) -> Option<Range<usize>> {
+    let (key, parents) = path.split_last()?;
+    let table = parents
+        .iter()
+        .try_fold(doc, |table, key| table.get(key)?.as_table_like())?;
+    let (key, item) = table.get_key_value(key)?;
+    key.span().or_else(|| item.span())
+}
+
+/// Reports invalid style and format strings, deprecated options and renamed options
+fn lint_values<'a>(
+    table: &'a dyn TableLike,
+    path: &mut Vec<&'a str>,
+    context: &Context,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (name, item) in table.iter() {
+        path.push(name);
+        let span = table
+            .get_key_value(name)
+            .and_then(|(key, item)| key.span().or_else(|| item.span()));
+        let full_name = path.join(".");
+
+        if let Some((_, hint)) = DEPRECATED_OPTIONS.iter().find(|(key, _)| *key == full_name) {
+            diagnostics.push(Diagnostic {
+                span: span.clone(),
+                message: format!("`{full_name}` is deprecated, {hint}"),
+            });
+        }
+
+        if let Some(new_path) = MIGRATIONS
+            .iter()
+            .find_map(|migration| migration.migrate_path(path))
+        {
+            diagnostics.push(Diagnostic {
+                span: span.clone(),
+                message: format!(
+                    "`{full_name}` was renamed to `{}`, run `starship config migrate` to update it",
+                    new_path.join(".")
+                ),
+            });
+        }
+
+        if let Some(table) = item.as_table_like() {
+            lint_values(table, path, context, diagnostics);
+        } else if let Some(value) = item.as_str() {
+            if let Some(message) = lint_string(path, value, context) {
+                diagnostics.push(Diagnostic {
+                    span: item.span().or(span),
+                    message: format!("`{full_name}`: {message}"),
+                });
+            }
+        } else if let Item::Value(toml_edit::Value::Array(array)) = item
+            && (name == "format_lines" || name == "right_format_lines")
+        {
+            for value in array {
+                if let Some(message) = value.as_str().and_then(lint_format) {
+                    diagnostics.push(Diagnostic {
+                        span: value.span(),
+                        message: format!("`{full_name}`: {message}"),
+                    });
+                }
+            }
+        }
+
+        path.pop();
+    }
+}
+
+fn lint_string(path: &[&str], value: &str, context: &Context) -> Option<String> {
+    let name = path.last()?;
+    if *name == "style" || name.ends_with("_style") {
+        lint_style(value, context)
+    } else if *name == "format"
+        || name.ends_with("_format")
+        || *name == NARROW_FORMAT_KEY
+        || *name == "continuation_prompt"
+        || path.len() == 2 && path[0] == "profiles"
+    {
+        lint_format(value)
+    } else {
+        None
+    }
+}
+
+fn lint_style(style: &str, context: &Context) -> Option<String> {
+    let invalid: Vec<_> = style
+        .split_whitespace()
+        .filter(|token| {
+            !token.eq_ignore_ascii_case("none")
+                && !token.eq_ignore_ascii_case("fg:none")
+                && parse_style_string(token, Some(context)).is_none()
+        })
+        .map(|token| format!("'{token}'"))
+        .collect();
+
+    (!invalid.is_empty()).then(|| format!("invalid style {}", invalid.join(", ")))
+}
+
+fn lint_format(format: &str) -> Option<String> {
+    StringFormatter::new(format)
+        .err()
+        .map(|error| format!("invalid format string: {error}"))
+}
+
+fn format_diagnostics(path: &str, content: &str, diagnostics: &[Diagnostic]) -> String {
+    let mut output = String::new();
+    for diagnostic in diagnostics {
+        match &diagnostic.span {
+            Some(span) => {
+                let before = &content[..span.start];
+                let line = before.matches('\n').count() + 1;
+                let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+                let _ = writeln!(output, "{path}:{line}:{column}: {}", diagnostic.message);
+            }
+            None => {
+                let _ = writeln!(output, "{path}: {}", diagnostic.message);
+            }
+        }
+    }
+
+    match diagnostics.len() {
+        0 => output.push_str("No problems found\n"),
+        1 => output.push_str("1 problem found\n"),
+        count => {
+            let _ = writeln!(output, "{count} problems found");
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    fn lint_to_string(content: &str) -> String {
+        let diagnostics = lint(content, &default_context());
+        format_diagnostics("starship.toml", content, &diagnostics)
+    }
+
+    #[test]
+    fn valid_config() {
+        let content =
+            "format = '$all'\n\n[directory]\ntruncation_length = 5\nstyle = 'bold cyan'\n";
+        assert_eq!(lint_to_string(content), "No problems found\n");
+    }
+
+    #[test]
+    fn unknown_keys_and_wrong_types() {
+        let content =
+            "[directory]\ntruncation_lenght = 5\nstyle = 42\n\n[unknown_module]\nformat = ''\n";
+        assert_eq!(
+            lint_to_string(content),
+            concat!(
+                "starship.toml:2:1: `directory.truncation_lenght`: unknown key (Did you mean 'truncation_length'?)\n",
+                "starship.toml:3:1: `directory.style`: invalid type: integer `42`, expected a borrowed string\n",
+                "starship.toml:5:2: `unknown_module`: unknown key\n",
+                "3 problems found\n"
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_styles_and_formats() {
+        let content = "format = '[$all'\n[git_branch]\nstyle = 'bold grean'\n[character]\nsuccess_symbol = '[>](fg:none)'\n";
+        let output = lint_to_string(content);
+        assert!(output.starts_with("starship.toml:1:10: `format`: invalid format string:"));
+        assert!(output.contains("starship.toml:3:9: `git_branch.style`: invalid style 'grean'\n"));
+        assert!(output.ends_with("2 problems found\n"));
+    }
+
+    #[test]
+    fn narrow_formats() {
+        let content = "width_threshold = 80\n[character]\nformat_narrow = '$symbol'\n[directory]\nformat_narrow = '[$path'\n";
+        let output = lint_to_string(content);
+        assert!(
+            output.starts_with(
+                "starship.toml:5:17: `directory.format_narrow`: invalid format string:"
+            )
+        );
+        assert!(output.ends_with("1 problem found\n"));
+    }
+
+    #[test]
+    fn deprecated_options() {
+        let content = "[jobs]\nthreshold = 2\n";
+        assert_eq!(
+            lint_to_string(content),
+            concat!(
+                "starship.toml:2:1: `jobs.threshold` is deprecated, use `symbol_threshold` and `number_threshold` instead\n",
+                "1 problem found\n"
+            )
+        );
+    }
+
+    #[test]
+    fn renamed_options() {
+        let content = "[character]\nvicmd_symbol = '<'\n";
+        assert_eq!(
+            lint_to_string(content),
+            concat!(
+                "starship.toml:2:1: `character.vicmd_symbol` was renamed to `character.vimcmd_symbol`, run `starship config migrate` to update it\n",
+                "1 problem found\n"
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_toml() {
+        let content = "[directory\n";
+        let output = lint_to_string(content);
+        assert!(output.starts_with("starship.toml:1:"));
+        assert!(output.contains("invalid TOML"));
+    }
+}
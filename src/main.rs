@@ -8,9 +8,12 @@ use std::time::SystemTime;
 use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::generate;
 use rand::RngExt;
-use starship::context::{Context, Properties, Target};
+use starship::context::{Context, OutputTarget, PromptArgs, Properties, Target};
 use starship::module::ALL_MODULES;
-use starship::{bug_report, configure, init, logger, num_rayon_threads, print, shadow};
+use starship::{
+    bug_report, cache, configurator, configure, daemon, init, lint, logger, num_rayon_threads,
+    print, shadow, timer,
+};
 
 #[derive(Parser, Debug)]
 #[clap(
@@ -63,23 +66,92 @@ enum Statuslines {
     ClaudeCode,
 }
 
+#[derive(Subcommand, Debug)]
+enum CacheCommands {
+    /// Remove the cached output of version probes and modules
+    Clear,
+}
+
+#[derive(Subcommand, Debug)]
+enum TimerCommands {
+    /// Start a timer shown by the `timer` module, replacing the running one
+    Start {
+        /// The length of the timer, such as `25m` or `1h30m`
+        duration: String,
+        /// A label shown next to the remaining time
+        #[clap(long)]
+        label: Option<String>,
+    },
+    /// Stop the running timer, or hide the expired one
+    Stop,
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Print the value of a configuration key
+    Get {
+        /// Configuration key to print
+        name: String,
+    },
+    /// Set a configuration key, checking that the value is valid
+    Set {
+        /// Configuration key to edit
+        name: String,
+        /// Value to place into that key
+        value: String,
+    },
+    /// Remove a configuration key, restoring its default value
+    Unset {
+        /// Configuration key to remove
+        name: String,
+    },
+    /// Check the configuration for unknown keys, invalid values and deprecated options
+    Lint {
+        /// The configuration file to check, defaults to the active one
+        path: Option<PathBuf>,
+    },
+    /// Rename the options of the configuration that were renamed or moved, keeping its comments
+    Migrate,
+    /// Allow the `.starship.toml` files within a directory to override the configuration
+    Trust {
+        /// The directory to trust, defaults to the current directory
+        path: Option<PathBuf>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
+    /// Detect whether the terminal background is light or dark, for `palette_light` and
+    /// `palette_dark`
+    Background,
     /// Create a pre-populated GitHub issue with information about your configuration
     BugReport,
+    /// Manage the caches of starship
+    Cache {
+        #[clap(subcommand)]
+        command: CacheCommands,
+    },
     /// Generate starship shell completions for your shell to stdout
     Completions {
         #[clap(value_enum)]
         shell: CompletionShell,
     },
     /// Edit the starship configuration
+    #[clap(args_conflicts_with_subcommands = true)]
     Config {
+        #[clap(subcommand)]
+        command: Option<ConfigCommands>,
         /// Configuration key to edit
         #[clap(requires = "value")]
         name: Option<String>,
         /// Value to place into that key
         value: Option<String>,
+        /// Edit the configuration in an interactive terminal UI, not supported on Windows
+        #[clap(long, conflicts_with = "name")]
+        tui: bool,
     },
+    /// Render prompts in a long-running process for `starship prompt --via-daemon`
+    Daemon,
     /// Explains the currently showing modules
     Explain(Properties),
     ///  Prints the shell function used to execute starship
@@ -96,17 +168,23 @@ enum Commands {
         /// List out all supported modules
         #[clap(short, long)]
         list: bool,
+        /// Print the segments of the module as JSON, with their styles
+        #[clap(long, conflicts_with = "list")]
+        json: bool,
         #[clap(flatten)]
         properties: Properties,
     },
     /// Prints a preset config
     Preset {
-        /// The name of preset to be printed
+        /// The names of the presets to be printed, later presets override earlier ones
         #[clap(required_unless_present("list"), value_enum)]
-        name: Option<print::Preset>,
+        name: Vec<print::Preset>,
         /// Output the preset to a file instead of stdout
         #[clap(short, long, conflicts_with = "list")]
         output: Option<PathBuf>,
+        /// Merge the preset into the current configuration instead of printing it
+        #[clap(short, long, conflicts_with = "list", conflicts_with = "output")]
+        apply: bool,
         /// List out all preset names
         #[clap(short, long)]
         list: bool,
@@ -119,20 +197,29 @@ enum Commands {
         /// Configuration keys to print
         name: Vec<String>,
     },
-    /// Prints the full starship prompt
-    Prompt {
-        /// Print the right prompt (instead of the standard left prompt)
+    /// Renders the prompt as HTML or SVG, for screenshots in documentation
+    Print {
+        /// The format to render the prompt in
+        #[clap(long, value_enum)]
+        format: print::RenderFormat,
+        /// Render the right prompt (instead of the standard left prompt)
         #[clap(long)]
         right: bool,
-        /// Print the prompt with the specified profile name (instead of the standard left prompt)
-        #[clap(long, conflicts_with = "right")]
-        profile: Option<String>,
-        /// Print the continuation prompt (instead of the standard left prompt)
-        #[clap(long, conflicts_with = "right", conflicts_with = "profile")]
-        continuation: bool,
         #[clap(flatten)]
         properties: Properties,
     },
+    /// Prints the full starship prompt
+    Prompt {
+        #[clap(flatten)]
+        args: PromptArgs,
+        /// Ask `starship daemon` for the prompt, rendering it here if it isn't running.
+        /// Also enabled by setting `STARSHIP_DAEMON=1`.
+        #[clap(long)]
+        via_daemon: bool,
+        /// Print the segments of the prompt as JSON, with their styles and modules
+        #[clap(long, conflicts_with = "via_daemon")]
+        json: bool,
+    },
     /// Generate random session key
     Session,
     /// Prints the statusline with a specific profile
@@ -147,8 +234,19 @@ enum Commands {
     /// Prints time in milliseconds
     #[clap(hide = true)]
     Time,
+    /// Manage the timer shown by the `timer` module, e.g. for pomodoros
+    Timer {
+        #[clap(subcommand)]
+        command: TimerCommands,
+    },
     /// Prints timings of all active modules
-    Timings(Properties),
+    Timings {
+        /// The output format, `json` and `trace` include the steps of each module
+        #[clap(long, value_enum, default_value_t)]
+        format: print::TimingsFormat,
+        #[clap(flatten)]
+        properties: Properties,
+    },
     /// Toggle a given starship module
     Toggle {
         /// The name of the module to be toggled
@@ -221,23 +319,45 @@ fn main() {
                 init::init_stub(&shell).expect("can't init_stub");
             }
         }
-        Commands::Prompt {
-            properties,
+        Commands::Print {
+            format,
             right,
-            profile,
-            continuation,
+            properties,
         } => {
-            let target = match (right, profile, continuation) {
-                (true, _, _) => Target::Right,
-                (_, Some(profile_name), _) => Target::Profile(profile_name),
-                (_, _, true) => Target::Continuation,
-                (_, _, _) => Target::Main,
-            };
-            print::prompt(properties, target);
+            let target = if right { Target::Right } else { Target::Main };
+            print::print_rendered(properties, target, format);
+        }
+        Commands::Prompt {
+            args,
+            via_daemon: _,
+            json: true,
+        } => {
+            let (properties, target) = args.into_parts();
+            print::prompt_json(properties, target);
+        }
+        Commands::Prompt {
+            args,
+            via_daemon,
+            json: false,
+        } => {
+            let via_daemon =
+                via_daemon || std::env::var_os("STARSHIP_DAEMON").is_some_and(|v| v == "1");
+            if via_daemon && let Some(prompt) = daemon::request_prompt() {
+                print!("{prompt}");
+                return;
+            }
+
+            let output = args.target;
+            let (properties, target) = args.into_parts();
+            match output {
+                OutputTarget::Shell => print::prompt(properties, target),
+                OutputTarget::Tmux => print::prompt_tmux(properties, target),
+            }
         }
         Commands::Module {
             name,
             list,
+            json,
             properties,
         } => {
             if list {
@@ -248,11 +368,44 @@ fn main() {
                 }
             }
             if let Some(module_name) = name {
-                print::module(&module_name, properties);
+                if json {
+                    print::module_json(&module_name, properties);
+                } else {
+                    print::module(&module_name, properties);
+                }
             }
         }
-        Commands::Preset { name, list, output } => print::preset_command(name, output, list),
-        Commands::Config { name, value } => {
+        Commands::Preset {
+            name,
+            list,
+            output,
+            apply,
+        } => print::preset_command(&name, output, list, apply),
+        Commands::Config {
+            command: Some(command),
+            ..
+        } => {
+            let context = Context::default();
+            match command {
+                ConfigCommands::Get { name } => {
+                    configure::print_configuration_value(&context, &name)
+                }
+                ConfigCommands::Set { name, value } => {
+                    configure::update_configuration(&context, &name, &value);
+                }
+                ConfigCommands::Unset { name } => configure::unset_configuration(&context, &name),
+                ConfigCommands::Lint { path } => {
+                    lint::lint_configuration(&context, path.map(PathBuf::into_os_string));
+                }
+                ConfigCommands::Migrate => configure::migrate_configuration(&context),
+                ConfigCommands::Trust { path } => {
+                    let path = path.unwrap_or_else(|| PathBuf::from("."));
+                    configure::trust_path(&context, &path);
+                }
+            }
+        }
+        Commands::Config { tui: true, .. } => configurator::run(Context::default()),
+        Commands::Config { name, value, .. } => {
             let context = Context::default();
             if let Some(name) = name {
                 if let Some(value) = value {
@@ -263,6 +416,7 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Commands::Daemon => daemon::run(),
         Commands::PrintConfig { default, name } => {
             configure::print_configuration(&Context::default(), default, &name);
         }
@@ -270,6 +424,9 @@ fn main() {
             configure::toggle_configuration(&Context::default(), &name, &value);
         }
         Commands::BugReport => bug_report::create(),
+        Commands::Cache {
+            command: CacheCommands::Clear,
+        } => cache::clear(&Context::default()),
         Commands::Time => {
             match SystemTime::now()
                 .duration_since(SystemTime::UNIX_EPOCH)
@@ -279,9 +436,16 @@ fn main() {
                 None => println!("{}", -1),
             }
         }
+        Commands::Timer { command } => match command {
+            TimerCommands::Start { duration, label } => {
+                timer::start(&Context::default(), &duration, label);
+            }
+            TimerCommands::Stop => timer::stop(&Context::default()),
+        },
         Commands::Explain(props) => print::explain(props),
-        Commands::Timings(props) => print::timings(props),
+        Commands::Timings { format, properties } => print::timings(properties, format),
         Commands::Completions { shell } => generate_completions(shell),
+        Commands::Background => print::background(),
         Commands::Session => println!(
             "{}",
             rand::rng()
@@ -1,5 +1,6 @@
 use crate::segment;
 use crate::segment::{FillSegment, Segment};
+use crate::utils::timing::ModuleProfile;
 use nu_ansi_term::{AnsiString, AnsiStrings, Style as AnsiStyle};
 use std::fmt;
 use std::time::Duration;
@@ -7,36 +8,45 @@ use std::time::Duration;
 // List of all modules
 // Default ordering is handled in configs/starship_root.rs
 pub const ALL_MODULES: &[&str] = &[
+    "agenda",
     "aws",
     "azure",
+    "azure_devops",
     #[cfg(feature = "battery")]
     "battery",
     "buf",
     "bun",
     "c",
     "character",
+    "ci_status",
     "claude_context",
     "claude_cost",
     "claude_model",
+    "cloud",
     "cmake",
     "cmd_duration",
+    "cms",
     "cobol",
     "conda",
     "container",
     "cpp",
+    "cpu_usage",
     "crystal",
     "daml",
     "dart",
     "deno",
     "directory",
     "direnv",
+    "disk_usage",
     "docker_context",
     "dotnet",
     "elixir",
     "elm",
+    "embedded",
     "erlang",
     "fennel",
     "fill",
+    "firebase",
     "fortran",
     "fossil_branch",
     "fossil_metrics",
@@ -47,7 +57,9 @@ pub const ALL_MODULES: &[&str] = &[
     "git_state",
     "git_status",
     "gleam",
+    "godot",
     "golang",
+    "gpg",
     "gradle",
     "guix_shell",
     "haskell",
@@ -62,6 +74,7 @@ pub const ALL_MODULES: &[&str] = &[
     "kotlin",
     "kubernetes",
     "line_break",
+    "load_avg",
     "localip",
     "lua",
     "maven",
@@ -70,7 +83,9 @@ pub const ALL_MODULES: &[&str] = &[
     "mise",
     "mojo",
     "nats",
+    "netlify",
     "netns",
+    "network",
     "nim",
     "nix_shell",
     "nodejs",
@@ -84,32 +99,44 @@ pub const ALL_MODULES: &[&str] = &[
     "php",
     "pijul_channel",
     "pixi",
+    "platformio",
+    "pull_request",
     "pulumi",
     "purescript",
     "python",
     "quarto",
     "raku",
     "red",
+    "release_pending",
     "rlang",
     "ruby",
     "rust",
     "scala",
+    "secrets_guard",
     "shell",
     "shlvl",
     "singularity",
     "solidity",
     "spack",
+    "ssh_agent",
     "status",
     "sudo",
+    "supabase",
     "swift",
+    #[cfg(feature = "temperature")]
+    "temperature",
     "terraform",
     "time",
+    "timer",
     "typst",
+    "unity",
     "username",
     "vagrant",
     "vcs",
     "vcsh",
+    "vercel",
     "vlang",
+    "wsl",
     "xmake",
     "zig",
 ];
@@ -131,6 +158,9 @@ pub struct Module<'a> {
 
     /// the time it took to compute this module
     pub duration: Duration,
+
+    /// The steps of computing this module, only recorded for `starship timings`
+    pub profile: Option<ModuleProfile>,
 }
 
 impl<'a> Module<'a> {
@@ -146,6 +176,7 @@ impl<'a> Module<'a> {
             description: desc.into(),
             segments: Vec::new(),
             duration: Duration::default(),
+            profile: None,
         }
     }
 
@@ -233,9 +264,7 @@ where
     if chunks.is_empty() {
         current
     } else {
-        let fill_size = term_width
-            .and_then(|tw| if tw > used { Some(tw - used) } else { None })
-            .map(|remaining| remaining / chunks.len());
+        let fill_size = term_width.map(|tw| tw.saturating_sub(used) / chunks.len());
         chunks
             .into_iter()
             .flat_map(|(strs, fill)| {
@@ -271,6 +300,7 @@ mod tests {
             description: desc.to_string(),
             segments: Vec::new(),
             duration: Duration::default(),
+            profile: None,
         };
 
         assert!(module.is_empty());
@@ -286,6 +316,7 @@ mod tests {
             description: desc.to_string(),
             segments: Segment::from_text(None, ""),
             duration: Duration::default(),
+            profile: None,
         };
 
         assert!(module.is_empty());
@@ -301,6 +332,7 @@ mod tests {
             description: desc.to_string(),
             segments: Segment::from_text(None, "\n"),
             duration: Duration::default(),
+            profile: None,
         };
 
         assert!(!module.is_empty());
@@ -316,6 +348,7 @@ mod tests {
             description: desc.to_string(),
             segments: Segment::from_text(None, " "),
             duration: Duration::default(),
+            profile: None,
         };
 
         assert!(!module.is_empty());
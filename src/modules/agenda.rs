@@ -0,0 +1,390 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use jiff::tz::TimeZone;
+use serde::{Deserialize, Serialize};
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::cache;
+use crate::configs::agenda::AgendaConfig;
+use crate::formatter::StringFormatter;
+use crate::utils::read_file;
+
+/// The file in the cache directory holding the events parsed from the calendar file
+pub const CACHE_FILE: &str = "agenda.json";
+
+/// An event of the calendar, starting at a Unix timestamp
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct Event {
+    start: i64,
+    title: String,
+}
+
+/// The events of a calendar file, until it is modified
+#[derive(Deserialize, Serialize)]
+struct Cache {
+    path: PathBuf,
+    modified: SystemTime,
+    size: u64,
+    events: Vec<Event>,
+}
+
+/// Parses the events of an iCalendar file. Recurring events only count their first
+/// occurrence, and all-day and cancelled events are left out.
+fn parse_ics(ics: &str) -> Vec<Event> {
+    // Long lines are folded by starting the next line with a space or a tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut event: Option<(Option<i64>, Option<String>, bool)> = None;
+    for line in &lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match (name.to_ascii_uppercase().as_str(), event.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some((None, None, false));
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                if let Some((Some(start), title, false)) = event.take() {
+                    events.push(Event {
+                        start,
+                        title: title.unwrap_or_default(),
+                    });
+                }
+            }
+            ("DTSTART", Some((start, _, _))) => *start = parse_start(params, value),
+            ("SUMMARY", Some((_, title, _))) => *title = Some(unescape(value)),
+            ("STATUS", Some((_, _, cancelled))) => {
+                *cancelled = value.eq_ignore_ascii_case("CANCELLED");
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Parses the start of an event, e.g. `20240115T093000Z`, in UTC, in the zone of its `TZID`
+/// parameter, or else in the local time zone
+fn parse_start(params: &str, value: &str) -> Option<i64> {
+    let (value, timezone) = match value.strip_suffix(['Z', 'z']) {
+        Some(value) => (value, TimeZone::UTC),
+        None => {
+            let tzid = params.split(';').find_map(|param| {
+                let (name, value) = param.split_once('=')?;
+                name.eq_ignore_ascii_case("TZID")
+                    .then(|| value.trim_matches('"'))
+            });
+            match tzid {
+                Some(tzid) => (value, TimeZone::get(tzid).ok()?),
+                None => (value, TimeZone::system()),
+            }
+        }
+    };
+
+    // All-day events only have a date, such as `20240115`, which doesn't match
+    let datetime = jiff::civil::DateTime::strptime("%Y%m%dT%H%M%S", value).ok()?;
+    Some(datetime.to_zoned(timezone).ok()?.timestamp().as_second())
+}
+
+/// Replaces the escaped characters of a text value, and line breaks with spaces
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        match (char, chars.clone().next()) {
+            ('\\', Some('n' | 'N')) => {
+                chars.next();
+                unescaped.push(' ');
+            }
+            ('\\', Some(escaped @ (',' | ';' | '\\'))) => {
+                chars.next();
+                unescaped.push(escaped);
+            }
+            _ => unescaped.push(char),
+        }
+    }
+    unescaped
+}
+
+/// The events of the calendar file, parsed again only when it was modified since the last
+/// prompt, as told by its modification time and size
+fn load_events(context: &Context, path: &Path) -> Option<Vec<Event>> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?;
+    let size = metadata.len();
+
+    let cache_path = cache::cache_dir(context).map(|dir| dir.join(CACHE_FILE));
+    let cached: Option<Cache> = cache_path
+        .as_ref()
+        .and_then(|cache_path| fs::read_to_string(cache_path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok());
+    if let Some(cached) = cached
+        && cached.path == path
+        && cached.modified == modified
+        && cached.size == size
+    {
+        log::trace!("Using the cached events of {path:?}");
+        return Some(cached.events);
+    }
+
+    let events = parse_ics(&read_file(path).ok()?);
+    if let Some(cache_path) = cache_path {
+        let cache = Cache {
+            path: path.to_path_buf(),
+            modified,
+            size,
+            events,
+        };
+        let saved = serde_json::to_string(&cache)
+            .map_err(|error| error.to_string())
+            .and_then(|content| fs::write(&cache_path, content).map_err(|error| error.to_string()));
+        if let Err(error) = saved {
+            log::debug!("Unable to save the events to {cache_path:?}: {error}");
+        }
+        return Some(cache.events);
+    }
+    Some(events)
+}
+
+/// The first event starting after `now`, and at most `horizon` seconds after it
+fn next_event(events: &[Event], now: i64, horizon: i64) -> Option<&Event> {
+    events
+        .iter()
+        .filter(|event| event.start > now && event.start - now <= horizon)
+        .min_by_key(|event| event.start)
+}
+
+/// Renders the time until an event, rounded up to the minute, e.g. `25m` or `1h05m`
+fn format_countdown(seconds: i64) -> String {
+    let minutes = (seconds + 59) / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h{minutes:02}m"),
+    }
+}
+
+/// Creates a module with the next event of a calendar file and the time until it starts
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("agenda");
+    let config = AgendaConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled || config.path.is_empty() {
+        return None;
+    }
+
+    let path = Context::expand_tilde(PathBuf::from(config.path));
+    let Some(events) = load_events(context, &path) else {
+        log::debug!("Unable to read the calendar file {path:?}");
+        return None;
+    };
+
+    let now = jiff::Timestamp::now().as_second();
+    let horizon = i64::try_from(config.horizon.saturating_mul(60)).unwrap_or(i64::MAX);
+    let event = next_event(&events, now, horizon)?;
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "title" => Some(Ok(event.title.clone())),
+                "countdown" => Some(Ok(format_countdown(event.start - now))),
+                "time" => {
+                    let start = jiff::Timestamp::from_second(event.start).ok()?;
+                    jiff::fmt::strtime::format(
+                        config.time_format,
+                        &start.to_zoned(TimeZone::system()),
+                    )
+                    .map_err(|error| {
+                        log::warn!("Invalid time_format `{}`: {error}", config.time_format);
+                    })
+                    .ok()
+                    .map(Ok)
+                }
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `agenda`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+
+    /// A calendar with an event starting `minutes` from now
+    fn calendar(minutes: i64, title: &str) -> String {
+        let start = jiff::Timestamp::now() + jiff::SignedDuration::from_mins(minutes);
+        format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART:{}\r\nSUMMARY:{title}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            start.strftime("%Y%m%dT%H%M%SZ")
+        )
+    }
+
+    #[test]
+    fn parses_events() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VTIMEZONE\r\nTZID:America/New_York\r\nEND:VTIMEZONE\r\n\
+                   BEGIN:VEVENT\r\nDTSTART;TZID=America/New_York:20240115T090000\r\n\
+                   SUMMARY:Stand-up\\, team\r\n  sync\r\nEND:VEVENT\r\n\
+                   BEGIN:VEVENT\r\nSUMMARY:Review\r\nDTSTART:20240115T160000Z\r\nEND:VEVENT\r\n\
+                   BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20240115\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\n\
+                   BEGIN:VEVENT\r\nDTSTART:20240115T170000Z\r\nSUMMARY:Gone\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+        assert_eq!(
+            parse_ics(ics),
+            vec![
+                Event {
+                    start: 1_705_327_200,
+                    title: "Stand-up, team sync".to_string(),
+                },
+                Event {
+                    start: 1_705_334_400,
+                    title: "Review".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn next_event_within_horizon() {
+        let events = [
+            Event {
+                start: 100,
+                title: "past".to_string(),
+            },
+            Event {
+                start: 900,
+                title: "later".to_string(),
+            },
+            Event {
+                start: 400,
+                title: "next".to_string(),
+            },
+        ];
+
+        assert_eq!(next_event(&events, 200, 3600).unwrap().title, "next");
+        assert_eq!(next_event(&events, 200, 60), None);
+        assert_eq!(next_event(&events, 900, 3600), None);
+    }
+
+    #[test]
+    fn countdown() {
+        assert_eq!(format_countdown(1), "1m");
+        assert_eq!(format_countdown(25 * 60), "25m");
+        assert_eq!(format_countdown(60 * 60), "1h");
+        assert_eq!(format_countdown(65 * 60 - 30), "1h05m");
+    }
+
+    #[test]
+    fn unescapes_text() {
+        assert_eq!(unescape(r"a\, b\; c\\d\ne"), r"a, b; c\d e");
+    }
+
+    #[test]
+    fn shows_next_event() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("work.ics");
+        fs::write(&path, calendar(30, "Planning"))?;
+
+        let actual = ModuleRenderer::new("agenda")
+            .config(toml::toml! {
+                [agenda]
+                disabled = false
+                path = (path.to_str().unwrap())
+            })
+            .env("STARSHIP_CACHE", dir.path().to_str().unwrap())
+            .collect();
+        let expected = Some(format!(
+            "{} ",
+            Color::Purple.bold().paint("📅 Planning in 30m")
+        ));
+
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
+    #[test]
+    fn event_beyond_horizon() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("work.ics");
+        fs::write(&path, calendar(90, "Planning"))?;
+
+        let actual = ModuleRenderer::new("agenda")
+            .config(toml::toml! {
+                [agenda]
+                disabled = false
+                path = (path.to_str().unwrap())
+            })
+            .env("STARSHIP_CACHE", dir.path().to_str().unwrap())
+            .collect();
+
+        assert_eq!(actual, None);
+        dir.close()
+    }
+
+    #[test]
+    fn caches_until_file_changes() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("work.ics");
+        let render = || {
+            ModuleRenderer::new("agenda")
+                .config(toml::toml! {
+                    [agenda]
+                    disabled = false
+                    format = "$title"
+                    path = (path.to_str().unwrap())
+                })
+                .env("STARSHIP_CACHE", dir.path().to_str().unwrap())
+                .collect()
+        };
+
+        fs::write(&path, calendar(30, "First"))?;
+        let modified = fs::metadata(&path)?.modified()?;
+        assert_eq!(render(), Some("First".to_string()));
+        assert!(dir.path().join(CACHE_FILE).exists());
+
+        // The file isn't parsed again while its modification time and size are the same
+        fs::write(&path, calendar(30, "Other"))?;
+        fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(modified)?;
+        assert_eq!(render(), Some("First".to_string()));
+
+        fs::write(&path, calendar(30, "Second"))?;
+        assert_eq!(render(), Some("Second".to_string()));
+        dir.close()
+    }
+}
@@ -10,6 +10,7 @@ use sha1::{Digest, Sha1};
 
 use super::{Context, Module, ModuleConfig};
 
+use super::cloud::CloudAccount;
 use crate::configs::aws::AwsConfig;
 use crate::formatter::StringFormatter;
 use crate::utils::render_time;
@@ -84,33 +85,57 @@ fn get_profile_creds<'a>(
     }
 }
 
+// Get the region of a profile in the config file. Profiles without a region inherit it from
+// their `source_profile`, or else from their `sso_session`.
 fn get_aws_region_from_config(
     context: &Context,
     aws_profile: &Option<Profile>,
     aws_config: &AwsConfigFile,
 ) -> Option<Region> {
     let config = get_config(context, aws_config)?;
-    let section = get_profile_config(config, aws_profile.as_ref())?;
-
-    section.get("region").map(std::borrow::ToOwned::to_owned)
+    let mut profile = aws_profile.clone();
+    let mut visited = Vec::new();
+    loop {
+        let section = get_profile_config(config, profile.as_ref())?;
+        if let Some(region) = section.get("region") {
+            return Some(region.to_owned());
+        }
+        visited.push(profile);
+
+        let Some(source_profile) = section.get("source_profile") else {
+            let sso_session =
+                config.section(Some(format!("sso-session {}", section.get("sso_session")?)))?;
+            return sso_session
+                .get("region")
+                .or_else(|| sso_session.get("sso_region"))
+                .map(std::borrow::ToOwned::to_owned);
+        };
+        profile = (source_profile != "default").then(|| source_profile.to_owned());
+        if visited.contains(&profile) {
+            log::warn!("AWS profile {source_profile:?} is its own source_profile");
+            return None;
+        }
+    }
 }
 
+const PROFILE_ENV_VARS: &[&str] = &[
+    "AWSU_PROFILE",
+    "AWS_VAULT",
+    "AWSUME_PROFILE",
+    "AWS_PROFILE",
+    "AWS_SSO_PROFILE",
+    "AWS_DEFAULT_PROFILE",
+];
+const REGION_ENV_VARS: &[&str] = &["AWS_REGION", "AWS_DEFAULT_REGION"];
+
 fn get_aws_profile_and_region(
     context: &Context,
     aws_config: &AwsConfigFile,
 ) -> (Option<Profile>, Option<Region>) {
-    let profile_env_vars = [
-        "AWSU_PROFILE",
-        "AWS_VAULT",
-        "AWSUME_PROFILE",
-        "AWS_PROFILE",
-        "AWS_SSO_PROFILE",
-    ];
-    let region_env_vars = ["AWS_REGION", "AWS_DEFAULT_REGION"];
-    let profile = profile_env_vars
+    let profile = PROFILE_ENV_VARS
         .iter()
         .find_map(|env_var| context.get_env(env_var));
-    let region = region_env_vars
+    let region = REGION_ENV_VARS
         .iter()
         .find_map(|env_var| context.get_env(env_var));
     match (profile, region) {
@@ -261,6 +286,25 @@ fn has_source_profile(
     Some(has_credential_process || has_credentials)
 }
 
+/// The profile and region shown by the `cloud` module, if they are set in the environment
+pub fn cloud_account<'a>(context: &'a Context) -> Option<CloudAccount<'a>> {
+    if !PROFILE_ENV_VARS
+        .iter()
+        .chain(REGION_ENV_VARS)
+        .any(|env_var| context.get_env(env_var).is_some())
+    {
+        return None;
+    }
+
+    let config = AwsConfig::try_load(context.new_module("aws").config);
+    let (aws_profile, aws_region) = get_aws_profile_and_region(context, &OnceCell::new());
+    Some(CloudAccount {
+        symbol: config.symbol,
+        name: alias_name(aws_profile, &config.profile_aliases),
+        region: alias_name(aws_region, &config.region_aliases),
+    })
+}
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("aws");
     let config: AwsConfig = AwsConfig::try_load(module.config);
@@ -284,16 +328,27 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let duration = {
-        get_credentials_duration(context, aws_profile.as_ref(), &aws_config, &aws_creds).map(
-            |duration| {
-                if duration > 0 {
-                    render_time((duration * 1000) as u128, false)
-                } else {
-                    config.expiration_symbol.to_string()
-                }
-            },
-        )
+    let remaining =
+        get_credentials_duration(context, aws_profile.as_ref(), &aws_config, &aws_creds);
+    let duration = remaining.map(|duration| {
+        if duration > 0 {
+            render_time((duration * 1000) as u128, false)
+        } else {
+            config.expiration_symbol.to_string()
+        }
+    });
+    let style = match remaining {
+        Some(duration) if duration <= 0 && !config.expiration_style.is_empty() => {
+            config.expiration_style
+        }
+        Some(duration)
+            if duration > 0
+                && duration.unsigned_abs() <= config.expiration_warning_threshold
+                && !config.expiration_warning_style.is_empty() =>
+        {
+            config.expiration_warning_style
+        }
+        _ => config.style,
     };
 
     let mapped_region = alias_name(aws_region, &config.region_aliases);
@@ -307,7 +362,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => Some(Ok(style)),
                 _ => None,
             })
             .map(|variable| match variable {
@@ -410,6 +465,45 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn profile_redacted() {
+        let actual = ModuleRenderer::new("aws")
+            .env("AWS_PROFILE", "astronauts")
+            .env("AWS_REGION", "ap-northeast-2")
+            .env("AWS_ACCESS_KEY_ID", "dummy")
+            .config(toml::toml! {
+                [redact]
+                enabled = true
+            })
+            .collect();
+        let expected = Some(format!(
+            "on {}",
+            Color::Yellow.bold().paint("☁️  ••• (ap-northeast-2) ")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn region_redacted_with_fake_name() {
+        let actual = ModuleRenderer::new("aws")
+            .env("AWS_PROFILE", "astronauts")
+            .env("AWS_REGION", "ap-northeast-2")
+            .env("AWS_ACCESS_KEY_ID", "dummy")
+            .config(toml::toml! {
+                [redact]
+                enabled = true
+                fake_names = true
+                [aws]
+                redact_variables = ["region"]
+            })
+            .collect()
+            .unwrap();
+
+        assert!(actual.contains("☁️  astronauts (region-"));
+        assert!(!actual.contains("ap-northeast-2"));
+    }
+
     #[test]
     fn profile_set_from_aws_vault() {
         let actual = ModuleRenderer::new("aws")
@@ -634,6 +728,104 @@ credential_process = /opt/bin/awscreds-retriever
         dir.close()
     }
 
+    #[test]
+    fn default_profile_env_var_set() {
+        let actual = ModuleRenderer::new("aws")
+            .env("AWS_DEFAULT_PROFILE", "astronauts")
+            .env("AWS_ACCESS_KEY_ID", "dummy")
+            .collect();
+        let expected = Some(format!(
+            "on {}",
+            Color::Yellow.bold().paint("☁️  astronauts ")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn region_inherited_from_source_profile() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = dir.path().join("config");
+        let mut file = File::create(&config_path)?;
+
+        file.write_all(
+            "[default]
+region = us-east-1
+
+[profile base]
+region = eu-west-1
+credential_process = /opt/bin/awscreds-retriever
+
+[profile admin]
+source_profile = base
+credential_process = /opt/bin/awscreds-retriever
+
+[profile deploy]
+source_profile = admin
+role_arn = arn:aws:iam::123456789012:role/deploy
+
+[profile loop]
+source_profile = loop
+credential_process = /opt/bin/awscreds-retriever
+"
+            .as_bytes(),
+        )?;
+
+        let render = |profile: &str| {
+            ModuleRenderer::new("aws")
+                .env("AWS_CONFIG_FILE", config_path.to_string_lossy().as_ref())
+                .env("AWS_PROFILE", profile)
+                .collect()
+        };
+        assert_eq!(
+            render("deploy"),
+            Some(format!(
+                "on {}",
+                Color::Yellow.bold().paint("☁️  deploy (eu-west-1) ")
+            ))
+        );
+        assert_eq!(
+            render("loop"),
+            Some(format!("on {}", Color::Yellow.bold().paint("☁️  loop ")))
+        );
+
+        dir.close()
+    }
+
+    #[test]
+    fn region_inherited_from_sso_session() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = dir.path().join("config");
+        let mut file = File::create(&config_path)?;
+
+        file.write_all(
+            "[profile astronauts]
+sso_session = company
+sso_account_id = 123456789012
+sso_role_name = Developer
+
+[sso-session company]
+sso_start_url = https://example.awsapps.com/start
+sso_region = ap-southeast-2
+"
+            .as_bytes(),
+        )?;
+
+        let actual = ModuleRenderer::new("aws")
+            .env("AWS_CONFIG_FILE", config_path.to_string_lossy().as_ref())
+            .env("AWS_PROFILE", "astronauts")
+            .collect();
+        let expected = Some(format!(
+            "on {}",
+            Color::Yellow
+                .bold()
+                .paint("☁️  astronauts (ap-southeast-2) ")
+        ));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
     #[test]
     fn profile_and_region_set_with_display_all() {
         let actual = ModuleRenderer::new("aws")
@@ -845,6 +1037,45 @@ aws_secret_access_key=dummy
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn expiration_styles() {
+        use chrono::{DateTime, SecondsFormat, Utc};
+
+        let render = |seconds_left: i64| {
+            let expiration =
+                DateTime::<Utc>::from_timestamp(chrono::Local::now().timestamp() + seconds_left, 0)
+                    .unwrap();
+            ModuleRenderer::new("aws")
+                .config(toml::toml! {
+                    [aws]
+                    format = "[$profile]($style)"
+                    expiration_warning_threshold = 900
+                    expiration_warning_style = "purple"
+                    expiration_style = "red"
+                })
+                .env("AWS_PROFILE", "astronauts")
+                .env("AWS_ACCESS_KEY_ID", "dummy")
+                .env(
+                    "AWS_SESSION_EXPIRATION",
+                    expiration.to_rfc3339_opts(SecondsFormat::Secs, true),
+                )
+                .collect()
+        };
+
+        assert_eq!(
+            render(3600),
+            Some(Color::Yellow.bold().paint("astronauts").to_string())
+        );
+        assert_eq!(
+            render(600),
+            Some(Color::Purple.paint("astronauts").to_string())
+        );
+        assert_eq!(
+            render(-60),
+            Some(Color::Red.paint("astronauts").to_string())
+        );
+    }
+
     #[test]
     fn expiration_date_set_expired() {
         use chrono::{DateTime, SecondsFormat, Utc};
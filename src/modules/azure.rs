@@ -4,6 +4,7 @@ use std::path::PathBuf;
 
 use super::{Context, Module, ModuleConfig};
 
+use super::cloud::CloudAccount;
 use crate::configs::azure::AzureConfig;
 use crate::formatter::StringFormatter;
 
@@ -28,6 +29,35 @@ struct Subscription {
     is_default: bool,
 }
 
+/// The subscription shown by the `cloud` module, if the Azure CLI or a subscription is
+/// selected in the environment
+pub fn cloud_account<'a>(context: &'a Context) -> Option<CloudAccount<'a>> {
+    let env_vars = [
+        "AZURE_CONFIG_DIR",
+        "AZURE_SUBSCRIPTION_ID",
+        "ARM_SUBSCRIPTION_ID",
+    ];
+    if !env_vars
+        .iter()
+        .any(|env_var| context.get_env(env_var).is_some())
+    {
+        return None;
+    }
+
+    let config = AzureConfig::try_load(context.new_module("azure").config);
+    let subscription = get_azure_profile_info(context);
+    Some(CloudAccount {
+        symbol: config.symbol,
+        name: subscription.map(|subscription| {
+            config
+                .subscription_aliases
+                .get(&subscription.name)
+                .map_or(subscription.name, ToString::to_string)
+        }),
+        region: None,
+    })
+}
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("azure");
     let config = AzureConfig::try_load(module.config);
@@ -0,0 +1,155 @@
+use std::borrow::Cow;
+
+use super::utils::forge;
+use super::{Context, Module, ModuleConfig, Resource};
+
+use crate::configs::azure_devops::AzureDevOpsConfig;
+use crate::formatter::StringFormatter;
+
+/// A repository hosted on Azure DevOps
+#[derive(Debug, PartialEq, Eq)]
+struct AzureDevOpsRepo<'a> {
+    organization: Cow<'a, str>,
+    project: Cow<'a, str>,
+    repository: Cow<'a, str>,
+}
+
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
+/// Creates a module with the organization and project on Azure DevOps of the upstream remote
+/// of the current branch, or of `origin`
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("azure_devops");
+    let config = AzureDevOpsConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let url = forge::remote_url(context)?;
+    let path = url.path.to_string();
+    let repo = parse_remote(url.host()?, &path)?;
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "organization" => Some(Ok(repo.organization.as_ref())),
+                "project" => Some(Ok(repo.project.as_ref())),
+                "repository" => Some(Ok(repo.repository.as_ref())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `azure_devops`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Reads the organization, project and repository from the host and path of a remote, in the
+/// forms used by `dev.azure.com` and by the older `<organization>.visualstudio.com`, over HTTPS
+/// and SSH
+fn parse_remote<'a>(host: &'a str, path: &'a str) -> Option<AzureDevOpsRepo<'a>> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let (organization, project, repository) = match (host, segments.as_slice()) {
+        ("dev.azure.com", [organization, project, "_git", repository])
+        | (
+            "ssh.dev.azure.com" | "vs-ssh.visualstudio.com",
+            ["v3", organization, project, repository],
+        ) => (*organization, *project, *repository),
+        (host, segments) => {
+            let organization = host.strip_suffix(".visualstudio.com")?;
+            match segments {
+                ["DefaultCollection", project, "_git", repository]
+                | [project, "_git", repository] => (organization, *project, *repository),
+                _ => return None,
+            }
+        }
+    };
+    let repository = repository.strip_suffix(".git").unwrap_or(repository);
+
+    // Names with spaces are percent-encoded in the URLs
+    let decode = |name: &'a str| urlencoding::decode(name).unwrap_or(Cow::Borrowed(name));
+    Some(AzureDevOpsRepo {
+        organization: decode(organization),
+        project: decode(project),
+        repository: decode(repository),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+
+    fn repo<'a>(
+        organization: &'a str,
+        project: &'a str,
+        repository: &'a str,
+    ) -> Option<AzureDevOpsRepo<'a>> {
+        Some(AzureDevOpsRepo {
+            organization: Cow::Borrowed(organization),
+            project: Cow::Borrowed(project),
+            repository: Cow::Borrowed(repository),
+        })
+    }
+
+    #[test]
+    fn not_in_repo() {
+        let actual = ModuleRenderer::new("azure_devops")
+            .path(tempfile::tempdir().unwrap().path())
+            .collect();
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn parses_https_remotes() {
+        assert_eq!(
+            parse_remote("dev.azure.com", "/contoso/Fabrikam%20Fiber/_git/website"),
+            repo("contoso", "Fabrikam Fiber", "website")
+        );
+        assert_eq!(
+            parse_remote(
+                "contoso.visualstudio.com",
+                "/DefaultCollection/Fabrikam/_git/website"
+            ),
+            repo("contoso", "Fabrikam", "website")
+        );
+        assert_eq!(
+            parse_remote("contoso.visualstudio.com", "/Fabrikam/_git/website"),
+            repo("contoso", "Fabrikam", "website")
+        );
+    }
+
+    #[test]
+    fn parses_ssh_remotes() {
+        assert_eq!(
+            parse_remote("ssh.dev.azure.com", "v3/contoso/Fabrikam/website"),
+            repo("contoso", "Fabrikam", "website")
+        );
+        assert_eq!(
+            parse_remote("vs-ssh.visualstudio.com", "/v3/contoso/Fabrikam/website"),
+            repo("contoso", "Fabrikam", "website")
+        );
+    }
+
+    #[test]
+    fn ignores_other_remotes() {
+        assert_eq!(parse_remote("github.com", "/contoso/website.git"), None);
+        assert_eq!(parse_remote("dev.azure.com", "/contoso/Fabrikam"), None);
+    }
+}
@@ -5,15 +5,55 @@ use mockall::automock;
 use starship_battery as battery;
 
 use crate::formatter::StringFormatter;
+use crate::segment::Segment;
 
 /// Creates a module for the battery percentage and charging state
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    let battery_status = get_battery_status(context)?;
-    let BatteryStatus { state, percentage } = battery_status;
-
     let mut module = context.new_module("battery");
     let config: BatteryConfig = BatteryConfig::try_load(module.config);
 
+    let statuses = match config.aggregation {
+        "list" => context
+            .battery_info_provider
+            .get_batteries()
+            .iter()
+            .filter_map(battery_status)
+            .collect(),
+        aggregation => {
+            if aggregation != "sum" {
+                log::warn!(
+                    "Unknown aggregation in [battery] ({aggregation}), expected `sum` or `list`"
+                );
+            }
+            vec![get_battery_status(context)?]
+        }
+    };
+
+    let segments: Vec<_> = statuses
+        .iter()
+        .filter_map(|status| format_battery(status, &config, context))
+        .flatten()
+        .collect();
+    if segments.is_empty() {
+        return None;
+    }
+    module.set_segments(segments);
+    Some(module)
+}
+
+/// Formats the status of a battery, or of all of them combined
+fn format_battery(
+    status: &BatteryStatus,
+    config: &BatteryConfig,
+    context: &Context,
+) -> Option<Vec<Segment>> {
+    let BatteryStatus {
+        state,
+        percentage,
+        power_draw,
+        time_remaining,
+    } = *status;
+
     // Parse config under `display`.
     // Select the style that is most minimally greater than the current battery percentage.
     // If no such style exists do not display battery module.
@@ -23,6 +63,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         .filter(|display_style| percentage <= display_style.threshold as f32)
         .min_by_key(|display_style| display_style.threshold)?;
 
+    let power_draw =
+        (f64::from(power_draw) > config.power_draw_threshold).then(|| format!("{power_draw:.1}W"));
+    let time_remaining = time_remaining
+        .filter(|minutes| {
+            config.time_remaining_threshold <= 0 || *minutes < config.time_remaining_threshold
+        })
+        .map(format_minutes);
+
     // Parse the format string and build the module
     match StringFormatter::new(config.format) {
         Ok(formatter) => {
@@ -47,14 +95,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 })
                 .map(|variable| match variable {
                     "percentage" => Some(Ok(format!("{}%", percentage.round()))),
+                    "power_draw" => power_draw.clone().map(Ok),
+                    "time_remaining" => time_remaining.clone().map(Ok),
                     _ => None,
                 });
 
             match formatter.parse(None, Some(context)) {
-                Ok(format_string) => {
-                    module.set_segments(format_string);
-                    Some(module)
-                }
+                Ok(format_string) => Some(format_string),
                 Err(e) => {
                     log::warn!("Cannot parse `battery.format`: {e}");
                     None
@@ -70,18 +117,42 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
 fn get_battery_status(context: &Context) -> Option<BatteryStatus> {
     let battery_info = context.battery_info_provider.get_battery_info()?;
+    battery_status(&battery_info)
+}
+
+fn battery_status(battery_info: &BatteryInfo) -> Option<BatteryStatus> {
     if battery_info.energy_full == 0.0 {
         None
     } else {
+        // The energy is in joules and the rate in watts, so this is the time left in seconds
+        let energy_left = match battery_info.state {
+            battery::State::Charging => Some(battery_info.energy_full - battery_info.energy),
+            battery::State::Discharging => Some(battery_info.energy),
+            _ => None,
+        };
+        let time_remaining = energy_left
+            .filter(|_| battery_info.energy_rate > 0.0)
+            .map(|energy| (energy / battery_info.energy_rate / 60.0).round() as i64);
+
         let battery = BatteryStatus {
             percentage: battery_info.energy / battery_info.energy_full * 100.0,
             state: battery_info.state,
+            power_draw: battery_info.energy_rate,
+            time_remaining,
         };
         log::debug!("Battery status: {battery:?}");
         Some(battery)
     }
 }
 
+/// Renders a number of minutes as hours and minutes, such as `1h05m`
+fn format_minutes(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, minutes) => format!("{hours}h{minutes:02}m"),
+    }
+}
+
 /// the merge returns Charging if at least one is charging
 ///                   Discharging if at least one is Discharging
 ///                   Full if both are Full or one is Full and the other Unknown
@@ -107,6 +178,8 @@ fn merge_battery_states(state1: battery::State, state2: battery::State) -> batte
 pub struct BatteryInfo {
     energy: f32,
     energy_full: f32,
+    /// The power drawn from or charging the battery, in watts
+    energy_rate: f32,
     state: battery::State,
 }
 
@@ -114,57 +187,72 @@ pub struct BatteryInfo {
 struct BatteryStatus {
     percentage: f32,
     state: battery::State,
+    power_draw: f32,
+    /// The minutes until the battery is empty or full
+    time_remaining: Option<i64>,
 }
 
 #[cfg_attr(test, automock)]
 pub trait BatteryInfoProvider {
+    /// Returns the batteries combined into one
     fn get_battery_info(&self) -> Option<BatteryInfo>;
+    /// Returns each of the batteries
+    fn get_batteries(&self) -> Vec<BatteryInfo>;
 }
 
 pub struct BatteryInfoProviderImpl;
 
 impl BatteryInfoProvider for BatteryInfoProviderImpl {
     fn get_battery_info(&self) -> Option<BatteryInfo> {
-        let battery_manager = battery::Manager::new().ok()?;
-        let batteries = battery_manager.batteries().ok()?;
-        Some(
-            batteries
-                .filter_map(|battery| match battery {
-                    Ok(battery) => {
-                        log::debug!("Battery found: {battery:?}");
-
-                        let charge_rate = battery.state_of_charge().value;
-                        let energy_full = battery.energy_full().value;
-                        Some(BatteryInfo {
-                            energy: charge_rate * energy_full,
-                            energy_full,
-                            state: battery.state(),
-                        })
-                    }
-                    Err(e) => {
-                        let level = if cfg!(target_os = "linux") {
-                            log::Level::Info
-                        } else {
-                            log::Level::Warn
-                        };
-                        log::log!(level, "Unable to access battery information:\n{}", &e);
-                        None
-                    }
-                })
-                .fold(
-                    BatteryInfo {
-                        energy: 0.0,
-                        energy_full: 0.0,
-                        state: battery::State::Unknown,
-                    },
-                    |mut acc, x| {
-                        acc.energy += x.energy;
-                        acc.energy_full += x.energy_full;
-                        acc.state = merge_battery_states(acc.state, x.state);
-                        acc
-                    },
-                ),
-        )
+        Some(self.get_batteries().into_iter().fold(
+            BatteryInfo {
+                energy: 0.0,
+                energy_full: 0.0,
+                energy_rate: 0.0,
+                state: battery::State::Unknown,
+            },
+            |mut acc, x| {
+                acc.energy += x.energy;
+                acc.energy_full += x.energy_full;
+                acc.energy_rate += x.energy_rate;
+                acc.state = merge_battery_states(acc.state, x.state);
+                acc
+            },
+        ))
+    }
+
+    fn get_batteries(&self) -> Vec<BatteryInfo> {
+        let Some(batteries) = battery::Manager::new()
+            .ok()
+            .and_then(|manager| manager.batteries().ok())
+        else {
+            return Vec::new();
+        };
+        batteries
+            .filter_map(|battery| match battery {
+                Ok(battery) => {
+                    log::debug!("Battery found: {battery:?}");
+
+                    let charge_rate = battery.state_of_charge().value;
+                    let energy_full = battery.energy_full().value;
+                    Some(BatteryInfo {
+                        energy: charge_rate * energy_full,
+                        energy_full,
+                        energy_rate: battery.energy_rate().value,
+                        state: battery.state(),
+                    })
+                }
+                Err(e) => {
+                    let level = if cfg!(target_os = "linux") {
+                        log::Level::Info
+                    } else {
+                        log::Level::Warn
+                    };
+                    log::log!(level, "Unable to access battery information:\n{}", &e);
+                    None
+                }
+            })
+            .collect()
     }
 }
 
@@ -201,6 +289,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 0.0,
                 energy_full: 0.0,
+                energy_rate: 0.0,
                 state: battery::State::Full,
             })
         });
@@ -226,6 +315,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 1000.0,
                 energy_full: 1000.0,
+                energy_rate: 0.0,
                 state: battery::State::Full,
             })
         });
@@ -251,6 +341,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 800.0,
                 energy_full: 1000.0,
+                energy_rate: 0.0,
                 state: battery::State::Charging,
             })
         });
@@ -276,6 +367,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 800.0,
                 energy_full: 1000.0,
+                energy_rate: 0.0,
                 state: battery::State::Discharging,
             })
         });
@@ -301,6 +393,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 0.0,
                 energy_full: 1.0,
+                energy_rate: 0.0,
                 state: battery::State::Unknown,
             })
         });
@@ -326,6 +419,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 0.0,
                 energy_full: 1000.0,
+                energy_rate: 0.0,
                 state: battery::State::Empty,
             })
         });
@@ -351,6 +445,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 600.0,
                 energy_full: 1000.0,
+                energy_rate: 0.0,
                 state: battery::State::Full,
             })
         });
@@ -376,6 +471,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 400.0,
                 energy_full: 1000.0,
+                energy_rate: 0.0,
                 state: battery::State::Discharging,
             })
         });
@@ -401,6 +497,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 129.87654,
                 energy_full: 1000.0,
+                energy_rate: 0.0,
                 state: battery::State::Discharging,
             })
         });
@@ -426,6 +523,7 @@ mod tests {
             Some(BatteryInfo {
                 energy: 50.0,
                 energy_full: 100.0,
+                energy_rate: 0.0,
                 state: battery::State::Discharging,
             })
         });
@@ -460,4 +558,127 @@ mod tests {
         let expected = Some(format!("{} ", Color::Green.bold().paint("󰂃 50%")));
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn battery_list() {
+        let mut mock = MockBatteryInfoProvider::new();
+
+        mock.expect_get_batteries().times(1).returning(|| {
+            vec![
+                BatteryInfo {
+                    energy: 30.0,
+                    energy_full: 100.0,
+                    energy_rate: 0.0,
+                    state: battery::State::Discharging,
+                },
+                BatteryInfo {
+                    energy: 90.0,
+                    energy_full: 100.0,
+                    energy_rate: 0.0,
+                    state: battery::State::Charging,
+                },
+                BatteryInfo {
+                    energy: 100.0,
+                    energy_full: 100.0,
+                    energy_rate: 0.0,
+                    state: battery::State::Full,
+                },
+            ]
+        });
+
+        let actual = ModuleRenderer::new("battery")
+            .config(toml::toml! {
+                [battery]
+                aggregation = "list"
+                [[battery.display]]
+                threshold = 95
+                style = ""
+            })
+            .battery_info_provider(&mock)
+            .collect();
+        let expected = Some(String::from("󰂃 30% 󰂄 90% "));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn power_draw_and_time_remaining() {
+        let mut mock = MockBatteryInfoProvider::new();
+
+        // 10 Wh left, drawing 4 W
+        mock.expect_get_battery_info().times(3).returning(|| {
+            Some(BatteryInfo {
+                energy: 36000.0,
+                energy_full: 72000.0,
+                energy_rate: 4.0,
+                state: battery::State::Discharging,
+            })
+        });
+
+        let actual = ModuleRenderer::new("battery")
+            .config(toml::toml! {
+                [battery]
+                format = "$percentage( $power_draw)( $time_remaining)"
+                [[battery.display]]
+                threshold = 100
+                style = ""
+            })
+            .battery_info_provider(&mock)
+            .collect();
+        assert_eq!(Some(String::from("50% 4.0W 2h30m")), actual);
+
+        let actual = ModuleRenderer::new("battery")
+            .config(toml::toml! {
+                [battery]
+                format = "$percentage( $power_draw)( $time_remaining)"
+                power_draw_threshold = 5.0
+                time_remaining_threshold = 120
+                [[battery.display]]
+                threshold = 100
+                style = ""
+            })
+            .battery_info_provider(&mock)
+            .collect();
+        assert_eq!(Some(String::from("50%")), actual);
+
+        let actual = ModuleRenderer::new("battery")
+            .config(toml::toml! {
+                [battery]
+                format = "$percentage( $power_draw)( $time_remaining)"
+                power_draw_threshold = 3.5
+                time_remaining_threshold = 180
+                [[battery.display]]
+                threshold = 100
+                style = ""
+            })
+            .battery_info_provider(&mock)
+            .collect();
+        assert_eq!(Some(String::from("50% 4.0W 2h30m")), actual);
+    }
+
+    #[test]
+    fn time_remaining_while_charging() {
+        let mut mock = MockBatteryInfoProvider::new();
+
+        mock.expect_get_battery_info().times(1).returning(|| {
+            Some(BatteryInfo {
+                energy: 54000.0,
+                energy_full: 72000.0,
+                energy_rate: 20.0,
+                state: battery::State::Charging,
+            })
+        });
+
+        let actual = ModuleRenderer::new("battery")
+            .config(toml::toml! {
+                [battery]
+                format = "$percentage $time_remaining"
+                [[battery.display]]
+                threshold = 100
+                style = ""
+            })
+            .battery_info_provider(&mock)
+            .collect();
+        assert_eq!(Some(String::from("75% 15m")), actual);
+    }
 }
@@ -1,8 +1,10 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use crate::configs::buf::BufConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("buf");
     let config: BufConfig = BufConfig::try_load(module.config);
@@ -1,10 +1,12 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::bun::BunConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 use crate::utils::get_command_string_output;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current Bun version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("bun");
@@ -1,6 +1,8 @@
-use super::{Context, Module};
+use super::{Context, Module, Resource};
 use crate::modules::cc::{Lang, module as cc_module};
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current C compiler and version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     cc_module(context, Lang::C)
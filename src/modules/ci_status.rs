@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::utils::forge::{self, Project, Provider};
+use super::{Context, Module, ModuleConfig, Resource};
+
+use crate::configs::ci_status::CiStatusConfig;
+use crate::formatter::StringFormatter;
+
+/// The file in the cache directory holding the CI status of the commits checked last
+pub const CACHE_FILE: &str = "ci_status.json";
+
+/// The status of the CI runs of a commit. A failure outweighs runs still in progress, which
+/// outweigh cancelled runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Success,
+    Cancelled,
+    Pending,
+    Failure,
+}
+
+impl Status {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Cancelled => "cancelled",
+            Self::Pending => "pending",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
+/// Creates a module with the status of the GitHub Actions workflows or GitLab pipelines of the
+/// commit checked out
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("ci_status");
+    let config = CiStatusConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let sha = context.get_repo().ok()?.open().head_id().ok()?.to_string();
+    let project = forge::remote_project(context, "ci_status", config.provider, &config.hosts)?;
+
+    let status = commit_status(context, &config, &project, &sha)?;
+    let (symbol, style) = match status {
+        Status::Success => (config.success_symbol, config.success_style),
+        Status::Cancelled => (config.cancelled_symbol, config.cancelled_style),
+        Status::Pending => (config.pending_symbol, config.pending_style),
+        Status::Failure => (config.failure_symbol, config.failure_style),
+    };
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "status" => Some(Ok(status.name())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `ci_status`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Returns the status of the commit, from the cache while it is fresh
+fn commit_status(
+    context: &Context,
+    config: &CiStatusConfig,
+    project: &Project,
+    sha: &str,
+) -> Option<Status> {
+    let key = format!("{}\0{}\0{sha}", project.api_url, project.path);
+    forge::cached(
+        context,
+        "ci_status",
+        CACHE_FILE,
+        &key,
+        Duration::from_secs(config.cache_ttl),
+        || fetch_status(context, config, project, sha),
+    )
+}
+
+/// Queries the API of the provider for the status of the commit. Returns `None` if the request
+/// failed, and `Some(None)` if the commit has no CI runs.
+fn fetch_status(
+    context: &Context,
+    config: &CiStatusConfig,
+    project: &Project,
+    sha: &str,
+) -> Option<Option<Status>> {
+    let endpoint = match project.provider {
+        Provider::GitHub => format!(
+            "repos/{}/actions/runs?head_sha={sha}&per_page=100",
+            project.id()
+        ),
+        Provider::GitLab => format!("projects/{}/pipelines?sha={sha}&per_page=1", project.id()),
+    };
+    let json = project.get(context, &endpoint, Duration::from_millis(config.timeout))?;
+    Some(match project.provider {
+        Provider::GitHub => github_status(&json),
+        Provider::GitLab => gitlab_status(&json),
+    })
+}
+
+/// The combined status of the GitHub Actions workflow runs of a commit
+fn github_status(json: &Value) -> Option<Status> {
+    json.get("workflow_runs")?
+        .as_array()?
+        .iter()
+        .map(|run| {
+            if run["status"].as_str() != Some("completed") {
+                return Status::Pending;
+            }
+            match run["conclusion"].as_str() {
+                Some("failure" | "timed_out" | "action_required" | "startup_failure") => {
+                    Status::Failure
+                }
+                Some("cancelled") => Status::Cancelled,
+                _ => Status::Success,
+            }
+        })
+        .max()
+}
+
+/// The status of the latest GitLab pipeline of a commit
+fn gitlab_status(json: &Value) -> Option<Status> {
+    let pipeline = json.as_array()?.first()?;
+    match pipeline["status"].as_str()? {
+        "success" => Some(Status::Success),
+        "failed" => Some(Status::Failure),
+        "canceled" | "canceling" => Some(Status::Cancelled),
+        "skipped" => None,
+        _ => Some(Status::Pending),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = ModuleRenderer::new("ci_status").collect();
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn combines_github_runs() {
+        let status = |runs: Value| github_status(&serde_json::json!({ "workflow_runs": runs }));
+        assert_eq!(status(serde_json::json!([])), None);
+        assert_eq!(
+            status(serde_json::json!([
+                { "status": "completed", "conclusion": "success" },
+                { "status": "completed", "conclusion": "skipped" },
+            ])),
+            Some(Status::Success)
+        );
+        assert_eq!(
+            status(serde_json::json!([
+                { "status": "completed", "conclusion": "success" },
+                { "status": "in_progress", "conclusion": null },
+            ])),
+            Some(Status::Pending)
+        );
+        assert_eq!(
+            status(serde_json::json!([
+                { "status": "completed", "conclusion": "timed_out" },
+                { "status": "queued", "conclusion": null },
+            ])),
+            Some(Status::Failure)
+        );
+    }
+
+    #[test]
+    fn latest_gitlab_pipeline() {
+        let status = |pipelines: Value| gitlab_status(&pipelines);
+        assert_eq!(status(serde_json::json!([])), None);
+        assert_eq!(
+            status(serde_json::json!([{ "status": "running" }, { "status": "failed" }])),
+            Some(Status::Pending)
+        );
+        assert_eq!(
+            status(serde_json::json!([{ "status": "canceled" }])),
+            Some(Status::Cancelled)
+        );
+    }
+}
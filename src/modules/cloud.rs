@@ -0,0 +1,151 @@
+use super::{Context, Module, ModuleConfig};
+use super::{aws, azure, gcloud, openstack};
+
+use crate::configs::cloud::CloudConfig;
+use crate::formatter::StringFormatter;
+
+/// The account of a cloud provider, shown by the `cloud` module when the provider is selected
+/// in the environment
+pub struct CloudAccount<'a> {
+    /// The symbol of the module of the provider
+    pub symbol: &'a str,
+    /// The profile, project, subscription or cloud in use
+    pub name: Option<String>,
+    pub region: Option<String>,
+}
+
+/// Creates a module with the account of the first cloud provider in `order` that is selected
+/// in the environment
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("cloud");
+    let config = CloudConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let (provider, account) = config.order.iter().find_map(|provider| {
+        let account = match *provider {
+            "aws" => aws::cloud_account(context),
+            "azure" => azure::cloud_account(context),
+            "gcloud" => gcloud::cloud_account(context),
+            "openstack" => openstack::cloud_account(context),
+            provider => {
+                log::warn!("Unknown cloud provider {provider:?} in `cloud.order`");
+                None
+            }
+        }?;
+        Some((*provider, account))
+    })?;
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(account.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "provider" => Some(Ok(provider)),
+                "name" => account.name.as_deref().map(Ok),
+                "region" => account.region.as_deref().map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `cloud`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+
+    #[test]
+    fn no_provider_selected() {
+        let actual = ModuleRenderer::new("cloud").collect();
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn aws_profile_and_region() {
+        let actual = ModuleRenderer::new("cloud")
+            .env("AWS_PROFILE", "astronauts")
+            .env("AWS_REGION", "ap-northeast-2")
+            .collect();
+        let expected = Some(format!(
+            "on {}",
+            Color::Yellow
+                .bold()
+                .paint("☁️  astronauts (ap-northeast-2) ")
+        ));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn first_provider_in_order() {
+        let renderer = || {
+            ModuleRenderer::new("cloud")
+                .env("AWS_PROFILE", "astronauts")
+                .env("OS_CLOUD", "corp")
+                .env("OS_REGION_NAME", "regionOne")
+        };
+
+        let actual = renderer()
+            .config(toml::toml! {
+                [cloud]
+                format = "$provider:$name:$region"
+            })
+            .collect();
+        assert_eq!(actual, Some("aws:astronauts:".to_string()));
+
+        let actual = renderer()
+            .config(toml::toml! {
+                [cloud]
+                format = "$provider:$name:$region"
+                order = ["gcloud", "openstack", "aws"]
+            })
+            .collect();
+        assert_eq!(actual, Some("openstack:corp:regionOne".to_string()));
+    }
+
+    #[test]
+    fn provider_aliases_and_symbol() {
+        let actual = ModuleRenderer::new("cloud")
+            .env("AWS_PROFILE", "astronauts")
+            .config(toml::toml! {
+                [cloud]
+                format = "$symbol$name"
+                [aws]
+                symbol = "aws "
+                [aws.profile_aliases]
+                astronauts = "space"
+            })
+            .collect();
+        assert_eq!(actual, Some("aws space".to_string()));
+    }
+
+    #[test]
+    fn disabled() {
+        let actual = ModuleRenderer::new("cloud")
+            .env("AWS_PROFILE", "astronauts")
+            .config(toml::toml! {
+                [cloud]
+                disabled = true
+            })
+            .collect();
+        assert_eq!(actual, None);
+    }
+}
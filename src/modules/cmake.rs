@@ -1,9 +1,11 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use crate::formatter::VersionFormatter;
 
 use crate::configs::cmake::CMakeConfig;
 use crate::formatter::StringFormatter;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current `CMake` version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("cmake");
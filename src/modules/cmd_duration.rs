@@ -21,14 +21,23 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     let elapsed = context.get_cmd_duration()?;
-    let config_min = config.min_time as u128;
+    // The duration is only shown from `min_time` on, while notification rules may notify sooner
+    let is_shown = elapsed >= config.min_time as u128;
+    if !is_shown && !config.show_notifications {
+        return None;
+    }
 
-    if elapsed < config_min {
+    let last_command = context.last_command();
+    if let Some(command) = &last_command
+        && is_ignored(&config, command)
+    {
+        log::trace!("Not showing the duration of ignored command {command:?}");
         return None;
     }
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
+            .map_style_rules(&config.style_rules, elapsed as f64)
             .map_style(|variable| match variable {
                 "style" => Some(Ok(config.style)),
                 _ => None,
@@ -48,7 +57,35 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         }
     });
 
-    Some(undistract_me(module, &config, context, elapsed))
+    let module = undistract_me(module, &config, context, elapsed, last_command.as_deref());
+    is_shown.then_some(module)
+}
+
+/// Whether the configuration uses the command line of the last command, which the init scripts
+/// only pass on to starship if it does
+pub fn uses_last_command(context: &Context) -> bool {
+    let module = context.new_module("cmd_duration");
+    let config = CmdDurationConfig::try_load(module.config);
+    !config.disabled
+        && (!config.ignore_commands.is_empty()
+            || config.show_notifications
+                && (!config.notify_rules.is_empty() || config.show_command_in_notifications))
+}
+
+/// Whether the command starts with one of the `ignore_commands`, comparing the name of the program
+/// without its directory and ignoring the spacing between words
+fn is_ignored(config: &CmdDurationConfig, command: &str) -> bool {
+    let mut words = command.split_whitespace();
+    let Some(program) = words.next() else {
+        return false;
+    };
+    let program = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    let words: Vec<&str> = std::iter::once(program).chain(words).collect();
+
+    config.ignore_commands.iter().any(|ignored| {
+        let ignored: Vec<&str> = ignored.split_whitespace().collect();
+        !ignored.is_empty() && words.starts_with(&ignored)
+    })
 }
 
 #[cfg(not(feature = "notify"))]
@@ -57,6 +94,7 @@ fn undistract_me<'a>(
     _config: &CmdDurationConfig,
     _context: &'a Context,
     _elapsed: u128,
+    _last_command: Option<&str>,
 ) -> Module<'a> {
     module
 }
@@ -67,52 +105,149 @@ fn undistract_me<'a>(
     config: &CmdDurationConfig,
     context: &'a Context,
     elapsed: u128,
+    last_command: Option<&str>,
 ) -> Module<'a> {
+    use crate::utils::redact;
     use notify_rust::{Notification, Timeout};
     use nu_ansi_term::{AnsiStrings, unstyle};
 
-    if config.show_notifications && config.min_time_to_notify as u128 <= elapsed {
-        if cfg!(target_os = "linux") {
-            let in_graphical_session = ["DISPLAY", "WAYLAND_DISPLAY", "MIR_SOCKET"]
-                .iter()
-                .find_map(|&var| context.get_env(var).filter(|val| !val.is_empty()))
-                .is_some();
-
-            if !in_graphical_session {
-                return module;
-            };
-        }
+    if !config.show_notifications {
+        return module;
+    }
 
-        // On macOS 26+ notify-rust will get stuck finding the current application identifier
-        // so we set it manually to the default terminal app.
-        #[cfg(target_os = "macos")]
-        let _ = notify_rust::set_application("com.apple.Terminal");
+    let rule = last_command.and_then(|command| notify_rule(config, command));
+    if !is_notified(config, rule, elapsed) {
+        return module;
+    }
 
-        let body = format!(
-            "Command execution {}",
-            unstyle(&AnsiStrings(&module.ansi_strings()))
-        );
+    if cfg!(target_os = "linux") {
+        let in_graphical_session = ["DISPLAY", "WAYLAND_DISPLAY", "MIR_SOCKET"]
+            .iter()
+            .find_map(|&var| context.get_env(var).filter(|val| !val.is_empty()))
+            .is_some();
 
-        let timeout = match config.notification_timeout {
-            Some(v) => Timeout::Milliseconds(v),
-            None => Timeout::Default,
+        if !in_graphical_session {
+            return module;
         };
+    }
 
-        let mut notification = Notification::new();
-        notification
-            .summary("Command finished")
-            .body(&body)
-            .icon("utilities-terminal")
-            .timeout(timeout);
+    if config.suppress_when_focused && is_terminal_focused(context) {
+        log::trace!("Not showing a notification, the terminal is focused");
+        return module;
+    }
+
+    // On macOS 26+ notify-rust will get stuck finding the current application identifier
+    // so we set it manually to the default terminal app.
+    #[cfg(target_os = "macos")]
+    let _ = notify_rust::set_application("com.apple.Terminal");
+
+    let body = format!(
+        "Command execution {}",
+        unstyle(&AnsiStrings(&module.ansi_strings()))
+    );
+
+    let timeout = match config.notification_timeout {
+        Some(v) => Timeout::Milliseconds(v),
+        None => Timeout::Default,
+    };
 
-        if let Err(err) = notification.show() {
-            log::trace!("Cannot show notification: {err}");
+    // The command line may contain secrets, so it is only shown if asked for
+    let summary = last_command
+        .filter(|_| config.show_command_in_notifications)
+        .map(|command| {
+            redact::current()
+                .and_then(|redaction| redaction.replace("command", command))
+                .unwrap_or_else(|| command.to_string())
+        })
+        .unwrap_or_else(|| "Command finished".to_string());
+
+    let mut notification = Notification::new();
+    notification
+        .summary(&summary)
+        .body(&body)
+        .icon("utilities-terminal")
+        .timeout(timeout);
+
+    #[cfg(not(target_os = "macos"))]
+    if let Some(urgency) = rule.and_then(|rule| rule.urgency) {
+        match notify_rust::Urgency::try_from(urgency) {
+            Ok(urgency) => {
+                notification.urgency(urgency);
+            }
+            Err(err) => log::warn!("Invalid urgency in [cmd_duration] notify_rules: {err}"),
         }
     }
 
+    if let Err(err) = notification.show() {
+        log::trace!("Cannot show notification: {err}");
+    }
+
     module
 }
 
+/// Returns the first notification rule whose `command_pattern` matches the command line
+#[cfg(feature = "notify")]
+fn notify_rule<'a, 'b>(
+    config: &'b CmdDurationConfig<'a>,
+    command: &str,
+) -> Option<&'b crate::configs::cmd_duration::NotifyRule<'a>> {
+    config
+        .notify_rules
+        .iter()
+        .find(|rule| match regex::Regex::new(rule.command_pattern) {
+            Ok(pattern) => pattern.is_match(command),
+            Err(err) => {
+                log::warn!(
+                    "Invalid command_pattern in [cmd_duration] notify_rules ({}): {err}",
+                    rule.command_pattern
+                );
+                false
+            }
+        })
+}
+
+/// Whether a command that took `elapsed` milliseconds is long enough to be notified, by the
+/// `min_duration` of its rule or else `min_time_to_notify`, regardless of `min_time`
+#[cfg(feature = "notify")]
+fn is_notified(
+    config: &CmdDurationConfig,
+    rule: Option<&crate::configs::cmd_duration::NotifyRule>,
+    elapsed: u128,
+) -> bool {
+    let min_time = rule
+        .and_then(|rule| rule.min_duration)
+        .unwrap_or(config.min_time_to_notify);
+    min_time >= 0 && (min_time as u128) <= elapsed
+}
+
+/// Whether the window of the terminal is focused, so a notification isn't needed. This can only
+/// be told on X11, where terminals set `WINDOWID`, and on macOS, where apps set
+/// `__CFBundleIdentifier` for the processes they start.
+#[cfg(feature = "notify")]
+fn is_terminal_focused(context: &Context) -> bool {
+    if let Some(window_id) = context.get_env("WINDOWID").filter(|id| !id.is_empty()) {
+        return context
+            .exec_cmd("xdotool", &["getactivewindow"])
+            .is_some_and(|output| output.stdout.trim() == window_id);
+    }
+
+    if cfg!(target_os = "macos")
+        && let Some(bundle_id) = context.get_env("__CFBundleIdentifier")
+    {
+        return context
+            .exec_cmd(
+                "osascript",
+                &[
+                    "-e",
+                    "tell application \"System Events\" to get bundle identifier of first process whose frontmost is true",
+                ],
+            )
+            .is_some_and(|output| output.stdout.trim() == bundle_id);
+    }
+
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::ModuleRenderer;
@@ -193,4 +328,182 @@ mod tests {
         let expected = Some(format!("underwent {} ", Color::Yellow.bold().paint("5s")));
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn config_style_rules() {
+        let config = toml::toml! {
+            [cmd_duration]
+            style_rules = [
+                { min = 10000, style = "bold red" },
+                { min = 60000, style = "bold purple" },
+            ]
+        };
+
+        let actual = ModuleRenderer::new("cmd_duration")
+            .config(config.clone())
+            .cmd_duration(5000)
+            .collect();
+        let expected = Some(format!("took {} ", Color::Yellow.bold().paint("5s")));
+        assert_eq!(expected, actual);
+
+        let actual = ModuleRenderer::new("cmd_duration")
+            .config(config.clone())
+            .cmd_duration(30000)
+            .collect();
+        let expected = Some(format!("took {} ", Color::Red.bold().paint("30s")));
+        assert_eq!(expected, actual);
+
+        let actual = ModuleRenderer::new("cmd_duration")
+            .config(config)
+            .cmd_duration(90000)
+            .collect();
+        let expected = Some(format!("took {} ", Color::Purple.bold().paint("1m30s")));
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "notify")]
+    fn notify_rule_for_command() {
+        use super::notify_rule;
+        use crate::config::ModuleConfig;
+        use crate::configs::cmd_duration::CmdDurationConfig;
+
+        let config = toml::Value::from(toml::toml! {
+            notify_rules = [
+                { command_pattern = "(", min_duration = 1 },
+                { command_pattern = "^cargo (build|test)", min_duration = 60000, urgency = "critical" },
+                { command_pattern = "cargo", min_duration = 120000 },
+            ]
+        });
+        let config = CmdDurationConfig::load(&config);
+
+        let rule = notify_rule(&config, "cargo build --release").unwrap();
+        assert_eq!(rule.min_duration, Some(60000));
+        assert_eq!(rule.urgency, Some("critical"));
+
+        let rule = notify_rule(&config, "cargo run").unwrap();
+        assert_eq!(rule.min_duration, Some(120000));
+
+        assert!(notify_rule(&config, "make").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "notify")]
+    fn notify_rule_below_min_time() {
+        use super::{is_notified, notify_rule};
+        use crate::config::ModuleConfig;
+        use crate::configs::cmd_duration::CmdDurationConfig;
+
+        let config = toml::toml! {
+            [cmd_duration]
+            min_time = 60000
+            show_notifications = true
+            notify_rules = [{ command_pattern = "^cargo build", min_duration = 5000 }]
+        };
+        let module_config = config["cmd_duration"].clone();
+        let module_config = CmdDurationConfig::load(&module_config);
+
+        // The rule notifies the end of the command, while its duration is below `min_time`
+        let rule = notify_rule(&module_config, "cargo build");
+        assert!(rule.is_some());
+        assert!(is_notified(&module_config, rule, 10000));
+        assert!(!is_notified(&module_config, None, 10000));
+
+        let actual = ModuleRenderer::new("cmd_duration")
+            .config(config)
+            .cmd_duration(10000)
+            .env("STARSHIP_LAST_COMMAND", "cargo build")
+            .collect();
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn last_command_from_environment() {
+        let actual = ModuleRenderer::new("cmd_duration")
+            .config(toml::toml! {
+                [cmd_duration]
+                ignore_commands = ["vim"]
+            })
+            .cmd_duration(5000)
+            .env("STARSHIP_LAST_COMMAND", "vim notes.md")
+            .collect();
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn last_command_is_only_passed_when_used() {
+        use super::uses_last_command;
+        use crate::test::default_context;
+
+        assert!(!uses_last_command(&default_context()));
+        for config in [
+            toml::toml! {
+                [cmd_duration]
+                ignore_commands = ["vim"]
+            },
+            toml::toml! {
+                [cmd_duration]
+                show_notifications = true
+                show_command_in_notifications = true
+            },
+        ] {
+            assert!(uses_last_command(&default_context().set_config(config)));
+        }
+        let config = toml::toml! {
+            [cmd_duration]
+            show_command_in_notifications = true
+        };
+        assert!(!uses_last_command(&default_context().set_config(config)));
+    }
+
+    #[test]
+    #[cfg(feature = "notify")]
+    fn terminal_focus_on_x11() {
+        use super::is_terminal_focused;
+        use crate::test::default_context;
+        use crate::utils::CommandOutput;
+
+        let mut context = default_context();
+        assert!(!is_terminal_focused(&context));
+
+        context.env.insert("WINDOWID", "41943046".to_string());
+        context.cmd.insert(
+            "xdotool getactivewindow",
+            Some(CommandOutput {
+                stdout: "41943046\n".to_string(),
+                stderr: String::new(),
+            }),
+        );
+        assert!(is_terminal_focused(&context));
+
+        context.env.insert("WINDOWID", "52428806".to_string());
+        assert!(!is_terminal_focused(&context));
+    }
+
+    #[test]
+    fn ignored_commands() {
+        let config = toml::toml! {
+            [cmd_duration]
+            ignore_commands = ["vim", "git commit"]
+        };
+
+        for command in ["vim notes.md", "/usr/bin/vim", "git   commit -m x"] {
+            let actual = ModuleRenderer::new("cmd_duration")
+                .config(config.clone())
+                .cmd_duration(5000)
+                .last_command(command)
+                .collect();
+            assert_eq!(None, actual, "{command}");
+        }
+
+        for command in ["vimdiff a b", "git push", "cargo build"] {
+            let actual = ModuleRenderer::new("cmd_duration")
+                .config(config.clone())
+                .cmd_duration(5000)
+                .last_command(command)
+                .collect();
+            let expected = Some(format!("took {} ", Color::Yellow.bold().paint("5s")));
+            assert_eq!(expected, actual, "{command}");
+        }
+    }
 }
@@ -0,0 +1,256 @@
+use std::path::Path;
+
+use regex::Regex;
+use serde_json::Value;
+
+use super::{Context, Module, ModuleConfig, Resource};
+
+use crate::configs::cms::CmsConfig;
+use crate::formatter::{StringFormatter, VersionFormatter};
+use crate::utils::read_file;
+
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
+/// A content management system whose core version can be read from its sources
+struct Cms {
+    name: &'static str,
+    /// Files only found in the root of a site, besides the version files
+    marker_files: &'static [&'static str],
+    /// Composer packages of the core, required by the `composer.json` of a site
+    packages: &'static [&'static str],
+    /// Files declaring the core version, relative to the site root
+    version_files: &'static [&'static str],
+    /// Pattern capturing the core version from a version file
+    version_pattern: &'static str,
+}
+
+const CMS_LIST: &[Cms] = &[
+    Cms {
+        name: "WordPress",
+        marker_files: &["wp-config.php"],
+        packages: &["roots/wordpress", "johnpbloch/wordpress"],
+        version_files: &[
+            "wp-includes/version.php",
+            "wp/wp-includes/version.php",
+            "web/wp/wp-includes/version.php",
+        ],
+        version_pattern: r#"\$wp_version\s*=\s*['"](?P<version>[^'"]+)['"]"#,
+    },
+    Cms {
+        name: "Drupal",
+        marker_files: &[],
+        packages: &["drupal/core", "drupal/core-recommended"],
+        version_files: &[
+            "core/lib/Drupal.php",
+            "web/core/lib/Drupal.php",
+            "docroot/core/lib/Drupal.php",
+        ],
+        version_pattern: r#"const\s+VERSION\s*=\s*['"](?P<version>[^'"]+)['"]"#,
+    },
+];
+
+impl Cms {
+    /// Whether `dir` is the root of a site, given the packages required by its `composer.json`
+    fn is_site_root(&self, dir: &Path, packages: &[String]) -> bool {
+        self.marker_files
+            .iter()
+            .chain(self.version_files)
+            .any(|file| dir.join(file).is_file())
+            || packages
+                .iter()
+                .any(|package| self.packages.contains(&package.as_str()))
+    }
+}
+
+/// Creates a module with the name and core version of the CMS powering the current site
+///
+/// Will display the CMS if the current directory or one of its parents, up to the root of the
+/// repository, contains `wp-config.php` or `wp-includes/version.php` (WordPress),
+/// `core/lib/Drupal.php` (Drupal), or a `composer.json` requiring their core.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("cms");
+    let config = CmsConfig::try_load(module.config);
+
+    let (cms, site_root) = context.project_ancestors().find_map(|dir| {
+        let packages = composer_packages(dir);
+        CMS_LIST
+            .iter()
+            .find(|cms| cms.is_site_root(dir, &packages))
+            .map(|cms| (cms, dir))
+    })?;
+    let raw_version = get_core_version(site_root, cms);
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "name" => Some(Ok(cms.name.to_string())),
+                "version" => VersionFormatter::format_module_version(
+                    module.get_name(),
+                    raw_version.as_deref()?,
+                    config.version_format,
+                )
+                .map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `cms`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// The packages required by the `composer.json` in `dir`, if there is one
+fn composer_packages(dir: &Path) -> Vec<String> {
+    let path = dir.join("composer.json");
+    if !path.is_file() {
+        return Vec::new();
+    }
+    let Some(Value::Object(required)) = read_file(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|mut json| json.get_mut("require").map(Value::take))
+    else {
+        return Vec::new();
+    };
+    required.into_iter().map(|(package, _)| package).collect()
+}
+
+fn get_core_version(site_root: &Path, cms: &Cms) -> Option<String> {
+    let contents = cms
+        .version_files
+        .iter()
+        .map(|file| site_root.join(file))
+        .find(|path| path.is_file())
+        .and_then(|path| read_file(path).ok())?;
+    let re = Regex::new(cms.version_pattern).unwrap();
+
+    Some(re.captures(&contents)?["version"].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn folder_without_cms() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("cms").path(dir.path()).collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn wordpress_site() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join("wp-includes"))?;
+        fs::write(
+            dir.path().join("wp-includes/version.php"),
+            "<?php\n/**\n * The WordPress version string.\n */\n$wp_version = '6.4.2';\n\n$wp_db_version = 56657;\n",
+        )?;
+
+        let actual = ModuleRenderer::new("cms").path(dir.path()).collect();
+
+        let expected = Some(format!(
+            "via {} ",
+            Color::Blue.bold().paint("🌐 WordPress v6.4.2")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn wordpress_site_from_theme_folder() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let theme_dir = dir.path().join("site/wp-content/themes/client");
+        fs::create_dir_all(&theme_dir)?;
+        fs::create_dir(dir.path().join("site/wp-includes"))?;
+        fs::write(
+            dir.path().join("site/wp-includes/version.php"),
+            "<?php\n$wp_version = '6.5';\n",
+        )?;
+
+        let actual = ModuleRenderer::new("cms")
+            .path(&theme_dir)
+            .env("HOME", dir.path().to_string_lossy())
+            .config(toml::toml! {
+                [cms]
+                format = "$name $version"
+            })
+            .collect();
+
+        assert_eq!(Some("WordPress v6.5".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn generic_folders_are_not_a_site() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir_all(dir.path().join("core/src"))?;
+        fs::create_dir_all(dir.path().join("web/lib"))?;
+        fs::write(
+            dir.path().join("composer.json"),
+            r#"{"require": {"drupal/core-dev": "^10", "php": ">=8.1"}}"#,
+        )?;
+
+        let actual = ModuleRenderer::new("cms").path(dir.path()).collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn drupal_from_composer_json() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("composer.json"),
+            r#"{"require": {"drupal/core-recommended": "^10.2", "drush/drush": "^12"}}"#,
+        )?;
+
+        // The core isn't installed yet, so its version is unknown
+        let actual = ModuleRenderer::new("cms").path(dir.path()).collect();
+
+        let expected = Some(format!("via {} ", Color::Blue.bold().paint("🌐 Drupal")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn drupal_composer_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let lib_dir = dir.path().join("web/core/lib");
+        fs::create_dir_all(&lib_dir)?;
+        fs::write(
+            lib_dir.join("Drupal.php"),
+            "<?php\n\nclass Drupal {\n\n  /**\n   * The current system version.\n   */\n  const VERSION = '10.2.1';\n}\n",
+        )?;
+
+        let actual = ModuleRenderer::new("cms").path(dir.path()).collect();
+
+        let expected = Some(format!(
+            "via {} ",
+            Color::Blue.bold().paint("🌐 Drupal v10.2.1")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+}
@@ -1,9 +1,11 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::cobol::CobolConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current COBOL version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("cobol");
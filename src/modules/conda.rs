@@ -3,16 +3,15 @@ use super::{Context, Module, ModuleConfig};
 use super::utils::directory::truncate;
 use crate::configs::conda::CondaConfig;
 use crate::formatter::StringFormatter;
+use std::path::Path;
 
 /// Creates a module with the current Conda environment
 ///
 /// Will display the Conda environment iff `$CONDA_DEFAULT_ENV` is set.
+/// Environments activated by micromamba or pixi are recognized as well.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     // Reference implementation: https://github.com/denysdovhan/spaceship-prompt/blob/master/sections/conda.zsh
-    let conda_env = context.get_env("CONDA_DEFAULT_ENV").unwrap_or_default();
-    if conda_env.trim().is_empty() {
-        return None;
-    }
+    let conda_env = get_conda_environment(context)?;
 
     let mut module = context.new_module("conda");
     let config: CondaConfig = CondaConfig::try_load(module.config);
@@ -24,12 +23,19 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let manager = get_manager(context);
+    let symbol = config
+        .manager_symbols
+        .get(manager)
+        .copied()
+        .unwrap_or(config.symbol);
+
     let conda_env = truncate(&conda_env, config.truncation_length).unwrap_or(conda_env);
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|variable, _| match variable {
-                "symbol" => Some(config.symbol),
+                "symbol" => Some(symbol),
                 _ => None,
             })
             .map_style(|variable| match variable {
@@ -38,6 +44,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "environment" => Some(Ok(conda_env.as_str())),
+                "manager" => Some(Ok(manager)),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -54,6 +61,43 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Returns the name of the active environment.
+///
+/// micromamba may leave `$CONDA_DEFAULT_ENV` unset, in which case the name is taken from the
+/// environment prefix, and pixi exposes its own `$PIXI_ENVIRONMENT_NAME`.
+fn get_conda_environment(context: &Context) -> Option<String> {
+    context
+        .get_env("CONDA_DEFAULT_ENV")
+        .or_else(|| context.get_env("PIXI_ENVIRONMENT_NAME"))
+        .or_else(|| {
+            context.get_env("MAMBA_ROOT_PREFIX")?;
+            let prefix = context.get_env("CONDA_PREFIX")?;
+            Some(
+                Path::new(&prefix)
+                    .file_name()?
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        })
+        .filter(|env| !env.trim().is_empty())
+}
+
+/// Returns the tool which activated the environment: `pixi`, `micromamba` or `conda`
+fn get_manager(context: &Context) -> &'static str {
+    if context.get_env("PIXI_ENVIRONMENT_NAME").is_some() {
+        return "pixi";
+    }
+
+    match (
+        context.get_env("MAMBA_ROOT_PREFIX"),
+        context.get_env("CONDA_PREFIX"),
+    ) {
+        (Some(root), Some(prefix)) if Path::new(&prefix).starts_with(&root) => "micromamba",
+        (Some(_), None) => "micromamba",
+        _ => "conda",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::ModuleRenderer;
@@ -119,4 +163,58 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn micromamba_env_from_prefix() {
+        let actual = ModuleRenderer::new("conda")
+            .env("MAMBA_ROOT_PREFIX", "/home/user/micromamba")
+            .env("CONDA_PREFIX", "/home/user/micromamba/envs/astronauts")
+            .config(toml::toml! {
+                [conda]
+                format = "[$manager $environment]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!(
+            "{}",
+            Color::Green.bold().paint("micromamba astronauts")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn conda_env_outside_mamba_root() {
+        let actual = ModuleRenderer::new("conda")
+            .env("CONDA_DEFAULT_ENV", "astronauts")
+            .env("MAMBA_ROOT_PREFIX", "/home/user/micromamba")
+            .env("CONDA_PREFIX", "/opt/conda/envs/astronauts")
+            .config(toml::toml! {
+                [conda]
+                format = "$manager"
+            })
+            .collect();
+
+        let expected = Some("conda".to_string());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn pixi_env_with_manager_symbol() {
+        let actual = ModuleRenderer::new("conda")
+            .env("PIXI_ENVIRONMENT_NAME", "default")
+            .config(toml::toml! {
+                [conda]
+                detect_env_vars = []
+                [conda.manager_symbols]
+                pixi = "🧚 "
+                micromamba = "🐍 "
+            })
+            .collect();
+
+        let expected = Some(format!("via {} ", Color::Green.bold().paint("🧚 default")));
+
+        assert_eq!(expected, actual);
+    }
 }
@@ -5,76 +5,34 @@ pub fn module<'a>(_context: &'a Context) -> Option<Module<'a>> {
     None
 }
 
+/// The container the shell runs in
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Container {
+    /// The name of the container, or of its image or engine when it has none
+    name: String,
+    /// The engine running the container, e.g. `docker`, `podman` or `kubernetes`
+    engine: String,
+    image: Option<String>,
+    pod_name: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+impl Container {
+    fn new(name: &str, engine: &str) -> Self {
+        Self {
+            name: name.into(),
+            engine: engine.into(),
+            ..Self::default()
+        }
+    }
+}
+
 #[cfg(target_os = "linux")]
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     use super::ModuleConfig;
     use crate::configs::container::ContainerConfig;
     use crate::formatter::StringFormatter;
-    use crate::utils::{self, read_file};
-
-    pub fn container_name(context: &Context) -> Option<String> {
-        use crate::utils::context_path;
-
-        if context_path(context, "/proc/vz").exists() && !context_path(context, "/proc/bc").exists()
-        {
-            // OpenVZ
-            return Some("OpenVZ".into());
-        }
-
-        if context_path(context, "/run/host/container-manager").exists() {
-            // OCI
-            return Some("OCI".into());
-        }
-
-        if context_path(context, "/dev/incus/sock").exists() {
-            // Incus
-            return Some("Incus".into());
-        }
-
-        let container_env_path = context_path(context, "/run/.containerenv");
-        if container_env_path.exists() {
-            // podman and others
-
-            let image_res = read_file(container_env_path)
-                .map(|s| {
-                    s.lines()
-                        .find_map(|l| {
-                            if let Some(name_val) = l.strip_prefix("name=\"") {
-                                return name_val.strip_suffix('"').map(|n| n.to_string());
-                            }
-
-                            l.starts_with("image=\"").then(|| {
-                                let r = l.split_at(7).1;
-                                let name = r.rfind('/').map(|n| r.split_at(n + 1).1);
-                                String::from(name.unwrap_or(r).trim_end_matches('"'))
-                            })
-                        })
-                        .unwrap_or_else(|| "podman".into())
-                })
-                .unwrap_or_else(|_| "podman".into());
-
-            return Some(image_res);
-        }
-
-        // WSL with systemd will set the contents of this file to "wsl"
-        // Avoid showing the container module in that case
-        // Honor the contents of this file if "docker" and not running in podman or wsl
-        let systemd_path = context_path(context, "/run/systemd/container");
-        if let Ok(s) = utils::read_file(systemd_path) {
-            match s.trim() {
-                "docker" => return Some("Docker".into()),
-                "wsl" => (),
-                _ => return Some("Systemd".into()),
-            }
-        }
-
-        if context_path(context, "/.dockerenv").exists() {
-            // docker
-            return Some("Docker".into());
-        }
-
-        None
-    }
 
     let mut module = context.new_module("container");
     let config: ContainerConfig = ContainerConfig::try_load(module.config);
@@ -83,7 +41,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let container_name = container_name(context)?;
+    let container = detect(context)?;
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -96,7 +54,10 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "name" => Some(Ok(&container_name)),
+                "name" => Some(Ok(container.name.as_str())),
+                "engine" => Some(Ok(container.engine.as_str())),
+                "image" => container.image.as_deref().map(Ok),
+                "pod_name" => container.pod_name.as_deref().map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -113,6 +74,150 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+#[cfg(target_os = "linux")]
+fn detect(context: &Context) -> Option<Container> {
+    // Every pod has the address of the Kubernetes API in its environment
+    let in_pod = context.get_env("KUBERNETES_SERVICE_HOST").is_some();
+    let mut container = engine_container(context)
+        .or_else(|| cgroup_container(context))
+        .or_else(|| in_pod.then(|| Container::new("Kubernetes", "kubernetes")))?;
+
+    // Pods run their containers through an engine such as containerd, which is of less
+    // interest than the pod itself
+    if in_pod || container.engine == "kubernetes" {
+        container.engine = "kubernetes".into();
+        container.pod_name = pod_name(context);
+        if let Some(pod_name) = &container.pod_name {
+            container.name.clone_from(pod_name);
+        }
+    }
+
+    Some(container)
+}
+
+/// Detects the container from the files its engine leaves in it
+#[cfg(target_os = "linux")]
+fn engine_container(context: &Context) -> Option<Container> {
+    use crate::utils::{context_path, read_file};
+
+    if context_path(context, "/proc/vz").exists() && !context_path(context, "/proc/bc").exists() {
+        // OpenVZ
+        return Some(Container::new("OpenVZ", "openvz"));
+    }
+
+    let container_manager = context_path(context, "/run/host/container-manager");
+    if container_manager.exists() {
+        // OCI
+        let engine = read_file(container_manager)
+            .ok()
+            .map(|engine| engine.trim().to_string())
+            .filter(|engine| !engine.is_empty());
+        return Some(Container::new("OCI", engine.as_deref().unwrap_or("oci")));
+    }
+
+    if context_path(context, "/dev/incus/sock").exists() {
+        // Incus
+        return Some(Container::new("Incus", "incus"));
+    }
+
+    let container_env_path = context_path(context, "/run/.containerenv");
+    if container_env_path.exists() {
+        // podman and others
+        let contents = read_file(container_env_path).unwrap_or_default();
+        return Some(containerenv(&contents));
+    }
+
+    // WSL with systemd will set the contents of this file to "wsl"
+    // Avoid showing the container module in that case
+    // Honor the contents of this file if "docker" and not running in podman or wsl
+    let systemd_path = context_path(context, "/run/systemd/container");
+    if let Ok(s) = read_file(systemd_path) {
+        match s.trim() {
+            "docker" => return Some(Container::new("Docker", "docker")),
+            "wsl" => (),
+            engine => return Some(Container::new("Systemd", engine)),
+        }
+    }
+
+    if context_path(context, "/.dockerenv").exists() {
+        // docker
+        return Some(Container::new("Docker", "docker"));
+    }
+
+    None
+}
+
+/// Parses `/run/.containerenv`, which podman fills with `key="value"` lines
+#[cfg(target_os = "linux")]
+fn containerenv(contents: &str) -> Container {
+    let value = |key: &str| {
+        contents.lines().find_map(|line| {
+            line.strip_prefix(key)?
+                .strip_prefix("=\"")?
+                .strip_suffix('"')
+                .filter(|value| !value.is_empty())
+        })
+    };
+
+    // Leave out the registry and repository of the image, e.g. `quay.io/fedora/`
+    let image = value("image").map(|image| image.rsplit('/').next().unwrap_or(image).to_string());
+    // The engine is followed by its version, e.g. `podman-4.9.3`
+    let engine = value("engine")
+        .map(|engine| engine.split('-').next().unwrap_or(engine))
+        .unwrap_or("podman");
+    let name = value("name")
+        .map(ToString::to_string)
+        .or_else(|| image.clone())
+        .unwrap_or_else(|| "podman".into());
+
+    Container {
+        name,
+        engine: engine.into(),
+        image,
+        pod_name: None,
+    }
+}
+
+/// Detects the container from the control groups of its first process, for engines that
+/// leave no files in the container
+#[cfg(target_os = "linux")]
+fn cgroup_container(context: &Context) -> Option<Container> {
+    let cgroup =
+        crate::utils::read_file(crate::utils::context_path(context, "/proc/1/cgroup")).ok()?;
+
+    let (name, engine) = cgroup.lines().find_map(|line| {
+        // Each line is `hierarchy-ID:controllers:path`
+        let path = line.splitn(3, ':').nth(2)?;
+        if path.contains("/kubepods") {
+            Some(("Kubernetes", "kubernetes"))
+        } else if path.contains("/libpod") {
+            Some(("podman", "podman"))
+        } else if path.starts_with("/docker") || path.contains("/docker-") {
+            Some(("Docker", "docker"))
+        } else if path.starts_with("/lxc") {
+            Some(("LXC", "lxc"))
+        } else {
+            None
+        }
+    })?;
+
+    Some(Container::new(name, engine))
+}
+
+/// The name of the pod, from `POD_NAME` or the downward API volume set up as in the
+/// Kubernetes documentation, or else the hostname, which is the name of the pod by default
+#[cfg(target_os = "linux")]
+fn pod_name(context: &Context) -> Option<String> {
+    use crate::utils::{context_path, read_file};
+
+    context
+        .get_env("POD_NAME")
+        .or_else(|| read_file(context_path(context, "/etc/podinfo/name")).ok())
+        .or_else(|| read_file(context_path(context, "/etc/hostname")).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test::ModuleRenderer;
@@ -328,4 +433,86 @@ mod tests {
         assert_eq!(actual, expected);
         Ok(())
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_containerenv_engine_and_image() -> std::io::Result<()> {
+        let renderer = ModuleRenderer::new("container").config(toml::toml! {
+            [container]
+            format = "$engine $image $name"
+        });
+
+        let containerenv = renderer.root_path().join("run/.containerenv");
+        fs::create_dir_all(containerenv.parent().unwrap())?;
+        utils::write_file(
+            &containerenv,
+            "engine=\"podman-4.9.3\"\nname=\"toolbox\"\nimage=\"quay.io/fedora/fedora-toolbox:40\"\n",
+        )?;
+
+        let actual = renderer.collect();
+        let expected = Some("podman fedora-toolbox:40 toolbox".to_string());
+
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_docker_cgroup() -> std::io::Result<()> {
+        let renderer = ModuleRenderer::new("container").config(toml::toml! {
+            [container]
+            format = "$engine( $image)"
+        });
+
+        let cgroup = renderer.root_path().join("proc/1/cgroup");
+        fs::create_dir_all(cgroup.parent().unwrap())?;
+        utils::write_file(
+            &cgroup,
+            "12:pids:/docker/8e3f0a\n1:name=systemd:/docker/8e3f0a\n",
+        )?;
+
+        let actual = renderer.collect();
+        let expected = Some("docker".to_string());
+
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_kubernetes_pod() {
+        let actual = ModuleRenderer::new("container")
+            .config(toml::toml! {
+                [container]
+                format = "$engine $pod_name"
+            })
+            .env("KUBERNETES_SERVICE_HOST", "10.96.0.1")
+            .env("POD_NAME", "web-7d4b9c")
+            .collect();
+        let expected = Some("kubernetes web-7d4b9c".to_string());
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_kubernetes_pod_from_hostname() -> std::io::Result<()> {
+        let renderer = ModuleRenderer::new("container").env("KUBERNETES_SERVICE_HOST", "10.96.0.1");
+
+        let cgroup = renderer.root_path().join("proc/1/cgroup");
+        fs::create_dir_all(cgroup.parent().unwrap())?;
+        utils::write_file(&cgroup, "0::/\n")?;
+        let hostname = renderer.root_path().join("etc/hostname");
+        fs::create_dir_all(hostname.parent().unwrap())?;
+        utils::write_file(&hostname, "web-7d4b9c\n")?;
+
+        let actual = renderer.collect();
+        let expected = Some(format!(
+            "{} ",
+            Color::Red.bold().dimmed().paint("⬢ [web-7d4b9c]")
+        ));
+
+        assert_eq!(actual, expected);
+        Ok(())
+    }
 }
@@ -0,0 +1,187 @@
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::cache;
+use crate::configs::cpu_usage::CpuUsageConfig;
+use crate::formatter::StringFormatter;
+use crate::utils::read_file;
+
+/// The file in the cache directory holding the CPU times of the previous prompt
+pub const CACHE_FILE: &str = "cpu_usage.json";
+
+/// The time all CPUs have spent since boot, and how much of it they were idle, in ticks
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+struct CpuTimes {
+    total: u64,
+    idle: u64,
+}
+
+/// Reads the CPU times from the aggregate `cpu` line of `/proc/stat`
+fn parse_proc_stat(stat: &str) -> Option<CpuTimes> {
+    let times = stat
+        .lines()
+        .find_map(|line| line.strip_prefix("cpu "))?
+        .split_whitespace()
+        .map(|time| time.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    // The fields are user, nice, system, idle, iowait, irq, softirq and steal, followed by the
+    // guest times, which are already counted in user and nice
+    let total = times.iter().take(8).sum();
+    let idle = times.get(3)? + times.get(4).unwrap_or(&0);
+    Some(CpuTimes { total, idle })
+}
+
+/// The percentage of time the CPUs were busy since the previous sample, or since boot without one
+fn usage(previous: Option<CpuTimes>, current: CpuTimes) -> f64 {
+    let (total, idle) = match previous {
+        Some(previous) if current.total > previous.total && current.idle >= previous.idle => {
+            (current.total - previous.total, current.idle - previous.idle)
+        }
+        _ => (current.total, current.idle),
+    };
+    if total == 0 {
+        return 0.0;
+    }
+    100.0 * total.saturating_sub(idle) as f64 / total as f64
+}
+
+/// Samples the CPU times, and returns the usage since the sample saved by the previous prompt
+fn cpu_usage(context: &Context) -> Option<f64> {
+    let current = parse_proc_stat(&read_file("/proc/stat").ok()?)?;
+
+    let cache_path = cache::cache_dir(context).map(|dir| dir.join(CACHE_FILE));
+    let previous = cache_path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok());
+    if let Some(path) = cache_path {
+        let saved = serde_json::to_string(&current)
+            .map_err(|error| error.to_string())
+            .and_then(|content| fs::write(&path, content).map_err(|error| error.to_string()));
+        if let Err(error) = saved {
+            log::debug!("Unable to save the CPU times to {path:?}: {error}");
+        }
+    }
+
+    Some(usage(previous, current))
+}
+
+/// Creates a module with the CPU usage since the previous prompt
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("cpu_usage");
+    let config = CpuUsageConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let Some(used_pct) = cpu_usage(context) else {
+        log::debug!("Unable to read the CPU usage from /proc/stat");
+        return None;
+    };
+
+    if (used_pct.round() as i64) < config.threshold {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style_rules(&config.style_rules, used_pct)
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "cpu_pct" => Some(Ok(format!("{used_pct:.0}%"))),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `cpu_usage`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::ModuleRenderer;
+
+    #[test]
+    fn proc_stat() {
+        let stat = "cpu  100 20 30 800 50 0 0 0 10 0\ncpu0 50 10 15 400 25 0 0 0 5 0\nintr 1234\n";
+        assert_eq!(
+            parse_proc_stat(stat),
+            Some(CpuTimes {
+                total: 1000,
+                idle: 850
+            })
+        );
+        assert_eq!(parse_proc_stat("intr 1234\n"), None);
+    }
+
+    #[test]
+    fn usage_since_previous_sample() {
+        let previous = CpuTimes {
+            total: 1000,
+            idle: 850,
+        };
+        let current = CpuTimes {
+            total: 1200,
+            idle: 900,
+        };
+        assert_eq!(usage(Some(previous), current), 75.0);
+        // Without a sample, or with one from before a reboot, the usage is the one since boot
+        assert_eq!(usage(None, current), 25.0);
+        assert_eq!(usage(Some(current), previous), 15.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn saves_sample() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let output = ModuleRenderer::new("cpu_usage")
+            .config(toml::toml! {
+                [cpu_usage]
+                disabled = false
+                threshold = 0
+            })
+            .env("STARSHIP_CACHE", dir.path().to_str().unwrap())
+            .collect();
+
+        assert!(output.is_some());
+        assert!(dir.path().join(CACHE_FILE).exists());
+        dir.close()
+    }
+
+    #[test]
+    fn impossible_threshold() {
+        let output = ModuleRenderer::new("cpu_usage")
+            .config(toml::toml! {
+                [cpu_usage]
+                disabled = false
+                threshold = 9999
+            })
+            .collect();
+
+        assert!(output.is_none());
+    }
+}
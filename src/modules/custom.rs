@@ -1,19 +1,34 @@
+use std::collections::HashMap;
 use std::env;
 use std::fmt::{self, Debug};
+use std::fs;
 use std::io::Write;
 use std::path::Path;
 use std::process::{Command, Stdio};
+use std::sync::OnceLock;
 use std::time::Duration;
 
 use process_control::{ChildExt, Control, Output};
+use regex::Regex;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::{
-    config::Either, configs::custom::CustomConfig, formatter::StringFormatter,
-    utils::create_command,
+    cache,
+    config::Either,
+    configs::custom::CustomConfig,
+    formatter::StringFormatter,
+    utils::{create_command, read_file},
 };
 
+/// The file in the cache directory holding the output of custom commands with `cache_ttl`
+pub const CACHE_FILE: &str = "custom_cache.json";
+
+/// Set for the process refreshing the cached output of an `async` module
+const REFRESH_VAR: &str = "STARSHIP_CUSTOM_REFRESH";
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a custom module with some configuration
 ///
 /// The relevant TOML config will set the files, extensions, and directories needed
@@ -39,15 +54,28 @@ pub fn module<'a>(name: &str, context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    if !env_requirements_met(name, context, &config) {
+        return None;
+    }
+
     // Note: Forward config if `Module` ends up needing `config`
     let mut module = Module::new(format!("custom.{name}"), config.description, None);
 
-    let mut is_match = context
-        .try_begin_scan()?
-        .set_extensions(&config.detect_extensions)
-        .set_files(&config.detect_files)
-        .set_folders(&config.detect_folders)
-        .is_match();
+    // Modules reading their output from a file are shown when it can be read, without a shell
+    let file_output = config.from_file.map(|file| {
+        let path = context.current_dir.join(Context::expand_tilde(file.into()));
+        read_file(&path)
+            .map_err(|error| log::debug!("Unable to read {path:?}: {error}"))
+            .ok()
+    });
+
+    let mut is_match = file_output.as_ref().is_some_and(Option::is_some)
+        || context
+            .try_begin_scan()?
+            .set_extensions(&config.detect_extensions)
+            .set_files(&config.detect_files)
+            .set_folders(&config.detect_folders)
+            .is_match();
 
     if !is_match {
         is_match = match config.when {
@@ -60,21 +88,66 @@ pub fn module<'a>(name: &str, context: &'a Context) -> Option<Module<'a>> {
         }
     }
 
-    let variables_closure = |variable: &str| match variable {
-        "output" => {
-            let output = exec_command(config.command, context, &config)?;
-            let trimmed = output.trim();
+    // `async` modules show their previous output, or the placeholder until there is one
+    let async_output = (config.run_async && file_output.is_none())
+        .then(|| async_command_output(name, context, &config));
+    let format = match async_output {
+        Some(None) => config.async_placeholder,
+        _ => config.format,
+    };
 
-            if trimmed.is_empty() {
-                None
-            } else {
-                Some(Ok(trimmed.to_string()))
-            }
+    // The command is only run once, even when its JSON output is used by several variables
+    let output = OnceLock::new();
+    let output = || {
+        output
+            .get_or_init(|| match (&file_output, &async_output) {
+                (Some(file_output), _) => file_output.clone(),
+                (None, Some(async_output)) => async_output.clone().flatten(),
+                (None, None) => cached_command_output(name, context, &config),
+            })
+            .as_deref()
+    };
+    let json_output = OnceLock::new();
+    let json_output = || {
+        json_output
+            .get_or_init(|| parse_json_output(name, output()?))
+            .as_ref()
+    };
+    let regex = config.parse_regex.and_then(|pattern| {
+        Regex::new(pattern)
+            .map_err(|error| {
+                log::warn!("Invalid parse_regex in module `custom.{name}`: {error}");
+            })
+            .ok()
+    });
+    let regex_output = OnceLock::new();
+    let regex_output = || {
+        regex_output
+            .get_or_init(|| regex_variables(regex.as_ref()?, output()?))
+            .as_ref()
+    };
+    let is_json = match config.format_output {
+        "json" => true,
+        "text" => false,
+        format_output => {
+            log::warn!(
+                "Unknown format_output `{format_output}` in module `custom.{name}`, expected `text` or `json`"
+            );
+            false
         }
-        _ => None,
     };
 
-    let parsed = StringFormatter::new(config.format).and_then(|mut formatter| {
+    let variables_closure = |variable: &str| {
+        let value = match variable {
+            _ if is_json => json_output()?.get(variable).and_then(json_value_to_string),
+            _ if regex.is_some() => Some(regex_output()?.get(variable)?.trim().to_string()),
+            "output" => Some(output()?.trim().to_string()),
+            _ => None,
+        };
+        value.filter(|value| !value.is_empty()).map(Ok)
+    };
+
+    let parsed = StringFormatter::new(format).and_then(|mut formatter| {
         formatter = formatter
             .map_meta(|var, _| match var {
                 "symbol" => Some(config.symbol),
@@ -103,6 +176,74 @@ pub fn module<'a>(name: &str, context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Whether every variable of `require_env_vars` is set, and every variable of `env_matches`
+/// matches its regular expression
+fn env_requirements_met(name: &str, context: &Context, config: &CustomConfig) -> bool {
+    let vars_set = config
+        .require_env_vars
+        .iter()
+        .all(|var| context.get_env_os(var).is_some());
+    vars_set
+        && config.env_matches.iter().all(|(var, pattern)| {
+            let Some(value) = context.get_env(var) else {
+                return false;
+            };
+            match Regex::new(pattern) {
+                Ok(regex) => regex.is_match(&value),
+                Err(error) => {
+                    log::warn!("Invalid regex for {var} in module `custom.{name}`: {error}");
+                    false
+                }
+            }
+        })
+}
+
+/// Returns the variables matched by `parse_regex` in the output: the named groups, and
+/// `output`, the first group or the whole match without groups
+fn regex_variables(regex: &Regex, output: &str) -> Option<HashMap<String, String>> {
+    let captures = regex.captures(output)?;
+    let mut variables: HashMap<String, String> = regex
+        .capture_names()
+        .flatten()
+        .filter_map(|name| Some((name.to_string(), captures.name(name)?.as_str().to_string())))
+        .collect();
+    let matched = captures.get(1).or_else(|| captures.get(0))?;
+    variables
+        .entry("output".to_string())
+        .or_insert_with(|| matched.as_str().to_string());
+    Some(variables)
+}
+
+/// Parses the output of a command as a JSON object, whose keys are the variables of the module
+fn parse_json_output(
+    name: &str,
+    output: &str,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    match serde_json::from_str(output) {
+        Ok(serde_json::Value::Object(object)) => Some(object),
+        Ok(value) => {
+            log::warn!(
+                "The output of module `custom.{name}` should be a JSON object, found {value}"
+            );
+            None
+        }
+        Err(error) => {
+            log::warn!("Unable to parse the output of module `custom.{name}` as JSON: {error}");
+            None
+        }
+    }
+}
+
+/// Renders a JSON value as the value of a variable. Strings are shown without quotes, and
+/// `null` hides the variable.
+fn json_value_to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(value) => Some(value.trim().to_string()),
+        value => Some(value.to_string()),
+    }
+}
+
 /// Gets the TOML config for the custom module, handling the case where the module is not defined
 fn get_config<'a>(module_name: &str, context: &'a Context<'a>) -> Option<&'a toml::Value> {
     struct DebugCustomModules<'tmp>(&'tmp toml::value::Table);
@@ -113,7 +254,9 @@ fn get_config<'a>(module_name: &str, context: &'a Context<'a>) -> Option<&'a tom
         }
     }
 
-    let config = context.config.get_custom_module_config(module_name);
+    let config = context
+        .module_config_source()
+        .get_custom_module_config(module_name);
 
     if config.is_some() {
         return config;
@@ -208,7 +351,7 @@ fn shell_command(cmd: &str, config: &CustomConfig, context: &Context) -> Option<
 
     if !config.ignore_timeout {
         output = output
-            .time_limit(Duration::from_millis(context.root_config.command_timeout))
+            .time_limit(context.command_time_limit())
             .terminate_for_timeout()
     }
 
@@ -249,6 +392,117 @@ fn exec_when(cmd: &str, config: &CustomConfig, context: &Context) -> bool {
     }
 }
 
+/// Returns the output of `command`, from the cache while it is younger than `cache_ttl`
+fn cached_command_output(name: &str, context: &Context, config: &CustomConfig) -> Option<String> {
+    let (Some(ttl), Some(path)) = (
+        cache_ttl(name, config),
+        cache::cache_dir(context).map(|dir| dir.join(CACHE_FILE)),
+    ) else {
+        return exec_command(config.command, context, config);
+    };
+
+    let key = cache_key(name, context, config);
+    let cached = cache::read_entry::<String>(&path, &key);
+    if let Some(entry) = cached.filter(|entry| !entry.is_expired()) {
+        log::trace!("Using the cached output of module `custom.{name}`");
+        return Some(entry.value);
+    }
+
+    // Failures aren't cached, so the command is run again by the next prompt
+    let output = exec_command(config.command, context, config)?;
+    cache::save_entry(&path, &key, &output, ttl);
+    Some(output)
+}
+
+/// Returns the output of the command of an `async` module, from the cache. Once it is older
+/// than `cache_ttl`, the command is run in the background to update the cache for the next
+/// prompt. Returns `None` until the command has output.
+///
+/// Phase 2 of a prompt rendered in two phases, for modules in `async_modules`, and the process
+/// updating the cache run the command instead.
+fn async_command_output(
+    name: &str,
+    context: &Context,
+    config: &CustomConfig,
+) -> Option<Option<String>> {
+    let Some(dir) = cache::cache_dir(context) else {
+        log::debug!("Unable to find the cache directory for module `custom.{name}`");
+        return None;
+    };
+    let path = dir.join(CACHE_FILE);
+    let key = cache_key(name, context, config);
+    let ttl = cache_ttl(name, config).unwrap_or_default();
+
+    if context.properties.phase == Some(2) || context.get_env(REFRESH_VAR).is_some() {
+        // The prompt isn't waiting for the command, so it isn't stopped by `command_timeout`
+        let config = CustomConfig {
+            ignore_timeout: true,
+            ..config.clone()
+        };
+        let output = exec_command(config.command, context, &config);
+        if let Some(output) = &output {
+            cache::save_entry(&path, &key, output, ttl);
+        }
+        // The lock file may not exist, when the refresh wasn't started by a prompt
+        let _ = fs::remove_file(cache::refresh_lock_path(&dir, "custom", &key));
+        return Some(output);
+    }
+
+    let cached = cache::read_entry::<String>(&path, &key);
+    if cached.as_ref().is_none_or(cache::CacheEntry::is_expired) {
+        cache::spawn_refresh(
+            context,
+            &format!("custom.{name}"),
+            &cache::refresh_lock_path(&dir, "custom", &key),
+            REFRESH_VAR,
+        );
+    }
+    cached.map(|entry| Some(entry.value))
+}
+
+/// Returns `cache_ttl`, if it is set and valid
+fn cache_ttl(name: &str, config: &CustomConfig) -> Option<Duration> {
+    let ttl = config.cache_ttl?;
+    let parsed = parse_ttl(ttl);
+    if parsed.is_none() {
+        log::warn!(
+            "Invalid cache_ttl `{ttl}` in module `custom.{name}`, expected a duration such as `30s` or `5m`"
+        );
+    }
+    parsed
+}
+
+/// Parses `cache_ttl`, such as `30s` or `5m`. A number without a unit is a number of seconds.
+fn parse_ttl(ttl: &str) -> Option<Duration> {
+    let ttl = match ttl.trim().parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => ttl
+            .trim()
+            .parse::<jiff::SignedDuration>()
+            .ok()?
+            .try_into()
+            .ok()?,
+    };
+    (!ttl.is_zero()).then_some(ttl)
+}
+
+/// The key the output of the command is cached under: the module, the command, and the values
+/// of `cache_key`. Entries starting with `$` are replaced with the value of that environment
+/// variable, and `$PWD` with the current directory.
+fn cache_key(name: &str, context: &Context, config: &CustomConfig) -> String {
+    let mut key = format!("custom.{name}\0{}", config.command);
+    for part in &config.cache_key {
+        let value = match part.strip_prefix('$') {
+            Some("PWD") => context.current_dir.to_string_lossy().into_owned(),
+            Some(variable) => context.get_env(variable).unwrap_or_default(),
+            None => (*part).to_string(),
+        };
+        key.push('\0');
+        key.push_str(&value);
+    }
+    key
+}
+
 /// Execute the given command, returning its output on success
 fn exec_command(cmd: &str, context: &Context, config: &CustomConfig) -> Option<String> {
     log::trace!("Running '{cmd}'");
@@ -819,4 +1073,244 @@ mod tests {
 
         dir.close()
     }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn json_output_variables() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("custom.test")
+            .path(dir.path())
+            .config(toml::toml! {
+                [custom.test]
+                format = "$branch $ahead $dirty( $note)( $empty)"
+                command = r#"echo run >> runs; echo '{"branch": "main", "ahead": 2, "dirty": false, "note": null, "empty": ""}'"#
+                shell = ["sh"]
+                when = true
+                ignore_timeout = true
+                format_output = "json"
+            })
+            .collect();
+        let expected = Some("main 2 false".to_string());
+        assert_eq!(expected, actual);
+
+        // The command is run once for all the variables
+        let runs = std::fs::read_to_string(dir.path().join("runs"))?;
+        assert_eq!(runs.lines().count(), 1);
+
+        dir.close()
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn invalid_json_output() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("custom.test")
+            .path(dir.path())
+            .config(toml::toml! {
+                [custom.test]
+                format = "[$symbol($branch)]($style)"
+                symbol = "x"
+                style = ""
+                command = "echo '[1, 2]'"
+                shell = ["sh"]
+                when = true
+                ignore_timeout = true
+                format_output = "json"
+            })
+            .collect();
+        let expected = Some("x".to_string());
+        assert_eq!(expected, actual);
+
+        dir.close()
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn caches_output_by_key() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let render = |context: &str| {
+            ModuleRenderer::new("custom.test")
+                .path(dir.path())
+                .config(toml::toml! {
+                    [custom.test]
+                    format = "$output"
+                    command = "echo run >> runs; wc -l < runs"
+                    shell = ["sh"]
+                    when = true
+                    ignore_timeout = true
+                    cache_ttl = "1h"
+                    cache_key = ["$KUBECONFIG"]
+                })
+                .env("STARSHIP_CACHE", dir.path().to_str().unwrap())
+                .env("KUBECONFIG", context)
+                .collect()
+                .map(|output| output.trim().to_string())
+        };
+
+        assert_eq!(render("dev"), Some("1".to_string()));
+        assert_eq!(render("dev"), Some("1".to_string()));
+        assert_eq!(render("prod"), Some("2".to_string()));
+        assert!(dir.path().join(CACHE_FILE).exists());
+
+        dir.close()
+    }
+
+    #[test]
+    fn ttls() {
+        assert_eq!(parse_ttl("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_ttl("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_ttl("45"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_ttl("0s"), None);
+        assert_eq!(parse_ttl("-1m"), None);
+        assert_eq!(parse_ttl("often"), None);
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn async_shows_previous_output() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let render = |refresh: bool| {
+            let mut renderer = ModuleRenderer::new("custom.test")
+                .path(dir.path())
+                .config(toml::toml! {
+                    [custom.test]
+                    format = "$output"
+                    command = "echo run >> runs; wc -l < runs"
+                    shell = ["sh"]
+                    when = true
+                    async = true
+                    async_placeholder = "…"
+                    cache_ttl = "1h"
+                })
+                .env("STARSHIP_CACHE", dir.path().to_str().unwrap());
+            if refresh {
+                renderer = renderer.env(REFRESH_VAR, "1");
+            }
+            renderer.collect().map(|output| output.trim().to_string())
+        };
+
+        // Nothing was cached yet, so the placeholder is shown while the cache is refreshed
+        assert_eq!(render(false), Some("…".to_string()));
+        assert!(!dir.path().join("runs").exists());
+        // The cache file has a lock file of its own, which is kept
+        let is_refreshing = || -> std::io::Result<bool> {
+            Ok(fs::read_dir(dir.path())?
+                .filter_map(Result::ok)
+                .any(|entry| {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    name.contains("_refresh_") && name.ends_with(".lock")
+                }))
+        };
+        assert!(is_refreshing()?);
+
+        // The refresh runs the command and removes the lock file
+        assert_eq!(render(true), Some("1".to_string()));
+        assert!(!is_refreshing()?);
+
+        // The next prompts show the cached output without running the command
+        assert_eq!(render(false), Some("1".to_string()));
+        assert_eq!(render(false), Some("1".to_string()));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("runs"))?.lines().count(),
+            1
+        );
+
+        dir.close()
+    }
+
+    #[test]
+    fn from_file_with_regex() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("build.gradle"),
+            "plugins {}\nversion = '1.4.2-beta'\n",
+        )?;
+
+        let actual = ModuleRenderer::new("custom.test")
+            .path(dir.path())
+            .config(toml::toml! {
+                [custom.test]
+                format = "$output ($pre)"
+                from_file = "build.gradle"
+                parse_regex = "version = '([0-9.]+)(?:-(?<pre>\\w+))?'"
+            })
+            .collect();
+        let expected = Some("1.4.2 beta".to_string());
+        assert_eq!(expected, actual);
+
+        dir.close()
+    }
+
+    #[test]
+    fn from_file_without_regex() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join(".tool-version"), "3.12\n")?;
+
+        let actual = ModuleRenderer::new("custom.test")
+            .path(dir.path())
+            .config(toml::toml! {
+                [custom.test]
+                format = "v$output"
+                from_file = ".tool-version"
+            })
+            .collect();
+        let expected = Some("v3.12".to_string());
+        assert_eq!(expected, actual);
+
+        dir.close()
+    }
+
+    #[test]
+    fn from_file_missing() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("custom.test")
+            .path(dir.path())
+            .config(toml::toml! {
+                [custom.test]
+                format = "v$output"
+                from_file = ".tool-version"
+            })
+            .collect();
+        assert_eq!(None, actual);
+
+        dir.close()
+    }
+
+    #[test]
+    fn env_requirements() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let render = |env: &[(&'static str, &str)]| {
+            let mut renderer =
+                ModuleRenderer::new("custom.test")
+                    .path(dir.path())
+                    .config(toml::toml! {
+                        [custom.test]
+                        format = "test"
+                        when = true
+                        require_env_vars = ["KUBECONFIG"]
+                        env_matches = { AWS_PROFILE = "^prod-" }
+                    });
+            for (key, value) in env {
+                renderer = renderer.env(key, *value);
+            }
+            renderer.collect()
+        };
+
+        let expected = Some("test".to_string());
+        assert_eq!(
+            render(&[("KUBECONFIG", ""), ("AWS_PROFILE", "prod-eu")]),
+            expected
+        );
+        assert_eq!(render(&[("AWS_PROFILE", "prod-eu")]), None);
+        assert_eq!(render(&[("KUBECONFIG", "~/.kube/config")]), None);
+        assert_eq!(
+            render(&[("KUBECONFIG", "~/.kube/config"), ("AWS_PROFILE", "dev")]),
+            None
+        );
+
+        dir.close()
+    }
 }
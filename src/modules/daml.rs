@@ -1,4 +1,4 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use crate::configs::daml::DamlConfig;
 use crate::formatter::{StringFormatter, VersionFormatter};
 
@@ -6,6 +6,8 @@ const DAML_SDK_VERSION: &str = "sdk-version";
 const DAML_SDK_VERSION_ENV: &str = "DAML_SDK_VERSION";
 const DAML_YAML: &str = "daml.yaml";
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current Daml version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("daml");
@@ -11,13 +11,15 @@ use std::iter::FromIterator;
 use std::path::{Path, PathBuf};
 use unicode_segmentation::UnicodeSegmentation;
 
-use super::{Context, Module};
+use super::{Context, Module, Resource};
 
 use super::utils::directory::truncate;
 use crate::config::{Either, ModuleConfig};
 use crate::configs::directory::{DirectoryConfig, SubstitutionConfig};
 use crate::formatter::StringFormatter;
 
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
 /// Creates a module with the current logical or physical directory
 ///
 /// Will perform path contraction, substitution, and truncation.
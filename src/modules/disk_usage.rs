@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use systemstat::data::ByteSize;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::disk_usage::DiskUsageConfig;
+use crate::formatter::StringFormatter;
+use crate::modules::memory_usage::display_bs;
+
+/// The size of a filesystem and the space left on it for the current user, in bytes
+struct DiskSpace {
+    total: u64,
+    available: u64,
+}
+
+impl DiskSpace {
+    fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    fn used_pct(&self) -> f64 {
+        100.0 * self.used() as f64 / self.total as f64
+    }
+}
+
+#[cfg(not(windows))]
+fn disk_space(path: &Path) -> Option<DiskSpace> {
+    let stat = match nix::sys::statvfs::statvfs(path) {
+        Ok(stat) => stat,
+        Err(e) => {
+            log::debug!("Failed to stat the filesystem of {path:?}: {e}");
+            return None;
+        }
+    };
+    let fragment_size = stat.fragment_size() as u64;
+    Some(DiskSpace {
+        total: stat.blocks() as u64 * fragment_size,
+        available: stat.blocks_available() as u64 * fragment_size,
+    })
+}
+
+#[cfg(windows)]
+fn disk_space(path: &Path) -> Option<DiskSpace> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::{Win32::Storage::FileSystem::GetDiskFreeSpaceExW, core::PCWSTR};
+
+    let wpath: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let (mut available, mut total) = (0, 0);
+    let result = unsafe {
+        GetDiskFreeSpaceExW(
+            PCWSTR(wpath.as_ptr()),
+            Some(&mut available),
+            Some(&mut total),
+            None,
+        )
+    };
+    if let Err(e) = result {
+        log::debug!("Failed to get the disk space of {path:?}: {e:?}");
+        return None;
+    }
+    Some(DiskSpace { total, available })
+}
+
+/// Creates a module with the disk usage of the filesystem of the current directory
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("disk_usage");
+    let config = DiskUsageConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let disk = disk_space(&context.current_dir).filter(|disk| disk.total > 0)?;
+    let used_pct = disk.used_pct();
+
+    if (used_pct.round() as i64) < config.threshold {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style_rules(&config.style_rules, used_pct)
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "free" => Some(Ok(display_bs(ByteSize(disk.available)))),
+                "used" => Some(Ok(display_bs(ByteSize(disk.used())))),
+                "total" => Some(Ok(display_bs(ByteSize(disk.total)))),
+                "used_pct" => Some(Ok(format!("{used_pct:.0}%"))),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `disk_usage`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::ModuleRenderer;
+
+    #[test]
+    fn used_space() {
+        let disk = DiskSpace {
+            total: 1024 * 1024 * 1024,
+            available: 256 * 1024 * 1024,
+        };
+        assert_eq!(disk.used(), 768 * 1024 * 1024);
+        assert_eq!(disk.used_pct(), 75.0);
+    }
+
+    #[test]
+    fn zero_threshold() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let output = ModuleRenderer::new("disk_usage")
+            .path(dir.path())
+            .config(toml::toml! {
+                [disk_usage]
+                disabled = false
+                threshold = 0
+                format = "$used_pct"
+            })
+            .collect();
+
+        assert!(output.is_some_and(|output| output.ends_with('%')));
+        dir.close()
+    }
+
+    #[test]
+    fn impossible_threshold() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let output = ModuleRenderer::new("disk_usage")
+            .path(dir.path())
+            .config(toml::toml! {
+                [disk_usage]
+                disabled = false
+                threshold = 9999
+            })
+            .collect();
+
+        assert!(output.is_none());
+        dir.close()
+    }
+}
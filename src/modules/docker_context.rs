@@ -1,11 +1,13 @@
 use std::path::PathBuf;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::docker_context::DockerContextConfig;
 use crate::formatter::StringFormatter;
 use crate::utils;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the currently active Docker context
 ///
 /// Will display the Docker context if the following criteria are met:
@@ -5,7 +5,7 @@ use std::iter::Iterator;
 use std::path::{Path, PathBuf};
 use std::str;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use crate::configs::dotnet::DotnetConfig;
 use crate::formatter::StringFormatter;
 use crate::utils;
@@ -15,6 +15,9 @@ use crate::formatter::VersionFormatter;
 
 const GLOBAL_JSON_FILE: &str = "global.json";
 const PROJECT_JSON_FILE: &str = "project.json";
+const DIRECTORY_BUILD_PROPS_FILE: &str = "Directory.Build.props";
+
+pub const RESOURCES: &[Resource] = &[Resource::Repo, Resource::DirContents];
 
 /// A module which shows the latest (or pinned) version of the dotnet SDK
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -70,7 +73,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     )
                     .map(Ok)
                 }
-                "tfm" => find_current_tfm(&dotnet_files).map(Ok),
+                "tfm" => find_current_tfm(context, &dotnet_files).map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -87,12 +90,44 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
-fn find_current_tfm(files: &[DotNetFile]) -> Option<String> {
-    let get_file_of_type = |t: FileType| files.iter().find(|f| f.file_type == t);
+/// Finds the target framework(s) of the nearest project file.
+///
+/// If the project doesn't declare them itself, the nearest `Directory.Build.props`,
+/// which MSBuild imports from the parent directories, is checked as well.
+/// The search stops at the root of the git repository or, outside of one, the home directory.
+fn find_current_tfm(context: &Context, files: &[DotNetFile]) -> Option<String> {
+    let local_project = files.iter().find(|f| f.file_type == FileType::ProjectFile);
+    if let Some(tfm) = local_project.and_then(|f| get_tfm_from_project_file(&f.path)) {
+        return Some(tfm);
+    }
 
-    let relevant_file = get_file_of_type(FileType::ProjectFile)?;
+    let mut found_project = local_project.is_some();
 
-    get_tfm_from_project_file(relevant_file.path.as_path())
+    for dir in context.project_ancestors() {
+        // Source files in subdirectories belong to the project file above them
+        if !found_project && let Some(project) = find_project_file(dir) {
+            found_project = true;
+            if let Some(tfm) = get_tfm_from_project_file(&project) {
+                return Some(tfm);
+            }
+        }
+
+        // MSBuild only imports the nearest one
+        let props = dir.join(DIRECTORY_BUILD_PROPS_FILE);
+        if props.is_file() {
+            return get_tfm_from_project_file(&props);
+        }
+    }
+
+    None
+}
+
+fn find_project_file(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.is_file() && get_dotnet_file_type(path) == Some(FileType::ProjectFile))
 }
 
 fn get_tfm_from_project_file(path: &Path) -> Option<String> {
@@ -547,6 +582,103 @@ mod tests {
         workspace.close()
     }
 
+    #[test]
+    fn shows_tfm_from_directory_build_props() -> io::Result<()> {
+        let workspace = create_workspace(true)?;
+        let props = make_csproj_with_tfm("TargetFramework", "net8.0");
+        touch_path(&workspace, "Directory.Build.props", Some(&props))?;
+        touch_path(
+            &workspace,
+            "src/project/project.csproj",
+            Some("<Project />"),
+        )?;
+        expect_output(
+            &workspace.path().join("src/project"),
+            Some(format!(
+                "via {}",
+                Color::Blue.bold().paint(".NET v3.1.103 🎯 net8.0 ")
+            )),
+        );
+        workspace.close()
+    }
+
+    #[test]
+    fn shows_tfm_of_project_in_parent_directory() -> io::Result<()> {
+        let workspace = create_workspace(true)?;
+        let csproj = make_csproj_with_tfm("TargetFrameworks", "net6.0;net8.0");
+        touch_path(&workspace, "project/project.csproj", Some(&csproj))?;
+        touch_path(&workspace, "project/Models/Model.cs", None)?;
+        let actual = ModuleRenderer::new("dotnet")
+            .path(workspace.path().join("project/Models"))
+            .config(toml::toml! {
+                [dotnet]
+                detect_extensions = ["cs"]
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Blue.bold().paint(".NET 🎯 net6.0;net8.0 ")
+        ));
+        assert_eq!(expected, actual);
+        workspace.close()
+    }
+
+    #[test]
+    fn project_tfm_takes_precedence_over_directory_build_props() -> io::Result<()> {
+        let workspace = create_workspace(true)?;
+        let props = make_csproj_with_tfm("TargetFramework", "net8.0");
+        touch_path(&workspace, "Directory.Build.props", Some(&props))?;
+        let csproj = make_csproj_with_tfm("TargetFramework", "netstandard2.0");
+        touch_path(&workspace, "project/project.csproj", Some(&csproj))?;
+        expect_output(
+            &workspace.path().join("project"),
+            Some(format!(
+                "via {}",
+                Color::Blue.bold().paint(".NET v3.1.103 🎯 netstandard2.0 ")
+            )),
+        );
+        workspace.close()
+    }
+
+    #[test]
+    fn only_nearest_directory_build_props_is_read() -> io::Result<()> {
+        let workspace = create_workspace(true)?;
+        let props = make_csproj_with_tfm("TargetFramework", "net8.0");
+        touch_path(&workspace, "Directory.Build.props", Some(&props))?;
+        touch_path(&workspace, "src/Directory.Build.props", Some("<Project />"))?;
+        touch_path(
+            &workspace,
+            "src/project/project.csproj",
+            Some("<Project />"),
+        )?;
+        expect_output(
+            &workspace.path().join("src/project"),
+            Some(format!(
+                "via {}",
+                Color::Blue.bold().paint(".NET v3.1.103 ")
+            )),
+        );
+        workspace.close()
+    }
+
+    #[test]
+    fn directory_build_props_outside_of_home_is_not_read() -> io::Result<()> {
+        let workspace = create_workspace(false)?;
+        let props = make_csproj_with_tfm("TargetFramework", "net8.0");
+        touch_path(&workspace, "Directory.Build.props", Some(&props))?;
+        touch_path(&workspace, "project/project.csproj", Some("<Project />"))?;
+        let actual = ModuleRenderer::new("dotnet")
+            .path(workspace.path().join("project"))
+            .env("HOME", workspace.path().join("project").to_string_lossy())
+            .collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Blue.bold().paint(".NET v3.1.103 ")
+        ));
+        assert_eq!(expected, actual);
+        workspace.close()
+    }
+
     #[test]
     fn version_from_dotnet_cli() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -1,4 +1,4 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::elixir::ElixirConfig;
 use crate::formatter::StringFormatter;
@@ -7,6 +7,8 @@ use crate::formatter::VersionFormatter;
 use std::ops::Deref;
 use std::sync::LazyLock;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Create a module with the current Elixir version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("elixir");
@@ -24,6 +26,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     let versions = LazyLock::new(|| get_elixir_version(context));
+    // Mix runs in the `dev` environment unless told otherwise
+    let mix_env = context
+        .get_env("MIX_ENV")
+        .filter(|mix_env| !mix_env.trim().is_empty())
+        .unwrap_or_else(|| "dev".to_string());
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -33,6 +40,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map_style(|variable| match variable {
                 "style" => Some(Ok(config.style)),
+                "mix_env_style" if mix_env == "prod" => Some(Ok(config.prod_style)),
+                "mix_env_style" => Some(Ok(config.style)),
                 _ => None,
             })
             .map(|variable| match variable {
@@ -53,6 +62,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     .as_ref()
                     .map(|(otp_version, _)| otp_version.to_string())
                     .map(Ok),
+                "mix_env" => Some(Ok(mix_env.clone())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -153,4 +163,51 @@ Elixir 1.13.0-dev (compiled with Erlang/OTP 23)
 
         dir.close()
     }
+
+    #[test]
+    fn test_mix_env_defaults_to_dev() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("mix.exs"))?.sync_all()?;
+
+        let expected = Some(format!(
+            "via {} {}",
+            Color::Purple.bold().paint("💧 v1.10"),
+            Color::Purple.bold().paint("dev")
+        ));
+        let output = ModuleRenderer::new("elixir")
+            .path(dir.path())
+            .config(toml::toml! {
+                [elixir]
+                format = "via [$symbol$version]($style) [$mix_env]($mix_env_style)"
+            })
+            .collect();
+
+        assert_eq!(output, expected);
+
+        dir.close()
+    }
+
+    #[test]
+    fn test_mix_env_prod() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("mix.exs"))?.sync_all()?;
+
+        let expected = Some(format!(
+            "via {} {}",
+            Color::Purple.bold().paint("💧 v1.10"),
+            Color::Red.bold().paint("prod")
+        ));
+        let output = ModuleRenderer::new("elixir")
+            .path(dir.path())
+            .env("MIX_ENV", "prod")
+            .config(toml::toml! {
+                [elixir]
+                format = "via [$symbol$version]($style) [$mix_env]($mix_env_style)"
+            })
+            .collect();
+
+        assert_eq!(output, expected);
+
+        dir.close()
+    }
 }
@@ -0,0 +1,266 @@
+use std::path::Path;
+
+use super::{Context, Module, ModuleConfig, Resource};
+
+use crate::configs::embedded::EmbeddedConfig;
+use crate::formatter::StringFormatter;
+use crate::utils::read_file;
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
+/// Creates a module with the chip and target triple an embedded Rust project is built for
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("embedded");
+    let config = EmbeddedConfig::try_load(module.config);
+
+    let is_embedded_project = context
+        .try_begin_scan()?
+        .set_files(&config.detect_files)
+        .set_extensions(&config.detect_extensions)
+        .set_folders(&config.detect_folders)
+        .is_match();
+
+    if !is_embedded_project {
+        return None;
+    }
+
+    let cargo_configs = get_cargo_configs(context);
+    let chip = get_chip(context, &cargo_configs);
+    let target = get_target(context, &cargo_configs);
+
+    // Without a chip or a cross-compilation target, this is a regular host build
+    if chip.is_none() && target.is_none() {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "chip" => chip.clone().map(Ok),
+                "target" => target.clone().map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `embedded`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Returns the chip configured for probe-rs, either through the environment,
+/// `Embed.toml` or the `--chip` argument of a cargo runner
+fn get_chip(context: &Context, cargo_configs: &[toml::Table]) -> Option<String> {
+    if let Some(chip) = context.get_env("PROBE_RS_CHIP") {
+        return Some(chip);
+    }
+
+    [".embed.toml", "Embed.toml"]
+        .iter()
+        .find_map(|file| get_chip_from_embed_toml(&context.current_dir.join(file)))
+        .or_else(|| cargo_configs.iter().find_map(get_chip_from_runner))
+}
+
+fn get_chip_from_embed_toml(path: &Path) -> Option<String> {
+    let embed_toml: toml::Table = toml::from_str(&read_file(path).ok()?).ok()?;
+    let get_profile_chip = |profile: &toml::Value| {
+        profile
+            .get("general")?
+            .get("chip")?
+            .as_str()
+            .map(ToString::to_string)
+    };
+
+    // Prefer the default profile, otherwise take the first one which sets a chip
+    embed_toml
+        .get("default")
+        .and_then(get_profile_chip)
+        .or_else(|| embed_toml.values().find_map(get_profile_chip))
+}
+
+fn get_chip_from_runner(cargo_config: &toml::Table) -> Option<String> {
+    cargo_config
+        .get("target")?
+        .as_table()?
+        .values()
+        .filter_map(|target| target.get("runner")?.as_str())
+        .find_map(|runner| {
+            let mut args = runner.split_whitespace();
+            args.by_ref().find(|arg| *arg == "--chip")?;
+            args.next().map(ToString::to_string)
+        })
+}
+
+/// Returns the target triple(s) cargo builds for, if it isn't the host
+fn get_target(context: &Context, cargo_configs: &[toml::Table]) -> Option<String> {
+    if let Some(target) = context.get_env("CARGO_BUILD_TARGET") {
+        return Some(target);
+    }
+
+    cargo_configs.iter().find_map(|cargo_config| {
+        match cargo_config.get("build")?.get("target")? {
+            toml::Value::String(target) => Some(target.clone()),
+            toml::Value::Array(targets) => {
+                let targets = targets
+                    .iter()
+                    .filter_map(toml::Value::as_str)
+                    .collect::<Vec<_>>();
+                (!targets.is_empty()).then(|| targets.join(", "))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Reads the cargo configuration files of the current directory and its parents,
+/// nearest first, in the same way cargo discovers them
+fn get_cargo_configs(context: &Context) -> Vec<toml::Table> {
+    context
+        .current_dir
+        .ancestors()
+        .filter_map(|dir| {
+            ["config.toml", "config"]
+                .iter()
+                .find_map(|file| read_file(dir.join(".cargo").join(file)).ok())
+        })
+        .filter_map(|contents| toml::from_str(&contents).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn folder_without_embedded_files() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("embedded").path(dir.path()).collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn host_build_with_cargo_config() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join(".cargo"))?;
+        fs::write(
+            dir.path().join(".cargo/config.toml"),
+            "[alias]\nxtask = \"run --package xtask --\"\n",
+        )?;
+
+        let actual = ModuleRenderer::new("embedded").path(dir.path()).collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn chip_from_embed_toml() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("Embed.toml"),
+            "[default.general]\nchip = \"nRF52840_xxAA\"\n\n[default.rtt]\nenabled = true\n",
+        )?;
+
+        let actual = ModuleRenderer::new("embedded").path(dir.path()).collect();
+
+        let expected = Some(format!(
+            "for {}",
+            Color::Cyan.bold().paint("📟 nRF52840_xxAA ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn chip_and_target_from_cargo_config() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join(".cargo"))?;
+        fs::write(
+            dir.path().join(".cargo/config.toml"),
+            r#"[target.'cfg(all(target_arch = "arm", target_os = "none"))']
+runner = "probe-rs run --chip STM32F411CEUx"
+
+[build]
+target = "thumbv7em-none-eabihf"
+"#,
+        )?;
+        fs::write(dir.path().join("memory.x"), "")?;
+
+        let actual = ModuleRenderer::new("embedded").path(dir.path()).collect();
+
+        let expected = Some(format!(
+            "for {}",
+            Color::Cyan
+                .bold()
+                .paint("📟 STM32F411CEUx (thumbv7em-none-eabihf) ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn target_from_parent_cargo_config() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let firmware_dir = dir.path().join("firmware");
+        fs::create_dir_all(dir.path().join(".cargo"))?;
+        fs::create_dir_all(&firmware_dir)?;
+        fs::write(
+            dir.path().join(".cargo/config.toml"),
+            "[build]\ntarget = \"riscv32imc-unknown-none-elf\"\n",
+        )?;
+        fs::write(firmware_dir.join("memory.x"), "")?;
+
+        let actual = ModuleRenderer::new("embedded")
+            .path(&firmware_dir)
+            .config(toml::toml! {
+                [embedded]
+                format = "$target"
+            })
+            .collect();
+
+        assert_eq!(Some("riscv32imc-unknown-none-elf".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn target_from_env_var() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join(".cargo"))?;
+        fs::write(
+            dir.path().join(".cargo/config.toml"),
+            "[build]\ntarget = \"thumbv6m-none-eabi\"\n",
+        )?;
+
+        let actual = ModuleRenderer::new("embedded")
+            .path(dir.path())
+            .env("CARGO_BUILD_TARGET", "wasm32-unknown-unknown")
+            .config(toml::toml! {
+                [embedded]
+                format = "$target"
+            })
+            .collect();
+
+        assert_eq!(Some("wasm32-unknown-unknown".to_string()), actual);
+        dir.close()
+    }
+}
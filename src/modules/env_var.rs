@@ -1,8 +1,10 @@
 use super::{Context, Module};
 use std::borrow::Cow;
 
+use regex::Regex;
+
 use crate::config::ModuleConfig;
-use crate::configs::env_var::EnvVarConfig;
+use crate::configs::env_var::{EnvVarConfig, TABLE_OPTIONS};
 use crate::formatter::StringFormatter;
 
 /// Creates a module with the value of the chosen environment variable
@@ -14,11 +16,11 @@ use crate::formatter::StringFormatter;
 pub fn module<'a>(name: Option<&str>, context: &'a Context) -> Option<Module<'a>> {
     let toml_config = match name {
         Some(name) => context
-            .config
+            .module_config_source()
             .get_config(&["env_var", name])
             .map(Cow::Borrowed),
         None => context
-            .config
+            .module_config_source()
             .get_module_config("env_var")
             .and_then(filter_config)
             .map(Cow::Owned)
@@ -32,7 +34,7 @@ pub fn module<'a>(name: Option<&str>, context: &'a Context) -> Option<Module<'a>
 
     let config = EnvVarConfig::try_load(toml_config.as_deref());
     // Note: Forward config if `Module` ends up needing `config`
-    let mut module = Module::new(mod_name, config.description, None);
+    let mut module = Module::new(mod_name.clone(), config.description, None);
     if config.disabled {
         return None;
     }
@@ -41,6 +43,12 @@ pub fn module<'a>(name: Option<&str>, context: &'a Context) -> Option<Module<'a>
 
     let env_value = context.get_env(variable_name);
     let env_value = env_value.as_deref().or(config.default)?;
+    let env_value = extract_value(&mod_name, env_value, config.parse_regex);
+    let env_value = config
+        .value_aliases
+        .get(env_value)
+        .copied()
+        .unwrap_or(env_value);
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -69,7 +77,26 @@ pub fn module<'a>(name: Option<&str>, context: &'a Context) -> Option<Module<'a>
     Some(module)
 }
 
-/// Filter `config` to only includes non-table values
+/// Returns the first group matched by `parse_regex` in the value, or the whole match without
+/// groups. The value is shown unchanged when it doesn't match.
+fn extract_value<'a>(mod_name: &str, value: &'a str, parse_regex: Option<&str>) -> &'a str {
+    let Some(pattern) = parse_regex else {
+        return value;
+    };
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(error) => {
+            log::warn!("Invalid parse_regex in module `{mod_name}`: {error}");
+            return value;
+        }
+    };
+    regex
+        .captures(value)
+        .and_then(|captures| captures.get(1).or_else(|| captures.get(0)))
+        .map_or(value, |matched| matched.as_str())
+}
+
+/// Filter `config` to only includes non-table values, and the options that are tables
 /// This filters the top-level table to only include its specific configuration
 fn filter_config(config: &toml::Value) -> Option<toml::Value> {
     let o = config
@@ -77,7 +104,7 @@ fn filter_config(config: &toml::Value) -> Option<toml::Value> {
         .map(|table| {
             table
                 .iter()
-                .filter(|(_key, val)| !val.is_table())
+                .filter(|(key, val)| !val.is_table() || TABLE_OPTIONS.contains(&key.as_str()))
                 .map(|(key, val)| (key.clone(), val.clone()))
                 .collect::<toml::value::Table>()
         })
@@ -347,6 +374,94 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn parse_regex_capture_group() {
+        let actual = ModuleRenderer::new("env_var.AWS_ROLE")
+            .config(toml::toml! {
+                [env_var.AWS_ROLE]
+                parse_regex = "role/(.+)$"
+            })
+            .env("AWS_ROLE", "arn:aws:iam::1234:role/foo")
+            .collect();
+        let expected = Some(format!("with {} ", style().paint("foo")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_regex_without_group() {
+        let actual = ModuleRenderer::new("env_var.TEST_VAR")
+            .config(toml::toml! {
+                [env_var.TEST_VAR]
+                parse_regex = "[0-9]+"
+            })
+            .env("TEST_VAR", "build-1234-release")
+            .collect();
+        let expected = Some(format!("with {} ", style().paint("1234")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_regex_no_match() {
+        let actual = ModuleRenderer::new("env_var.TEST_VAR")
+            .config(toml::toml! {
+                [env_var.TEST_VAR]
+                parse_regex = "role/(.+)$"
+            })
+            .env("TEST_VAR", TEST_VAR_VALUE)
+            .collect();
+        let expected = Some(format!("with {} ", style().paint(TEST_VAR_VALUE)));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn value_aliases() {
+        let actual = ModuleRenderer::new("env_var.TEST_VAR")
+            .config(toml::toml! {
+                [env_var.TEST_VAR]
+                value_aliases = { astronauts = "🚀" }
+            })
+            .env("TEST_VAR", TEST_VAR_VALUE)
+            .collect();
+        let expected = Some(format!("with {} ", style().paint("🚀")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn value_aliases_after_parse_regex() {
+        let actual = ModuleRenderer::new("env_var.AWS_ROLE")
+            .config(toml::toml! {
+                [env_var.AWS_ROLE]
+                parse_regex = "role/(.+)$"
+                [env_var.AWS_ROLE.value_aliases]
+                foo = "admin"
+            })
+            .env("AWS_ROLE", "arn:aws:iam::1234:role/foo")
+            .collect();
+        let expected = Some(format!("with {} ", style().paint("admin")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn value_aliases_in_root_module() {
+        let actual = ModuleRenderer::new("env_var")
+            .config(toml::toml! {
+                [env_var]
+                variable = "TEST_VAR"
+                [env_var.value_aliases]
+                astronauts = "🚀"
+            })
+            .env("TEST_VAR", TEST_VAR_VALUE)
+            .collect();
+        let expected = Some(format!("with {} ", style().paint("🚀")));
+
+        assert_eq!(expected, actual);
+    }
+
     fn style() -> Style {
         // default style
         Color::Black.bold().dimmed()
@@ -16,7 +16,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let style = parse_style_string(config.style, Some(context));
 
-    module.set_segments(vec![Segment::fill(style, config.symbol)]);
+    module.set_segments(vec![Segment::fill_with_limits(
+        style,
+        config.symbol,
+        config.min_width,
+        config.max_width,
+    )]);
 
     Some(module)
 }
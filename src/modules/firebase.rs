@@ -0,0 +1,216 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::firebase::FirebaseConfig;
+use crate::formatter::StringFormatter;
+use crate::utils;
+
+/// The project `firebase deploy` targets, and the alias it was selected with
+#[derive(Debug, PartialEq, Eq)]
+struct ActiveProject {
+    id: String,
+    alias: Option<String>,
+}
+
+/// Creates a module with the Firebase project that `firebase deploy` would target from the
+/// current directory
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("firebase");
+    let config = FirebaseConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let root = context
+        .begin_ancestor_scan()
+        .set_files(&["firebase.json", ".firebaserc"])
+        .scan()?;
+    let project = active_project(context, &root)?;
+    let id = config
+        .project_aliases
+        .get(&project.id)
+        .map_or(project.id.as_str(), |alias| *alias);
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "project" => Some(Ok(id)),
+                "alias" => project.alias.as_deref().map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `firebase`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Finds the project selected with `firebase use` for the project directory, which
+/// firebase-tools keeps in its global config, or else the default project of `.firebaserc`.
+/// Either can be an alias defined in the `projects` of `.firebaserc`.
+fn active_project(context: &Context, root: &Path) -> Option<ActiveProject> {
+    let firebaserc = read_json(&root.join(".firebaserc"));
+    let aliases = firebaserc
+        .as_ref()
+        .and_then(|rc| rc["projects"].as_object());
+
+    let selected = tools_config_path(context)
+        .and_then(|path| read_json(&path))
+        .and_then(|tools_config| {
+            let active_projects = tools_config["activeProjects"].as_object()?;
+            let project = active_projects.get(root.to_str()?)?.as_str()?;
+            Some(project.to_string())
+        })
+        .or_else(|| Some(aliases?.get("default")?.as_str()?.to_string()))?;
+
+    if let Some(id) = aliases
+        .and_then(|aliases| aliases.get(&selected))
+        .and_then(Value::as_str)
+    {
+        return Some(ActiveProject {
+            id: id.to_string(),
+            alias: Some(selected),
+        });
+    }
+    let alias = aliases.and_then(|aliases| {
+        aliases
+            .iter()
+            .find(|(_, id)| id.as_str() == Some(&selected))
+            .map(|(alias, _)| alias.clone())
+    });
+    Some(ActiveProject {
+        id: selected,
+        alias,
+    })
+}
+
+/// The global config of firebase-tools, which holds the project selected in each directory
+fn tools_config_path(context: &Context) -> Option<PathBuf> {
+    let config_dir = context
+        .get_env("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| Some(context.get_home()?.join(".config")))?;
+    Some(config_dir.join("configstore").join("firebase-tools.json"))
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let contents = utils::read_file(path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(json) => Some(json),
+        Err(error) => {
+            log::debug!("Unable to parse {}: {error}", path.display());
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    const FIREBASERC: &str = r#"{
+        "projects": {
+            "default": "acme-dev",
+            "prod": "acme-prod-4f2a"
+        }
+    }"#;
+
+    #[test]
+    fn folder_without_firebase_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = ModuleRenderer::new("firebase").path(dir.path()).collect();
+        assert_eq!(actual, None);
+        dir.close()
+    }
+
+    #[test]
+    fn default_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join(".firebaserc"), FIREBASERC)?;
+        fs::create_dir(dir.path().join("functions"))?;
+
+        let (renderer, home) = ModuleRenderer::new_with_home("firebase")?;
+        let actual = renderer.path(dir.path().join("functions")).collect();
+        let expected = Some(format!(
+            "on {} ",
+            Color::Yellow.bold().paint("󰥧 acme-dev (default)")
+        ));
+        assert_eq!(actual, expected);
+        home.close()?;
+        dir.close()
+    }
+
+    #[test]
+    fn project_selected_with_firebase_use() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join(".firebaserc"), FIREBASERC)?;
+        fs::write(dir.path().join("firebase.json"), "{}")?;
+
+        let (renderer, home) = ModuleRenderer::new_with_home("firebase")?;
+        let configstore = home.path().join(".config").join("configstore");
+        fs::create_dir_all(&configstore)?;
+        fs::write(
+            configstore.join("firebase-tools.json"),
+            serde_json::json!({ "activeProjects": { dir.path().to_str().unwrap(): "prod" } })
+                .to_string(),
+        )?;
+
+        let actual = renderer
+            .path(dir.path())
+            .config(toml::toml! {
+                [firebase.project_aliases]
+                "acme-prod-4f2a" = "production"
+            })
+            .collect();
+        let expected = Some(format!(
+            "on {} ",
+            Color::Yellow.bold().paint("󰥧 production (prod)")
+        ));
+        assert_eq!(actual, expected);
+        home.close()?;
+        dir.close()
+    }
+
+    #[test]
+    fn project_id_without_alias() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("firebase.json"), "{}")?;
+
+        let (renderer, home) = ModuleRenderer::new_with_home("firebase")?;
+        let configstore = home.path().join(".config").join("configstore");
+        fs::create_dir_all(&configstore)?;
+        fs::write(
+            configstore.join("firebase-tools.json"),
+            serde_json::json!({ "activeProjects": { dir.path().to_str().unwrap(): "acme-test" } })
+                .to_string(),
+        )?;
+
+        let actual = renderer.path(dir.path()).collect();
+        let expected = Some(format!("on {} ", Color::Yellow.bold().paint("󰥧 acme-test")));
+        assert_eq!(actual, expected);
+        home.close()?;
+        dir.close()
+    }
+}
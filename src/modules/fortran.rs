@@ -8,7 +8,9 @@ use crate::{
     formatter::{StringFormatter, VersionFormatter},
 };
 
-use super::{Context, Module};
+use super::{Context, Module, Resource};
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
 
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("fortran");
@@ -1,11 +1,13 @@
 use ini::Ini;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::{LazyLock, OnceLock};
 
 use super::{Context, Module, ModuleConfig};
 
+use super::cloud::CloudAccount;
 use crate::configs::gcloud::GcloudConfig;
 use crate::formatter::StringFormatter;
 use crate::utils;
@@ -81,6 +83,72 @@ fn get_active_config(context: &Context, config_dir: &Path) -> Option<String> {
     })
 }
 
+/// The project and region shown by the `cloud` module, if a configuration or project is
+/// selected in the environment
+pub fn cloud_account<'a>(context: &'a Context) -> Option<CloudAccount<'a>> {
+    let env_vars = [
+        "CLOUDSDK_CONFIG",
+        "CLOUDSDK_ACTIVE_CONFIG_NAME",
+        "CLOUDSDK_CORE_PROJECT",
+    ];
+    if !env_vars
+        .iter()
+        .any(|env_var| context.get_env(env_var).is_some())
+    {
+        return None;
+    }
+
+    let config = GcloudConfig::try_load(context.new_module("gcloud").config);
+    let (config_name, config_path) = get_current_config(context)?;
+    if config_name == "NONE" {
+        return None;
+    }
+    let gcloud_context = GcloudContext::new(&config_name, &config_path);
+
+    let project = context
+        .get_env("CLOUDSDK_CORE_PROJECT")
+        .or_else(|| gcloud_context.get_project().map(String::from));
+    let region = gcloud_context.get_region();
+    Some(CloudAccount {
+        symbol: config.symbol,
+        name: project.map(|project| {
+            get_project_alias(Cow::Owned(project), &config.project_aliases).into_owned()
+        }),
+        region: region.map(|region| {
+            config
+                .region_aliases
+                .get(region)
+                .copied()
+                .unwrap_or(region)
+                .to_string()
+        }),
+    })
+}
+
+/// Returns the alias of a project in `project_aliases`. Keys that aren't the name of the project
+/// are matched as regular expressions against the whole name, and the alias can refer to their
+/// capture groups, e.g. `$1`.
+fn get_project_alias<'a>(
+    project: Cow<'a, str>,
+    aliases: &HashMap<String, &'a str>,
+) -> Cow<'a, str> {
+    if let Some(alias) = aliases.get(project.as_ref()) {
+        return Cow::Borrowed(alias);
+    }
+    let alias = aliases.iter().find_map(|(pattern, alias)| {
+        let re = match regex::Regex::new(&format!("^{pattern}$")) {
+            Ok(re) => re,
+            Err(error) => {
+                log::warn!("Could not compile regular expression `^{pattern}$`:\n{error}");
+                return None;
+            }
+        };
+        re.is_match(&project)
+            .then(|| re.replace(&project, *alias).into_owned())
+    });
+    alias.map_or(project, Cow::Owned)
+}
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("gcloud");
     let config: GcloudConfig = GcloudConfig::try_load(module.config);
@@ -125,13 +193,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     .get_env("CLOUDSDK_CORE_PROJECT")
                     .map(Cow::Owned)
                     .or_else(|| gcloud_context.get_project().map(Cow::Borrowed))
-                    .map(|project| {
-                        config
-                            .project_aliases
-                            .get(project.as_ref())
-                            .copied()
-                            .map_or(project, Cow::Borrowed)
-                    })
+                    .map(|project| get_project_alias(project, &config.project_aliases))
                     .map(Ok),
                 "active" => Some(Ok(Cow::Borrowed(&gcloud_context.config_name))),
                 _ => None,
@@ -451,6 +513,39 @@ project = very-long-project-name
         dir.close()
     }
 
+    #[test]
+    fn project_set_with_regex_alias() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let active_config_path = dir.path().join("active_config");
+        let mut active_config_file = File::create(active_config_path)?;
+        active_config_file.write_all(b"default")?;
+
+        create_dir(dir.path().join("configurations"))?;
+        let config_default_path = dir.path().join("configurations").join("config_default");
+        let mut config_default_file = File::create(config_default_path)?;
+        config_default_file.write_all(
+            b"\
+[core]
+project = my-team-prod-1234
+",
+        )?;
+
+        let actual = ModuleRenderer::new("gcloud")
+            .env("CLOUDSDK_CONFIG", dir.path().to_string_lossy())
+            .config(toml::toml! {
+                [gcloud]
+                format = "on [$symbol$project]($style) "
+                [gcloud.project_aliases]
+                "my-team-(dev|prod)-\\d+" = "$1"
+                "other-project" = "other"
+            })
+            .collect();
+        let expected = Some(format!("on {} ", Color::Blue.bold().paint("☁️  prod")));
+
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
     #[test]
     fn region_not_set_with_display_region() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
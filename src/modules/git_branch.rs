@@ -1,13 +1,34 @@
+use std::time::Duration;
+
 use gix::bstr::ByteSlice;
+use gix::commit::describe::SelectRef;
+use serde::{Deserialize, Serialize};
 use unicode_segmentation::UnicodeSegmentation;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
+use crate::cache;
 use crate::configs::git_branch::GitBranchConfig;
 use crate::context::Repo;
-use crate::formatter::StringFormatter;
+use crate::formatter::{StringFormatter, VariableHolder};
 use crate::modules::git_status::uses_reftables;
 
+/// The file in the cache directory holding what the detached HEAD of each repository is based on
+pub const CACHE_FILE: &str = "detached_from.json";
+
+/// How long what a detached HEAD is based on is cached, if HEAD doesn't move
+const DETACHED_FROM_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
+/// The tag or branch a detached HEAD was found to be based on, which is kept until HEAD moves,
+/// as finding it walks the history. It is cached under the path of the git directory.
+#[derive(Deserialize, Serialize)]
+struct DetachedFrom {
+    head: String,
+    name: Option<String>,
+}
+
 /// Creates a module with the Git branch in the current directory
 ///
 /// Will display the branch name if the current directory is a git repo
@@ -35,7 +56,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     // Get branch and remote information
-    let (branch_name, remote_branch, remote_name) = if uses_reftables(&gix_repo) {
+    let (branch_name, remote_branch, remote_name, is_detached) = if uses_reftables(&gix_repo) {
         // Use git executable for branch information
         match get_branch_info_from_git(context, repo) {
             Some((branch, remote_branch)) => {
@@ -63,6 +84,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                         },
                     ),
                     remote_name,
+                    false,
                 )
             }
             None => {
@@ -74,11 +96,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     return None;
                 }
                 // Fallback to HEAD for detached state or when branch can't be determined
-                ("HEAD".to_string(), None, None)
+                ("HEAD".to_string(), None, None, true)
             }
         }
     } else {
-        if config.only_attached && gix_repo.head().ok()?.is_detached() {
+        let is_detached = gix_repo.head().ok()?.is_detached();
+        if config.only_attached && is_detached {
             return None;
         }
 
@@ -88,7 +111,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         } else {
             (None, None)
         };
-        (branch, remote_branch, remote_name)
+        (branch, remote_branch, remote_name, is_detached)
     };
 
     if config
@@ -125,7 +148,22 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let show_remote = config.always_show_remote
         || (!graphemes.eq(&remote_branch_graphemes) && !remote_branch_graphemes.is_empty());
 
-    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+    let (format, detached_from) = if is_detached {
+        let format = config.detached_format.unwrap_or(config.format);
+        // Finding what HEAD is based on walks the history, so it's only done if it is shown
+        let shows_detached_from = StringFormatter::new(format)
+            .is_ok_and(|formatter| formatter.get_variables().contains("detached_from"));
+        let detached_from = if shows_detached_from {
+            get_detached_from(context, repo, &gix_repo)
+        } else {
+            None
+        };
+        (format, detached_from)
+    } else {
+        (config.format, None)
+    };
+
+    let parsed = StringFormatter::new(format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
                 "symbol" => Some(config.symbol),
@@ -144,6 +182,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                         None
                     }
                 }
+                "detached_from" => detached_from.clone().map(Ok),
                 "remote_name" => {
                     if show_remote && !remote_name_graphemes.is_empty() {
                         Some(Ok(remote_name_graphemes.concat()))
@@ -219,6 +258,67 @@ fn get_branch_info_from_git(
     Some((branch_name, remote_info))
 }
 
+/// The name of the nearest tag, or else branch, the detached HEAD is based on, as found by
+/// `git describe`. It is cached until HEAD moves.
+fn get_detached_from(context: &Context, repo: &Repo, gix_repo: &gix::Repository) -> Option<String> {
+    let head = if uses_reftables(gix_repo) {
+        repo.exec_git(context, ["rev-parse", "HEAD"])?
+            .stdout
+            .trim()
+            .to_string()
+    } else {
+        gix_repo.head_id().ok()?.to_string()
+    };
+
+    let cache_path = cache::cache_dir(context).map(|dir| dir.join(CACHE_FILE));
+    let key = repo.path.to_string_lossy();
+    let cached = cache_path
+        .as_ref()
+        .and_then(|path| cache::read_entry::<DetachedFrom>(path, &key))
+        .filter(|cached| !cached.is_expired() && cached.value.head == head);
+    if let Some(cached) = cached {
+        return cached.value.name;
+    }
+
+    let name = describe(context, repo, gix_repo);
+    if let Some(path) = &cache_path {
+        let detached_from = DetachedFrom {
+            head,
+            name: name.clone(),
+        };
+        cache::save_entry(path, &key, &detached_from, DETACHED_FROM_TTL);
+    }
+    name
+}
+
+fn describe(context: &Context, repo: &Repo, gix_repo: &gix::Repository) -> Option<String> {
+    if uses_reftables(gix_repo) {
+        // Like the branch, use the git executable to read the references
+        return ["--tags", "--all"].into_iter().find_map(|names| {
+            let output = repo.exec_git(context, ["describe", names, "--abbrev=0", "HEAD"])?;
+            let name = output.stdout.trim();
+            let name = ["heads/", "tags/", "remotes/"]
+                .into_iter()
+                .find_map(|prefix| name.strip_prefix(prefix))
+                .unwrap_or(name);
+            (!name.is_empty()).then(|| name.to_string())
+        });
+    }
+
+    let head_commit = gix_repo.head_commit().ok()?;
+    [SelectRef::AllTags, SelectRef::AllRefs]
+        .into_iter()
+        .find_map(|names| {
+            let resolution = head_commit
+                .describe()
+                .names(names)
+                .traverse_first_parent(true)
+                .try_resolve()
+                .ok()??;
+            Some(resolution.outcome.name?.to_string())
+        })
+}
+
 fn get_first_grapheme(text: &str) -> &str {
     UnicodeSegmentation::graphemes(text, true)
         .next()
@@ -609,6 +709,79 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_detached_from_tag() -> io::Result<()> {
+        for mode in NORMAL_AND_REFTABLE {
+            let repo_dir = fixture_repo(mode)?;
+
+            create_command("git")?
+                .args(["tag", "v0.1.0", "@~1"])
+                .current_dir(repo_dir.path())
+                .output()?;
+            create_command("git")?
+                .args(["checkout", "--detach"])
+                .current_dir(repo_dir.path())
+                .output()?;
+
+            let cache_dir = tempfile::tempdir()?;
+            let render = || {
+                ModuleRenderer::new("git_branch")
+                    .config(toml::toml! {
+                        [git_branch]
+                        detached_format = "on [$symbol$branch( \\(from $detached_from\\))]($style) "
+                    })
+                    .env("STARSHIP_CACHE", cache_dir.path().to_string_lossy())
+                    .path(repo_dir.path())
+                    .collect()
+            };
+
+            let expected = Some(format!(
+                "on {} ",
+                Color::Purple
+                    .bold()
+                    .paint(format!("\u{e0a0} {}", "HEAD (from v0.1.0)")),
+            ));
+            assert_eq!(expected, render());
+
+            // Kept until HEAD moves
+            create_command("git")?
+                .args(["tag", "--delete", "v0.1.0"])
+                .current_dir(repo_dir.path())
+                .output()?;
+            assert_eq!(expected, render());
+
+            cache_dir.close()?;
+            repo_dir.close()?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_detached_from_branch() -> io::Result<()> {
+        for mode in NORMAL_AND_REFTABLE {
+            let repo_dir = fixture_repo(mode)?;
+
+            create_command("git")?
+                .args(["checkout", "--detach"])
+                .current_dir(repo_dir.path())
+                .output()?;
+
+            let actual = ModuleRenderer::new("git_branch")
+                .config(toml::toml! {
+                    [git_branch]
+                    detached_format = "$detached_from"
+                })
+                .path(repo_dir.path())
+                .collect();
+
+            let expected = Some("master".into());
+
+            assert_eq!(expected, actual);
+            repo_dir.close()?;
+        }
+        Ok(())
+    }
+
     // This test is not possible until we switch to `git status --porcelain`
     // where we can mock the env for the specific git process. This is because
     // git2 does not care about our mocking and when we set the real `GIT_DIR`
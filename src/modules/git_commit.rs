@@ -1,10 +1,12 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use gix::commit::describe::SelectRef::AllTags;
 
 use crate::configs::git_commit::GitCommitConfig;
 use crate::context::Repo;
 use crate::formatter::StringFormatter;
 
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
 /// Creates a module with the Git commit in the current directory
 ///
 /// Will display the commit hash if the current directory is a git repo
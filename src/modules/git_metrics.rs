@@ -4,7 +4,7 @@ use gix::diff::blob::pipeline::WorktreeRoots;
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use regex::Regex;
 
-use super::Context;
+use super::{Context, Resource};
 use crate::configs::git_status::GitStatusConfig;
 use crate::modules::git_status::uses_reftables;
 use crate::{
@@ -12,6 +12,8 @@ use crate::{
     formatter::string_formatter::StringFormatterError, module::Module,
 };
 
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
 /// Creates a module with the current added/deleted lines in the git repository at the
 /// current directory
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
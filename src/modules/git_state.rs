@@ -1,11 +1,13 @@
 use gix::state::InProgress;
 use std::path::PathBuf;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use crate::configs::git_state::GitStateConfig;
 use crate::context::Repo;
 use crate::formatter::StringFormatter;
 
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
 /// Creates a module with the state of the git repository at the current directory
 ///
 /// During a git operation it will show: REBASING, BISECTING, MERGING, etc.
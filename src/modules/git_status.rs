@@ -1,6 +1,6 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use crate::configs::git_status::GitStatusConfig;
-use crate::formatter::StringFormatter;
+use crate::formatter::{StringFormatter, VariableHolder};
 use crate::segment::Segment;
 use crate::{context, num_configured_starship_threads, num_rayon_threads};
 use gix::bstr::ByteVec;
@@ -12,6 +12,9 @@ use std::sync::{Arc, OnceLock};
 
 const ALL_STATUS_FORMAT: &str =
     "$conflicted$stashed$deleted$renamed$modified$typechanged$staged$untracked";
+const SUMMARY_STATUS_FORMAT: &str = "$conflicted$stashed$summary";
+
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
 
 /// Creates a module with the Git branch in the current directory
 ///
@@ -49,11 +52,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     let info = GitStatusInfo::load(context, repo, config.clone());
+    let count_format = CountFormat::parse(config.count_format);
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|variable, _| match variable {
-                "all_status" => Some(ALL_STATUS_FORMAT),
+                "all_status" => Some(match count_format {
+                    CountFormat::Summary => SUMMARY_STATUS_FORMAT,
+                    CountFormat::Symbols | CountFormat::Counts => ALL_STATUS_FORMAT,
+                }),
                 _ => None,
             })
             .map_style(|variable: &str| match variable {
@@ -63,7 +70,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             .map_variables_to_segments(|variable: &str| {
                 let segments = match variable {
                     "stashed" => info.get_stashed().and_then(|count| {
-                        format_count(config.stashed, "git_status.stashed", context, count)
+                        format_count(
+                            config.stashed,
+                            "git_status.stashed",
+                            context,
+                            count,
+                            count_format,
+                        )
                     }),
                     "ahead_behind" => info.get_ahead_behind().and_then(|(ahead, behind)| {
                         let (ahead, behind) = (ahead?, behind?);
@@ -79,94 +92,40 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                                 },
                             )
                         } else if ahead > 0 && behind == 0 {
-                            format_count(config.ahead, "git_status.ahead", context, ahead)
+                            format_count(
+                                config.ahead,
+                                "git_status.ahead",
+                                context,
+                                ahead,
+                                count_format,
+                            )
                         } else if behind > 0 && ahead == 0 {
-                            format_count(config.behind, "git_status.behind", context, behind)
+                            format_count(
+                                config.behind,
+                                "git_status.behind",
+                                context,
+                                behind,
+                                count_format,
+                            )
                         } else {
                             format_symbol(config.up_to_date, "git_status.up_to_date", context)
                         }
                     }),
-                    "conflicted" => info.get_conflicted().and_then(|count| {
-                        format_count(config.conflicted, "git_status.conflicted", context, count)
-                    }),
-                    "deleted" => info.get_deleted().and_then(|count| {
-                        format_count(config.deleted, "git_status.deleted", context, count)
-                    }),
-                    "renamed" => info.get_renamed().and_then(|count| {
-                        format_count(config.renamed, "git_status.renamed", context, count)
-                    }),
-                    "modified" => info.get_modified().and_then(|count| {
-                        format_count(config.modified, "git_status.modified", context, count)
-                    }),
-                    "staged" => info.get_staged().and_then(|count| {
-                        format_count(config.staged, "git_status.staged", context, count)
-                    }),
-                    "untracked" => info.get_untracked().and_then(|count| {
-                        format_count(config.untracked, "git_status.untracked", context, count)
-                    }),
-                    "typechanged" => info.get_typechanged().and_then(|count| {
-                        format_count(config.typechanged, "git_status.typechanged", context, count)
-                    }),
-                    "worktree_added" => info.get_worktree_added().and_then(|count| {
-                        format_count(
-                            config.worktree_added,
-                            "git_status.worktree_added",
-                            context,
-                            count,
-                        )
-                    }),
-                    "worktree_deleted" => info.get_worktree_deleted().and_then(|count| {
-                        format_count(
-                            config.worktree_deleted,
-                            "git_status.worktree_deleted",
-                            context,
-                            count,
-                        )
-                    }),
-                    "worktree_modified" => info.get_worktree_modified().and_then(|count| {
-                        format_count(
-                            config.worktree_modified,
-                            "git_status.worktree_modified",
-                            context,
-                            count,
-                        )
-                    }),
-                    "worktree_typechanged" => info.get_worktree_typechanged().and_then(|count| {
-                        format_count(
-                            config.worktree_typechanged,
-                            "git_status.worktree_typechanged",
-                            context,
-                            count,
-                        )
-                    }),
-                    "index_added" => info.get_index_added().and_then(|count| {
-                        format_count(config.index_added, "git_status.index_added", context, count)
-                    }),
-                    "index_deleted" => info.get_index_deleted().and_then(|count| {
+                    "summary" => info
+                        .get_repo_status()
+                        .map(RepoStatus::summary)
+                        .filter(|summary| !summary.is_empty())
+                        .map(|summary| Segment::from_text(None, summary)),
+                    _ => status_format(&config, variable).and_then(|format_str| {
+                        let count = info.get_repo_status()?.count(variable)?;
                         format_count(
-                            config.index_deleted,
-                            "git_status.index_deleted",
+                            format_str,
+                            &format!("git_status.{variable}"),
                             context,
                             count,
+                            count_format,
                         )
                     }),
-                    "index_modified" => info.get_index_modified().and_then(|count| {
-                        format_count(
-                            config.index_modified,
-                            "git_status.index_modified",
-                            context,
-                            count,
-                        )
-                    }),
-                    "index_typechanged" => info.get_index_typechanged().and_then(|count| {
-                        format_count(
-                            config.index_typechanged,
-                            "git_status.index_typechanged",
-                            context,
-                            count,
-                        )
-                    }),
-                    _ => None,
                 };
                 segments.map(Ok)
             })
@@ -189,6 +148,33 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// How the number of files in each state is shown
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CountFormat {
+    /// Only the formats of the states, which can show the number with `$count`
+    Symbols,
+    /// The formats of the states followed by the number of files
+    Counts,
+    /// A compact `+3 ~2 -1` summary of the added, changed and deleted files
+    Summary,
+}
+
+impl CountFormat {
+    fn parse(count_format: &str) -> Self {
+        match count_format {
+            "symbols" => Self::Symbols,
+            "counts" => Self::Counts,
+            "summary" => Self::Summary,
+            count_format => {
+                log::warn!(
+                    "Unknown count_format in [git_status] ({count_format}), expected `symbols`, `counts` or `summary`"
+                );
+                Self::Symbols
+            }
+        }
+    }
+}
+
 struct GitStatusInfo<'a> {
     context: &'a Context<'a>,
     repo: &'a context::Repo,
@@ -235,66 +221,33 @@ impl<'a> GitStatusInfo<'a> {
             })
         })
     }
+}
 
-    pub fn get_conflicted(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.conflicted)
-    }
-
-    pub fn get_deleted(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.deleted)
-    }
-
-    pub fn get_renamed(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.renamed)
-    }
-
-    pub fn get_modified(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.modified)
-    }
-
-    pub fn get_staged(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.staged)
-    }
-
-    pub fn get_untracked(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.untracked)
-    }
-
-    pub fn get_typechanged(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.typechanged)
-    }
-
-    pub fn get_worktree_added(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.worktree_added)
-    }
-
-    pub fn get_worktree_deleted(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.worktree_deleted)
-    }
-
-    pub fn get_worktree_modified(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.worktree_modified)
-    }
-
-    pub fn get_worktree_typechanged(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.worktree_typechanged)
-    }
-
-    pub fn get_index_added(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.index_added)
-    }
-
-    pub fn get_index_deleted(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.index_deleted)
-    }
-
-    pub fn get_index_modified(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.index_modified)
-    }
-
-    pub fn get_index_typechanged(&self) -> Option<usize> {
-        self.get_repo_status().map(|data| data.index_typechanged)
-    }
+/// The format of a variable showing the number of files in a state
+fn status_format<'a>(config: &GitStatusConfig<'a>, variable: &str) -> Option<&'a str> {
+    let format_str = match variable {
+        "conflicted" => config.conflicted,
+        "conflicted_added" => config.conflicted_added,
+        "conflicted_deleted" => config.conflicted_deleted,
+        "conflicted_modified" => config.conflicted_modified,
+        "deleted" => config.deleted,
+        "renamed" => config.renamed,
+        "modified" => config.modified,
+        "staged" => config.staged,
+        "untracked" => config.untracked,
+        "typechanged" => config.typechanged,
+        "type_changed" => config.type_changed,
+        "worktree_added" => config.worktree_added,
+        "worktree_deleted" => config.worktree_deleted,
+        "worktree_modified" => config.worktree_modified,
+        "worktree_typechanged" => config.worktree_typechanged,
+        "index_added" => config.index_added,
+        "index_deleted" => config.index_deleted,
+        "index_modified" => config.index_modified,
+        "index_typechanged" => config.index_typechanged,
+        _ => return None,
+    };
+    Some(format_str)
 }
 
 /// Return a globally shared version the repository status so it can be reused.
@@ -380,8 +333,7 @@ fn get_repo_status(
             .stack_size(256 * 1024)
             .spawn({
                 let is_interrupted = is_interrupted.clone();
-                let abort_after =
-                    std::time::Duration::from_millis(context.root_config.command_timeout);
+                let abort_after = context.command_time_limit();
                 move || {
                     std::thread::sleep(abort_after);
                     is_interrupted.store(true, std::sync::atomic::Ordering::SeqCst);
@@ -501,10 +453,20 @@ fn get_repo_status(
                     use gix::status::plumbing::index_as_worktree::{Change, EntryStatus};
                     match change {
                         Item::Modification {
-                            status: EntryStatus::Conflict { .. },
+                            status: EntryStatus::Conflict { summary, .. },
                             ..
                         } => {
+                            use gix::status::plumbing::index_as_worktree::Conflict;
                             repo_status.conflicted += 1;
+                            match summary {
+                                Conflict::BothModified => repo_status.conflicted_modified += 1,
+                                Conflict::BothAdded
+                                | Conflict::AddedByUs
+                                | Conflict::AddedByThem => repo_status.conflicted_added += 1,
+                                Conflict::BothDeleted
+                                | Conflict::DeletedByUs
+                                | Conflict::DeletedByThem => repo_status.conflicted_deleted += 1,
+                            }
                         }
                         Item::Modification {
                             status: EntryStatus::Change(Change::Removed),
@@ -608,6 +570,9 @@ pub(crate) struct RepoStatus {
     behind: Option<usize>,
     pub(crate) changes: Vec<gix::status::Item>,
     conflicted: usize,
+    conflicted_added: usize,
+    conflicted_deleted: usize,
+    conflicted_modified: usize,
     deleted: usize,
     renamed: usize,
     modified: usize,
@@ -625,6 +590,62 @@ pub(crate) struct RepoStatus {
 }
 
 impl RepoStatus {
+    /// The number of files in the state shown by `variable`
+    fn count(&self, variable: &str) -> Option<usize> {
+        let count = match variable {
+            "conflicted" => self.conflicted,
+            "conflicted_added" => self.conflicted_added,
+            "conflicted_deleted" => self.conflicted_deleted,
+            "conflicted_modified" => self.conflicted_modified,
+            "deleted" => self.deleted,
+            "renamed" => self.renamed,
+            "modified" => self.modified,
+            "staged" => self.staged,
+            "untracked" => self.untracked,
+            "typechanged" => self.typechanged,
+            "type_changed" => self.index_typechanged + self.worktree_typechanged,
+            "worktree_added" => self.worktree_added,
+            "worktree_deleted" => self.worktree_deleted,
+            "worktree_modified" => self.worktree_modified,
+            "worktree_typechanged" => self.worktree_typechanged,
+            "index_added" => self.index_added,
+            "index_deleted" => self.index_deleted,
+            "index_modified" => self.index_modified,
+            "index_typechanged" => self.index_typechanged,
+            _ => return None,
+        };
+        Some(count)
+    }
+
+    /// A compact summary of the added, changed and deleted files, like `+3 ~2 -1`, in the index
+    /// and the working directory
+    fn summary(&self) -> String {
+        let added = self.index_added + self.worktree_added + self.untracked;
+        let changed = self.index_modified
+            + self.worktree_modified
+            + self.renamed
+            + self.index_typechanged
+            + self.worktree_typechanged;
+        let deleted = self.index_deleted + self.worktree_deleted;
+        [("+", added), ("~", changed), ("-", deleted)]
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(sign, count)| format!("{sign}{count}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Counts a conflict by its kind, from the two letters of the `XY` field of an unmerged entry
+    fn parse_conflict_status(&mut self, short_status: &str) {
+        self.conflicted += 1;
+        match short_status {
+            "UU" => self.conflicted_modified += 1,
+            "AA" | "AU" | "UA" => self.conflicted_added += 1,
+            "DD" | "DU" | "UD" => self.conflicted_deleted += 1,
+            _ => log::debug!("Unknown kind of conflict in git status output: {short_status}"),
+        }
+    }
+
     fn is_index_typechanged(short_status: &str) -> bool {
         short_status.starts_with('T')
     }
@@ -703,7 +724,7 @@ impl RepoStatus {
                 self.renamed += 1;
                 self.parse_normal_status(&s[2..4]);
             }
-            Some('u') => self.conflicted += 1,
+            Some('u') => self.parse_conflict_status(&s[2..4]),
             Some('?') => self.untracked += 1,
             Some('!') => (),
             Some(_) => log::error!("Unknown line type in git status output"),
@@ -777,11 +798,25 @@ fn format_count(
     config_path: &str,
     context: &Context,
     count: usize,
+    count_format: CountFormat,
 ) -> Option<Vec<Segment>> {
     if count == 0 {
         return None;
     }
 
+    // Formats that show the count themselves are left alone, as are empty ones, which hide a state
+    let with_count;
+    let format_str = if count_format == CountFormat::Counts
+        && !format_str.is_empty()
+        && !StringFormatter::new(format_str)
+            .is_ok_and(|formatter| formatter.get_variables().contains("count"))
+    {
+        with_count = format!("{format_str}$count");
+        &with_count
+    } else {
+        format_str
+    };
+
     format_text(
         format_str,
         config_path,
@@ -1167,6 +1202,28 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[test]
+    fn shows_conflicted_by_kind() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+
+        create_conflict(repo_dir.path())?;
+
+        let actual = ModuleRenderer::new("git_status")
+            .config(toml::toml! {
+                [git_status]
+                format = "$conflicted_added$conflicted_deleted$conflicted_modified"
+                conflicted_added = "A$count"
+                conflicted_deleted = "D$count"
+                conflicted_modified = "M$count"
+            })
+            .path(repo_dir.path())
+            .collect();
+        let expected = Some(String::from("A1"));
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
     #[test]
     fn shows_untracked_file() -> io::Result<()> {
         for mode in NORMAL_AND_REFTABLES {
@@ -1680,6 +1737,27 @@ pub(crate) mod tests {
         repo_dir.close()
     }
 
+    #[test]
+    fn shows_type_changed_with_count() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+
+        create_staged_typechange(repo_dir.path())?;
+
+        let actual = ModuleRenderer::new("git_status")
+            .config(toml::toml! {
+                [git_status]
+                format = "$typechanged$type_changed"
+                typechanged = "W$count"
+                type_changed = "T$count"
+            })
+            .path(repo_dir.path())
+            .collect();
+        let expected = Some(String::from("T1"));
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
     #[test]
     fn shows_staged_and_modified_file() -> io::Result<()> {
         for mode in NORMAL_AND_REFTABLES {
@@ -1698,6 +1776,49 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[test]
+    fn shows_counts_after_symbols() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+
+        create_staged(repo_dir.path())?;
+        create_modified(repo_dir.path())?;
+
+        let actual = ModuleRenderer::new("git_status")
+            .config(toml::toml! {
+                [git_status]
+                count_format = "counts"
+                staged = "+$count"
+            })
+            .path(repo_dir.path())
+            .collect();
+        let expected = format_output("!1+1");
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn shows_summary() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+
+        create_modified(repo_dir.path())?;
+        create_staged(repo_dir.path())?;
+        fs::remove_file(repo_dir.path().join("Cargo.toml"))?;
+        File::create(repo_dir.path().join("notes.md"))?.sync_all()?;
+
+        let actual = ModuleRenderer::new("git_status")
+            .config(toml::toml! {
+                [git_status]
+                count_format = "summary"
+            })
+            .path(repo_dir.path())
+            .collect();
+        let expected = format_output("+2 ~1 -1");
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
     #[test]
     fn shows_renamed_file() -> io::Result<()> {
         for mode in NORMAL_AND_REFTABLES {
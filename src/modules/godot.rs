@@ -0,0 +1,222 @@
+use super::{Context, Module, ModuleConfig, Resource};
+
+use crate::configs::godot::GodotConfig;
+use crate::formatter::{StringFormatter, VersionFormatter};
+use crate::utils::read_file;
+
+use std::sync::LazyLock;
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
+/// Creates a module with the Godot engine version of the current project
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("godot");
+    let config = GodotConfig::try_load(module.config);
+
+    let is_godot_project = context
+        .try_begin_scan()?
+        .set_files(&config.detect_files)
+        .set_extensions(&config.detect_extensions)
+        .set_folders(&config.detect_folders)
+        .is_match();
+
+    if !is_godot_project {
+        return None;
+    }
+
+    let features = LazyLock::new(|| get_project_features(context).unwrap_or_default());
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "version" => {
+                    let godot_version = get_godot_version(context)?;
+                    VersionFormatter::format_module_version(
+                        module.get_name(),
+                        &godot_version,
+                        config.version_format,
+                    )
+                    .map(Ok)
+                }
+                "engine_version" => {
+                    let engine_version = features.iter().find(|f| is_version(f))?;
+                    VersionFormatter::format_module_version(
+                        module.get_name(),
+                        engine_version,
+                        config.version_format,
+                    )
+                    .map(Ok)
+                }
+                "features" => {
+                    let features = features
+                        .iter()
+                        .filter(|f| !is_version(f))
+                        .map(String::as_str)
+                        .collect::<Vec<_>>();
+                    (!features.is_empty()).then(|| Ok(features.join(", ")))
+                }
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `godot`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+fn get_godot_version(context: &Context) -> Option<String> {
+    let output = context.exec_cmd("godot", &["--version"])?.stdout;
+    parse_godot_version(&output)
+}
+
+/// Extracts the numeric part of a version like `4.2.1.stable.official.b09f793f5`
+fn parse_godot_version(godot_version: &str) -> Option<String> {
+    let version = godot_version
+        .trim()
+        .split('.')
+        .take_while(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        .collect::<Vec<_>>()
+        .join(".");
+
+    (!version.is_empty()).then_some(version)
+}
+
+/// Reads the `config/features` list from the `[application]` section of `project.godot`
+fn get_project_features(context: &Context) -> Option<Vec<String>> {
+    let contents = read_file(context.current_dir.join("project.godot")).ok()?;
+
+    let mut in_application = false;
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_application = line == "[application]";
+        } else if in_application && let Some(value) = line.strip_prefix("config/features=") {
+            // e.g. PackedStringArray("4.2", "Forward Plus")
+            let features = value
+                .split('"')
+                .skip(1)
+                .step_by(2)
+                .map(ToString::to_string)
+                .collect();
+            return Some(features);
+        }
+    }
+
+    None
+}
+
+fn is_version(feature: &str) -> bool {
+    feature
+        .split('.')
+        .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_godot_version;
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    const PROJECT_GODOT: &str = r#"; Engine configuration file.
+config_version=5
+
+[application]
+
+config/name="Space Game"
+run/main_scene="res://main.tscn"
+config/features=PackedStringArray("4.2", "Forward Plus")
+
+[rendering]
+
+renderer/rendering_method="forward_plus"
+"#;
+
+    #[test]
+    fn folder_without_godot_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("godot").path(dir.path()).collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_godot_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("project.godot"), PROJECT_GODOT)?;
+
+        let actual = ModuleRenderer::new("godot").path(dir.path()).collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Rgb(71, 140, 191).bold().paint("🤖 v4.2 (v4.2.1) ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn godot_project_features() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("project.godot"), PROJECT_GODOT)?;
+
+        let actual = ModuleRenderer::new("godot")
+            .path(dir.path())
+            .config(toml::toml! {
+                [godot]
+                format = "$engine_version $features"
+            })
+            .collect();
+
+        assert_eq!(Some("v4.2 Forward Plus".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn godot_project_without_features() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("project.godot"),
+            "config_version=4\n\n[application]\n\nconfig/name=\"Old Game\"\n",
+        )?;
+
+        let actual = ModuleRenderer::new("godot").path(dir.path()).collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Rgb(71, 140, 191).bold().paint("🤖 (v4.2.1) ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_godot_version() {
+        assert_eq!(
+            parse_godot_version("4.2.1.stable.official.b09f793f5\n"),
+            Some("4.2.1".to_string())
+        );
+        assert_eq!(
+            parse_godot_version("3.5.stable.official.991bb6ac7"),
+            Some("3.5".to_string())
+        );
+        assert_eq!(parse_godot_version("unknown"), None);
+    }
+}
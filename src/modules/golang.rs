@@ -1,4 +1,4 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::go::GoConfig;
 use crate::formatter::StringFormatter;
@@ -10,6 +10,8 @@ use semver::VersionReq;
 use std::ops::Deref;
 use std::sync::LazyLock;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current Go version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("golang");
@@ -0,0 +1,269 @@
+use std::path::PathBuf;
+
+use super::{Context, Module, ModuleConfig, Resource};
+
+use crate::configs::gpg::GpgConfig;
+use crate::formatter::StringFormatter;
+
+/// The key git signs commits with, from `user.signingkey` and `gpg.format`
+struct SigningKey {
+    format: String,
+    key: String,
+    /// The program set with `gpg.program` or `gpg.<format>.program`
+    program: Option<String>,
+}
+
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
+/// Creates a module showing whether the key git signs commits with is available, so a missing
+/// security key or key file shows up before a commit fails
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("gpg");
+    let config = GpgConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let signing_key = signing_key(context)?;
+    let available = match signing_key.format.as_str() {
+        "openpgp" => openpgp_key_available(context, &signing_key),
+        "ssh" => ssh_key_available(context, &signing_key.key),
+        format => {
+            log::debug!("Unable to check signing keys of format {format:?}");
+            return None;
+        }
+    };
+    let (format, style) = if available {
+        (config.format, config.style)
+    } else {
+        (config.unavailable_format, config.unavailable_style)
+    };
+
+    let parsed = StringFormatter::new(format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "key" => Some(Ok(signing_key.key.as_str())),
+                "format" => Some(Ok(signing_key.format.as_str())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `gpg`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+fn signing_key(context: &Context) -> Option<SigningKey> {
+    let repo = context.get_repo().ok()?.open();
+    let git_config = repo.config_snapshot();
+    let key = git_config.string("user.signingkey")?.to_string();
+    let format = git_config
+        .string("gpg.format")
+        .map_or_else(|| "openpgp".to_string(), |format| format.to_string());
+    let program = git_config
+        .string(format!("gpg.{format}.program"))
+        .or_else(|| {
+            // `gpg.program` only applies to OpenPGP
+            (format == "openpgp")
+                .then(|| git_config.string("gpg.program"))
+                .flatten()
+        })
+        .map(|program| program.to_string());
+    Some(SigningKey {
+        format,
+        key,
+        program,
+    })
+}
+
+/// Whether gpg has the secret of a key able to sign, on disk or on a smartcard that is
+/// plugged in
+fn openpgp_key_available(context: &Context, signing_key: &SigningKey) -> bool {
+    let gpg = signing_key.program.as_deref().unwrap_or("gpg");
+    let Some(output) = context.exec_cmd(
+        gpg,
+        &["--list-secret-keys", "--with-colons", &signing_key.key],
+    ) else {
+        return false;
+    };
+
+    // See doc/DETAILS in the sources of GnuPG for the format of the listing
+    let mut card_serials = Vec::new();
+    for line in output.stdout.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        let (Some(record), Some(capabilities), Some(token)) =
+            (fields.first(), fields.get(11), fields.get(14))
+        else {
+            continue;
+        };
+        if !matches!(*record, "sec" | "ssb") || !capabilities.contains('s') {
+            continue;
+        }
+        match *token {
+            // The secret is stored by gpg
+            "+" => return true,
+            // Only a stub of the secret is stored, e.g. when it was moved to another machine
+            "#" | "" => {}
+            serial => card_serials.push(serial),
+        }
+    }
+    if card_serials.is_empty() {
+        return false;
+    }
+
+    let Some(output) = context.exec_cmd("gpg-connect-agent", &["scd serialno", "/bye"]) else {
+        return false;
+    };
+    output.stdout.lines().any(|line| {
+        line.strip_prefix("S SERIALNO ")
+            .and_then(|rest| rest.split_whitespace().next())
+            .is_some_and(|serial| {
+                card_serials
+                    .iter()
+                    .any(|card_serial| card_serial.eq_ignore_ascii_case(serial))
+            })
+    })
+}
+
+/// Whether the SSH key file exists. Keys given literally, with `key::`, are expected in the
+/// SSH agent and always count as available.
+fn ssh_key_available(context: &Context, key: &str) -> bool {
+    if key.starts_with("key::") || key.starts_with("ssh-") {
+        return true;
+    }
+    let path = match key.strip_prefix("~/") {
+        Some(rest) => match context.get_home() {
+            Some(home) => home.join(rest),
+            None => return false,
+        },
+        None => context.current_dir.join(PathBuf::from(key)),
+    };
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{ModuleRenderer, default_context};
+    use crate::utils::CommandOutput;
+    use std::fs;
+    use std::io;
+
+    fn openpgp_key() -> SigningKey {
+        SigningKey {
+            format: "openpgp".to_string(),
+            key: "ABCD1234".to_string(),
+            program: None,
+        }
+    }
+
+    fn output(stdout: &str) -> Option<CommandOutput> {
+        Some(CommandOutput {
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        })
+    }
+
+    const SECRET_KEYS_ON_CARD: &str = "\
+sec:u:255:22:ABCD1234ABCD1234:1700000000:::u:::cC:::D2760001240100000006123456780000::ed25519:::0:
+fpr:::::::::0123456789ABCDEF0123456789ABCDEFABCD1234:
+ssb:u:255:22:1234ABCD1234ABCD:1700000000::::::s:::D2760001240100000006123456780000::ed25519::
+ssb:u:255:18:ABCDABCDABCDABCD:1700000000::::::e:::D2760001240100000006123456780000::cv25519::
+";
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = ModuleRenderer::new("gpg").collect();
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn openpgp_key_on_disk() {
+        let mut context = default_context();
+        context.cmd.insert(
+            "gpg --list-secret-keys --with-colons ABCD1234",
+            output(
+                "sec:u:255:22:ABCD1234ABCD1234:1700000000:::u:::scSC:::+::ed25519:::0:\n\
+                 ssb:u:255:18:ABCDABCDABCDABCD:1700000000::::::e:::+::cv25519::\n",
+            ),
+        );
+        assert!(openpgp_key_available(&context, &openpgp_key()));
+    }
+
+    #[test]
+    fn openpgp_key_missing() {
+        let mut context = default_context();
+        context
+            .cmd
+            .insert("gpg --list-secret-keys --with-colons ABCD1234", None);
+        assert!(!openpgp_key_available(&context, &openpgp_key()));
+
+        context.cmd.insert(
+            "gpg --list-secret-keys --with-colons ABCD1234",
+            output("sec:u:255:22:ABCD1234ABCD1234:1700000000:::u:::scSC:::#::ed25519:::0:\n"),
+        );
+        assert!(!openpgp_key_available(&context, &openpgp_key()));
+    }
+
+    #[test]
+    fn openpgp_key_on_card() {
+        let mut context = default_context();
+        context.cmd.insert(
+            "gpg --list-secret-keys --with-colons ABCD1234",
+            output(SECRET_KEYS_ON_CARD),
+        );
+        context.cmd.insert(
+            "gpg-connect-agent scd serialno /bye",
+            output("S SERIALNO D2760001240100000006123456780000\nOK\n"),
+        );
+        assert!(openpgp_key_available(&context, &openpgp_key()));
+
+        // Another card is plugged in
+        context.cmd.insert(
+            "gpg-connect-agent scd serialno /bye",
+            output("S SERIALNO D2760001240100000006999999990000\nOK\n"),
+        );
+        assert!(!openpgp_key_available(&context, &openpgp_key()));
+
+        context.cmd.insert(
+            "gpg-connect-agent scd serialno /bye",
+            output("ERR 100696144 No such device <SCD>\n"),
+        );
+        assert!(!openpgp_key_available(&context, &openpgp_key()));
+    }
+
+    #[test]
+    fn ssh_key_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context();
+        context
+            .env
+            .insert("HOME", dir.path().to_string_lossy().to_string());
+
+        assert!(!ssh_key_available(&context, "~/.ssh/id_ed25519.pub"));
+        fs::create_dir(dir.path().join(".ssh"))?;
+        fs::write(
+            dir.path().join(".ssh").join("id_ed25519.pub"),
+            "ssh-ed25519 AAAA",
+        )?;
+        assert!(ssh_key_available(&context, "~/.ssh/id_ed25519.pub"));
+        assert!(ssh_key_available(&context, "key::ssh-ed25519 AAAA"));
+        dir.close()
+    }
+}
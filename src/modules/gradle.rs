@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use super::Resource;
+
 use crate::{
     config::ModuleConfig,
     configs::gradle::GradleConfig,
@@ -9,6 +11,8 @@ use crate::{
     utils,
 };
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("gradle");
     let config = GradleConfig::try_load(module.config);
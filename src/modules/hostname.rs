@@ -6,6 +6,47 @@ use crate::formatter::StringFormatter;
 
 use whoami::hostname;
 
+/// The variables the init scripts count nested SSH sessions in. They are named like locale
+/// variables, which ssh forwards by default on most systems.
+const SSH_DEPTH_VAR: &str = "LC_STARSHIP_SSH_DEPTH";
+const SSH_CONNECTION_VAR: &str = "LC_STARSHIP_SSH_CONNECTION";
+
+/// How the terminal is connected to the host
+#[derive(Debug, PartialEq)]
+enum Session {
+    Local,
+    Ssh,
+    /// A tmux session attached by a client connected over SSH
+    TmuxSsh,
+}
+
+/// Returns the SSH connection of the tmux session, which tmux updates when a client attaches,
+/// unlike the environment of the shell
+fn tmux_ssh_connection(context: &Context) -> Option<String> {
+    let output = context.exec_cmd("tmux", &["show-environment", "SSH_CONNECTION"])?;
+    // Prints `SSH_CONNECTION=<connection>`, or `-SSH_CONNECTION` once it was removed
+    output
+        .stdout
+        .trim()
+        .strip_prefix("SSH_CONNECTION=")
+        .filter(|connection| !connection.is_empty())
+        .map(ToString::to_string)
+}
+
+/// Returns the number of nested SSH sessions the connection is the last of
+fn ssh_depth(context: &Context, connection: &str) -> u32 {
+    let depth = context
+        .get_env(SSH_DEPTH_VAR)
+        .and_then(|depth| depth.parse::<u32>().ok())
+        .unwrap_or(0);
+    // The init script of the shell already counted the connection, unless it wasn't run
+    if context.get_env(SSH_CONNECTION_VAR).as_deref() == Some(connection) {
+        depth.max(1)
+    } else {
+        depth + 1
+    }
+}
+
 /// Creates a module with the system hostname
 ///
 /// Will display the hostname if all of the following criteria are met:
@@ -16,7 +57,22 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("hostname");
     let config: HostnameConfig = HostnameConfig::try_load(module.config);
 
-    let ssh_connection = context.get_env("SSH_CONNECTION");
+    // Inside tmux, the environment of the shell is the one of the first client, so the session
+    // is asked for the current one when there's a format or style for tmux over SSH
+    let tmux_ssh = context.get_env("TMUX").is_some()
+        && (config.tmux_ssh_format.is_some() || config.tmux_ssh_style.is_some());
+    let (session, ssh_connection) = if tmux_ssh {
+        match tmux_ssh_connection(context) {
+            Some(connection) => (Session::TmuxSsh, Some(connection)),
+            None => (Session::Local, None),
+        }
+    } else {
+        match context.get_env("SSH_CONNECTION") {
+            Some(connection) => (Session::Ssh, Some(connection)),
+            None => (Session::Local, None),
+        }
+    };
+    log::trace!("Hostname session: {session:?}");
 
     if (config.ssh_only && ssh_connection.is_none())
         || !context.detect_env_vars(&config.detect_env_vars)
@@ -40,7 +96,28 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         host = alias;
     }
 
-    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+    let ssh_depth = ssh_connection
+        .as_deref()
+        .map(|connection| ssh_depth(context, connection));
+    let (format, style) = match session {
+        Session::Local => (config.format, config.style),
+        Session::Ssh => (
+            config.ssh_format.unwrap_or(config.format),
+            config.ssh_style.unwrap_or(config.style),
+        ),
+        Session::TmuxSsh => (
+            config
+                .tmux_ssh_format
+                .or(config.ssh_format)
+                .unwrap_or(config.format),
+            config
+                .tmux_ssh_style
+                .or(config.ssh_style)
+                .unwrap_or(config.style),
+        ),
+    };
+
+    let parsed = StringFormatter::new(format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
                 "ssh_symbol" => {
@@ -53,11 +130,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => Some(Ok(style)),
                 _ => None,
             })
             .map(|variable| match variable {
-                "hostname" => Some(Ok(host)),
+                "hostname" => Some(Ok(host.to_string())),
+                "ssh_depth" => ssh_depth.map(|depth| Ok(depth.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -78,6 +156,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 mod tests {
     use super::hostname;
     use crate::test::ModuleRenderer;
+    use crate::utils::CommandOutput;
     use nu_ansi_term::{Color, Style};
     use unicode_segmentation::UnicodeSegmentation;
 
@@ -307,6 +386,79 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn ssh_format_and_depth() {
+        let hostname = get_hostname!();
+        let actual = ModuleRenderer::new("hostname")
+            .config(toml::toml! {
+                [hostname]
+                trim_at = ""
+                ssh_format = "[$hostname \\($ssh_depth\\)]($style) "
+                ssh_style = "red"
+            })
+            .env("SSH_CONNECTION", "10.0.0.1 50000 10.0.0.2 22")
+            .env("LC_STARSHIP_SSH_DEPTH", "2")
+            .env("LC_STARSHIP_SSH_CONNECTION", "10.0.0.1 50000 10.0.0.2 22")
+            .collect();
+        let expected = Some(format!("{} ", Color::Red.paint(format!("{hostname} (2)"))));
+        assert_eq!(expected, actual);
+
+        // The connection wasn't counted by the shell on this host
+        let actual = ModuleRenderer::new("hostname")
+            .config(toml::toml! {
+                [hostname]
+                format = "$ssh_depth"
+            })
+            .env("SSH_CONNECTION", "10.0.0.3 50000 10.0.0.4 22")
+            .env("LC_STARSHIP_SSH_DEPTH", "2")
+            .env("LC_STARSHIP_SSH_CONNECTION", "10.0.0.1 50000 10.0.0.2 22")
+            .collect();
+        assert_eq!(Some("3".to_string()), actual);
+    }
+
+    #[test]
+    fn tmux_over_ssh() {
+        let hostname = get_hostname!();
+        let config = toml::toml! {
+            [hostname]
+            trim_at = ""
+            tmux_ssh_style = "bold red"
+        };
+
+        // The shell was started locally, and the session attached over SSH later
+        let actual = ModuleRenderer::new("hostname")
+            .config(config.clone())
+            .env("TMUX", "/tmp/tmux-1000/default,1234,0")
+            .cmd(
+                "tmux show-environment SSH_CONNECTION",
+                Some(CommandOutput {
+                    stdout: "SSH_CONNECTION=10.0.0.1 50000 10.0.0.2 22\n".to_string(),
+                    stderr: String::new(),
+                }),
+            )
+            .collect();
+        let expected = Some(format!(
+            "{} in ",
+            Color::Red.bold().paint(format!("🌐 {hostname}"))
+        ));
+        assert_eq!(expected, actual);
+
+        // The shell was started over SSH, and the session attached locally later
+        let actual = ModuleRenderer::new("hostname")
+            .config(config)
+            .env("TMUX", "/tmp/tmux-1000/default,1234,0")
+            .env("SSH_CONNECTION", "10.0.0.1 50000 10.0.0.2 22")
+            .cmd(
+                "tmux show-environment SSH_CONNECTION",
+                Some(CommandOutput {
+                    stdout: "-SSH_CONNECTION\n".to_string(),
+                    stderr: String::new(),
+                }),
+            )
+            .collect();
+        assert_eq!(None, actual);
+    }
+
     fn style() -> Style {
         Color::Green.bold().dimmed()
     }
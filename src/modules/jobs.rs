@@ -71,6 +71,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         }
     }
 
+    let names = job_names(&props.job_names, &config);
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -83,6 +85,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "number" => Some(Ok(module_number.clone())),
+                "names" if !names.is_empty() => Some(Ok(names.clone())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -99,6 +102,20 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Lists the programs the jobs run, leaving out those after the first `max_names`
+fn job_names(commands: &[String], config: &JobsConfig) -> String {
+    let mut names: Vec<&str> = commands
+        .iter()
+        .filter_map(|command| command.split_whitespace().next())
+        .map(|program| program.rsplit(['/', '\\']).next().unwrap_or(program))
+        .collect();
+    if config.max_names > 0 && names.len() > config.max_names {
+        names.truncate(config.max_names);
+        names.push(config.names_truncation_symbol);
+    }
+    names.join(", ")
+}
+
 #[cfg(test)]
 mod test {
     use crate::test::ModuleRenderer;
@@ -332,4 +349,30 @@ mod test {
         let expected = Some(format!("{} ", Color::Blue.bold().paint("✦1")));
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn job_names() {
+        let config = toml::toml! {
+            [jobs]
+            format = "$number( \\[$names\\])"
+            number_threshold = 1
+        };
+
+        let actual = ModuleRenderer::new("jobs")
+            .config(config.clone())
+            .jobs(2)
+            .job_names(&["cargo build --release", "/usr/bin/vim notes.md"])
+            .collect();
+        assert_eq!(Some("2 [cargo, vim]".to_string()), actual);
+
+        let actual = ModuleRenderer::new("jobs")
+            .config(config.clone())
+            .jobs(4)
+            .job_names(&["vim", "less", "man ls", "sleep 60"])
+            .collect();
+        assert_eq!(Some("4 [vim, less, man, …]".to_string()), actual);
+
+        let actual = ModuleRenderer::new("jobs").config(config).jobs(1).collect();
+        assert_eq!(Some("1".to_string()), actual);
+    }
 }
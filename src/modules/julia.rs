@@ -1,8 +1,13 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::julia::JuliaConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
+use crate::utils::read_file;
+
+use std::path::Path;
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
 
 /// Creates a module with the current Julia version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -41,6 +46,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     )
                     .map(Ok)
                 }
+                "environment" => get_julia_environment(context).map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -57,6 +63,45 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Returns the name of the active Julia project environment, following the rules Julia uses
+/// to resolve `$JULIA_PROJECT`, and the nearest project file when it is unset.
+fn get_julia_environment(context: &Context) -> Option<String> {
+    const PROJECT_FILES: &[&str] = &["JuliaProject.toml", "Project.toml"];
+
+    let project_dir = match context.get_env("JULIA_PROJECT") {
+        Some(project) if project.starts_with('@') && project != "@." => {
+            // A named shared environment, e.g. `@v1.10` or `@myenv`
+            return Some(project[1..].to_string());
+        }
+        Some(project) if !project.is_empty() && project != "@." => {
+            let path = Context::expand_tilde(project.into());
+            if path.is_absolute() {
+                path
+            } else {
+                context.current_dir.join(path)
+            }
+        }
+        // `@.` and an empty value both mean the nearest project
+        _ => context
+            .begin_ancestor_scan()
+            .set_files(PROJECT_FILES)
+            .scan()?,
+    };
+
+    PROJECT_FILES
+        .iter()
+        .find_map(|file| read_file(project_dir.join(file)).ok())
+        .and_then(|contents| {
+            let project: toml::Table = toml::from_str(&contents).ok()?;
+            Some(project.get("name")?.as_str()?.to_string())
+        })
+        .or_else(|| dir_name(&project_dir))
+}
+
+fn dir_name(path: &Path) -> Option<String> {
+    Some(path.file_name()?.to_string_lossy().into_owned())
+}
+
 fn parse_julia_version(julia_stdout: &str) -> Option<String> {
     // julia version output looks like this:
     // julia version 1.4.0
@@ -127,6 +172,84 @@ mod tests {
         dir.close()
     }
 
+    #[test]
+    fn project_environment_name() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("Project.toml"), "name = \"Starship\"\n")?;
+
+        let actual = ModuleRenderer::new("julia")
+            .path(dir.path())
+            .config(toml::toml! {
+                [julia]
+                format = "via [$symbol($environment )]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!("via {}", Color::Purple.bold().paint("ஃ Starship ")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn unnamed_project_environment_from_subdir() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let project_dir = dir.path().join("analysis");
+        let src_dir = project_dir.join("src");
+        std::fs::create_dir_all(&src_dir)?;
+        File::create(project_dir.join("Project.toml"))?.sync_all()?;
+        File::create(src_dir.join("main.jl"))?.sync_all()?;
+
+        let actual = ModuleRenderer::new("julia")
+            .path(&src_dir)
+            .config(toml::toml! {
+                [julia]
+                format = "$environment"
+            })
+            .collect();
+
+        assert_eq!(Some("analysis".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn shared_environment_from_env_var() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("hello.jl"))?.sync_all()?;
+
+        let actual = ModuleRenderer::new("julia")
+            .path(dir.path())
+            .env("JULIA_PROJECT", "@plots")
+            .config(toml::toml! {
+                [julia]
+                format = "$environment"
+            })
+            .collect();
+
+        assert_eq!(Some("plots".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn project_path_from_env_var() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let project_dir = dir.path().join("env");
+        std::fs::create_dir(&project_dir)?;
+        std::fs::write(project_dir.join("JuliaProject.toml"), "name = \"Tools\"\n")?;
+        File::create(dir.path().join("hello.jl"))?.sync_all()?;
+
+        let actual = ModuleRenderer::new("julia")
+            .path(dir.path())
+            .env("JULIA_PROJECT", "env")
+            .config(toml::toml! {
+                [julia]
+                format = "$environment"
+            })
+            .collect();
+
+        assert_eq!(Some("Tools".to_string()), actual);
+        dir.close()
+    }
+
     #[test]
     fn test_parse_julia_version() {
         let input = "julia version 1.4.0";
@@ -4,7 +4,7 @@ use yaml_rust2::{Yaml, YamlLoader};
 use std::borrow::Cow;
 use std::env;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::kubernetes::KubernetesConfig;
 use crate::formatter::StringFormatter;
@@ -132,6 +132,8 @@ impl DataValue for Yaml {
     }
 }
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("kubernetes");
     let config: KubernetesConfig = KubernetesConfig::try_load(module.config);
@@ -0,0 +1,140 @@
+use systemstat::{Platform, System, data::LoadAverage};
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::load_avg::LoadAvgConfig;
+use crate::formatter::StringFormatter;
+
+/// Returns the load averages selected by `averages`, divided by the number of cores if
+/// `normalize` is set
+fn selected_averages(config: &LoadAvgConfig, load: &LoadAverage, cores: usize) -> Vec<f64> {
+    let divisor = if config.normalize { cores.max(1) } else { 1 } as f64;
+    config
+        .averages
+        .iter()
+        .filter_map(|average| match *average {
+            "1" => Some(load.one),
+            "5" => Some(load.five),
+            "15" => Some(load.fifteen),
+            average => {
+                log::warn!(
+                    "Unknown load average in [load_avg] ({average}), expected `1`, `5` or `15`"
+                );
+                None
+            }
+        })
+        .map(|load| f64::from(load) / divisor)
+        .collect()
+}
+
+/// Creates a module with the system load averages
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("load_avg");
+    let config = LoadAvgConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let load = match System::new().load_average() {
+        Ok(load) => load,
+        Err(e) => {
+            log::debug!("Failed to retrieve the load average: {e}");
+            return None;
+        }
+    };
+    let cores = std::thread::available_parallelism().map_or(1, usize::from);
+    let averages = selected_averages(&config, &load, cores);
+
+    // Show the module when any of the selected averages reaches the threshold
+    let highest = averages.iter().copied().reduce(f64::max)?;
+    if highest < config.threshold {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style_rules(&config.style_rules, highest)
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "load" => Some(Ok(averages
+                    .iter()
+                    .map(|average| format!("{average:.2}"))
+                    .collect::<Vec<_>>()
+                    .join(" "))),
+                "one" => Some(Ok(format!("{:.2}", load.one))),
+                "five" => Some(Ok(format!("{:.2}", load.five))),
+                "fifteen" => Some(Ok(format!("{:.2}", load.fifteen))),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `load_avg`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::ModuleRenderer;
+
+    #[test]
+    fn averages() {
+        let load = LoadAverage {
+            one: 4.0,
+            five: 2.0,
+            fifteen: 1.0,
+        };
+        let mut config = LoadAvgConfig::default();
+        assert_eq!(selected_averages(&config, &load, 4), vec![1.0, 0.5, 0.25]);
+
+        config.averages = vec!["15", "1", "10"];
+        config.normalize = false;
+        assert_eq!(selected_averages(&config, &load, 4), vec![1.0, 4.0]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn zero_threshold() {
+        let output = ModuleRenderer::new("load_avg")
+            .config(toml::toml! {
+                [load_avg]
+                disabled = false
+                threshold = 0.0
+            })
+            .collect();
+
+        assert!(output.is_some());
+    }
+
+    #[test]
+    fn impossible_threshold() {
+        let output = ModuleRenderer::new("load_avg")
+            .config(toml::toml! {
+                [load_avg]
+                disabled = false
+                threshold = 9999.0
+            })
+            .collect();
+
+        assert!(output.is_none());
+    }
+}
@@ -4,8 +4,11 @@ use crate::config::ModuleConfig;
 use crate::configs::localip::LocalipConfig;
 use crate::formatter::StringFormatter;
 
+use crate::modules::network::interfaces;
+
+use regex::Regex;
 use std::io::Error;
-use std::net::UdpSocket;
+use std::net::{IpAddr, UdpSocket};
 
 fn get_local_ipv4() -> Result<String, Error> {
     let socket = UdpSocket::bind("0.0.0.0:0")?;
@@ -16,6 +19,53 @@ fn get_local_ipv4() -> Result<String, Error> {
     Ok(addr.ip().to_string())
 }
 
+fn get_local_ipv6() -> Result<IpAddr, Error> {
+    let socket = UdpSocket::bind("[::]:0")?;
+    socket.connect("[2001:db8::1]:80")?;
+
+    Ok(socket.local_addr()?.ip())
+}
+
+fn is_link_local(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(addr) => addr.is_link_local(),
+        IpAddr::V6(addr) => addr.is_unicast_link_local(),
+    }
+}
+
+/// Returns the addresses of the interfaces whose names match any of the `interfaces` patterns,
+/// leaving out loopback addresses
+fn interface_addresses(patterns: &[&str]) -> Vec<IpAddr> {
+    let patterns: Vec<Regex> = patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::warn!("Invalid pattern in `localip.interfaces` ({pattern}): {e}");
+                None
+            }
+        })
+        .collect();
+    interfaces()
+        .into_iter()
+        .filter(|(name, _)| patterns.iter().any(|pattern| pattern.is_match(name)))
+        .flat_map(|(_, addrs)| addrs)
+        .filter(|addr| !addr.is_loopback())
+        .collect()
+}
+
+/// Orders the addresses by family, IPv4 first unless `prefer_ipv6` is set, and leaves out the
+/// link-local ones if `exclude_link_local` is set
+fn select_addresses(
+    mut addrs: Vec<IpAddr>,
+    prefer_ipv6: bool,
+    exclude_link_local: bool,
+) -> Vec<IpAddr> {
+    addrs.retain(|addr| !(exclude_link_local && is_link_local(addr)));
+    addrs.sort_by_key(|addr| addr.is_ipv6() != prefer_ipv6);
+    addrs
+}
+
 /// Creates a module with the ipv4 address of the local machine.
 ///
 /// The IP address is gathered from the local endpoint of an UDP socket
@@ -23,6 +73,9 @@ fn get_local_ipv4() -> Result<String, Error> {
 /// way, especially if there are multiple IP addresses available.
 /// There should be no actual packets send over the wire.
 ///
+/// When `localip.interfaces` is set, the addresses of the interfaces matching it are shown
+/// instead.
+///
 /// Will display the ip if all of the following criteria are met:
 ///     - `localip.disabled` is false
 ///     - `localip.ssh_only` is false OR the user is currently connected as an SSH session (`$SSH_CONNECTION`)
@@ -41,17 +94,53 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let localip = match get_local_ipv4() {
-        Ok(ip) => ip,
-        Err(e) => {
-            // ErrorKind::NetworkUnreachable is unstable
-            if cfg!(target_os = "linux") && e.raw_os_error() == Some(101) {
-                "NetworkUnreachable".to_string()
-            } else {
-                log::warn!("unable to determine local ipv4 address: {e}");
-                return None;
+    let (addrs, unreachable) = if config.interfaces.is_empty() {
+        let mut addrs = Vec::new();
+        let mut unreachable = false;
+        match get_local_ipv4() {
+            Ok(ip) => addrs.extend(ip.parse::<IpAddr>()),
+            Err(e) => {
+                // ErrorKind::NetworkUnreachable is unstable
+                if cfg!(target_os = "linux") && e.raw_os_error() == Some(101) {
+                    unreachable = true;
+                } else {
+                    log::warn!("unable to determine local ipv4 address: {e}");
+                }
             }
         }
+        match get_local_ipv6() {
+            Ok(ip) => addrs.push(ip),
+            Err(e) => log::debug!("unable to determine local ipv6 address: {e}"),
+        }
+        (addrs, unreachable)
+    } else {
+        (interface_addresses(&config.interfaces), false)
+    };
+    let addrs = select_addresses(addrs, config.prefer_ipv6, config.exclude_link_local);
+    log::debug!("Local addresses: {addrs:?}");
+
+    let localipv4 = match addrs.iter().find(|addr| addr.is_ipv4()) {
+        Some(addr) => Some(addr.to_string()),
+        None if unreachable => Some("NetworkUnreachable".to_string()),
+        None => None,
+    };
+    let localipv6 = addrs
+        .iter()
+        .find(|addr| addr.is_ipv6())
+        .map(ToString::to_string);
+    let max_addresses = match config.max_addresses {
+        0 => addrs.len(),
+        max => max,
+    };
+    let localip = if addrs.is_empty() {
+        localipv4.clone()?
+    } else {
+        addrs
+            .iter()
+            .take(max_addresses)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(config.separator)
     };
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
@@ -61,7 +150,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "localipv4" => Some(Ok(&localip)),
+                "localip" => Some(Ok(localip.clone())),
+                "localipv4" => localipv4.clone().map(Ok),
+                "localipv6" => localipv6.clone().map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -80,7 +171,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
 #[cfg(test)]
 mod tests {
-    use crate::modules::localip::get_local_ipv4;
+    use crate::modules::localip::{get_local_ipv4, select_addresses};
     use crate::test::ModuleRenderer;
     use nu_ansi_term::{Color, Style};
 
@@ -165,6 +256,44 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn address_selection() {
+        let addrs: Vec<std::net::IpAddr> = ["fe80::1", "169.254.0.2", "2001:db8::2", "10.0.0.2"]
+            .iter()
+            .map(|addr| addr.parse().unwrap())
+            .collect();
+
+        let to_strings = |addrs: Vec<std::net::IpAddr>| -> Vec<String> {
+            addrs.iter().map(ToString::to_string).collect()
+        };
+        assert_eq!(
+            to_strings(select_addresses(addrs.clone(), false, true)),
+            vec!["10.0.0.2", "2001:db8::2"]
+        );
+        assert_eq!(
+            to_strings(select_addresses(addrs.clone(), true, true)),
+            vec!["2001:db8::2", "10.0.0.2"]
+        );
+        assert_eq!(
+            to_strings(select_addresses(addrs, false, false)),
+            vec!["169.254.0.2", "10.0.0.2", "fe80::1", "2001:db8::2"]
+        );
+    }
+
+    #[test]
+    fn unmatched_interfaces() {
+        let actual = ModuleRenderer::new("localip")
+            .config(toml::toml! {
+                [localip]
+                ssh_only = false
+                disabled = false
+                interfaces = ["^no-such-interface$"]
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
     fn style() -> Style {
         Color::Yellow.bold()
     }
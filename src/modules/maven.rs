@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use super::Resource;
+
 use crate::{
     config::ModuleConfig,
     configs::maven::MavenConfig,
@@ -9,6 +11,8 @@ use crate::{
     utils,
 };
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("maven");
     let config = MavenConfig::try_load(module.config);
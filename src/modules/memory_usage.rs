@@ -1,15 +1,21 @@
+use std::path::Path;
+
 use systemstat::{
     Platform, System,
-    data::{ByteSize, saturating_sub_bytes},
+    data::{ByteSize, Memory, saturating_sub_bytes},
 };
 
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::memory_usage::MemoryConfig;
 use crate::formatter::StringFormatter;
+use crate::utils::read_file;
+
+/// Where the cgroup v2 hierarchy is mounted
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
 
 // Display a `ByteSize` in a human readable format.
-fn display_bs(bs: ByteSize) -> String {
+pub(crate) fn display_bs(bs: ByteSize) -> String {
     let mut display_bytes = bs.to_string_as(true);
     let mut keep = true;
     // Skip decimals and the space before the byte unit.
@@ -41,6 +47,49 @@ fn format_usage_total(total: ByteSize, free: ByteSize) -> String {
     )
 }
 
+/// Returns the memory limit of the cgroup v2 the process is in and the memory left under it,
+/// if the cgroup or any of its ancestors has a limit
+fn cgroup_memory(root: &Path, proc_cgroup: &str) -> Option<(ByteSize, ByteSize)> {
+    // The cgroup v2 entry of `/proc/self/cgroup` has the form `0::/path/of/cgroup`
+    let cgroup = proc_cgroup
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))?;
+    let cgroup = root.join(cgroup.trim().trim_start_matches('/'));
+
+    // The effective limit is the lowest one set on the cgroup and its ancestors, and it is
+    // compared to the memory used by the whole cgroup that sets it
+    let (limit, limited) = cgroup
+        .ancestors()
+        .take_while(|dir| dir.starts_with(root))
+        .filter_map(|dir| {
+            let max = read_file(dir.join("memory.max")).ok()?;
+            Some((max.trim().parse::<u64>().ok()?, dir))
+        })
+        .min_by_key(|(max, _)| *max)?;
+    let current = read_file(limited.join("memory.current"))
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    Some((
+        ByteSize(limit),
+        saturating_sub_bytes(ByteSize(limit), ByteSize(current)),
+    ))
+}
+
+/// Returns the memory to show and where its total comes from: the limit of the cgroup,
+/// when it is lower than the memory of the system
+fn limited_memory(memory: Memory) -> (ByteSize, ByteSize, &'static str) {
+    let cgroup = read_file("/proc/self/cgroup")
+        .ok()
+        .and_then(|proc_cgroup| cgroup_memory(Path::new(CGROUP_ROOT), &proc_cgroup));
+    match cgroup {
+        Some((limit, free)) if limit < memory.total => (limit, free, "cgroup"),
+        _ => (memory.total, memory.free, "system"),
+    }
+}
+
 /// Creates a module with system memory usage information
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("memory_usage");
@@ -76,7 +125,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         }
     };
 
-    let used_pct = pct(memory.total, memory.free);
+    let (total, free, limit_source) = limited_memory(memory);
+    let used_pct = pct(total, free);
 
     if (used_pct.round() as i64) < config.threshold {
         return None;
@@ -88,13 +138,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "symbol" => Some(config.symbol),
                 _ => None,
             })
+            .map_style_rules(&config.style_rules, used_pct)
             .map_style(|variable| match variable {
                 "style" => Some(Ok(config.style)),
                 _ => None,
             })
             .map(|variable| match variable {
-                "ram" => Some(Ok(format_usage_total(memory.total, memory.free))),
+                "ram" => Some(Ok(format_usage_total(total, free))),
                 "ram_pct" => Some(Ok(format!("{used_pct:.0}%"))),
+                "limit_source" => Some(Ok(limit_source.to_string())),
                 "swap" => Some(Ok(format_usage_total(
                     swap.as_ref()?.total,
                     swap.as_ref()?.free,
@@ -160,6 +212,45 @@ mod test {
         assert_eq!(pct(ByteSize(1024 * 1024 * 1024), ByteSize(0)), 100.0);
     }
 
+    #[test]
+    fn cgroup_limit() -> std::io::Result<()> {
+        let root = tempfile::tempdir()?;
+        let container = root.path().join("system.slice/container.scope");
+        std::fs::create_dir_all(&container)?;
+        std::fs::write(root.path().join("system.slice/memory.max"), "2147483648\n")?;
+        std::fs::write(
+            root.path().join("system.slice/memory.current"),
+            "1073741824\n",
+        )?;
+        std::fs::write(container.join("memory.max"), "max\n")?;
+        std::fs::write(container.join("memory.current"), "536870912\n")?;
+
+        // The memory left is the one left to the slice setting the limit
+        assert_eq!(
+            cgroup_memory(root.path(), "0::/system.slice/container.scope\n"),
+            Some((
+                ByteSize(2 * 1024 * 1024 * 1024),
+                ByteSize(1024 * 1024 * 1024)
+            ))
+        );
+        // cgroup v1 hierarchies are not read
+        assert_eq!(
+            cgroup_memory(root.path(), "4:memory:/system.slice/container.scope\n"),
+            None
+        );
+        root.close()
+    }
+
+    #[test]
+    fn cgroup_without_limit() -> std::io::Result<()> {
+        let root = tempfile::tempdir()?;
+        std::fs::write(root.path().join("memory.max"), "max\n")?;
+        std::fs::write(root.path().join("memory.current"), "536870912\n")?;
+
+        assert_eq!(cgroup_memory(root.path(), "0::/\n"), None);
+        root.close()
+    }
+
     #[test]
     fn zero_threshold() {
         let output = ModuleRenderer::new("memory_usage")
@@ -1,20 +1,26 @@
 // While adding out new module add out module to src/module.rs ALL_MODULES const array also.
+pub mod agenda;
 mod aws;
 mod azure;
+mod azure_devops;
 mod buf;
 mod bun;
 mod c;
 mod cc;
 mod character;
+pub mod ci_status;
 mod claude_context;
 mod claude_cost;
 mod claude_model;
+mod cloud;
 mod cmake;
-mod cmd_duration;
+pub mod cmd_duration;
+mod cms;
 mod cobol;
 mod conda;
 mod container;
 mod cpp;
+pub mod cpu_usage;
 mod crystal;
 pub mod custom;
 mod daml;
@@ -22,25 +28,30 @@ mod dart;
 mod deno;
 mod directory;
 mod direnv;
+mod disk_usage;
 mod docker_context;
 mod dotnet;
 mod elixir;
 mod elm;
+mod embedded;
 mod env_var;
 mod erlang;
 mod fennel;
 mod fill;
+mod firebase;
 mod fortran;
 mod fossil_branch;
 mod fossil_metrics;
 mod gcloud;
-mod git_branch;
+pub mod git_branch;
 mod git_commit;
 mod git_metrics;
 mod git_state;
 pub(crate) mod git_status;
 mod gleam;
+mod godot;
 mod golang;
+mod gpg;
 mod gradle;
 mod guix_shell;
 mod haskell;
@@ -55,6 +66,7 @@ mod julia;
 mod kotlin;
 mod kubernetes;
 mod line_break;
+mod load_avg;
 mod localip;
 mod lua;
 mod maven;
@@ -63,7 +75,9 @@ mod meson;
 mod mise;
 mod mojo;
 mod nats;
+mod netlify;
 mod netns;
+mod network;
 mod nim;
 mod nix_shell;
 mod nodejs;
@@ -77,83 +91,106 @@ mod perl;
 mod php;
 mod pijul_channel;
 mod pixi;
+mod platformio;
+pub mod pull_request;
 mod pulumi;
 mod purescript;
 mod python;
 mod quarto;
 mod raku;
 mod red;
+mod release_pending;
 mod rlang;
 mod ruby;
 mod rust;
 mod scala;
+mod secrets_guard;
 mod shell;
 mod shlvl;
 mod singularity;
 mod solidity;
 mod spack;
+mod ssh_agent;
 mod status;
 mod sudo;
+mod supabase;
 mod swift;
 mod terraform;
 mod time;
+mod timer;
+mod unity;
 mod username;
 mod utils;
 mod vagrant;
 mod vcs;
 mod vcsh;
+mod vercel;
 mod vlang;
+mod wsl;
 mod xmake;
 mod zig;
 
 #[cfg(feature = "battery")]
 mod battery;
+#[cfg(feature = "temperature")]
+mod temperature;
 mod typst;
 
 #[cfg(feature = "battery")]
 pub use self::battery::{BatteryInfoProvider, BatteryInfoProviderImpl};
 
 use crate::config::ModuleConfig;
-use crate::context::{Context, Detected, Shell};
+use crate::context::{Context, Detected, Resource, Shell};
 use crate::module::Module;
+use crate::utils::{redact, timing};
 use std::time::Instant;
 
 pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
     let start: Instant = Instant::now();
-    let mut m: Option<Module> = {
+    let _redaction = redact::enter(context, module);
+    let (mut m, profile) = timing::profile_module(|| {
         match module {
             // Keep these ordered alphabetically.
             // Default ordering is handled in configs/starship_root.rs
+            "agenda" => agenda::module(context),
             "aws" => aws::module(context),
             "azure" => azure::module(context),
+            "azure_devops" => azure_devops::module(context),
             #[cfg(feature = "battery")]
             "battery" => battery::module(context),
             "buf" => buf::module(context),
             "bun" => bun::module(context),
             "c" => c::module(context),
             "character" => character::module(context),
+            "ci_status" => ci_status::module(context),
             "claude_context" => claude_context::module(context),
             "claude_cost" => claude_cost::module(context),
             "claude_model" => claude_model::module(context),
+            "cloud" => cloud::module(context),
             "cmake" => cmake::module(context),
             "cmd_duration" => cmd_duration::module(context),
+            "cms" => cms::module(context),
             "cobol" => cobol::module(context),
             "conda" => conda::module(context),
             "container" => container::module(context),
             "cpp" => cpp::module(context),
+            "cpu_usage" => cpu_usage::module(context),
             "daml" => daml::module(context),
             "dart" => dart::module(context),
             "deno" => deno::module(context),
             "directory" => directory::module(context),
             "direnv" => direnv::module(context),
+            "disk_usage" => disk_usage::module(context),
             "docker_context" => docker_context::module(context),
             "dotnet" => dotnet::module(context),
             "elixir" => elixir::module(context),
             "elm" => elm::module(context),
+            "embedded" => embedded::module(context),
             "erlang" => erlang::module(context),
             "env_var" => env_var::module(None, context),
             "fennel" => fennel::module(context),
             "fill" => fill::module(context),
+            "firebase" => firebase::module(context),
             "fortran" => fortran::module(context),
             "fossil_branch" => fossil_branch::module(context),
             "fossil_metrics" => fossil_metrics::module(context),
@@ -164,7 +201,9 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
             "git_state" => git_state::module(context),
             "git_status" => git_status::module(context),
             "gleam" => gleam::module(context),
+            "godot" => godot::module(context),
             "golang" => golang::module(context),
+            "gpg" => gpg::module(context),
             "gradle" => gradle::module(context),
             "guix_shell" => guix_shell::module(context),
             "haskell" => haskell::module(context),
@@ -179,6 +218,7 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
             "kotlin" => kotlin::module(context),
             "kubernetes" => kubernetes::module(context),
             "line_break" => line_break::module(context),
+            "load_avg" => load_avg::module(context),
             "localip" => localip::module(context),
             "lua" => lua::module(context),
             "maven" => maven::module(context),
@@ -187,7 +227,9 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
             "mise" => mise::module(context),
             "mojo" => mojo::module(context),
             "nats" => nats::module(context),
+            "netlify" => netlify::module(context),
             "netns" => netns::module(context),
+            "network" => network::module(context),
             "nim" => nim::module(context),
             "nix_shell" => nix_shell::module(context),
             "nodejs" => nodejs::module(context),
@@ -201,33 +243,45 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
             "php" => php::module(context),
             "pijul_channel" => pijul_channel::module(context),
             "pixi" => pixi::module(context),
+            "platformio" => platformio::module(context),
+            "pull_request" => pull_request::module(context),
             "pulumi" => pulumi::module(context),
             "purescript" => purescript::module(context),
             "python" => python::module(context),
             "quarto" => quarto::module(context),
             "raku" => raku::module(context),
+            "release_pending" => release_pending::module(context),
             "rlang" => rlang::module(context),
             "red" => red::module(context),
             "ruby" => ruby::module(context),
             "rust" => rust::module(context),
             "scala" => scala::module(context),
+            "secrets_guard" => secrets_guard::module(context),
             "shell" => shell::module(context),
             "shlvl" => shlvl::module(context),
             "singularity" => singularity::module(context),
             "solidity" => solidity::module(context),
             "spack" => spack::module(context),
+            "ssh_agent" => ssh_agent::module(context),
             "swift" => swift::module(context),
             "status" => status::module(context),
             "sudo" => sudo::module(context),
+            "supabase" => supabase::module(context),
+            #[cfg(feature = "temperature")]
+            "temperature" => temperature::module(context),
             "terraform" => terraform::module(context),
             "time" => time::module(context),
+            "timer" => timer::module(context),
             "typst" => typst::module(context),
             "crystal" => crystal::module(context),
+            "unity" => unity::module(context),
             "username" => username::module(context),
             "vlang" => vlang::module(context),
+            "wsl" => wsl::module(context),
             "vagrant" => vagrant::module(context),
             "vcs" => vcs::module(context),
             "vcsh" => vcsh::module(context),
+            "vercel" => vercel::module(context),
             "xmake" => xmake::module(context),
             "zig" => zig::module(context),
             env if env.starts_with("env_var.") => {
@@ -244,7 +298,7 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
                 None
             }
         }
-    };
+    });
 
     let elapsed = start.elapsed();
     log::trace!("Took {elapsed:?} to compute module {module:?}");
@@ -255,13 +309,104 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
         // need to create an empty module just to hold the duration for that case
         m.get_or_insert_with(|| context.new_module(module)).duration = elapsed;
     }
+    if profile.is_some() {
+        // Keep the profile of every module when profiling, even if it took less than 1ms
+        m.get_or_insert_with(|| context.new_module(module)).profile = profile;
+    }
     m
 }
 
+/// The resources shared between modules that a module declares as `RESOURCES`, which are
+/// computed before the module is started
+pub fn resources(module: &str) -> &'static [Resource] {
+    match module {
+        // Keep these ordered alphabetically.
+        "azure_devops" => azure_devops::RESOURCES,
+        "buf" => buf::RESOURCES,
+        "bun" => bun::RESOURCES,
+        "c" => c::RESOURCES,
+        "ci_status" => ci_status::RESOURCES,
+        "cmake" => cmake::RESOURCES,
+        "cms" => cms::RESOURCES,
+        "cobol" => cobol::RESOURCES,
+        "cpp" => cpp::RESOURCES,
+        "crystal" => crystal::RESOURCES,
+        "daml" => daml::RESOURCES,
+        "dart" => dart::RESOURCES,
+        "deno" => deno::RESOURCES,
+        "directory" => directory::RESOURCES,
+        "direnv" => direnv::RESOURCES,
+        "docker_context" => docker_context::RESOURCES,
+        "dotnet" => dotnet::RESOURCES,
+        "elixir" => elixir::RESOURCES,
+        "elm" => elm::RESOURCES,
+        "embedded" => embedded::RESOURCES,
+        "erlang" => erlang::RESOURCES,
+        "fennel" => fennel::RESOURCES,
+        "fortran" => fortran::RESOURCES,
+        "git_branch" => git_branch::RESOURCES,
+        "git_commit" => git_commit::RESOURCES,
+        "git_metrics" => git_metrics::RESOURCES,
+        "git_state" => git_state::RESOURCES,
+        "git_status" => git_status::RESOURCES,
+        "gleam" => gleam::RESOURCES,
+        "godot" => godot::RESOURCES,
+        "golang" => golang::RESOURCES,
+        "gpg" => gpg::RESOURCES,
+        "gradle" => gradle::RESOURCES,
+        "haskell" => haskell::RESOURCES,
+        "haxe" => haxe::RESOURCES,
+        "helm" => helm::RESOURCES,
+        "java" => java::RESOURCES,
+        "julia" => julia::RESOURCES,
+        "kotlin" => kotlin::RESOURCES,
+        "kubernetes" => kubernetes::RESOURCES,
+        "lua" => lua::RESOURCES,
+        "maven" => maven::RESOURCES,
+        "mise" => mise::RESOURCES,
+        "mojo" => mojo::RESOURCES,
+        "nim" => nim::RESOURCES,
+        "nodejs" => nodejs::RESOURCES,
+        "ocaml" => ocaml::RESOURCES,
+        "odin" => odin::RESOURCES,
+        "opa" => opa::RESOURCES,
+        "package" => package::RESOURCES,
+        "perl" => perl::RESOURCES,
+        "php" => php::RESOURCES,
+        "pixi" => pixi::RESOURCES,
+        "platformio" => platformio::RESOURCES,
+        "pull_request" => pull_request::RESOURCES,
+        "pulumi" => pulumi::RESOURCES,
+        "purescript" => purescript::RESOURCES,
+        "python" => python::RESOURCES,
+        "quarto" => quarto::RESOURCES,
+        "raku" => raku::RESOURCES,
+        "red" => red::RESOURCES,
+        "rlang" => rlang::RESOURCES,
+        "ruby" => ruby::RESOURCES,
+        "rust" => rust::RESOURCES,
+        "scala" => scala::RESOURCES,
+        "solidity" => solidity::RESOURCES,
+        "swift" => swift::RESOURCES,
+        "terraform" => terraform::RESOURCES,
+        "typst" => typst::RESOURCES,
+        "unity" => unity::RESOURCES,
+        "vagrant" => vagrant::RESOURCES,
+        "vcs" => vcs::RESOURCES,
+        "vlang" => vlang::RESOURCES,
+        "xmake" => xmake::RESOURCES,
+        "zig" => zig::RESOURCES,
+        custom if custom.starts_with("custom") => custom::RESOURCES,
+        _ => &[],
+    }
+}
+
 pub fn description(module: &str) -> &'static str {
     match module {
+        "agenda" => "The next event of a calendar file and the time until it starts",
         "aws" => "The current AWS region and profile",
         "azure" => "The current Azure subscription",
+        "azure_devops" => "The Azure DevOps organization and project of the current repo",
         "battery" => "The current charge of the device's battery and its current charging status",
         "buf" => "The currently installed version of the Buf CLI",
         "bun" => "The currently installed version of the Bun",
@@ -269,28 +414,35 @@ pub fn description(module: &str) -> &'static str {
         "character" => {
             "A character (usually an arrow) beside where the text is entered in your terminal"
         }
+        "ci_status" => "The CI status of the current commit on GitHub or GitLab",
         "claude_context" => "Context window usage for Claude Code session",
         "claude_cost" => "Cost info for Claude Code session",
         "claude_model" => "AI model name for Claude Code session",
+        "cloud" => "The account of the active cloud provider",
         "cmake" => "The currently installed version of CMake",
         "cmd_duration" => "How long the last command took to execute",
+        "cms" => "The CMS name and core version of the current site",
         "cobol" => "The currently installed version of COBOL/GNUCOBOL",
         "conda" => "The current conda environment, if $CONDA_DEFAULT_ENV is set",
         "container" => "The container indicator, if inside a container.",
         "cpp" => "your cpp compiler type",
+        "cpu_usage" => "Current CPU usage",
         "crystal" => "The currently installed version of Crystal",
         "daml" => "The Daml SDK version of your project",
         "dart" => "The currently installed version of Dart",
         "deno" => "The currently installed version of Deno",
         "directory" => "The current working directory",
         "direnv" => "The currently applied direnv file",
+        "disk_usage" => "Disk usage of the filesystem of the current directory",
         "docker_context" => "The current docker context",
         "dotnet" => "The relevant version of the .NET Core SDK for the current directory",
         "elixir" => "The currently installed versions of Elixir and OTP",
         "elm" => "The currently installed version of Elm",
+        "embedded" => "The chip and target triple of an embedded Rust project",
         "erlang" => "Current OTP version",
         "fennel" => "The currently installed version of Fennel",
         "fill" => "Fills the remaining space on the line with a pad string",
+        "firebase" => "The Firebase project targeted from the current directory",
         "fortran" => "The currently used version of Fortran",
         "fossil_branch" => "The active branch of the check-out in your current directory",
         "fossil_metrics" => "The currently added/deleted lines in your check-out",
@@ -301,7 +453,9 @@ pub fn description(module: &str) -> &'static str {
         "git_state" => "The current git operation, and it's progress",
         "git_status" => "Symbol representing the state of the repo",
         "gleam" => "The currently installed version of Gleam",
+        "godot" => "The Godot engine version of the current project",
         "golang" => "The currently installed version of Golang",
+        "gpg" => "Whether the key git signs commits with is available",
         "gradle" => "The currently installed version of Gradle",
         "guix_shell" => "The guix-shell environment",
         "haskell" => "The selected version of the Haskell toolchain",
@@ -316,6 +470,7 @@ pub fn description(module: &str) -> &'static str {
         "kotlin" => "The currently installed version of Kotlin",
         "kubernetes" => "The current Kubernetes context name and, if set, the namespace",
         "line_break" => "Separates the prompt into two lines",
+        "load_avg" => "The system load averages",
         "localip" => "The currently assigned ipv4 address",
         "lua" => "The currently installed version of Lua",
         "maven" => "The Maven Wrapper version of the current project",
@@ -326,7 +481,9 @@ pub fn description(module: &str) -> &'static str {
         "mise" => "The current mise status",
         "mojo" => "The currently installed version of Mojo",
         "nats" => "The current NATS context",
+        "netlify" => "The Netlify site the current directory is linked to",
         "netns" => "The current network namespace",
+        "network" => "The interface of the default route and whether a VPN is up",
         "nim" => "The currently installed version of Nim",
         "nix_shell" => "The nix-shell environment",
         "nodejs" => "The currently installed version of NodeJS",
@@ -342,32 +499,43 @@ pub fn description(module: &str) -> &'static str {
         "pixi" => {
             "The currently installed version of Pixi, and the active environment if $PIXI_ENVIRONMENT_NAME is set"
         }
+        "platformio" => "The default PlatformIO environments and their boards",
+        "pull_request" => "The open pull request of the current branch on GitHub or GitLab",
         "pulumi" => "The current username, stack, and installed version of Pulumi",
         "purescript" => "The currently installed version of PureScript",
         "python" => "The currently installed version of Python",
         "quarto" => "The current installed version of quarto",
         "raku" => "The currently installed version of Raku",
         "red" => "The currently installed version of Red",
+        "release_pending" => "The number of pending changesets or unreleased changelog entries",
         "rlang" => "The currently installed version of R",
         "ruby" => "The currently installed version of Ruby",
         "rust" => "The currently installed version of Rust",
         "scala" => "The currently installed version of Scala",
+        "secrets_guard" => "A warning when secrets are set in the environment",
         "shell" => "The currently used shell indicator",
         "shlvl" => "The current value of SHLVL",
         "singularity" => "The currently used Singularity image",
         "solidity" => "The current installed version of Solidity",
         "spack" => "The current spack environment, if $SPACK_ENV is set",
+        "ssh_agent" => "The number of identities loaded in the SSH agent",
         "status" => "The status of the last command",
         "sudo" => "The sudo credentials are currently cached",
+        "supabase" => "Whether the Supabase CLI targets the local stack or a linked project",
         "swift" => "The currently installed version of Swift",
+        "temperature" => "The temperature of the hottest hardware sensor, on Linux",
         "terraform" => "The currently selected terraform workspace and version",
         "time" => "The current local time",
+        "timer" => "The time left on the timer started by `starship timer start`",
         "typst" => "The current installed version of typst",
+        "unity" => "The Unity editor version required by the current project",
         "username" => "The active user's username",
         "vagrant" => "The currently installed version of Vagrant",
         "vcs" => "The currently active VCS repository (first one matching)",
         "vcsh" => "The currently active VCSH repository",
+        "vercel" => "The Vercel project and team the current directory is linked to",
         "vlang" => "The currently installed version of V",
+        "wsl" => "The WSL distribution and version, and whether the directory is on Windows",
         "xmake" => "The currently installed version of XMake",
         "zig" => "The currently installed version of Zig",
         _ => "<no description>",
@@ -378,6 +546,7 @@ pub fn description(module: &str) -> &'static str {
 mod test {
     use super::*;
     use crate::module::ALL_MODULES;
+    use crate::test::{FixtureProvider, ModuleRenderer, fixture_repo};
 
     #[test]
     fn all_modules_have_description() {
@@ -386,4 +555,20 @@ mod test {
             assert_ne!(description(module), "<no description>");
         }
     }
+
+    #[test]
+    fn modules_declare_the_resources_they_use() -> std::io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        for module in ALL_MODULES {
+            let context: Context = ModuleRenderer::new(module).path(repo_dir.path()).into();
+            handle(module, &context);
+            for resource in [Resource::Repo, Resource::DirContents] {
+                assert!(
+                    !context.is_prepared(resource) || resources(module).contains(&resource),
+                    "{module} uses {resource:?} without declaring it in `RESOURCES`"
+                );
+            }
+        }
+        repo_dir.close()
+    }
 }
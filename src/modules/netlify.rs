@@ -0,0 +1,112 @@
+use serde_json::Value;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::netlify::NetlifyConfig;
+use crate::formatter::StringFormatter;
+use crate::utils;
+
+/// Creates a module with the Netlify site the current directory is linked to with
+/// `netlify link`
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("netlify");
+    let config = NetlifyConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let root = context
+        .begin_ancestor_scan()
+        .set_folders(&[".netlify"])
+        .scan()?;
+    let state_path = root.join(".netlify").join("state.json");
+    let state: Value = match serde_json::from_str(&utils::read_file(&state_path).ok()?) {
+        Ok(state) => state,
+        Err(error) => {
+            log::warn!("Unable to parse {}: {error}", state_path.display());
+            return None;
+        }
+    };
+
+    // The CLI only records the ID of the site
+    let site_id = state["siteId"].as_str()?;
+    let site = config.site_aliases.get(site_id).copied().unwrap_or(site_id);
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "site" => Some(Ok(site)),
+                "site_id" => Some(Ok(site_id)),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `netlify`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    const SITE_ID: &str = "2b0a1f7e-5c3d-4e8a-9f61-0d2c7b4a8e13";
+
+    #[test]
+    fn folder_without_linked_site() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join(".netlify"))?;
+        let actual = ModuleRenderer::new("netlify").path(dir.path()).collect();
+        assert_eq!(actual, None);
+        dir.close()
+    }
+
+    #[test]
+    fn linked_site() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join(".netlify"))?;
+        fs::write(
+            dir.path().join(".netlify").join("state.json"),
+            format!(r#"{{"siteId":"{SITE_ID}"}}"#),
+        )?;
+
+        let actual = ModuleRenderer::new("netlify").path(dir.path()).collect();
+        let expected = Some(format!(
+            "on {} ",
+            Color::Cyan.bold().paint(format!("◈ {SITE_ID}"))
+        ));
+        assert_eq!(actual, expected);
+
+        let mut site_aliases = toml::Table::new();
+        site_aliases.insert(SITE_ID.to_string(), "docs".into());
+        let actual = ModuleRenderer::new("netlify")
+            .path(dir.path())
+            .config(toml::toml! {
+                [netlify]
+                site_aliases = site_aliases
+            })
+            .collect();
+        let expected = Some(format!("on {} ", Color::Cyan.bold().paint("◈ docs")));
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+}
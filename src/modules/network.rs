@@ -0,0 +1,209 @@
+use std::net::{IpAddr, UdpSocket};
+
+use regex::Regex;
+use systemstat::{Platform, System};
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::network::NetworkConfig;
+use crate::formatter::StringFormatter;
+
+/// Returns the local address of the default route, from a UDP socket connected to a reserved
+/// address, which sends no packets
+fn default_route_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.0:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// Returns the names of the interfaces that are up, along with their addresses
+pub(crate) fn interfaces() -> Vec<(String, Vec<IpAddr>)> {
+    let networks = match System::new().networks() {
+        Ok(networks) => networks,
+        Err(e) => {
+            log::debug!("Failed to list the network interfaces: {e}");
+            return Vec::new();
+        }
+    };
+    networks
+        .into_values()
+        .map(|network| {
+            let addrs: Vec<IpAddr> = network
+                .addrs
+                .iter()
+                .filter_map(|addr| match addr.addr {
+                    systemstat::IpAddr::V4(addr) => Some(IpAddr::V4(addr)),
+                    systemstat::IpAddr::V6(addr) => Some(IpAddr::V6(addr)),
+                    _ => None,
+                })
+                .collect();
+            (network.name, addrs)
+        })
+        .filter(|(_, addrs)| !addrs.is_empty())
+        .collect()
+}
+
+/// Returns the names of the interfaces matching any of the `vpn_interfaces` patterns
+fn vpn_interfaces<'a>(names: impl Iterator<Item = &'a str>, patterns: &[&str]) -> Vec<&'a str> {
+    let patterns: Vec<Regex> = patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::warn!("Invalid pattern in `network.vpn_interfaces` ({pattern}): {e}");
+                None
+            }
+        })
+        .collect();
+    names
+        .filter(|name| patterns.iter().any(|pattern| pattern.is_match(name)))
+        .collect()
+}
+
+/// Whether the module is shown in the situation described by `show_when`
+fn is_shown(show_when: &str, online: bool, vpn_up: bool) -> bool {
+    match show_when {
+        "vpn_up" => vpn_up,
+        "vpn_down" => !vpn_up,
+        "offline" => !online,
+        "always" => true,
+        show_when => {
+            log::warn!(
+                "Unknown show_when in [network] ({show_when}), expected `always`, `vpn_up`, `vpn_down` or `offline`"
+            );
+            true
+        }
+    }
+}
+
+/// Returns the name of the Wi-Fi network the interface is connected to
+fn get_ssid(context: &Context, interface: &str) -> Option<String> {
+    let ssid = if cfg!(target_os = "macos") {
+        // Prints `Current Wi-Fi Network: <SSID>`
+        let output = context.exec_cmd("networksetup", &["-getairportnetwork", interface])?;
+        output
+            .stdout
+            .trim()
+            .split_once(": ")
+            .map(|(_, ssid)| ssid.to_string())
+    } else if cfg!(windows) {
+        // Prints a `SSID : <SSID>` line for each connected interface
+        let output = context.exec_cmd("netsh", &["wlan", "show", "interfaces"])?;
+        output.stdout.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == "SSID").then(|| value.trim().to_string())
+        })
+    } else {
+        let output = context.exec_cmd("iwgetid", &["--raw", interface])?;
+        Some(output.stdout.trim().to_string())
+    };
+    ssid.filter(|ssid| !ssid.is_empty())
+}
+
+/// Creates a module with the interface of the default route and the VPN interfaces that are up
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("network");
+    let config = NetworkConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let interfaces = interfaces();
+    let default_interface = default_route_address().and_then(|address| {
+        interfaces
+            .iter()
+            .find(|(_, addrs)| addrs.contains(&address))
+            .map(|(name, _)| name.as_str())
+    });
+    let vpns = vpn_interfaces(
+        interfaces.iter().map(|(name, _)| name.as_str()),
+        &config.vpn_interfaces,
+    );
+    log::debug!("Default route interface: {default_interface:?}, VPN interfaces: {vpns:?}");
+
+    if !is_shown(
+        config.show_when,
+        default_interface.is_some(),
+        !vpns.is_empty(),
+    ) {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                "vpn_symbol" => (!vpns.is_empty()).then_some(config.vpn_symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "interface" => default_interface.map(|interface| Ok(interface.to_string())),
+                "vpn" => (!vpns.is_empty()).then(|| Ok(vpns.join(", "))),
+                "ssid" => get_ssid(context, default_interface?).map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `network`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::ModuleRenderer;
+
+    #[test]
+    fn vpn_interface_patterns() {
+        let names = ["lo", "eth0", "wg0", "tailscale0", "tunnel"];
+        assert_eq!(
+            vpn_interfaces(names.into_iter(), &NetworkConfig::default().vpn_interfaces),
+            vec!["wg0", "tailscale0", "tunnel"]
+        );
+        assert_eq!(
+            vpn_interfaces(names.into_iter(), &[r"^tun\d", "(", "^eth"]),
+            vec!["eth0"]
+        );
+    }
+
+    #[test]
+    fn show_when() {
+        assert!(is_shown("always", true, false));
+        assert!(is_shown("vpn_up", true, true));
+        assert!(!is_shown("vpn_up", true, false));
+        assert!(is_shown("vpn_down", true, false));
+        assert!(!is_shown("vpn_down", false, true));
+        assert!(is_shown("offline", false, false));
+        assert!(!is_shown("offline", true, false));
+    }
+
+    #[test]
+    fn hidden_without_vpn() {
+        let output = ModuleRenderer::new("network")
+            .config(toml::toml! {
+                [network]
+                disabled = false
+                vpn_interfaces = ["^no-such-interface$"]
+                show_when = "vpn_up"
+            })
+            .collect();
+
+        assert_eq!(output, None);
+    }
+}
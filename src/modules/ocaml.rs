@@ -1,4 +1,4 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use std::ops::Deref;
 use std::path::Path;
 use std::sync::LazyLock;
@@ -14,6 +14,8 @@ enum SwitchType {
 }
 type OpamSwitch = (SwitchType, String);
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current OCaml version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("ocaml");
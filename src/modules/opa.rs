@@ -1,11 +1,13 @@
 /// Creates a module with the current Open Policy Agent version
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::opa::OpaConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 use crate::utils::get_command_string_output;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current Open Policy Agent version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("opa");
@@ -2,6 +2,7 @@ use yaml_rust2::YamlLoader;
 
 use super::{Context, Module, ModuleConfig};
 
+use super::cloud::CloudAccount;
 use crate::configs::openstack::OspConfig;
 use crate::formatter::StringFormatter;
 use crate::utils;
@@ -46,6 +47,17 @@ fn get_osp_cloud_and_project(context: &Context) -> (Option<Cloud>, Option<Projec
     }
 }
 
+/// The cloud and region shown by the `cloud` module, if a cloud is selected in the environment
+pub fn cloud_account<'a>(context: &'a Context) -> Option<CloudAccount<'a>> {
+    let osp_cloud = context.get_env("OS_CLOUD")?;
+    let config = OspConfig::try_load(context.new_module("openstack").config);
+    Some(CloudAccount {
+        symbol: config.symbol,
+        name: Some(osp_cloud),
+        region: context.get_env("OS_REGION_NAME"),
+    })
+}
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("openstack");
     let config: OspConfig = OspConfig::try_load(module.config);
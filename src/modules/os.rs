@@ -1,7 +1,10 @@
+use std::collections::HashMap;
+
 use super::{Context, Module, ModuleConfig};
 
-use crate::configs::os::OSConfig;
+use crate::configs::os::{OSConfig, OSSymbolKey};
 use crate::formatter::StringFormatter;
+use crate::utils::{context_path, read_file};
 
 /// Creates a module with the current operating system
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -18,10 +21,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     #[cfg(test)]
     let os = os_info::Info::default();
 
+    let os_release = os_release(context);
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|variable, _| match variable {
-                "symbol" => get_symbol(&config, os.os_type()),
+                "symbol" => get_symbol(&config, os.os_type(), &os_release),
                 _ => None,
             })
             .map_style(|variable| match variable {
@@ -34,6 +39,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "name" => get_name(&os).map(Ok),
                 "type" => get_type(&os).map(Ok),
                 "version" => get_version(&os).map(Ok),
+                "variant" => get_variant(&os_release).map(Ok),
+                "build_id" => os_release.get("BUILD_ID").cloned().map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -49,12 +56,80 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Reads the fields of `/etc/os-release`, or of `/usr/lib/os-release` if it is missing
+fn os_release(context: &Context) -> HashMap<String, String> {
+    let Ok(contents) = read_file(context_path(context, "/etc/os-release"))
+        .or_else(|_| read_file(context_path(context, "/usr/lib/os-release")))
+    else {
+        return HashMap::new();
+    };
+
+    contents
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .or_else(|| {
+                    value
+                        .strip_prefix('\'')
+                        .and_then(|value| value.strip_suffix('\''))
+                })
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+}
+
 // Get the operating system symbol from user config, or else default config
 // when user has not defined a symbol for the operating system.
-fn get_symbol<'a>(config: &'a OSConfig, os_type: os_info::Type) -> Option<&'a str> {
-    config
-        .get_symbol(os_type)
-        .or_else(|| OSConfig::default().get_symbol(os_type))
+// Distributions os_info doesn't know are matched by their `ID` in os-release, then by the
+// distributions they are like in `ID_LIKE`, either in the symbols set for IDs, or by type.
+fn get_symbol<'a>(
+    config: &'a OSConfig,
+    os_type: os_info::Type,
+    os_release: &HashMap<String, String>,
+) -> Option<&'a str> {
+    let type_symbol = |os_type| {
+        config
+            .get_symbol(os_type)
+            .or_else(|| OSConfig::default().get_symbol(os_type))
+    };
+
+    if matches!(os_type, os_info::Type::Linux | os_info::Type::Unknown) {
+        let ids = os_release
+            .get("ID")
+            .into_iter()
+            .chain(os_release.get("ID_LIKE"))
+            .flat_map(|ids| ids.split_whitespace());
+        for id in ids {
+            if let Some(symbol) = config.get_id_symbol(id) {
+                return Some(symbol);
+            }
+            if let Some(symbol) = type_from_id(id).and_then(type_symbol) {
+                return Some(symbol);
+            }
+        }
+    }
+
+    type_symbol(os_type)
+}
+
+/// Returns the type whose name matches the `ID` of a distribution, e.g. `Debian` for `debian`
+fn type_from_id(id: &str) -> Option<os_info::Type> {
+    OSConfig::default()
+        .symbols
+        .into_keys()
+        .find_map(|key| match key {
+            OSSymbolKey::Type(os_type) if format!("{os_type:?}").eq_ignore_ascii_case(id) => {
+                Some(os_type)
+            }
+            _ => None,
+        })
 }
 
 fn get_codename(os: &os_info::Info) -> Option<String> {
@@ -74,6 +149,17 @@ fn get_type(os: &os_info::Info) -> Option<String> {
     Some(format!("{:?}", os.os_type()))
 }
 
+/// The variant of the distribution, e.g. `Silverblue` or `CoreOS` for Fedora, or `edge` for
+/// the development branch of Alpine, which is only named in `PRETTY_NAME`
+fn get_variant(os_release: &HashMap<String, String>) -> Option<String> {
+    os_release.get("VARIANT").cloned().or_else(|| {
+        os_release
+            .get("PRETTY_NAME")?
+            .ends_with(" edge")
+            .then(|| "edge".to_string())
+    })
+}
+
 fn get_version(os: &os_info::Info) -> Option<String> {
     Some(os.version())
         .filter(|&x| x != &os_info::Version::Unknown)
@@ -126,6 +212,79 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn variant_and_build_id() -> std::io::Result<()> {
+        let renderer = ModuleRenderer::new("os").config(toml::toml! {
+            [os]
+            disabled = false
+            format = "$variant $build_id"
+        });
+
+        let os_release = renderer.root_path().join("etc/os-release");
+        std::fs::create_dir_all(os_release.parent().unwrap())?;
+        crate::utils::write_file(
+            &os_release,
+            "NAME=\"Fedora Linux\"\nID=fedora\n# A comment\nVARIANT=\"Silverblue\"\nVARIANT_ID=silverblue\nBUILD_ID='40.20240620.0'\n",
+        )?;
+
+        assert_eq!(
+            renderer.collect(),
+            Some("Silverblue 40.20240620.0".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn alpine_edge_variant() {
+        let os_release = HashMap::from([
+            ("ID".to_string(), "alpine".to_string()),
+            ("PRETTY_NAME".to_string(), "Alpine Linux edge".to_string()),
+        ]);
+
+        assert_eq!(get_variant(&os_release), Some("edge".to_string()));
+        assert_eq!(get_variant(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn get_symbol_from_os_release_id() {
+        let config_toml = toml::toml! {
+            [symbols]
+            rhel = "R "
+            kinoite = "K "
+        };
+        let config = OSConfig::load(&config_toml);
+        let os_release = |id: &str, id_like: &str| {
+            HashMap::from([
+                ("ID".to_string(), id.to_string()),
+                ("ID_LIKE".to_string(), id_like.to_string()),
+            ])
+        };
+
+        // The ID is matched first, then the IDs in ID_LIKE in order
+        assert_eq!(
+            get_symbol(&config, Type::Linux, &os_release("kinoite", "rhel fedora")),
+            Some("K ")
+        );
+        assert_eq!(
+            get_symbol(&config, Type::Linux, &os_release("myos", "rhel fedora")),
+            Some("R ")
+        );
+        // IDs are matched against the names of types as well
+        assert_eq!(
+            get_symbol(&config, Type::Unknown, &os_release("myos", "debian")),
+            Some("🌀 ")
+        );
+        assert_eq!(
+            get_symbol(&config, Type::Linux, &os_release("myos", "other")),
+            Some("🐧 ")
+        );
+        // Known types are not looked up by ID
+        assert_eq!(
+            get_symbol(&config, Type::Arch, &os_release("rhel", "")),
+            Some("🎗️ ")
+        );
+    }
+
     #[test]
     fn get_symbol_default() {
         let config = OSConfig::default();
@@ -171,7 +330,7 @@ mod tests {
         ];
 
         for (t, e) in type_expected_pairs {
-            assert_eq!(get_symbol(&config, t), e);
+            assert_eq!(get_symbol(&config, t, &HashMap::new()), e);
         }
     }
 
@@ -261,7 +420,7 @@ mod tests {
         ];
 
         for (t, e) in type_expected_pairs {
-            assert_eq!(get_symbol(&config, t), e);
+            assert_eq!(get_symbol(&config, t, &HashMap::new()), e);
         }
     }
 
@@ -316,7 +475,7 @@ mod tests {
         ];
 
         for (t, e) in type_expected_pairs {
-            assert_eq!(get_symbol(&config, t), e);
+            assert_eq!(get_symbol(&config, t, &HashMap::new()), e);
         }
     }
 
@@ -1,4 +1,4 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use crate::configs::package::PackageConfig;
 use crate::formatter::{StringFormatter, VersionFormatter};
 use crate::utils::read_file;
@@ -13,11 +13,13 @@ use std::fs;
 use std::io::Read;
 use versions::Version;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current package version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("package");
     let config: PackageConfig = PackageConfig::try_load(module.config);
-    let module_version = get_version(context, &config)?;
+    let package = get_package(context, &config)?;
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -30,7 +32,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => Some(Ok(&module_version)),
+                "version" => Some(Ok(package.version.as_str())),
+                "name" => package.name.as_deref().map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -47,7 +50,25 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
-fn get_node_package_version(context: &Context, config: &PackageConfig) -> Option<String> {
+/// The name and formatted version of a package, read from the same manifest
+struct PackageInfo {
+    name: Option<String>,
+    version: String,
+}
+
+impl PackageInfo {
+    fn new(name: Option<&str>, version: String) -> Self {
+        Self {
+            name: name
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(ToOwned::to_owned),
+            version,
+        }
+    }
+}
+
+fn get_node_package_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("package.json")?;
     let package_json: json::Value = json::from_str(&file_contents).ok()?;
 
@@ -62,16 +83,17 @@ fn get_node_package_version(context: &Context, config: &PackageConfig) -> Option
         return None;
     }
 
+    let name = package_json.get("name").and_then(json::Value::as_str);
     let formatted_version = format_version(raw_version, config.version_format)?;
     if formatted_version == "v0.0.0-development" || formatted_version.starts_with("v0.0.0-semantic")
     {
-        return Some("semantic".to_string());
+        return Some(PackageInfo::new(name, "semantic".to_string()));
     }
 
-    Some(formatted_version)
+    Some(PackageInfo::new(name, formatted_version))
 }
 
-fn get_jsr_package_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_jsr_package_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let (filename, contents) = ["deno.json", "deno.jsonc", "jsr.json", "jsr.jsonc"]
         .iter()
         .find_map(|filename| {
@@ -87,7 +109,11 @@ fn get_jsr_package_version(context: &Context, config: &PackageConfig) -> Option<
     };
 
     let raw_version = json_content.get("version")?.as_str()?;
-    format_version(raw_version, config.version_format)
+    let name = json_content.get("name").and_then(json::Value::as_str);
+    Some(PackageInfo::new(
+        name,
+        format_version(raw_version, config.version_format)?,
+    ))
 }
 
 fn get_poetry_version(pyproject: &toml::Table) -> Option<String> {
@@ -99,6 +125,10 @@ fn get_poetry_version(pyproject: &toml::Table) -> Option<String> {
         .map(|s| s.to_owned())
 }
 
+fn get_poetry_name(pyproject: &toml::Table) -> Option<&str> {
+    pyproject.get("tool")?.get("poetry")?.get("name")?.as_str()
+}
+
 fn parse_file_version_for_hatchling(context: &Context, path: &str) -> Option<String> {
     let file_contents = read_file(context.current_dir.join(path)).ok()?;
     // https://hatch.pypa.io/latest/version/
@@ -151,16 +181,28 @@ fn get_pep621_version(context: &Context, pyproject: &toml::Table) -> Option<Stri
     get_pep621_static_version(pyproject).or_else(|| get_pep621_dynamic_version(context, pyproject))
 }
 
-fn get_pyproject_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_pep621_name(pyproject: &toml::Table) -> Option<&str> {
+    pyproject.get("project")?.get("name")?.as_str()
+}
+
+fn get_pyproject_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("pyproject.toml")?;
     let pyproject_toml: toml::Table = toml::from_str(&file_contents).ok()?;
 
-    get_pep621_version(context, &pyproject_toml)
-        .or_else(|| get_poetry_version(&pyproject_toml))
-        .and_then(|raw_version| format_string_version(raw_version, config.version_format))
+    let (name, raw_version) = get_pep621_version(context, &pyproject_toml)
+        .map(|version| (get_pep621_name(&pyproject_toml), version))
+        .or_else(|| {
+            get_poetry_version(&pyproject_toml)
+                .map(|version| (get_poetry_name(&pyproject_toml), version))
+        })?;
+
+    Some(PackageInfo::new(
+        name,
+        format_string_version(raw_version, config.version_format)?,
+    ))
 }
 
-fn get_setup_cfg_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_setup_cfg_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("setup.cfg")?;
     let ini = Ini::load_from_str(&file_contents).ok()?;
     let raw_version = ini.get_from(Some("metadata"), "version")?;
@@ -168,11 +210,14 @@ fn get_setup_cfg_version(context: &Context, config: &PackageConfig) -> Option<St
     if raw_version.starts_with("attr:") || raw_version.starts_with("file:") {
         None
     } else {
-        format_version(raw_version, config.version_format)
+        Some(PackageInfo::new(
+            ini.get_from(Some("metadata"), "name"),
+            format_version(raw_version, config.version_format)?,
+        ))
     }
 }
 
-fn get_gradle_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_gradle_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     context
         .read_file_from_pwd("gradle.properties")
         .and_then(|contents| {
@@ -186,65 +231,95 @@ fn get_gradle_version(context: &Context, config: &PackageConfig) -> Option<Strin
             format_version(&caps["version"], config.version_format)
 
         })
+        .map(|version| {
+            // The project name lives in the settings file, not next to the version
+            let name = ["settings.gradle", "settings.gradle.kts"]
+                .iter()
+                .find_map(|file| context.read_file_from_pwd(file))
+                .and_then(|contents| {
+                    let re = Regex::new(r#"(?m)^\s*rootProject\.name\s*=\s*['"](?P<name>[^'"]+)['"]"#).unwrap();
+                    re.captures(&contents).map(|caps| caps["name"].to_owned())
+                });
+            PackageInfo::new(name.as_deref(), version)
+        })
 }
 
-fn get_composer_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_composer_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("composer.json")?;
     let composer_json: json::Value = json::from_str(&file_contents).ok()?;
     let raw_version = composer_json.get("version")?.as_str()?;
 
-    format_version(raw_version, config.version_format)
+    Some(PackageInfo::new(
+        composer_json.get("name").and_then(json::Value::as_str),
+        format_version(raw_version, config.version_format)?,
+    ))
 }
 
-fn get_julia_project_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_julia_project_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("Project.toml")?;
     let project_toml: toml::Table = toml::from_str(&file_contents).ok()?;
     let raw_version = project_toml.get("version")?.as_str()?;
 
-    format_version(raw_version, config.version_format)
+    Some(PackageInfo::new(
+        project_toml.get("name").and_then(toml::Value::as_str),
+        format_version(raw_version, config.version_format)?,
+    ))
 }
 
-fn get_helm_package_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_helm_package_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("Chart.yaml")?;
     let yaml = yaml_rust2::YamlLoader::load_from_str(&file_contents).ok()?;
-    let version = yaml.first()?["version"].as_str()?;
+    let chart = yaml.first()?;
+    let version = chart["version"].as_str()?;
 
-    format_version(version, config.version_format)
+    Some(PackageInfo::new(
+        chart["name"].as_str(),
+        format_version(version, config.version_format)?,
+    ))
 }
 
-fn get_mix_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_mix_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("mix.exs")?;
     let re = Regex::new(r#"(?m)version: "(?P<version>[^"]+)""#).unwrap();
     let caps = re.captures(&file_contents)?;
 
-    format_version(&caps["version"], config.version_format)
+    let name_re = Regex::new(r"(?m)app: :(?P<name>\w+)").unwrap();
+    let name = name_re.captures(&file_contents);
+
+    Some(PackageInfo::new(
+        name.as_ref().map(|caps| &caps["name"]),
+        format_version(&caps["version"], config.version_format)?,
+    ))
 }
 
-fn get_maven_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_maven_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("pom.xml")?;
     let mut reader = QXReader::from_str(&file_contents);
     reader.config_mut().trim_text(true);
 
     let mut buf = vec![];
     let mut in_ver = false;
+    let mut in_artifact_id = false;
+    let mut name = None;
+    let mut version = None;
     let mut depth = 0;
     loop {
         match reader.read_event_into(&mut buf) {
             Ok(QXEvent::Start(ref e)) => {
                 in_ver = depth == 1 && e.name().as_ref() == b"version";
+                in_artifact_id = depth == 1 && e.name().as_ref() == b"artifactId";
                 depth += 1;
             }
             Ok(QXEvent::End(_)) => {
                 in_ver = false;
+                in_artifact_id = false;
                 depth -= 1;
             }
+            Ok(QXEvent::Text(t)) if in_artifact_id => {
+                name = t.decode().ok().map(std::borrow::Cow::into_owned);
+            }
             Ok(QXEvent::Text(t)) if in_ver => {
-                let ver = t.decode().ok().map(std::borrow::Cow::into_owned);
-                return match ver {
-                    // Ignore version which is just a property reference
-                    Some(ref v) if !v.starts_with('$') => format_version(v, config.version_format),
-                    _ => None,
-                };
+                version = t.decode().ok().map(std::borrow::Cow::into_owned);
             }
             Ok(QXEvent::Eof) => break,
             Ok(_) => (),
@@ -254,12 +329,22 @@ fn get_maven_version(context: &Context, config: &PackageConfig) -> Option<String
                 break;
             }
         }
+        if name.is_some() && version.is_some() {
+            break;
+        }
     }
 
-    None
+    match version {
+        // Ignore version which is just a property reference
+        Some(ref v) if !v.starts_with('$') => Some(PackageInfo::new(
+            name.as_deref(),
+            format_version(v, config.version_format)?,
+        )),
+        _ => None,
+    }
 }
 
-fn get_meson_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_meson_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context
         .read_file_from_pwd("meson.build")?
         .split_ascii_whitespace()
@@ -268,35 +353,63 @@ fn get_meson_version(context: &Context, config: &PackageConfig) -> Option<String
     let re = Regex::new(r"project\([^())]*,version:'(?P<version>[^']+)'[^())]*\)").unwrap();
     let caps = re.captures(&file_contents)?;
 
-    format_version(&caps["version"], config.version_format)
+    let name_re = Regex::new(r"project\('(?P<name>[^']+)'").unwrap();
+    let name = name_re.captures(&file_contents);
+
+    Some(PackageInfo::new(
+        name.as_ref().map(|caps| &caps["name"]),
+        format_version(&caps["version"], config.version_format)?,
+    ))
 }
 
-fn get_vmod_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_vmod_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("v.mod")?;
     let re = Regex::new(r"(?m)^\s*version\s*:\s*'(?P<version>[^']+)'").unwrap();
     let caps = re.captures(&file_contents)?;
-    format_version(&caps["version"], config.version_format)
+
+    let name_re = Regex::new(r"(?m)^\s*name\s*:\s*'(?P<name>[^']+)'").unwrap();
+    let name = name_re.captures(&file_contents);
+
+    Some(PackageInfo::new(
+        name.as_ref().map(|caps| &caps["name"]),
+        format_version(&caps["version"], config.version_format)?,
+    ))
 }
 
-fn get_vpkg_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_vpkg_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("vpkg.json")?;
     let vpkg_json: json::Value = json::from_str(&file_contents).ok()?;
     let raw_version = vpkg_json.get("version")?.as_str()?;
 
-    format_version(raw_version, config.version_format)
+    Some(PackageInfo::new(
+        vpkg_json.get("name").and_then(json::Value::as_str),
+        format_version(raw_version, config.version_format)?,
+    ))
 }
 
-fn get_sbt_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_sbt_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("build.sbt")?;
     let re = Regex::new(r"(?m)^(.*/)*\s*version\s*:=\s*.(?P<version>[\d\.]+)").unwrap();
     let caps = re.captures(&file_contents)?;
-    format_version(&caps["version"], config.version_format)
+
+    let name_re = Regex::new(r#"(?m)^\s*name\s*:=\s*"(?P<name>[^"]+)""#).unwrap();
+    let name = name_re.captures(&file_contents);
+
+    Some(PackageInfo::new(
+        name.as_ref().map(|caps| &caps["name"]),
+        format_version(&caps["version"], config.version_format)?,
+    ))
 }
 
-fn get_cargo_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_cargo_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let mut file_contents = context.read_file_from_pwd("Cargo.toml")?;
 
     let mut cargo_toml: toml::Table = toml::from_str(&file_contents).ok()?;
+    let name = cargo_toml
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(toml::Value::as_str)
+        .map(ToOwned::to_owned);
     let cargo_version = cargo_toml.get("package").and_then(|p| p.get("version"));
     let raw_version = if let Some(v) = cargo_version.and_then(toml::Value::as_str) {
         // regular version string
@@ -340,10 +453,13 @@ fn get_cargo_version(context: &Context, config: &PackageConfig) -> Option<String
             .as_str()?
     };
 
-    format_version(raw_version, config.version_format)
+    Some(PackageInfo::new(
+        name.as_deref(),
+        format_version(raw_version, config.version_format)?,
+    ))
 }
 
-fn get_nimble_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_nimble_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     if !context
         .try_begin_scan()?
         .set_extensions(&["nimble"])
@@ -357,53 +473,62 @@ fn get_nimble_version(context: &Context, config: &PackageConfig) -> Option<Strin
 
     let raw_version = nimble_json.get("version")?.as_str()?;
 
-    format_version(raw_version, config.version_format)
+    Some(PackageInfo::new(
+        nimble_json.get("name").and_then(json::Value::as_str),
+        format_version(raw_version, config.version_format)?,
+    ))
 }
 
-fn get_shard_version(context: &Context, config: &PackageConfig) -> Option<String> {
-    let file_contents = context.read_file_from_pwd("shard.yml")?;
+/// Reads the `name` and `version` keys from the first document of a YAML manifest
+fn get_yaml_manifest_version(
+    context: &Context,
+    config: &PackageConfig,
+    file_name: &str,
+) -> Option<PackageInfo> {
+    let file_contents = context.read_file_from_pwd(file_name)?;
 
     let data = yaml_rust2::YamlLoader::load_from_str(&file_contents).ok()?;
-    let raw_version = data.first()?["version"].as_str()?;
+    let manifest = data.first()?;
+    let raw_version = manifest["version"].as_str()?;
 
-    format_version(raw_version, config.version_format)
+    Some(PackageInfo::new(
+        manifest["name"].as_str(),
+        format_version(raw_version, config.version_format)?,
+    ))
 }
 
-fn get_daml_project_version(context: &Context, config: &PackageConfig) -> Option<String> {
-    let file_contents = context.read_file_from_pwd("daml.yaml")?;
-
-    let daml_yaml = yaml_rust2::YamlLoader::load_from_str(&file_contents).ok()?;
-    let raw_version = daml_yaml.first()?["version"].as_str()?;
-
-    format_version(raw_version, config.version_format)
+fn get_shard_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
+    get_yaml_manifest_version(context, config, "shard.yml")
 }
 
-fn get_dart_pub_version(context: &Context, config: &PackageConfig) -> Option<String> {
-    let file_contents = context.read_file_from_pwd("pubspec.yaml")?;
-
-    let data = yaml_rust2::YamlLoader::load_from_str(&file_contents).ok()?;
-    let raw_version = data.first()?["version"].as_str()?;
+fn get_daml_project_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
+    get_yaml_manifest_version(context, config, "daml.yaml")
+}
 
-    format_version(raw_version, config.version_format)
+fn get_dart_pub_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
+    get_yaml_manifest_version(context, config, "pubspec.yaml")
 }
 
-fn get_rlang_version(context: &Context, config: &PackageConfig) -> Option<String> {
+fn get_rlang_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
     let file_contents = context.read_file_from_pwd("DESCRIPTION")?;
     let re = Regex::new(r"(?m)^Version:\s*(?P<version>.*$)").unwrap();
     let caps = re.captures(&file_contents)?;
-    format_version(&caps["version"], config.version_format)
-}
 
-fn get_galaxy_version(context: &Context, config: &PackageConfig) -> Option<String> {
-    let file_contents = context.read_file_from_pwd("galaxy.yml")?;
-    let data = yaml_rust2::YamlLoader::load_from_str(&file_contents).ok()?;
-    let raw_version = data.first()?["version"].as_str()?;
+    let name_re = Regex::new(r"(?m)^Package:\s*(?P<name>.*$)").unwrap();
+    let name = name_re.captures(&file_contents);
+
+    Some(PackageInfo::new(
+        name.as_ref().map(|caps| &caps["name"]),
+        format_version(&caps["version"], config.version_format)?,
+    ))
+}
 
-    format_version(raw_version, config.version_format)
+fn get_galaxy_version(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
+    get_yaml_manifest_version(context, config, "galaxy.yml")
 }
 
-fn get_version(context: &Context, config: &PackageConfig) -> Option<String> {
-    let package_version_fn: Vec<fn(&Context, &PackageConfig) -> Option<String>> = vec![
+fn get_package(context: &Context, config: &PackageConfig) -> Option<PackageInfo> {
+    let package_version_fn: Vec<fn(&Context, &PackageConfig) -> Option<PackageInfo>> = vec![
         get_cargo_version,
         get_nimble_version,
         get_node_package_version,
@@ -1665,6 +1790,97 @@ Title: Starship
         project_dir.close()
     }
 
+    #[test]
+    fn test_format_name_cargo() -> io::Result<()> {
+        let config_content = toml::toml! {
+            [package]
+            name = "starship"
+            version = "0.1.0"
+        }
+        .to_string();
+
+        let project_dir = create_project_dir()?;
+        fill_config(&project_dir, "Cargo.toml", Some(&config_content))?;
+        expect_output(
+            &project_dir,
+            Some("starship v0.1.0"),
+            Some(toml::toml! {
+                [package]
+                format = "is [$symbol$name $version]($style) "
+            }),
+        );
+        project_dir.close()
+    }
+
+    #[test]
+    fn test_format_name_npm() -> io::Result<()> {
+        let config_content = json::json!({
+            "name": "@starship/prompt",
+            "version": "1.19.0"
+        })
+        .to_string();
+
+        let project_dir = create_project_dir()?;
+        fill_config(&project_dir, "package.json", Some(&config_content))?;
+        expect_output(
+            &project_dir,
+            Some("@starship/prompt v1.19.0"),
+            Some(toml::toml! {
+                [package]
+                format = "is [$symbol$name $version]($style) "
+            }),
+        );
+        project_dir.close()
+    }
+
+    #[test]
+    fn test_format_name_maven_artifact_id() -> io::Result<()> {
+        let pom = "
+            <project>
+              <modelVersion>4.0.0</modelVersion>
+              <version>0.3.20</version>
+              <artifactId>app</artifactId>
+              <dependencies>
+                  <dependency>
+                      <artifactId>jta</artifactId>
+                      <version>1.2.3</version>
+                  </dependency>
+              </dependencies>
+            </project>";
+
+        let project_dir = create_project_dir()?;
+        fill_config(&project_dir, "pom.xml", Some(pom))?;
+        expect_output(
+            &project_dir,
+            Some("app v0.3.20"),
+            Some(toml::toml! {
+                [package]
+                format = "is [$symbol$name $version]($style) "
+            }),
+        );
+        project_dir.close()
+    }
+
+    #[test]
+    fn test_format_name_missing() -> io::Result<()> {
+        let config_content = json::json!({
+            "version": "1.19.0"
+        })
+        .to_string();
+
+        let project_dir = create_project_dir()?;
+        fill_config(&project_dir, "package.json", Some(&config_content))?;
+        expect_output(
+            &project_dir,
+            Some("v1.19.0"),
+            Some(toml::toml! {
+                [package]
+                format = "is [$symbol($name )$version]($style) "
+            }),
+        );
+        project_dir.close()
+    }
+
     fn create_project_dir() -> io::Result<TempDir> {
         tempfile::tempdir()
     }
@@ -1,8 +1,15 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::php::PhpConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
+use crate::modules::utils::version::{compare_segments, parse_segments};
+
+use serde_json as json;
+use std::cmp::Ordering;
+use std::sync::LazyLock;
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
 
 /// Creates a module with the current PHP version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -19,6 +26,28 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let php_version = LazyLock::new(|| {
+        context
+            .exec_cmd(
+                "php",
+                &[
+                    "-nr",
+                    "echo PHP_MAJOR_VERSION.\".\".PHP_MINOR_VERSION.\".\".PHP_RELEASE_VERSION;",
+                ],
+            )
+            .map(|cmd| cmd.stdout)
+    });
+    let expected_version = LazyLock::new(|| get_composer_php_requirement(context));
+    let is_expected_version =
+        LazyLock::new(
+            || match (php_version.as_deref(), expected_version.as_deref()) {
+                (Some(php_version), Some(expected_version)) => {
+                    check_php_version(php_version, expected_version)
+                }
+                _ => true,
+            },
+        );
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|variable, _| match variable {
@@ -26,20 +55,26 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if *is_expected_version {
+                        Some(Ok(config.style))
+                    } else {
+                        Some(Ok(config.not_capable_style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => {
-                    let php_version = context.exec_cmd(
-                        "php",
-                        &[
-                            "-nr",
-                            "echo PHP_MAJOR_VERSION.\".\".PHP_MINOR_VERSION.\".\".PHP_RELEASE_VERSION;",
-                        ],
-                    )?.stdout;
-                    VersionFormatter::format_module_version(module.get_name(), &php_version, config.version_format).map(Ok)
-                }
+                "version" => VersionFormatter::format_module_version(
+                    module.get_name(),
+                    php_version.as_deref()?,
+                    config.version_format,
+                )
+                .map(Ok),
+                "expected_version" => (!*is_expected_version)
+                    .then(|| expected_version.clone())
+                    .flatten()
+                    .map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -56,8 +91,129 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Returns the PHP platform requirement, e.g. `^8.1`, from the `require` section of `composer.json`
+fn get_composer_php_requirement(context: &Context) -> Option<String> {
+    let file_contents = context.read_file_from_pwd("composer.json")?;
+    let composer_json: json::Value = json::from_str(&file_contents).ok()?;
+    let requirement = composer_json.get("require")?.get("php")?.as_str()?;
+
+    Some(requirement.trim().to_string())
+}
+
+/// Checks a PHP version against a Composer version constraint.
+///
+/// Constraints separated by `||` are alternatives, while constraints separated by spaces or
+/// commas must all match. Constraints which can't be parsed are treated as matching.
+fn check_php_version(php_version: &str, constraint: &str) -> bool {
+    let Some(version) = parse_segments(php_version) else {
+        return true;
+    };
+
+    constraint
+        .split("||")
+        .flat_map(|alt| alt.split('|'))
+        .any(|alternative| {
+            let mut tokens = alternative
+                .split([' ', ','])
+                .filter(|token| !token.is_empty())
+                .peekable();
+            let mut matches = true;
+            while let Some(token) = tokens.next() {
+                // An operator can be separated from its version, as in `>= 8.1`
+                let constraint = if is_operator(token) {
+                    format!("{token}{}", tokens.next().unwrap_or_default())
+                } else {
+                    token.to_string()
+                };
+                matches &= if tokens.next_if_eq(&"-").is_some() {
+                    let upper = tokens.next().unwrap_or_default();
+                    check_php_range(&version, &constraint, upper)
+                } else {
+                    check_php_constraint(&version, &constraint)
+                };
+            }
+            matches
+        })
+}
+
+fn is_operator(token: &str) -> bool {
+    token
+        .chars()
+        .all(|c| matches!(c, '<' | '>' | '=' | '!' | '^' | '~'))
+}
+
+/// Checks a hyphenated range such as `8.0 - 8.2`, whose partial upper bound includes every
+/// release it is a prefix of, so it means `>=8.0 <8.3`
+fn check_php_range(version: &[u64], lower: &str, upper: &str) -> bool {
+    let (Some(lower), Some(mut upper)) = (
+        parse_segments(lower.trim_start_matches(['v', 'V'])),
+        parse_segments(upper.trim_start_matches(['v', 'V'])),
+    ) else {
+        return true;
+    };
+
+    if compare_segments(version, &lower) == Ordering::Less {
+        return false;
+    }
+    if upper.len() < 3 {
+        if let Some(last) = upper.last_mut() {
+            *last += 1;
+        }
+        compare_segments(version, &upper) == Ordering::Less
+    } else {
+        compare_segments(version, &upper) != Ordering::Greater
+    }
+}
+
+fn check_php_constraint(version: &[u64], constraint: &str) -> bool {
+    let constraint = constraint.trim_start_matches(['v', 'V']);
+    if constraint == "*" {
+        return true;
+    }
+
+    let operator_len = constraint
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(constraint.len());
+    let (operator, expected) = constraint.split_at(operator_len);
+    let wildcard = expected.ends_with(".*");
+    let Some(expected) = parse_segments(expected.trim_end_matches(".*")) else {
+        return true;
+    };
+    let cmp = compare_segments(version, &expected);
+
+    match operator {
+        "" | "=" | "==" if wildcard => version.starts_with(&expected),
+        "" | "=" | "==" => cmp == Ordering::Equal,
+        "!=" | "<>" => cmp != Ordering::Equal,
+        ">" => cmp == Ordering::Greater,
+        ">=" => cmp != Ordering::Less,
+        "<" => cmp == Ordering::Less,
+        "<=" => cmp != Ordering::Greater,
+        // `^8.1` means `>=8.1 <9.0`, and `^0.3` means `>=0.3 <0.4`
+        "^" => {
+            let significant = expected
+                .iter()
+                .position(|segment| *segment != 0)
+                .unwrap_or(expected.len() - 1);
+            let mut upper = expected[..=significant].to_vec();
+            upper[significant] += 1;
+            cmp != Ordering::Less && compare_segments(version, &upper) == Ordering::Less
+        }
+        // `~8.1` means `>=8.1 <9.0`, and `~8.1.2` means `>=8.1.2 <8.2`
+        "~" => {
+            let mut upper = expected[..expected.len().saturating_sub(1).max(1)].to_vec();
+            if let Some(last) = upper.last_mut() {
+                *last += 1;
+            }
+            cmp != Ordering::Less && compare_segments(version, &upper) == Ordering::Less
+        }
+        _ => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::check_php_version;
     use crate::test::ModuleRenderer;
     use nu_ansi_term::Color;
     use std::fs::File;
@@ -118,4 +274,73 @@ mod tests {
         assert_eq!(expected, actual);
         dir.close()
     }
+
+    #[test]
+    fn test_check_php_version() {
+        assert!(check_php_version("8.2.4", "^8.1"));
+        assert!(!check_php_version("9.0.0", "^8.1"));
+        assert!(!check_php_version("8.0.30", "^8.1"));
+        assert!(check_php_version("7.4.33", "^7.4 || ^8.0"));
+        assert!(check_php_version("8.1.2", "~8.1.0"));
+        assert!(!check_php_version("8.2.0", "~8.1.0"));
+        assert!(check_php_version("8.1.27", "8.1.*"));
+        assert!(check_php_version("8.1.27", ">=8.0 <8.3"));
+        assert!(!check_php_version("8.3.0", ">=8.0, <8.3"));
+        assert!(check_php_version("7.3.8", "*"));
+        assert!(check_php_version("8.2.0", ">= 8.1"));
+        assert!(!check_php_version("8.0.0", ">= 8.1"));
+        assert!(check_php_version("8.2.9", "8.0 - 8.2"));
+        assert!(!check_php_version("8.3.0", "8.0 - 8.2"));
+        assert!(!check_php_version("8.2.1", "8.0.0 - 8.2.0"));
+        assert!(check_php_version("8.3.1", ">=7.4 <8.0 || >= 8.1"));
+        assert!(!check_php_version("8.0.5", ">=7.4 <8.0 || >= 8.1"));
+    }
+
+    #[test]
+    fn composer_php_requirement_mismatch() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("composer.json"),
+            r#"{"require": {"php": "^8.1", "laravel/framework": "^10.0"}}"#,
+        )?;
+
+        let actual = ModuleRenderer::new("php")
+            .path(dir.path())
+            .config(toml::toml! {
+                [php]
+                format = "via [$symbol($version )(needs $expected_version )]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Red.bold().paint("🐘 v7.3.8 needs ^8.1 ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn composer_php_requirement_match() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("composer.json"),
+            r#"{"require": {"php": ">=7.2"}}"#,
+        )?;
+
+        let actual = ModuleRenderer::new("php")
+            .path(dir.path())
+            .config(toml::toml! {
+                [php]
+                format = "via [$symbol($version )(needs $expected_version )]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Fixed(147).bold().paint("🐘 v7.3.8 ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
 }
@@ -0,0 +1,250 @@
+use ini::{Ini, ParseOption};
+
+use super::{Context, Module, ModuleConfig, Resource};
+
+use crate::configs::platformio::PlatformioConfig;
+use crate::formatter::StringFormatter;
+use crate::utils::read_file;
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
+/// Creates a module with the PlatformIO environments built by `pio run`
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("platformio");
+    let config = PlatformioConfig::try_load(module.config);
+
+    let is_platformio_project = context
+        .try_begin_scan()?
+        .set_files(&config.detect_files)
+        .set_extensions(&config.detect_extensions)
+        .set_folders(&config.detect_folders)
+        .is_match();
+
+    if !is_platformio_project {
+        return None;
+    }
+
+    let project = read_project_config(context)?;
+    let environments = get_default_environments(context, &project);
+    if environments.is_empty() {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "environment" => Some(Ok(environments.join(", "))),
+                "board" => {
+                    let mut boards: Vec<&str> = Vec::new();
+                    for board in environments
+                        .iter()
+                        .filter_map(|env| get_board(&project, env))
+                    {
+                        if !boards.contains(&board) {
+                            boards.push(board);
+                        }
+                    }
+                    (!boards.is_empty()).then(|| Ok(boards.join(", ")))
+                }
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `platformio`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+fn read_project_config(context: &Context) -> Option<Ini> {
+    let contents = read_file(context.current_dir.join("platformio.ini")).ok()?;
+    let options = ParseOption {
+        enabled_escape: false,
+        enabled_indented_mutiline_value: true,
+        ..ParseOption::default()
+    };
+
+    Ini::load_from_str_opt(&contents, options)
+        .map_err(|error| log::debug!("Unable to parse platformio.ini: {error}"))
+        .ok()
+}
+
+/// Returns the environments processed by `pio run` without the `-e` option.
+///
+/// These are the `default_envs` of the project, which can be overridden with the
+/// `PLATFORMIO_DEFAULT_ENVS` environment variable, or otherwise all environments.
+fn get_default_environments(context: &Context, project: &Ini) -> Vec<String> {
+    let default_envs = context.get_env("PLATFORMIO_DEFAULT_ENVS").or_else(|| {
+        project
+            .section(Some("platformio"))?
+            .get("default_envs")
+            .map(ToString::to_string)
+    });
+
+    if let Some(default_envs) = default_envs {
+        let environments = split_list(&default_envs);
+        if !environments.is_empty() {
+            return environments;
+        }
+    }
+
+    project
+        .sections()
+        .flatten()
+        .filter_map(|section| section.strip_prefix("env:"))
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Returns the board of an environment, which may be inherited from the common `[env]` section
+fn get_board<'a>(project: &'a Ini, environment: &str) -> Option<&'a str> {
+    [format!("env:{environment}"), String::from("env")]
+        .iter()
+        .find_map(|section| project.section(Some(section.as_str()))?.get("board"))
+        .map(strip_comment)
+        .filter(|board| !board.is_empty())
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split([',', '\n'])
+        .map(strip_comment)
+        .filter(|item| !item.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn strip_comment(value: &str) -> &str {
+    value.split(" ;").next().unwrap_or_default().trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    const PLATFORMIO_INI: &str = "; PlatformIO Project Configuration File
+[platformio]
+default_envs = uno
+
+[env]
+framework = arduino
+lib_deps =
+    bblanchon/ArduinoJson
+    adafruit/Adafruit NeoPixel
+
+[env:uno]
+platform = atmelavr
+board = uno ; Arduino Uno
+
+[env:esp32]
+platform = espressif32
+board = esp32dev
+";
+
+    #[test]
+    fn folder_without_platformio_ini() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("platformio").path(dir.path()).collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn shows_default_environment() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("platformio.ini"), PLATFORMIO_INI)?;
+
+        let actual = ModuleRenderer::new("platformio").path(dir.path()).collect();
+
+        let expected = Some(format!(
+            "via {} ",
+            Color::Rgb(245, 130, 42).bold().paint("🔌 uno (uno)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn shows_all_environments_without_default_envs() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let ini = PLATFORMIO_INI.replace("default_envs = uno\n", "");
+        fs::write(dir.path().join("platformio.ini"), ini)?;
+
+        let actual = ModuleRenderer::new("platformio").path(dir.path()).collect();
+
+        let expected = Some(format!(
+            "via {} ",
+            Color::Rgb(245, 130, 42)
+                .bold()
+                .paint("🔌 uno, esp32 (uno, esp32dev)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn board_from_common_environment() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let ini = "[platformio]
+default_envs = debug, release
+
+[env]
+board = nucleo_f401re
+
+[env:debug]
+build_type = debug
+
+[env:release]
+build_flags = -O2
+";
+        fs::write(dir.path().join("platformio.ini"), ini)?;
+
+        let actual = ModuleRenderer::new("platformio")
+            .path(dir.path())
+            .config(toml::toml! {
+                [platformio]
+                format = "$environment $board"
+            })
+            .collect();
+
+        assert_eq!(Some("debug, release nucleo_f401re".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn default_environments_from_env_var() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("platformio.ini"), PLATFORMIO_INI)?;
+
+        let actual = ModuleRenderer::new("platformio")
+            .path(dir.path())
+            .env("PLATFORMIO_DEFAULT_ENVS", "esp32")
+            .config(toml::toml! {
+                [platformio]
+                format = "$environment $board"
+            })
+            .collect();
+
+        assert_eq!(Some("esp32 esp32dev".to_string()), actual);
+        dir.close()
+    }
+}
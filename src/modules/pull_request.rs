@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::utils::forge::{self, Project, Provider};
+use super::{Context, Module, ModuleConfig, Resource};
+
+use crate::configs::pull_request::PullRequestConfig;
+use crate::formatter::StringFormatter;
+
+/// The file in the cache directory holding the pull requests of the branches checked last
+pub const CACHE_FILE: &str = "pull_request.json";
+
+/// The open pull request, or merge request, of a branch
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct PullRequest {
+    number: u64,
+    title: String,
+    url: String,
+    draft: bool,
+    review: Option<Review>,
+}
+
+/// The outcome of the reviews of a pull request. Changes requested by a reviewer outweigh
+/// approvals by others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Review {
+    Approved,
+    ChangesRequested,
+    Required,
+}
+
+impl Review {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Approved => "approved",
+            Self::ChangesRequested => "changes_requested",
+            Self::Required => "review_required",
+        }
+    }
+}
+
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
+/// Creates a module with the number and review state of the open pull request of the current
+/// branch on GitHub, or merge request on GitLab
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("pull_request");
+    let config = PullRequestConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let repo = context.get_repo().ok()?;
+    // The branch is pushed under the name of its upstream branch, if it has one
+    let branch = repo
+        .remote
+        .as_ref()
+        .and_then(|remote| remote.branch.as_deref())
+        .or(repo.branch.as_deref())?;
+    let project = forge::remote_project(context, "pull_request", config.provider, &config.hosts)?;
+
+    let pull_request = branch_pull_request(context, &config, &project, branch)?;
+    let review_symbol = match pull_request.review {
+        Some(Review::Approved) => config.approved_symbol,
+        Some(Review::ChangesRequested) => config.changes_requested_symbol,
+        Some(Review::Required) => config.review_required_symbol,
+        None => "",
+    };
+    let (state, style) = if pull_request.draft {
+        ("draft", config.draft_style)
+    } else {
+        ("open", config.style)
+    };
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                "review_symbol" => Some(review_symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "number" => Some(Ok(pull_request.number.to_string())),
+                "title" => Some(Ok(pull_request.title.clone())),
+                "url" => Some(Ok(pull_request.url.clone())),
+                "state" => Some(Ok(state.to_string())),
+                "review" => pull_request
+                    .review
+                    .map(|review| Ok(review.name().to_string())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `pull_request`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Returns the open pull request of the branch, from the cache while it is fresh
+fn branch_pull_request(
+    context: &Context,
+    config: &PullRequestConfig,
+    project: &Project,
+    branch: &str,
+) -> Option<PullRequest> {
+    let key = format!("{}\0{}\0{branch}", project.api_url, project.path);
+    forge::cached(
+        context,
+        "pull_request",
+        CACHE_FILE,
+        &key,
+        Duration::from_secs(config.cache_ttl),
+        || fetch_pull_request(context, config, project, branch),
+    )
+}
+
+/// Queries the API of the provider for the open pull request of the branch. Returns `None` if
+/// a request failed, and `Some(None)` if the branch has no open pull request.
+fn fetch_pull_request(
+    context: &Context,
+    config: &PullRequestConfig,
+    project: &Project,
+    branch: &str,
+) -> Option<Option<PullRequest>> {
+    let timeout = Duration::from_millis(config.timeout);
+    let get = |endpoint: &str| project.get(context, endpoint, timeout);
+    let branch = urlencoding::encode(branch);
+
+    match project.provider {
+        Provider::GitHub => {
+            let owner = project.path.split('/').next()?;
+            let pulls = get(&format!(
+                "repos/{}/pulls?head={owner}:{branch}&state=open&per_page=1",
+                project.id()
+            ))?;
+            let Some(mut pull_request) = github_pull_request(&pulls) else {
+                return Some(None);
+            };
+            let reviews = get(&format!(
+                "repos/{}/pulls/{}/reviews?per_page=100",
+                project.id(),
+                pull_request.number
+            ))?;
+            pull_request.review = github_review(&pulls[0], &reviews);
+            Some(Some(pull_request))
+        }
+        Provider::GitLab => {
+            let merge_requests = get(&format!(
+                "projects/{}/merge_requests?source_branch={branch}&state=opened&per_page=1",
+                project.id()
+            ))?;
+            let Some(mut pull_request) = gitlab_merge_request(&merge_requests) else {
+                return Some(None);
+            };
+            if pull_request.review.is_none() {
+                let approvals = get(&format!(
+                    "projects/{}/merge_requests/{}/approvals",
+                    project.id(),
+                    pull_request.number
+                ))?;
+                pull_request.review = gitlab_approvals(&approvals);
+            }
+            Some(Some(pull_request))
+        }
+    }
+}
+
+/// The first pull request listed by GitHub, without its review state
+fn github_pull_request(pulls: &Value) -> Option<PullRequest> {
+    let pull = pulls.as_array()?.first()?;
+    Some(PullRequest {
+        number: pull["number"].as_u64()?,
+        title: pull["title"].as_str().unwrap_or_default().to_string(),
+        url: pull["html_url"].as_str().unwrap_or_default().to_string(),
+        draft: pull["draft"].as_bool().unwrap_or(false),
+        review: None,
+    })
+}
+
+/// The review state of a GitHub pull request, from the latest review of each reviewer and
+/// the reviewers still asked for a review
+fn github_review(pull: &Value, reviews: &Value) -> Option<Review> {
+    let mut latest: HashMap<&str, &str> = HashMap::new();
+    for review in reviews.as_array().into_iter().flatten() {
+        let (Some(user), Some(state)) =
+            (review["user"]["login"].as_str(), review["state"].as_str())
+        else {
+            continue;
+        };
+        // Comments don't change the verdict of a reviewer
+        if matches!(state, "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED") {
+            latest.insert(user, state);
+        }
+    }
+
+    if latest.values().any(|state| *state == "CHANGES_REQUESTED") {
+        Some(Review::ChangesRequested)
+    } else if pull["requested_reviewers"]
+        .as_array()
+        .is_some_and(|reviewers| !reviewers.is_empty())
+        || pull["requested_teams"]
+            .as_array()
+            .is_some_and(|teams| !teams.is_empty())
+    {
+        Some(Review::Required)
+    } else if latest.values().any(|state| *state == "APPROVED") {
+        Some(Review::Approved)
+    } else {
+        None
+    }
+}
+
+/// The first merge request listed by GitLab, with its review state if GitLab includes it
+fn gitlab_merge_request(merge_requests: &Value) -> Option<PullRequest> {
+    let merge_request = merge_requests.as_array()?.first()?;
+    let review = match merge_request["detailed_merge_status"].as_str() {
+        Some("requested_changes") => Some(Review::ChangesRequested),
+        Some("not_approved") => Some(Review::Required),
+        _ => None,
+    };
+    Some(PullRequest {
+        number: merge_request["iid"].as_u64()?,
+        title: merge_request["title"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        url: merge_request["web_url"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        draft: merge_request["draft"].as_bool().unwrap_or(false),
+        review,
+    })
+}
+
+/// Whether a GitLab merge request was approved
+fn gitlab_approvals(approvals: &Value) -> Option<Review> {
+    approvals["approved_by"]
+        .as_array()
+        .is_some_and(|approvers| !approvers.is_empty())
+        .then_some(Review::Approved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+    use serde_json::json;
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = ModuleRenderer::new("pull_request").collect();
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn reads_github_pull_request() {
+        let pulls = json!([{
+            "number": 42,
+            "title": "Add a module",
+            "html_url": "https://github.com/starship/starship/pull/42",
+            "draft": true,
+        }]);
+        assert_eq!(
+            github_pull_request(&pulls),
+            Some(PullRequest {
+                number: 42,
+                title: "Add a module".to_string(),
+                url: "https://github.com/starship/starship/pull/42".to_string(),
+                draft: true,
+                review: None,
+            })
+        );
+        assert_eq!(github_pull_request(&json!([])), None);
+    }
+
+    #[test]
+    fn combines_github_reviews() {
+        let review = |user: &str, state: &str| json!({ "user": { "login": user }, "state": state });
+        let pull = json!({ "requested_reviewers": [], "requested_teams": [] });
+
+        assert_eq!(github_review(&pull, &json!([])), None);
+        assert_eq!(
+            github_review(
+                &pull,
+                &json!([review("a", "APPROVED"), review("a", "COMMENTED")])
+            ),
+            Some(Review::Approved)
+        );
+        assert_eq!(
+            github_review(
+                &pull,
+                &json!([review("a", "CHANGES_REQUESTED"), review("b", "APPROVED")])
+            ),
+            Some(Review::ChangesRequested)
+        );
+        // A later approval replaces the changes requested by the same reviewer
+        assert_eq!(
+            github_review(
+                &pull,
+                &json!([review("a", "CHANGES_REQUESTED"), review("a", "APPROVED")])
+            ),
+            Some(Review::Approved)
+        );
+        assert_eq!(
+            github_review(
+                &json!({ "requested_reviewers": [{ "login": "c" }] }),
+                &json!([review("a", "APPROVED")])
+            ),
+            Some(Review::Required)
+        );
+    }
+
+    #[test]
+    fn reads_gitlab_merge_request() {
+        let merge_requests = json!([{
+            "iid": 7,
+            "title": "Draft: Add a module",
+            "web_url": "https://gitlab.com/group/project/-/merge_requests/7",
+            "draft": true,
+            "detailed_merge_status": "requested_changes",
+        }]);
+        let merge_request = gitlab_merge_request(&merge_requests).unwrap();
+        assert_eq!(merge_request.number, 7);
+        assert!(merge_request.draft);
+        assert_eq!(merge_request.review, Some(Review::ChangesRequested));
+
+        assert_eq!(
+            gitlab_approvals(&json!({ "approved_by": [{ "user": { "username": "a" } }] })),
+            Some(Review::Approved)
+        );
+        assert_eq!(gitlab_approvals(&json!({ "approved_by": [] })), None);
+    }
+}
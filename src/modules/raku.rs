@@ -1,4 +1,4 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::raku::RakuConfig;
 use crate::formatter::StringFormatter;
@@ -6,6 +6,8 @@ use crate::formatter::VersionFormatter;
 use std::ops::Deref;
 use std::sync::LazyLock;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current raku version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("raku");
@@ -0,0 +1,264 @@
+use std::fs;
+use std::path::Path;
+
+use super::{Context, Module, ModuleConfig};
+use crate::configs::release_pending::ReleasePendingConfig;
+use crate::formatter::StringFormatter;
+use crate::utils::read_file;
+
+/// Creates a module showing how many changes are waiting to be released
+///
+/// Pending changes are counted from the `.changeset` folder used by changesets,
+/// falling back to the entries of the "Unreleased" section of a changelog.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("release_pending");
+    let config: ReleasePendingConfig = ReleasePendingConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let (source, count) = count_changesets(context, &config)
+        .map(|count| ("changeset", count))
+        .or_else(|| count_unreleased_entries(context, &config).map(|count| ("changelog", count)))?;
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "count" => Some(Ok(count.to_string())),
+                "source" => Some(Ok(source.to_string())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `release_pending`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Counts the changeset files in the nearest changeset folder, ignoring its README
+fn count_changesets(context: &Context, config: &ReleasePendingConfig) -> Option<usize> {
+    let root = context
+        .begin_ancestor_scan()
+        .set_folders(&[config.changeset_folder])
+        .scan()?;
+
+    let count = fs::read_dir(root.join(config.changeset_folder))
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| is_changeset(path))
+        .count();
+
+    (count > 0).then_some(count)
+}
+
+fn is_changeset(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        && !path
+            .file_stem()
+            .is_some_and(|stem| stem.eq_ignore_ascii_case("README"))
+}
+
+/// Counts the list entries below the "Unreleased" heading of the nearest changelog
+fn count_unreleased_entries(context: &Context, config: &ReleasePendingConfig) -> Option<usize> {
+    let root = context
+        .begin_ancestor_scan()
+        .set_files(&config.changelog_files)
+        .scan()?;
+    let contents = config
+        .changelog_files
+        .iter()
+        .find_map(|file| read_file(root.join(file)).ok())?;
+
+    let count = count_section_entries(&contents, config.unreleased_heading);
+    (count > 0).then_some(count)
+}
+
+fn count_section_entries(changelog: &str, heading: &str) -> usize {
+    let heading = heading.to_lowercase();
+    let mut section_level = None;
+    let mut count = 0;
+
+    for line in changelog.lines().map(str::trim) {
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if level > 0 {
+            match section_level {
+                // A heading of the same or a higher level closes the section
+                Some(section) if level <= section => break,
+                Some(_) => {}
+                None if line.to_lowercase().contains(&heading) => section_level = Some(level),
+                None => {}
+            }
+        } else if section_level.is_some() && (line.starts_with("- ") || line.starts_with("* ")) {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn disabled_by_default() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join(".changeset"))?;
+        fs::write(dir.path().join(".changeset/brave-cats-run.md"), "")?;
+
+        let actual = ModuleRenderer::new("release_pending")
+            .path(dir.path())
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_without_changes() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join(".changeset"))?;
+        fs::write(dir.path().join(".changeset/README.md"), "")?;
+        fs::write(dir.path().join(".changeset/config.json"), "{}")?;
+
+        let actual = ModuleRenderer::new("release_pending")
+            .path(dir.path())
+            .config(toml::toml! {
+                [release_pending]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn counts_changesets() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join(".changeset"))?;
+        fs::write(dir.path().join(".changeset/README.md"), "")?;
+        fs::write(dir.path().join(".changeset/config.json"), "{}")?;
+        fs::write(dir.path().join(".changeset/brave-cats-run.md"), "")?;
+        fs::write(dir.path().join(".changeset/quiet-dogs-sleep.md"), "")?;
+
+        let actual = ModuleRenderer::new("release_pending")
+            .path(dir.path())
+            .config(toml::toml! {
+                [release_pending]
+                disabled = false
+            })
+            .collect();
+
+        let expected = Some(format!("{} ", Color::Purple.bold().paint("🦋 2")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn counts_changesets_from_package_dir() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let package_dir = dir.path().join("packages").join("core");
+        fs::create_dir_all(&package_dir)?;
+        fs::write(package_dir.join("CHANGELOG.md"), "# core\n")?;
+        fs::create_dir(dir.path().join(".changeset"))?;
+        fs::write(dir.path().join(".changeset/brave-cats-run.md"), "")?;
+
+        let actual = ModuleRenderer::new("release_pending")
+            .path(&package_dir)
+            .config(toml::toml! {
+                [release_pending]
+                disabled = false
+                format = "$count $source"
+            })
+            .collect();
+
+        assert_eq!(Some("1 changeset".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn counts_unreleased_changelog_entries() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let changelog = "# Changelog
+
+## [Unreleased]
+
+### Added
+
+- New module
+- Another option
+
+### Fixed
+
+* A crash
+
+## [1.0.0] - 2024-01-01
+
+- Initial release
+";
+        fs::write(dir.path().join("CHANGELOG.md"), changelog)?;
+
+        let actual = ModuleRenderer::new("release_pending")
+            .path(dir.path())
+            .config(toml::toml! {
+                [release_pending]
+                disabled = false
+                format = "$count $source"
+            })
+            .collect();
+
+        assert_eq!(Some("3 changelog".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn empty_unreleased_section() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let changelog = "# Changelog
+
+## Unreleased
+
+## 1.0.0
+
+- Initial release
+";
+        fs::write(dir.path().join("CHANGELOG.md"), changelog)?;
+
+        let actual = ModuleRenderer::new("release_pending")
+            .path(dir.path())
+            .config(toml::toml! {
+                [release_pending]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+}
@@ -1,10 +1,16 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use crate::formatter::VersionFormatter;
 
 use crate::configs::rlang::RLangConfig;
 use crate::formatter::StringFormatter;
 use crate::utils::get_command_string_output;
 
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("rlang");
     let config: RLangConfig = RLangConfig::try_load(module.config);
@@ -41,6 +47,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     )
                     .map(Ok)
                 }
+                "renv" => get_renv_status(context, &config).map(|status| Ok(status.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -57,6 +64,44 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Compares the renv lockfile with the project library, reporting the library as outdated when
+/// the lockfile changed after the last package was installed or removed (or nothing is installed).
+fn get_renv_status<'a>(context: &Context, config: &RLangConfig<'a>) -> Option<&'a str> {
+    let project_dir = context
+        .begin_ancestor_scan()
+        .set_files(&["renv.lock"])
+        .scan()?;
+    let lockfile_modified = fs::metadata(project_dir.join("renv.lock"))
+        .and_then(|meta| meta.modified())
+        .ok()?;
+
+    let library_modified = latest_modification(&project_dir.join("renv").join("library"), 3);
+    if library_modified.is_some_and(|modified| modified >= lockfile_modified) {
+        Some(config.renv_synced)
+    } else {
+        Some(config.renv_outdated)
+    }
+}
+
+/// Returns the most recent modification time of a directory and its subdirectories.
+///
+/// The renv library is laid out as `<platform>/<R version>/<arch>/<package>`, so installing or
+/// removing a package updates the modification time of a directory a few levels down.
+fn latest_modification(dir: &Path, depth: usize) -> Option<SystemTime> {
+    let modified = fs::metadata(dir).and_then(|meta| meta.modified()).ok()?;
+    if depth == 0 {
+        return Some(modified);
+    }
+
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter_map(|entry| latest_modification(&entry.path(), depth - 1))
+        .chain(std::iter::once(modified))
+        .max()
+}
+
 fn parse_r_version(r_version: &str) -> Option<String> {
     r_version
         .lines()
@@ -77,6 +122,7 @@ mod tests {
     use std::fs;
     use std::fs::File;
     use std::io;
+    use std::time::{Duration, SystemTime};
 
     #[test]
     fn test_parse_r_version() {
@@ -149,6 +195,80 @@ https://www.gnu.org/licenses/."#;
         dir.close()
     }
 
+    #[test]
+    fn renv_library_in_sync() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let library = dir
+            .path()
+            .join("renv/library/linux/R-4.1/x86_64-pc-linux-gnu");
+        fs::create_dir_all(library.join("jsonlite"))?;
+        File::create(dir.path().join("renv.lock"))?
+            .set_modified(SystemTime::now() - Duration::from_secs(60))?;
+
+        check_renv_render(&dir, "renv");
+        dir.close()
+    }
+
+    #[test]
+    fn renv_lockfile_newer_than_library() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let library = dir
+            .path()
+            .join("renv/library/linux/R-4.1/x86_64-pc-linux-gnu");
+        fs::create_dir_all(library.join("jsonlite"))?;
+        let past = SystemTime::now() - Duration::from_secs(60);
+        for path in [
+            dir.path().join("renv"),
+            dir.path().join("renv/library"),
+            dir.path().join("renv/library/linux"),
+            dir.path().join("renv/library/linux/R-4.1"),
+            library.clone(),
+            library.join("jsonlite"),
+        ] {
+            File::open(path)?.set_modified(past)?;
+        }
+        File::create(dir.path().join("renv.lock"))?.sync_all()?;
+
+        check_renv_render(&dir, "renv*");
+        dir.close()
+    }
+
+    #[test]
+    fn renv_library_missing() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("renv.lock"))?.sync_all()?;
+
+        check_renv_render(&dir, "renv*");
+        dir.close()
+    }
+
+    #[test]
+    fn renv_without_lockfile() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("analysis.R"))?.sync_all()?;
+
+        let actual = ModuleRenderer::new("rlang")
+            .path(dir.path())
+            .config(toml::toml! {
+                [rlang]
+                format = "R( $renv)"
+            })
+            .collect();
+        assert_eq!(Some("R".to_string()), actual);
+        dir.close()
+    }
+
+    fn check_renv_render(dir: &tempfile::TempDir, renv: &str) {
+        let actual = ModuleRenderer::new("rlang")
+            .path(dir.path())
+            .config(toml::toml! {
+                [rlang]
+                format = "$renv"
+            })
+            .collect();
+        assert_eq!(Some(renv.to_string()), actual);
+    }
+
     fn check_r_render(dir: &tempfile::TempDir) {
         let actual = ModuleRenderer::new("rlang").path(dir.path()).collect();
         let expected = Some(format!("via {}", Color::Blue.bold().paint("📐 v4.1.0 ")));
@@ -1,9 +1,14 @@
 use regex::Regex;
+use std::cmp::Ordering;
+use std::sync::LazyLock;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::ruby::RubyConfig;
 use crate::formatter::{StringFormatter, VersionFormatter};
+use crate::modules::utils::version::{compare_segments, parse_segments};
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
 
 /// Creates a module with the current Ruby version
 ///
@@ -31,6 +36,17 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let ruby_version = LazyLock::new(|| context.exec_cmd("ruby", &["-v"]).map(|cmd| cmd.stdout));
+    let expected_version = LazyLock::new(|| get_expected_version(context));
+    let is_expected_version =
+        LazyLock::new(
+            || match (ruby_version.as_deref(), expected_version.as_deref()) {
+                (Some(ruby_version), Some(expected_version)) => parse_ruby_version(ruby_version)
+                    .is_none_or(|version| check_ruby_version(version, expected_version)),
+                _ => true,
+            },
+        );
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -38,18 +54,28 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if *is_expected_version {
+                        Some(Ok(config.style))
+                    } else {
+                        Some(Ok(config.not_capable_style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => format_ruby_version(
-                    &context.exec_cmd("ruby", &["-v"])?.stdout,
-                    config.version_format,
-                )
-                .map(Ok),
-                "gemset" => {
-                    format_rvm_gemset(&context.exec_cmd("rvm", &["current"])?.stdout).map(Ok)
+                "version" => {
+                    format_ruby_version(ruby_version.as_deref()?, config.version_format).map(Ok)
                 }
+                "expected_version" => (!*is_expected_version)
+                    .then(|| expected_version.clone())
+                    .flatten()
+                    .map(Ok),
+                "gemset" => context
+                    .get_env("GEM_HOME")
+                    .and_then(|gem_home| format_rvm_gemset(&gem_home))
+                    .or_else(|| format_rvm_gemset(&context.exec_cmd("rvm", &["current"])?.stdout))
+                    .map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -66,8 +92,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
-fn format_ruby_version(ruby_version: &str, version_format: &str) -> Option<String> {
-    let version = ruby_version
+fn parse_ruby_version(ruby_version: &str) -> Option<&str> {
+    ruby_version
         // split into ["ruby", "2.6.0p0", "linux/amd64"]
         .split_whitespace()
         // return "2.6.0p0"
@@ -75,7 +101,11 @@ fn format_ruby_version(ruby_version: &str, version_format: &str) -> Option<Strin
         // split into ["2.6.0", "0"]
         .split('p')
         // return "2.6.0"
-        .next()?;
+        .next()
+}
+
+fn format_ruby_version(ruby_version: &str, version_format: &str) -> Option<String> {
+    let version = parse_ruby_version(ruby_version)?;
 
     match VersionFormatter::format_version(version, version_format) {
         Ok(formatted) => Some(formatted),
@@ -86,6 +116,72 @@ fn format_ruby_version(ruby_version: &str, version_format: &str) -> Option<Strin
     }
 }
 
+/// Returns the Ruby version pinned by `.ruby-version` or the `ruby` directive of the `Gemfile`
+fn get_expected_version(context: &Context) -> Option<String> {
+    let ruby_version_file = context
+        .read_file_from_pwd(".ruby-version")
+        .and_then(|contents| {
+            let version = contents.lines().next()?.trim();
+            // Only pins of the default interpreter (MRI) can be compared with `ruby -v`
+            let version = version.strip_prefix("ruby-").unwrap_or(version);
+            version
+                .starts_with(|c: char| c.is_ascii_digit())
+                .then(|| version.to_string())
+        });
+
+    ruby_version_file.or_else(|| {
+        let gemfile = context.read_file_from_pwd("Gemfile")?;
+        let directive_re = Regex::new(r#"(?m)^\s*ruby\s*\(?\s*(["'].*)$"#).unwrap();
+        let requirement_re = Regex::new(r#"["']([^"']+)["']"#).unwrap();
+        let directive = directive_re.captures(&gemfile)?.get(1)?.as_str();
+        let requirements = requirement_re
+            .captures_iter(directive)
+            .map(|caps| caps[1].trim().to_string())
+            .collect::<Vec<_>>();
+
+        (!requirements.is_empty()).then(|| requirements.join(", "))
+    })
+}
+
+/// Checks a Ruby version against a comma separated list of RubyGems requirements,
+/// such as `~> 3.2, >= 3.2.1`. A bare version matches every release it is a prefix of.
+fn check_ruby_version(ruby_version: &str, expected_version: &str) -> bool {
+    let Some(version) = parse_segments(ruby_version) else {
+        return true;
+    };
+
+    expected_version.split(',').all(|requirement| {
+        let requirement = requirement.trim();
+        let operator_len = requirement
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(requirement.len());
+        let (operator, expected) = requirement.split_at(operator_len);
+        let Some(expected) = parse_segments(expected) else {
+            return true;
+        };
+
+        match operator.trim() {
+            "" => version.starts_with(&expected),
+            "=" => compare_segments(&version, &expected) == Ordering::Equal,
+            "!=" => compare_segments(&version, &expected) != Ordering::Equal,
+            ">" => compare_segments(&version, &expected) == Ordering::Greater,
+            ">=" => compare_segments(&version, &expected) != Ordering::Less,
+            "<" => compare_segments(&version, &expected) == Ordering::Less,
+            "<=" => compare_segments(&version, &expected) != Ordering::Greater,
+            "~>" => {
+                // `~> 3.2.1` means `>= 3.2.1, < 3.3`, `~> 3.2` means `>= 3.2, < 4`
+                let mut upper = expected[..expected.len().saturating_sub(1).max(1)].to_vec();
+                if let Some(last) = upper.last_mut() {
+                    *last += 1;
+                }
+                compare_segments(&version, &expected) != Ordering::Less
+                    && compare_segments(&version, &upper) == Ordering::Less
+            }
+            _ => true,
+        }
+    })
+}
+
 fn format_rvm_gemset(current: &str) -> Option<String> {
     let gemset_re = Regex::new(r"@(\S+)").unwrap();
     if let Some(gemset) = gemset_re.captures(current) {
@@ -256,4 +352,109 @@ mod tests {
             Some("v2.7.0".to_string())
         );
     }
+
+    #[test]
+    fn test_check_ruby_version() {
+        assert!(check_ruby_version("3.2.2", "3.2.2"));
+        assert!(check_ruby_version("3.2.2", "3.2"));
+        assert!(!check_ruby_version("3.3.0", "3.2"));
+        assert!(check_ruby_version("3.2.4", "~> 3.2.1"));
+        assert!(!check_ruby_version("3.3.0", "~> 3.2.1"));
+        assert!(check_ruby_version("3.3.0", "~> 3.2"));
+        assert!(!check_ruby_version("4.0.0", "~> 3.2"));
+        assert!(check_ruby_version("3.2.2", ">= 3.1, < 3.3"));
+        assert!(!check_ruby_version("3.0.6", ">= 3.1, < 3.3"));
+        assert!(check_ruby_version("3.2.2", "!= 3.2.1"));
+    }
+
+    #[test]
+    fn ruby_version_file_mismatch() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join(".ruby-version"), "ruby-3.2.2\n")?;
+
+        let actual = ModuleRenderer::new("ruby")
+            .path(dir.path())
+            .config(toml::toml! {
+                [ruby]
+                format = "via [$symbol($version )(expected $expected_version )]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Yellow.bold().paint("💎 v2.5.1 expected 3.2.2 ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn gemfile_ruby_directive_match() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("Gemfile"),
+            "source \"https://rubygems.org\"\n\nruby \"~> 2.5.0\"\n",
+        )?;
+
+        let actual = ModuleRenderer::new("ruby")
+            .path(dir.path())
+            .config(toml::toml! {
+                [ruby]
+                format = "via [$symbol($version )(expected $expected_version )]($style)"
+                style = "bold green"
+                not_capable_style = "bold red"
+            })
+            .collect();
+
+        let expected = Some(format!("via {}", Color::Green.bold().paint("💎 v2.5.1 ")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn gemfile_ruby_directive_mismatch() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("Gemfile"), "ruby '>= 3.1', '< 3.3'\n")?;
+
+        let actual = ModuleRenderer::new("ruby")
+            .path(dir.path())
+            .config(toml::toml! {
+                [ruby]
+                format = "via [$symbol($version )(expected $expected_version )]($style)"
+                style = "bold green"
+                not_capable_style = "bold blue"
+            })
+            .collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Blue
+                .bold()
+                .paint("💎 v2.5.1 expected >= 3.1, < 3.3 ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn rvm_gemset_from_gem_home() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("Gemfile"))?.sync_all()?;
+
+        let actual = ModuleRenderer::new("ruby")
+            .path(dir.path())
+            .env("GEM_HOME", "/home/user/.rvm/gems/ruby-2.5.1@rails")
+            .config(toml::toml! {
+                [ruby]
+                format = "via [$symbol($version)(@$gemset) ]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Red.bold().paint("💎 v2.5.1@rails ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
 }
@@ -5,7 +5,7 @@ use std::process::Output;
 use serde::Deserialize;
 use std::collections::HashMap;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::rust::RustConfig;
 use crate::formatter::{StringFormatter, VersionFormatter};
@@ -155,6 +155,8 @@ impl RustToolingEnvironmentInfo {
     }
 }
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current Rust version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("rust");
@@ -1,10 +1,12 @@
 use crate::configs::scala::ScalaConfig;
 use crate::formatter::StringFormatter;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 use crate::formatter::VersionFormatter;
 use crate::utils::get_command_string_output;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("scala");
     let config: ScalaConfig = ScalaConfig::try_load(module.config);
@@ -0,0 +1,127 @@
+use regex::Regex;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::secrets_guard::SecretsGuardConfig;
+use crate::formatter::StringFormatter;
+
+/// Returns the names of the environment variables that match any of the `variables` patterns
+/// and aren't empty, sorted by name
+fn exposed_variables(context: &Context, patterns: &[&str]) -> Vec<String> {
+    let patterns: Vec<Regex> = patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::warn!("Invalid pattern in `secrets_guard.variables` ({pattern}): {e}");
+                None
+            }
+        })
+        .collect();
+    context
+        .env
+        .get_env_with_prefix("")
+        .into_iter()
+        .filter(|(name, value)| {
+            !value.is_empty() && patterns.iter().any(|pattern| pattern.is_match(name))
+        })
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Creates a module warning about secrets set in the environment
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("secrets_guard");
+    let config = SecretsGuardConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let variables = exposed_variables(context, &config.variables);
+    if variables.is_empty() {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "count" => Some(Ok(variables.len().to_string())),
+                "variables" => Some(Ok(variables.join(", "))),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `secrets_guard`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+
+    #[test]
+    fn no_secrets() {
+        let actual = ModuleRenderer::new("secrets_guard")
+            .config(toml::toml! {
+                [secrets_guard]
+                disabled = false
+            })
+            .env("AWS_PROFILE", "default")
+            .env("GITHUB_TOKEN", "")
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn default_variables() {
+        let actual = ModuleRenderer::new("secrets_guard")
+            .config(toml::toml! {
+                [secrets_guard]
+                disabled = false
+            })
+            .env("AWS_SECRET_ACCESS_KEY", "wJalrXUtnFEMI")
+            .env("OPENAI_API_KEY", "sk-123")
+            .collect();
+        let expected = Some(format!("{} ", Color::Red.bold().paint("🔑 2")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn custom_variables() {
+        let actual = ModuleRenderer::new("secrets_guard")
+            .config(toml::toml! {
+                [secrets_guard]
+                disabled = false
+                format = "$variables"
+                variables = ["^VAULT_TOKEN$", "PASSWORD"]
+            })
+            .env("VAULT_TOKEN", "hvs.123")
+            .env("PGPASSWORD", "hunter2")
+            .env("GITHUB_TOKEN", "ghp_123")
+            .collect();
+
+        assert_eq!(Some("PGPASSWORD, VAULT_TOKEN".to_string()), actual);
+    }
+}
@@ -3,6 +3,11 @@ use super::{Context, Module, ModuleConfig, Shell};
 use crate::configs::shell::ShellConfig;
 use crate::formatter::StringFormatter;
 
+/// Whether a flag of the shell, exported by the init script as `1` or `0`, is set
+fn shell_flag(context: &Context, name: &str) -> Option<bool> {
+    context.get_env(name).map(|value| value == "1")
+}
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("shell");
     let config: ShellConfig = ShellConfig::try_load(module.config);
@@ -12,6 +17,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     let shell = &context.shell;
+    // Set by the init scripts of the shells that have a version and these flags. Shells that
+    // don't export them are taken to be interactive shells that aren't login shells.
+    let version = context
+        .get_env("STARSHIP_SHELL_VERSION")
+        .filter(|version| !version.is_empty());
+    let is_login = shell_flag(context, "STARSHIP_SHELL_LOGIN").unwrap_or(false);
+    let is_interactive = shell_flag(context, "STARSHIP_SHELL_INTERACTIVE").unwrap_or(true);
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -28,6 +40,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     Shell::Nu => Some(config.nu_indicator),
                     Shell::Xonsh => Some(config.xonsh_indicator),
                     Shell::Cmd => Some(config.cmd_indicator),
+                    Shell::Osh => Some(config.osh_indicator),
+                    Shell::Ysh => Some(config.ysh_indicator),
+                    Shell::Murex => Some(config.murex_indicator),
                     Shell::Unknown => Some(config.unknown_indicator),
                 },
                 _ => None,
@@ -47,7 +62,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "tcsh_indicator" => Some(Ok(config.tcsh_indicator)),
                 "xonsh_indicator" => Some(Ok(config.xonsh_indicator)),
                 "cmd_indicator" => Some(Ok(config.cmd_indicator)),
+                "osh_indicator" => Some(Ok(config.osh_indicator)),
+                "ysh_indicator" => Some(Ok(config.ysh_indicator)),
+                "murex_indicator" => Some(Ok(config.murex_indicator)),
                 "unknown_indicator" => Some(Ok(config.unknown_indicator)),
+                "version" => version.as_deref().map(Ok),
+                "login" => is_login.then_some(Ok(config.login_indicator)),
+                "non_interactive" => {
+                    (!is_interactive).then_some(Ok(config.non_interactive_indicator))
+                }
                 _ => None,
             })
             .parse(None, Some(context))
@@ -391,6 +414,78 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_osh_default_format() {
+        let expected = Some(format!("{} ", Color::White.bold().paint("osh")));
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Osh)
+            .config(toml::toml! {
+                [shell]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_osh_custom_format() {
+        let expected = Some(format!("{} ", Color::Cyan.bold().paint("osh")));
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Osh)
+            .config(toml::toml! {
+                [shell]
+                osh_indicator = "[osh](bold cyan)"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_ysh_default_format() {
+        let expected = Some(format!("{} ", Color::White.bold().paint("ysh")));
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Ysh)
+            .config(toml::toml! {
+                [shell]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_ysh_custom_format() {
+        let expected = Some(format!("{} ", Color::Cyan.bold().paint("ysh")));
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Ysh)
+            .config(toml::toml! {
+                [shell]
+                ysh_indicator = "[ysh](bold cyan)"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_murex_default_format() {
+        let expected = Some(format!("{} ", Color::White.bold().paint("mx")));
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Murex)
+            .config(toml::toml! {
+                [shell]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn test_custom_format_conditional_indicator_match() {
         let expected = Some(format!("{} ", "B"));
@@ -454,4 +549,54 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_version_and_login() {
+        let expected = Some("zsh 5.9 (login)".to_string());
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Zsh)
+            .env("STARSHIP_SHELL_VERSION", "5.9")
+            .env("STARSHIP_SHELL_LOGIN", "1")
+            .env("STARSHIP_SHELL_INTERACTIVE", "1")
+            .config(toml::toml! {
+                [shell]
+                format = "$indicator( $version)( \\($login$non_interactive\\))"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_non_interactive() {
+        let expected = Some("bsh (non-interactive)".to_string());
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Bash)
+            .env("STARSHIP_SHELL_LOGIN", "0")
+            .env("STARSHIP_SHELL_INTERACTIVE", "0")
+            .config(toml::toml! {
+                [shell]
+                format = "$indicator( $version)( \\($login$non_interactive\\))"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_no_flags_without_init_variables() {
+        let expected = Some("fsh".to_string());
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Fish)
+            .config(toml::toml! {
+                [shell]
+                format = "$indicator( $version)( \\($login$non_interactive\\))"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
 }
@@ -7,6 +7,9 @@ use std::borrow::Cow;
 use std::convert::TryInto;
 
 const SHLVL_ENV_VAR: &str = "SHLVL";
+/// The `SHLVL` of the first shell in the terminal or multiplexer pane, exported by the init
+/// scripts
+const SHLVL_BASE_ENV_VAR: &str = "STARSHIP_SHLVL_BASE";
 
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let props = &context.properties;
@@ -19,6 +22,16 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     // As we default to disabled=true, we have to check here after loading our config module,
     // before it was only checking against whatever is in the config starship.toml
+    // Count the levels from the first shell in the terminal, which starts at a higher level
+    // in terminal multiplexers such as tmux, so the first shell is at level 1
+    let shlvl = match context.get_env(SHLVL_BASE_ENV_VAR) {
+        Some(base) if config.auto_baseline => match base.parse::<i64>() {
+            Ok(base) => shlvl - base + 1,
+            Err(_) => shlvl,
+        },
+        _ => shlvl,
+    };
+
     if config.disabled || shlvl < config.threshold {
         return None;
     }
@@ -79,7 +92,7 @@ mod tests {
 
     use crate::test::ModuleRenderer;
 
-    use super::SHLVL_ENV_VAR;
+    use super::{SHLVL_BASE_ENV_VAR, SHLVL_ENV_VAR};
 
     fn style() -> Style {
         // default style
@@ -260,4 +273,38 @@ mod tests {
         // threshold not high enough; hide
         assert_eq!(get_actual(2, 1, 2), Some(format!("{}", style().paint("~"))));
     }
+
+    #[test]
+    fn auto_baseline() {
+        let renderer = |shlvl: &str| {
+            ModuleRenderer::new("shlvl")
+                .config(toml::toml! {
+                    [shlvl]
+                    auto_baseline = true
+                    disabled = false
+                })
+                .env(SHLVL_ENV_VAR, shlvl)
+                .env(SHLVL_BASE_ENV_VAR, "2")
+                .collect()
+        };
+
+        // The first shell in a tmux pane is at level 2
+        assert_eq!(renderer("2"), None);
+        assert_eq!(renderer("3"), Some(format!("{} ", style().paint("↕️  2"))));
+    }
+
+    #[test]
+    fn baseline_ignored_by_default() {
+        let actual = ModuleRenderer::new("shlvl")
+            .config(toml::toml! {
+                [shlvl]
+                disabled = false
+            })
+            .env(SHLVL_ENV_VAR, "2")
+            .env(SHLVL_BASE_ENV_VAR, "2")
+            .collect();
+        let expected = Some(format!("{} ", style().paint("↕️  2")));
+
+        assert_eq!(expected, actual);
+    }
 }
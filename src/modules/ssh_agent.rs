@@ -0,0 +1,198 @@
+use super::{Context, Module};
+
+#[cfg(not(unix))]
+pub fn module<'a>(_context: &'a Context) -> Option<Module<'a>> {
+    None
+}
+
+/// Creates a module with the number of identities loaded in the agent at `SSH_AUTH_SOCK`, or a
+/// warning when the agent doesn't answer
+#[cfg(unix)]
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    use super::ModuleConfig;
+    use crate::configs::ssh_agent::SshAgentConfig;
+    use crate::formatter::StringFormatter;
+    use std::time::Duration;
+
+    let mut module = context.new_module("ssh_agent");
+    let config = SshAgentConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let socket = context.get_env_os("SSH_AUTH_SOCK")?;
+    let count = match count_identities(socket.as_ref(), Duration::from_millis(config.timeout)) {
+        Ok(count) => Some(count),
+        Err(error) => {
+            log::debug!("Unable to list the identities of the SSH agent: {error}");
+            None
+        }
+    };
+    let (format, style) = match count {
+        None => (config.unreachable_format, config.unreachable_style),
+        Some(0) => (config.format, config.empty_style),
+        Some(_) => (config.format, config.style),
+    };
+
+    let parsed = StringFormatter::new(format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "count" => count.map(|count| Ok(count.to_string())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `ssh_agent`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Asks the agent listening on `socket` for its identities, and returns how many it holds
+#[cfg(unix)]
+fn count_identities(
+    socket: &std::path::Path,
+    timeout: std::time::Duration,
+) -> std::io::Result<u32> {
+    use std::io::{Error, Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    // Message numbers of the SSH agent protocol
+    const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+    const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    // Messages are prefixed with their length, as a big-endian u32
+    stream.write_all(&[0, 0, 0, 1, SSH_AGENTC_REQUEST_IDENTITIES])?;
+
+    // Only the start of the answer is needed: its length, type, and number of identities
+    let mut answer = [0; 9];
+    stream.read_exact(&mut answer)?;
+    if answer[4] != SSH_AGENT_IDENTITIES_ANSWER {
+        return Err(Error::other(format!(
+            "unexpected answer of type {}",
+            answer[4]
+        )));
+    }
+    Ok(u32::from_be_bytes([
+        answer[5], answer[6], answer[7], answer[8],
+    ]))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::io::{self, Read, Write};
+    use std::os::unix::net::UnixListener;
+    use std::path::Path;
+    use std::thread;
+
+    /// Answers one request for identities like an agent holding `count` of them, without the
+    /// keys themselves
+    fn fake_agent(socket: &Path, count: u32) -> io::Result<thread::JoinHandle<()>> {
+        let listener = UnixListener::bind(socket)?;
+        Ok(thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 5];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(request, [0, 0, 0, 1, 11]);
+            let mut answer = vec![0, 0, 0, 5, 12];
+            answer.extend(count.to_be_bytes());
+            stream.write_all(&answer).unwrap();
+        }))
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = ModuleRenderer::new("ssh_agent")
+            .env("SSH_AUTH_SOCK", "/nonexistent/agent.sock")
+            .collect();
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn no_agent_configured() {
+        let actual = ModuleRenderer::new("ssh_agent")
+            .config(toml::toml! {
+                [ssh_agent]
+                disabled = false
+            })
+            .collect();
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn identities_loaded() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let socket = dir.path().join("agent.sock");
+        let agent = fake_agent(&socket, 2)?;
+
+        let actual = ModuleRenderer::new("ssh_agent")
+            .env("SSH_AUTH_SOCK", socket.to_string_lossy())
+            .config(toml::toml! {
+                [ssh_agent]
+                disabled = false
+            })
+            .collect();
+        agent.join().unwrap();
+        let expected = Some(format!("{} ", Color::Green.bold().paint("󰌆 2")));
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
+    #[test]
+    fn no_identities_loaded() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let socket = dir.path().join("agent.sock");
+        let agent = fake_agent(&socket, 0)?;
+
+        let actual = ModuleRenderer::new("ssh_agent")
+            .env("SSH_AUTH_SOCK", socket.to_string_lossy())
+            .config(toml::toml! {
+                [ssh_agent]
+                disabled = false
+            })
+            .collect();
+        agent.join().unwrap();
+        let expected = Some(format!("{} ", Color::Yellow.bold().paint("󰌆 0")));
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
+    #[test]
+    fn agent_unreachable() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = ModuleRenderer::new("ssh_agent")
+            .env(
+                "SSH_AUTH_SOCK",
+                dir.path().join("agent.sock").to_string_lossy(),
+            )
+            .config(toml::toml! {
+                [ssh_agent]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!("{} ", Color::Red.bold().paint("󰌆 !")));
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+}
@@ -2,6 +2,8 @@ use std::string::ToString;
 
 use super::{Context, Module, ModuleConfig};
 
+use indexmap::IndexMap;
+
 use crate::configs::status::StatusConfig;
 use crate::formatter::{StringFormatter, string_formatter::StringFormatterError};
 use crate::segment::Segment;
@@ -61,12 +63,19 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let segment_format = config.pipestatus_segment_format.unwrap_or(config.format);
     let segment_format_with_separator = [segment_format, config.pipestatus_separator].join("");
 
-    // Create pipestatus segments
+    // Create pipestatus segments, leaving out the first ones of long pipelines
     let pipestatus = match pipestatus_status {
-        PipeStatusStatus::Pipe(ps) => ps
-            .iter()
-            .enumerate()
-            .filter_map(|(i, ec)| {
+        PipeStatusStatus::Pipe(ps) => {
+            let skipped = match config.max_pipe_elements {
+                0 => 0,
+                max => ps.len().saturating_sub(max),
+            };
+            let truncation = if skipped > 0 {
+                format_truncation(&config, context)
+            } else {
+                Vec::new()
+            };
+            let elements = ps.iter().enumerate().skip(skipped).filter_map(|(i, ec)| {
                 let formatted = format_exit_code(
                     ec.as_str(),
                     if i == ps.len() - 1 {
@@ -85,9 +94,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                         None
                     }
                 }
-            })
-            .flatten()
-            .collect(),
+            });
+            truncation.into_iter().chain(elements.flatten()).collect()
+        }
         _ => Vec::new(),
     };
 
@@ -107,6 +116,47 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Formats the symbol standing in for the elements of a pipeline left out, followed by the separator
+fn format_truncation(config: &StatusConfig, context: &Context) -> Vec<Segment> {
+    let format = ["$truncation_symbol", config.pipestatus_separator].join("");
+    let parsed = StringFormatter::new(&format).and_then(|formatter| {
+        formatter
+            .map(|variable| match variable {
+                "truncation_symbol" => Some(Ok(config.pipestatus_truncation_symbol)),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+    parsed.unwrap_or_else(|e| {
+        log::warn!("Error parsing format string in `status.pipestatus_separator`: {e:?}");
+        Vec::new()
+    })
+}
+
+/// The value a map such as `code_symbols` sets for the exit code, or for the signal that ended
+/// the command
+fn code_value<'a>(
+    values: &IndexMap<String, &'a str>,
+    exit_code: &str,
+    exit_code_int: ExitCode,
+    signal_name: Option<&str>,
+) -> Option<&'a str> {
+    values
+        .get(exit_code)
+        .or_else(|| values.get(&exit_code_int.to_string()))
+        .or_else(|| {
+            let signal_name = signal_name?;
+            values
+                .iter()
+                .find(|(key, _)| {
+                    let key = key.to_uppercase();
+                    key.strip_prefix("SIG").unwrap_or(&key) == signal_name
+                })
+                .map(|(_, value)| value)
+        })
+        .copied()
+}
+
 fn format_exit_code<'a>(
     exit_code: &'a str,
     format: &'a str,
@@ -135,6 +185,12 @@ fn format_exit_code<'a>(
     let signal_name =
         raw_signal_number.and_then(|sn| status_signal_name(sn).or(signal_number.as_deref()));
 
+    let description = code_value(&config.descriptions, exit_code, exit_code_int, signal_name)
+        .or_else(|| match raw_signal_number {
+            Some(signal) => status_signal_description(signal),
+            None => status_description(exit_code_int),
+        });
+
     // If not a signal and not a common meaning, it should at least print the raw exit code number
     let maybe_exit_code_number = if common_meaning.is_none() && signal_name.is_none() {
         Some(exit_code)
@@ -145,21 +201,24 @@ fn format_exit_code<'a>(
     StringFormatter::new(format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
-                "symbol" => match exit_code_int {
-                    0 => Some(config.success_symbol),
-                    126 if config.map_symbol => Some(config.not_executable_symbol),
-                    127 if config.map_symbol => Some(config.not_found_symbol),
-                    130 if config.recognize_signal_code && config.map_symbol => {
-                        Some(config.sigint_symbol)
-                    }
-                    x if (129..256).contains(&x)
-                        && config.recognize_signal_code
-                        && config.map_symbol =>
-                    {
-                        Some(config.signal_symbol)
-                    }
-                    _ => Some(config.symbol),
-                },
+                "symbol" => Some(
+                    code_value(&config.code_symbols, exit_code, exit_code_int, signal_name)
+                        .unwrap_or(match exit_code_int {
+                            0 => config.success_symbol,
+                            126 if config.map_symbol => config.not_executable_symbol,
+                            127 if config.map_symbol => config.not_found_symbol,
+                            130 if config.recognize_signal_code && config.map_symbol => {
+                                config.sigint_symbol
+                            }
+                            x if (129..256).contains(&x)
+                                && config.recognize_signal_code
+                                && config.map_symbol =>
+                            {
+                                config.signal_symbol
+                            }
+                            _ => config.symbol,
+                        }),
+                ),
                 _ => None,
             })
             .map_style(|variable| match variable {
@@ -179,6 +238,7 @@ fn format_exit_code<'a>(
                 "common_meaning" => Ok(common_meaning).transpose(),
                 "signal_number" => Ok(signal_number.as_deref()).transpose(),
                 "signal_name" => Ok(signal_name).transpose(),
+                "description" => Ok(description).transpose(),
                 _ => None,
             })
             .map_variables_to_segments(|variable| match variable {
@@ -229,6 +289,35 @@ fn status_common_meaning(ex: ExitCode) -> Option<&'static str> {
     }
 }
 
+/// A description of what the exit code usually means, for `$description`
+fn status_description(ex: ExitCode) -> Option<&'static str> {
+    match ex {
+        1 => Some("general error"),
+        2 => Some("misuse of shell builtin"),
+
+        // status codes 64-78 from libc
+        64 => Some("usage error"),
+        65 => Some("data format error"),
+        66 => Some("cannot open input"),
+        67 => Some("addressee unknown"),
+        68 => Some("host name unknown"),
+        69 => Some("service unavailable"),
+        70 => Some("internal software error"),
+        71 => Some("system error"),
+        72 => Some("critical OS file missing"),
+        73 => Some("can't create output file"),
+        74 => Some("input/output error"),
+        75 => Some("temporary failure"),
+        76 => Some("remote error in protocol"),
+        77 => Some("permission denied"),
+        78 => Some("configuration error"),
+
+        126 => Some("command not executable"),
+        127 => Some("command not found"),
+        _ => None,
+    }
+}
+
 fn status_to_signal(ex: ExitCode) -> Option<SignalNumber> {
     if ex < 129 {
         return None;
@@ -265,6 +354,35 @@ fn status_signal_name(signal: SignalNumber) -> Option<&'static str> {
     }
 }
 
+/// A description of the signal, for `$description`
+fn status_signal_description(signal: SignalNumber) -> Option<&'static str> {
+    match signal {
+        1 => Some("hangup"),
+        2 => Some("interrupted"),
+        3 => Some("quit"),
+        4 => Some("illegal instruction"),
+        5 => Some("trace trap"),
+        6 => Some("aborted"),
+        7 => Some("bus error"),
+        8 => Some("floating point exception"),
+        9 => Some("killed"),
+        10 => Some("user defined signal 1"),
+        11 => Some("segmentation fault"),
+        12 => Some("user defined signal 2"),
+        13 => Some("broken pipe"),
+        14 => Some("alarm clock"),
+        15 => Some("terminated"),
+        16 => Some("stack fault"),
+        17 => Some("child exited"),
+        18 => Some("continued"),
+        19 => Some("stopped"),
+        20 => Some("stopped (tty)"),
+        21 => Some("stopped (tty input)"),
+        22 => Some("stopped (tty output)"),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use nu_ansi_term::{Color, Style};
@@ -884,4 +1002,64 @@ mod tests {
             .collect();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn code_symbols() {
+        let config = toml::toml! {
+            [status]
+            format = "$symbol$status"
+            code_symbols = { 130 = "⌃C", SIGKILL = "OOM" }
+            disabled = false
+        };
+
+        for (status, expected) in [(130, "⌃C130"), (137, "OOM137"), (1, "❌1")] {
+            let actual = ModuleRenderer::new("status")
+                .config(config.clone())
+                .status(status)
+                .collect();
+            assert_eq!(Some(expected.to_string()), actual);
+        }
+    }
+
+    #[test]
+    fn pipestatus_max_pipe_elements() {
+        let expected = Some("[…|141|0|1] => 1".to_string());
+        let actual = ModuleRenderer::new("status")
+            .config(toml::toml! {
+                [status]
+                format = "$status"
+                pipestatus = true
+                pipestatus_format = "\\[$pipestatus\\] => $status"
+                max_pipe_elements = 3
+                disabled = false
+            })
+            .status(1)
+            .pipestatus(&[0, 2, 141, 0, 1])
+            .collect();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn description() {
+        let config = toml::toml! {
+            [status]
+            format = "$status( \\($description\\))"
+            descriptions = { 1 = "allgemeiner Fehler", SIGINT = "abgebrochen" }
+            disabled = false
+        };
+
+        for (status, expected) in [
+            (127, "127 (command not found)"),
+            (139, "139 (segmentation fault)"),
+            (1, "1 (allgemeiner Fehler)"),
+            (130, "130 (abgebrochen)"),
+            (3, "3"),
+        ] {
+            let actual = ModuleRenderer::new("status")
+                .config(config.clone())
+                .status(status)
+                .collect();
+            assert_eq!(Some(expected.to_string()), actual);
+        }
+    }
 }
@@ -1,9 +1,35 @@
 use std::env;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
 
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::sudo::SudoConfig;
 use crate::formatter::StringFormatter;
+use crate::utils::render_time;
+
+/// The directories sudo keeps the time stamps of the cached credentials of each user in
+const TIMESTAMP_DIRS: &[&str] = &[
+    "/run/sudo/ts",
+    "/var/run/sudo/ts",
+    "/var/db/sudo/ts",
+    "/var/lib/sudo/ts",
+];
+
+/// Returns how long the cached credentials of the user stay valid, from the modification time
+/// of their time stamp file. The file is usually only readable by root.
+fn remaining_time(dirs: &[&str], user: &str, timeout: Duration) -> Option<Duration> {
+    let modified = dirs
+        .iter()
+        .find_map(|dir| fs::metadata(Path::new(dir).join(user)).ok())?
+        .modified()
+        .ok()?;
+    let elapsed = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    timeout.checked_sub(elapsed)
+}
 
 /// Creates a module with sudo credential cache status
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -24,6 +50,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let remaining = context.get_env("USER").and_then(|user| {
+        let timeout = Duration::from_secs(config.timestamp_timeout * 60);
+        remaining_time(TIMESTAMP_DIRS, &user, timeout)
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|variable, _| match variable {
@@ -34,6 +65,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "style" => Some(Ok(config.style)),
                 _ => None,
             })
+            .map(|variable| match variable {
+                "remaining" => {
+                    remaining.map(|remaining| Ok(render_time(remaining.as_millis(), false)))
+                }
+                _ => None,
+            })
             .parse(None, Some(context))
     });
 
@@ -50,9 +87,36 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::{test::ModuleRenderer, utils::CommandOutput};
     use nu_ansi_term::Color;
 
+    #[test]
+    fn timestamp_remaining_time() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let ts = dir.path().join("ts");
+        fs::create_dir(&ts)?;
+        fs::File::create(ts.join("astronaut"))?
+            .set_modified(SystemTime::now() - Duration::from_secs(5 * 60))?;
+        let dirs = ["/nonexistent/sudo/ts", ts.to_str().unwrap()];
+
+        let remaining = remaining_time(&dirs, "astronaut", Duration::from_secs(15 * 60));
+        assert!(
+            remaining.is_some_and(|remaining| remaining <= Duration::from_secs(10 * 60)
+                && remaining > Duration::from_secs(9 * 60))
+        );
+        // The credentials have expired
+        assert_eq!(
+            remaining_time(&dirs, "astronaut", Duration::from_secs(60)),
+            None
+        );
+        assert_eq!(
+            remaining_time(&dirs, "cosmonaut", Duration::from_secs(15 * 60)),
+            None
+        );
+        dir.close()
+    }
+
     #[test]
     fn test_sudo_not_cached() {
         let actual = ModuleRenderer::new("sudo")
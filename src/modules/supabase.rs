@@ -0,0 +1,135 @@
+use std::path::Path;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::supabase::SupabaseConfig;
+use crate::formatter::StringFormatter;
+use crate::utils;
+
+/// Creates a module showing whether the Supabase CLI targets the local stack or the hosted
+/// project linked with `supabase link`
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("supabase");
+    let config = SupabaseConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let supabase_dir = context
+        .begin_ancestor_scan()
+        .set_folders(&["supabase"])
+        .scan()?
+        .join("supabase");
+    let project_config = utils::read_file(supabase_dir.join("config.toml")).ok()?;
+    let project_id = toml::from_str::<toml::Table>(&project_config)
+        .ok()
+        .and_then(|table| Some(table.get("project_id")?.as_str()?.to_string()));
+    let project_ref = linked_project_ref(&supabase_dir);
+
+    let (mode, style) = if project_ref.is_some() {
+        ("linked", config.linked_style)
+    } else {
+        ("local", config.style)
+    };
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "mode" => Some(Ok(mode)),
+                "project_ref" => project_ref.as_deref().map(Ok),
+                "project_id" => project_id.as_deref().map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `supabase`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// The ref of the hosted project, which `supabase link` writes to `.temp/project-ref`
+fn linked_project_ref(supabase_dir: &Path) -> Option<String> {
+    let project_ref = utils::read_file(supabase_dir.join(".temp").join("project-ref")).ok()?;
+    let project_ref = project_ref.trim();
+    (!project_ref.is_empty()).then(|| project_ref.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    fn supabase_project() -> io::Result<tempfile::TempDir> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir_all(dir.path().join("supabase").join("migrations"))?;
+        fs::write(
+            dir.path().join("supabase").join("config.toml"),
+            "project_id = \"acme\"\n\n[api]\nport = 54321\n",
+        )?;
+        Ok(dir)
+    }
+
+    #[test]
+    fn folder_without_supabase_config() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join("supabase"))?;
+        let actual = ModuleRenderer::new("supabase").path(dir.path()).collect();
+        assert_eq!(actual, None);
+        dir.close()
+    }
+
+    #[test]
+    fn local_project() -> io::Result<()> {
+        let dir = supabase_project()?;
+        let actual = ModuleRenderer::new("supabase")
+            .path(dir.path().join("supabase").join("migrations"))
+            .collect();
+        let expected = Some(format!("via {} ", Color::Green.bold().paint("󱐋 local")));
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
+    #[test]
+    fn linked_project() -> io::Result<()> {
+        let dir = supabase_project()?;
+        let temp_dir = dir.path().join("supabase").join(".temp");
+        fs::create_dir(&temp_dir)?;
+        fs::write(temp_dir.join("project-ref"), "abcdefghijklmnopqrst\n")?;
+
+        let actual = ModuleRenderer::new("supabase").path(dir.path()).collect();
+        let expected = Some(format!(
+            "via {} ",
+            Color::Yellow.bold().paint("󱐋 linked abcdefghijklmnopqrst")
+        ));
+        assert_eq!(actual, expected);
+
+        let actual = ModuleRenderer::new("supabase")
+            .path(dir.path())
+            .config(toml::toml! {
+                [supabase]
+                format = "[$project_id]($style)"
+            })
+            .collect();
+        let expected = Some(Color::Yellow.bold().paint("acme").to_string());
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+}
@@ -0,0 +1,222 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::temperature::TemperatureConfig;
+use crate::formatter::StringFormatter;
+use crate::utils::read_file;
+
+/// Where Linux exposes the hardware monitoring chips
+const HWMON_ROOT: &str = "/sys/class/hwmon";
+
+/// A temperature sensor of a hardware monitoring chip
+#[derive(Debug, PartialEq)]
+struct Sensor {
+    /// The name of the chip and the label of the sensor, such as `coretemp/Package id 0`
+    name: String,
+    /// The temperature, in degrees Celsius
+    celsius: f64,
+}
+
+impl Sensor {
+    /// Whether the sensor is selected by an entry of `sensors`, which is either the name of a
+    /// chip, the label of a sensor or both separated by `/`
+    fn matches(&self, selection: &str) -> bool {
+        let (chip, label) = self.name.split_once('/').unwrap_or((&self.name, ""));
+        self.name == selection || chip == selection || label == selection
+    }
+}
+
+/// Reads the temperature sensors on the operating system `os`, which is only supported on Linux
+fn sensors(os: &str) -> Option<Vec<Sensor>> {
+    if os != "linux" {
+        log::debug!("The temperature sensors can't be read on {os}, only on Linux");
+        return None;
+    }
+    Some(hwmon_sensors(Path::new(HWMON_ROOT)))
+}
+
+/// Reads the temperature sensors of all hwmon chips under `root`
+fn hwmon_sensors(root: &Path) -> Vec<Sensor> {
+    let Ok(chips) = fs::read_dir(root) else {
+        log::debug!("Unable to read the hardware monitoring chips in {root:?}");
+        return Vec::new();
+    };
+
+    let mut sensors = Vec::new();
+    for chip in chips.flatten().map(|entry| entry.path()) {
+        let chip_name = read_file(chip.join("name"))
+            .map(|name| name.trim().to_string())
+            .unwrap_or_else(|_| {
+                chip.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into()
+            });
+        let Ok(files) = fs::read_dir(&chip) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let file_name = file.file_name();
+            let Some(sensor) = file_name
+                .to_str()
+                .and_then(|name| name.strip_suffix("_input"))
+                .filter(|sensor| sensor.starts_with("temp"))
+            else {
+                continue;
+            };
+            // The temperatures are in thousandths of a degree Celsius
+            let Some(millidegrees) = read_file(file.path())
+                .ok()
+                .and_then(|value| value.trim().parse::<f64>().ok())
+            else {
+                continue;
+            };
+            let label = read_file(chip.join(format!("{sensor}_label")))
+                .map(|label| label.trim().to_string())
+                .unwrap_or_else(|_| sensor.to_string());
+            sensors.push(Sensor {
+                name: format!("{chip_name}/{label}"),
+                celsius: millidegrees / 1000.0,
+            });
+        }
+    }
+    sensors
+}
+
+/// Creates a module with the temperature of the hottest sensor, once it is over the threshold
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("temperature");
+    let config = TemperatureConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let sensors = sensors(env::consts::OS)?;
+    let hottest = sensors
+        .iter()
+        .filter(|sensor| {
+            config.sensors.is_empty()
+                || config
+                    .sensors
+                    .iter()
+                    .any(|selection| sensor.matches(selection))
+        })
+        .max_by(|a, b| a.celsius.total_cmp(&b.celsius))?;
+    log::debug!("Hottest temperature sensor: {hottest:?}");
+
+    if hottest.celsius < config.threshold {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "temperature" => Some(Ok(format!("{:.0}°C", hottest.celsius))),
+                "sensor" => Some(Ok(hottest.name.clone())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `temperature`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::ModuleRenderer;
+
+    #[test]
+    fn hwmon() -> std::io::Result<()> {
+        let root = tempfile::tempdir()?;
+        let coretemp = root.path().join("hwmon0");
+        fs::create_dir(&coretemp)?;
+        fs::write(coretemp.join("name"), "coretemp\n")?;
+        fs::write(coretemp.join("temp1_input"), "91000\n")?;
+        fs::write(coretemp.join("temp1_label"), "Package id 0\n")?;
+        fs::write(coretemp.join("temp1_max"), "100000\n")?;
+        let nvme = root.path().join("hwmon1");
+        fs::create_dir(&nvme)?;
+        fs::write(nvme.join("name"), "nvme\n")?;
+        fs::write(nvme.join("temp1_input"), "45850\n")?;
+
+        let mut sensors = hwmon_sensors(root.path());
+        sensors.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            sensors,
+            vec![
+                Sensor {
+                    name: "coretemp/Package id 0".to_string(),
+                    celsius: 91.0
+                },
+                Sensor {
+                    name: "nvme/temp1".to_string(),
+                    celsius: 45.85
+                },
+            ]
+        );
+
+        assert!(sensors[0].matches("coretemp"));
+        assert!(sensors[0].matches("Package id 0"));
+        assert!(sensors[0].matches("coretemp/Package id 0"));
+        assert!(!sensors[0].matches("nvme"));
+        root.close()
+    }
+
+    #[test]
+    fn unsupported_platforms() {
+        assert_eq!(sensors("macos"), None);
+        assert_eq!(sensors("windows"), None);
+        assert!(sensors("linux").is_some());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "linux"))]
+    fn hidden_on_unsupported_platforms() {
+        let output = ModuleRenderer::new("temperature")
+            .config(toml::toml! {
+                [temperature]
+                disabled = false
+                threshold = 0.0
+            })
+            .collect();
+
+        assert!(output.is_none());
+    }
+
+    #[test]
+    fn impossible_threshold() {
+        let output = ModuleRenderer::new("temperature")
+            .config(toml::toml! {
+                [temperature]
+                disabled = false
+                threshold = 9999.0
+            })
+            .collect();
+
+        assert!(output.is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::terraform::TerraformConfig;
 use crate::formatter::StringFormatter;
@@ -8,6 +8,8 @@ use crate::formatter::VersionFormatter;
 use std::io;
 use std::path::PathBuf;
 
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
 /// Creates a module with the current Terraform version and workspace
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("terraform");
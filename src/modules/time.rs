@@ -40,6 +40,19 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         format_time(time_format, Local::now())
     };
 
+    let utc_now = Utc::now();
+    let zone_times: Vec<(&str, String)> = config
+        .zones
+        .iter()
+        .filter_map(|zone| {
+            create_zone_time_string(utc_now, zone.zone, time_format)
+                .map(|time| (zone.alias(), time))
+                .map_err(|error| log::warn!("Invalid time zone `{}`: {error}", zone.zone))
+                .ok()
+        })
+        .collect();
+    let world_clock = create_world_clock(&zone_times, config.world_clock_separator);
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_style(|variable| match variable {
@@ -47,8 +60,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "time" => Some(Ok(&formatted_time_string)),
-                _ => None,
+                "time" => Some(Ok(formatted_time_string.as_str())),
+                "world_clock" => Some(Ok(world_clock.as_str())),
+                _ => {
+                    let alias = variable.strip_prefix("time_")?;
+                    zone_times
+                        .iter()
+                        .find(|(zone_alias, _)| zone_alias.eq_ignore_ascii_case(alias))
+                        .map(|(_, time)| Ok(time.as_str()))
+                }
             })
             .parse(None, Some(context))
     });
@@ -90,6 +110,44 @@ fn create_offset_time_string(
     }
 }
 
+/// Formats the time in a time zone of the tz database, such as `America/New_York`. `utc`
+/// and `local` are the UTC and system time zones.
+fn create_zone_time_string(
+    utc_time: DateTime<Utc>,
+    zone: &str,
+    time_format: &str,
+) -> Result<String, jiff::Error> {
+    let timezone = if zone.eq_ignore_ascii_case("utc") {
+        jiff::tz::TimeZone::UTC
+    } else if zone.eq_ignore_ascii_case("local") {
+        jiff::tz::TimeZone::system()
+    } else {
+        jiff::tz::TimeZone::get(zone)?
+    };
+
+    // The offset changes with daylight saving time, so it is looked up for the given time
+    let timestamp = jiff::Timestamp::from_second(utc_time.timestamp())?;
+    let offset_in_seconds = timezone.to_offset(timestamp).seconds();
+    let Some(timezone_offset) = FixedOffset::east_opt(offset_in_seconds) else {
+        unreachable!("time zone offsets are less than a day");
+    };
+    log::trace!("Time zone {zone} has offset {timezone_offset}");
+
+    Ok(format_time_fixed_offset(
+        time_format,
+        utc_time.with_timezone(&timezone_offset),
+    ))
+}
+
+/// Joins the time of every zone after its alias, e.g. `NYC 09:00:00 | Tokyo 23:00:00`
+fn create_world_clock(zone_times: &[(&str, String)], separator: &str) -> String {
+    zone_times
+        .iter()
+        .map(|(alias, time)| format!("{alias} {time}"))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
 /// Format a given time into the given string. This function should be referentially
 /// transparent, which makes it easy to test (unlike anything involving the actual time)
 fn format_time(time_format: &str, local_time: DateTime<Local>) -> String {
@@ -364,6 +422,43 @@ mod tests {
             .expect_err("Invalid timezone offset.");
     }
 
+    #[test]
+    fn test_create_zone_time_string_with_daylight_saving_time() {
+        let winter: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let summer: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 7, 15, 12, 0, 0).unwrap();
+
+        let actual = create_zone_time_string(winter, "America/New_York", FMT_24).unwrap();
+        assert_eq!(actual, "07:00:00");
+        let actual = create_zone_time_string(summer, "America/New_York", FMT_24).unwrap();
+        assert_eq!(actual, "08:00:00");
+    }
+
+    #[test]
+    fn test_create_zone_time_string_with_utc() {
+        let utc_time: DateTime<Utc> = Utc.with_ymd_and_hms(2014, 7, 8, 15, 36, 47).unwrap();
+
+        let actual = create_zone_time_string(utc_time, "UTC", FMT_12).unwrap();
+        assert_eq!(actual, "03:36:47 PM");
+    }
+
+    #[test]
+    fn test_create_zone_time_string_with_invalid_zone() {
+        let utc_time: DateTime<Utc> = Utc.with_ymd_and_hms(2014, 7, 8, 15, 36, 47).unwrap();
+
+        create_zone_time_string(utc_time, "Middle/Earth", FMT_12).expect_err("Invalid zone.");
+    }
+
+    #[test]
+    fn test_create_world_clock() {
+        let zone_times = [("NYC", "09:00".to_string()), ("Tokyo", "23:00".to_string())];
+
+        assert_eq!(
+            create_world_clock(&zone_times, " | "),
+            "NYC 09:00 | Tokyo 23:00"
+        );
+        assert_eq!(create_world_clock(&[], " | "), "");
+    }
+
     #[test]
     fn test_parse_invalid_time_range() {
         let time_range = "10:00:00-12:00:00-13:00:00";
@@ -503,4 +598,27 @@ mod tests {
         assert!(actual.starts_with(&col_prefix));
         assert!(actual.ends_with(&col_suffix));
     }
+
+    #[test]
+    fn config_zone_variables() {
+        let actual = ModuleRenderer::new("time")
+            .config(toml::toml! {
+                [time]
+                disabled = false
+                format = "$time_utc|$time_tokyo|$time_invalid|$world_clock"
+                time_format = "%Z"
+                world_clock_separator = ", "
+                zones = [
+                    { zone = "utc" },
+                    { zone = "Asia/Tokyo" },
+                    { zone = "Not/A_Zone", alias = "invalid" },
+                ]
+            })
+            .collect();
+
+        assert_eq!(
+            actual,
+            Some("+00:00|+09:00||utc +00:00, Tokyo +09:00".to_string())
+        );
+    }
 }
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::timer::TimerConfig;
+use crate::formatter::StringFormatter;
+use crate::timer;
+use crate::utils::render_time;
+
+/// Creates a module with the time left on the timer started by `starship timer start`
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("timer");
+    let config = TimerConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let timer = timer::load(context)?;
+    let remaining = timer.remaining();
+    if remaining.is_none() && config.expired_timeout > 0 {
+        let expired_for = timer.expired_for().unwrap_or_default();
+        if expired_for > Duration::from_secs(config.expired_timeout * 60) {
+            return None;
+        }
+    }
+
+    let (format, symbol, style) = if remaining.is_some() {
+        (config.format, config.symbol, config.style)
+    } else {
+        (
+            config.expired_format,
+            config.expired_symbol,
+            config.expired_style,
+        )
+    };
+
+    let parsed = StringFormatter::new(format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "remaining" => {
+                    remaining.map(|remaining| Ok(render_time(remaining.as_millis(), false)))
+                }
+                "duration" => Some(Ok(render_time(timer.duration.as_millis(), false))),
+                "label" => timer.label.clone().map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `timer`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::SystemTime;
+
+    use nu_ansi_term::Color;
+
+    use crate::test::ModuleRenderer;
+    use crate::timer::{STATE_FILE, Timer};
+
+    /// Saves a timer of `duration` seconds started `elapsed` seconds ago
+    fn save_timer(dir: &std::path::Path, elapsed: u64, duration: u64, label: Option<&str>) {
+        let timer = Timer {
+            started_at: SystemTime::now() - std::time::Duration::from_secs(elapsed),
+            duration: std::time::Duration::from_secs(duration),
+            label: label.map(ToString::to_string),
+        };
+        fs::write(dir.join(STATE_FILE), serde_json::to_string(&timer).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn no_timer() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = ModuleRenderer::new("timer")
+            .env("STARSHIP_STATE", dir.path().to_str().unwrap())
+            .collect();
+
+        assert_eq!(actual, None);
+        dir.close()
+    }
+
+    #[test]
+    fn running_timer() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        save_timer(dir.path(), 0, 25 * 60 + 30, Some("focus"));
+
+        let actual = ModuleRenderer::new("timer")
+            .config(toml::toml! {
+                [timer]
+                format = "[$symbol$duration( $label)]($style) "
+            })
+            .env("STARSHIP_STATE", dir.path().to_str().unwrap())
+            .collect();
+        let expected = Some(format!("{} ", Color::Red.bold().paint("🍅 25m30s focus")));
+
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
+    #[test]
+    fn expired_timer() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        save_timer(dir.path(), 10 * 60, 5 * 60, Some("focus"));
+
+        let actual = ModuleRenderer::new("timer")
+            .env("STARSHIP_STATE", dir.path().to_str().unwrap())
+            .collect();
+        let expected = Some(format!("{} ", Color::Green.bold().paint("⏰ focus")));
+
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
+    #[test]
+    fn expired_timer_hidden_after_timeout() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        save_timer(dir.path(), 60 * 60, 5 * 60, None);
+
+        let actual = ModuleRenderer::new("timer")
+            .env("STARSHIP_STATE", dir.path().to_str().unwrap())
+            .collect();
+
+        assert_eq!(actual, None);
+        dir.close()
+    }
+}
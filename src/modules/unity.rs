@@ -0,0 +1,245 @@
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use super::{Context, Module, ModuleConfig, Resource};
+use crate::configs::unity::UnityConfig;
+use crate::formatter::{StringFormatter, VersionFormatter};
+use crate::utils::read_file;
+
+pub const RESOURCES: &[Resource] = &[Resource::DirContents];
+
+/// Creates a module with the Unity editor version required by the current project
+///
+/// The version is flagged when it differs from the default editor set in Unity Hub.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("unity");
+    let config = UnityConfig::try_load(module.config);
+
+    let is_unity_project = context
+        .try_begin_scan()?
+        .set_files(&config.detect_files)
+        .set_extensions(&config.detect_extensions)
+        .set_folders(&config.detect_folders)
+        .is_match();
+
+    if !is_unity_project {
+        return None;
+    }
+
+    let project_version = get_project_version(context)?;
+    let hub_version = LazyLock::new(|| get_hub_default_version(context));
+    let is_hub_version = LazyLock::new(|| {
+        hub_version
+            .as_deref()
+            .is_none_or(|hub_version| hub_version == project_version)
+    });
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => {
+                    if *is_hub_version {
+                        Some(Ok(config.style))
+                    } else {
+                        Some(Ok(config.not_capable_style))
+                    }
+                }
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "version" => VersionFormatter::format_module_version(
+                    module.get_name(),
+                    &project_version,
+                    config.version_format,
+                )
+                .map(Ok),
+                "hub_version" => (!*is_hub_version)
+                    .then(|| hub_version.clone())
+                    .flatten()
+                    .map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `unity`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+fn get_project_version(context: &Context) -> Option<String> {
+    let contents = read_file(
+        context
+            .current_dir
+            .join("ProjectSettings")
+            .join("ProjectVersion.txt"),
+    )
+    .ok()?;
+
+    contents.lines().find_map(|line| {
+        let version = line.strip_prefix("m_EditorVersion:")?.trim();
+        (!version.is_empty()).then(|| version.to_string())
+    })
+}
+
+/// Reads the editor version which Unity Hub uses by default for new projects
+fn get_hub_default_version(context: &Context) -> Option<String> {
+    let contents = read_file(get_hub_config_dir(context)?.join("defaultEditor.json")).ok()?;
+    let version = match serde_json::from_str::<serde_json::Value>(&contents).ok()? {
+        serde_json::Value::String(version) => version,
+        _ => return None,
+    };
+
+    (!version.is_empty()).then_some(version)
+}
+
+fn get_hub_config_dir(context: &Context) -> Option<PathBuf> {
+    if cfg!(windows)
+        && let Some(app_data) = context.get_env("APPDATA")
+    {
+        return Some(PathBuf::from(app_data).join("UnityHub"));
+    }
+
+    let home = context.get_home()?;
+    let config_dir = if cfg!(target_os = "macos") {
+        home.join("Library").join("Application Support")
+    } else if cfg!(windows) {
+        home.join("AppData").join("Roaming")
+    } else {
+        context
+            .get_env("XDG_CONFIG_HOME")
+            .map_or_else(|| home.join(".config"), PathBuf::from)
+    };
+
+    Some(config_dir.join("UnityHub"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    fn write_project_version(dir: &Path, version: &str) -> io::Result<()> {
+        fs::create_dir_all(dir.join("ProjectSettings"))?;
+        fs::write(
+            dir.join("ProjectSettings/ProjectVersion.txt"),
+            format!(
+                "m_EditorVersion: {version}\nm_EditorVersionWithRevision: {version} (ff3792e53c62)\n"
+            ),
+        )
+    }
+
+    fn hub_config_dir(home: &Path) -> PathBuf {
+        if cfg!(target_os = "macos") {
+            home.join("Library/Application Support/UnityHub")
+        } else if cfg!(windows) {
+            home.join("AppData/Roaming/UnityHub")
+        } else {
+            home.join(".config/UnityHub")
+        }
+    }
+
+    fn write_hub_default(home: &Path, version: &str) -> io::Result<()> {
+        let dir = hub_config_dir(home);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("defaultEditor.json"), format!("\"{version}\""))
+    }
+
+    #[test]
+    fn folder_without_project_settings() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("unity").path(dir.path()).collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_without_project_version() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join("ProjectSettings"))?;
+
+        let actual = ModuleRenderer::new("unity").path(dir.path()).collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_project_version() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let home = tempfile::tempdir()?;
+        write_project_version(dir.path(), "2022.3.10f1")?;
+
+        let actual = ModuleRenderer::new("unity")
+            .path(dir.path())
+            .env("HOME", home.path().to_str().unwrap())
+            .collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::White.bold().paint("🎮 v2022.3.10f1 ")
+        ));
+        assert_eq!(expected, actual);
+        home.close()?;
+        dir.close()
+    }
+
+    #[test]
+    fn project_version_matches_hub_default() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let home = tempfile::tempdir()?;
+        write_project_version(dir.path(), "2022.3.10f1")?;
+        write_hub_default(home.path(), "2022.3.10f1")?;
+
+        let actual = ModuleRenderer::new("unity")
+            .path(dir.path())
+            .env("HOME", home.path().to_str().unwrap())
+            .collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::White.bold().paint("🎮 v2022.3.10f1 ")
+        ));
+        assert_eq!(expected, actual);
+        home.close()?;
+        dir.close()
+    }
+
+    #[test]
+    fn project_version_differs_from_hub_default() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let home = tempfile::tempdir()?;
+        write_project_version(dir.path(), "2021.3.5f1")?;
+        write_hub_default(home.path(), "2022.3.10f1")?;
+
+        let actual = ModuleRenderer::new("unity")
+            .path(dir.path())
+            .env("HOME", home.path().to_str().unwrap())
+            .collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Yellow
+                .bold()
+                .paint("🎮 v2021.3.5f1 (hub 2022.3.10f1) ")
+        ));
+        assert_eq!(expected, actual);
+        home.close()?;
+        dir.close()
+    }
+}
@@ -16,6 +16,7 @@ const USERNAME_ENV_VAR: &str = "USERNAME";
 ///     - The current user isn't the same as the one that is logged in (`$LOGNAME` != `$USER`) [2]
 ///     - The user is currently connected as an SSH session (`$SSH_CONNECTION`) [3]
 ///     - The option `username.detect_env_vars` is set with a not negated environment variable [4]
+///     - The user switched to the current one with sudo or doas (`$SUDO_USER`, `$DOAS_USER`) [5]
 /// Does not display the username:
 ///     - If the option `username.detect_env_vars` is set with a negated environment variable [A]
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -37,11 +38,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         username = "Administrator".to_string();
     }
 
+    let real_user = get_real_user(context, &username);
+
     let show_username = config.show_always
         || is_root // [1]
         || !is_login_user(context, &username) // [2]
         || is_ssh_session(context) // [3]
-        || has_detected_env_var == Detected::Yes; // [4]
+        || has_detected_env_var == Detected::Yes // [4]
+        || real_user.is_some(); // [5]
 
     if !show_username || has_detected_env_var == Detected::Negated {
         return None; // [A]
@@ -62,10 +66,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     };
                     Some(Ok(module_style))
                 }
+                "style_real_user" => Some(Ok(config.style_real_user)),
                 _ => None,
             })
             .map(|variable| match variable {
                 "user" => Some(Ok(&username)),
+                "real_user" => real_user.as_ref().map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -81,6 +87,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Returns the user that switched to the current one with sudo or doas
+fn get_real_user(context: &Context, username: &str) -> Option<String> {
+    ["SUDO_USER", "DOAS_USER"]
+        .iter()
+        .find_map(|var| context.get_env(var))
+        .filter(|real_user| !real_user.is_empty() && real_user != username)
+}
+
 fn is_login_user(context: &Context, username: &str) -> bool {
     context
         .get_env("LOGNAME")
@@ -128,6 +142,7 @@ fn is_ssh_session(context: &Context) -> bool {
 mod tests {
 
     use crate::test::ModuleRenderer;
+    use nu_ansi_term::{AnsiStrings, Color};
 
     // TODO: Add tests for if root user (UID == 0)
     // Requires mocking
@@ -188,6 +203,47 @@ mod tests {
         assert_eq!(expected, actual.as_deref());
     }
 
+    #[test]
+    fn sudo_user() {
+        let actual = ModuleRenderer::new("username")
+            .env(super::USERNAME_ENV_VAR, "root")
+            .env("LOGNAME", "root")
+            .env("SUDO_USER", "astronaut")
+            .config(toml::toml! {
+                [username]
+                style_root = ""
+                style_user = ""
+                style_real_user = ""
+            })
+            .collect();
+
+        let expected = Some("astronaut→root in ");
+        assert_eq!(expected, actual.as_deref());
+    }
+
+    #[test]
+    fn doas_user_styles() {
+        let actual = ModuleRenderer::new("username")
+            .env(super::USERNAME_ENV_VAR, "postgres")
+            .env("LOGNAME", "postgres")
+            .env("DOAS_USER", "astronaut")
+            .config(toml::toml! {
+                [username]
+                style_user = "red"
+                style_real_user = "blue"
+            })
+            .collect();
+
+        let expected = Some(format!(
+            "{} in ",
+            AnsiStrings(&[
+                Color::Blue.paint("astronaut→"),
+                Color::Red.paint("postgres")
+            ])
+        ));
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn no_env_variables() {
         let actual = ModuleRenderer::new("username").collect();
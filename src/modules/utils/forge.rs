@@ -0,0 +1,384 @@
+//! Queries the APIs of GitHub and GitLab for the modules showing the state of a repository on
+//! its host. The answers are cached and fetched again in the background, so the prompt never
+//! waits for the network.
+
+use std::borrow::Cow;
+use std::fs;
+use std::io::Write;
+use std::process::Stdio;
+use std::time::Duration;
+
+use process_control::{ChildExt, Control};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::cache;
+use crate::context::Context;
+use crate::utils::create_command;
+
+/// Set for the process refreshing the cached answer of a module
+const REFRESH_VAR: &str = "STARSHIP_FORGE_REFRESH";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Provider {
+    GitHub,
+    GitLab,
+}
+
+/// A repository on GitHub or GitLab, and the API it is queried through
+#[derive(Debug, PartialEq, Eq)]
+pub struct Project {
+    pub provider: Provider,
+    pub api_url: String,
+    pub path: String,
+}
+
+impl Project {
+    /// The path of the project in the URLs of the API
+    pub fn id(&self) -> Cow<'_, str> {
+        match self.provider {
+            Provider::GitHub => Cow::Borrowed(&self.path),
+            Provider::GitLab => urlencoding::encode(&self.path),
+        }
+    }
+
+    /// The headers of the requests to the API, with the token from the environment, if one
+    /// is set
+    fn headers(&self, context: &Context) -> Vec<String> {
+        let token = |env_vars: &[&str]| env_vars.iter().find_map(|var| context.get_env(var));
+        match self.provider {
+            Provider::GitHub => std::iter::once("Accept: application/vnd.github+json".to_string())
+                .chain(
+                    token(&["GITHUB_TOKEN", "GH_TOKEN"])
+                        .map(|token| format!("Authorization: Bearer {token}")),
+                )
+                .collect(),
+            Provider::GitLab => token(&["GITLAB_TOKEN", "GL_TOKEN"])
+                .map(|token| format!("PRIVATE-TOKEN: {token}"))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Requests `endpoint` of the API, e.g. `repos/starship/starship/pulls`, with the token
+    /// from the environment, if one is set
+    pub fn get(&self, context: &Context, endpoint: &str, timeout: Duration) -> Option<Value> {
+        let url = format!("{}/{endpoint}", self.api_url);
+        let response = request(&url, &self.headers(context), timeout)?;
+        match serde_json::from_str(&response) {
+            Ok(json) => Some(json),
+            Err(error) => {
+                log::debug!("Unable to parse the response of {url}: {error}");
+                None
+            }
+        }
+    }
+}
+
+/// The fetch URL of the upstream remote of the current branch, or of `origin`
+pub fn remote_url(context: &Context) -> Option<gix::Url> {
+    let repo = context.get_repo().ok()?;
+    let remote_name = repo
+        .remote
+        .as_ref()
+        .and_then(|remote| remote.name.as_deref())
+        .unwrap_or("origin");
+    let git_repo = repo.open();
+    let remote = git_repo.find_remote(remote_name).ok()?;
+    remote.url(gix::remote::Direction::Fetch).cloned()
+}
+
+/// The project of the upstream remote of the current branch, or of `origin`.
+///
+/// Tokens are sent to the host of the project, so only `github.com` and `gitlab.com` are
+/// detected. Self-hosted instances are queried when the `provider` option of `module` is set
+/// and their host is listed in its `hosts` option.
+pub fn remote_project(
+    context: &Context,
+    module: &str,
+    provider: &str,
+    hosts: &[&str],
+) -> Option<Project> {
+    let url = remote_url(context)?;
+    parse_project(url.host()?, &url.path.to_string(), module, provider, hosts)
+}
+
+fn parse_project(
+    host: &str,
+    path: &str,
+    module: &str,
+    provider: &str,
+    hosts: &[&str],
+) -> Option<Project> {
+    let host = host.to_ascii_lowercase();
+    let provider = match provider {
+        "" if host == "github.com" => Provider::GitHub,
+        "" if host == "gitlab.com" => Provider::GitLab,
+        "" => return None,
+        "github" => Provider::GitHub,
+        "gitlab" => Provider::GitLab,
+        provider => {
+            log::warn!(
+                "Unknown provider {provider:?} in `{module}.provider`, expected \"github\" or \"gitlab\""
+            );
+            return None;
+        }
+    };
+    let public_host = match provider {
+        Provider::GitHub => "github.com",
+        Provider::GitLab => "gitlab.com",
+    };
+    if host != public_host
+        && !hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&host))
+    {
+        log::debug!("The host {host:?} is not listed in `{module}.hosts`");
+        return None;
+    }
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if path.is_empty() {
+        return None;
+    }
+
+    let api_url = match provider {
+        Provider::GitHub if host == "github.com" => "https://api.github.com".to_string(),
+        Provider::GitHub => format!("https://{host}/api/v3"),
+        Provider::GitLab => format!("https://{host}/api/v4"),
+    };
+    Some(Project {
+        provider,
+        api_url,
+        path: path.to_string(),
+    })
+}
+
+/// Returns the answer cached under `key` in `cache_file`. Once it is older than `ttl`,
+/// `module` is rendered in the background to update the cache for the next prompt.
+///
+/// Phase 2 of a prompt rendered in two phases, for modules in `async_modules`, and the process
+/// updating the cache call `fetch` instead. It returns `None` if the request failed, in which
+/// case the previous answer is kept until the next refresh instead of retrying every prompt.
+pub fn cached<T: Serialize + DeserializeOwned>(
+    context: &Context,
+    module: &str,
+    cache_file: &str,
+    key: &str,
+    ttl: Duration,
+    fetch: impl FnOnce() -> Option<Option<T>>,
+) -> Option<T> {
+    let Some(dir) = cache::cache_dir(context) else {
+        log::debug!("Unable to find the cache directory for module `{module}`");
+        return None;
+    };
+    let path = dir.join(cache_file);
+    let lock_path = cache::refresh_lock_path(&dir, module, key);
+    let cached = cache::read_entry::<Option<T>>(&path, key);
+
+    if context.properties.phase == Some(2) || context.get_env(REFRESH_VAR).is_some() {
+        let value = fetch().unwrap_or_else(|| cached.and_then(|entry| entry.value));
+        cache::save_entry(&path, key, &value, ttl);
+        // The lock file may not exist, when the refresh wasn't started by a prompt
+        let _ = fs::remove_file(lock_path);
+        return value;
+    }
+
+    if cached.as_ref().is_none_or(cache::CacheEntry::is_expired) {
+        cache::spawn_refresh(context, module, &lock_path, REFRESH_VAR);
+    }
+    cached?.value
+}
+
+/// Requests `url` with curl, stopping it after `timeout`. The headers are passed on stdin, so
+/// tokens don't show up in the list of processes. Redirects aren't followed, so the headers
+/// are only sent to the host of `url`.
+fn request(url: &str, headers: &[String], timeout: Duration) -> Option<String> {
+    let mut command = create_command("curl").ok()?;
+    command
+        .args(["--silent", "--show-error", "--fail"])
+        .arg("--max-time")
+        .arg(format!("{:.3}", timeout.as_secs_f64()))
+        .args(["--config", "-"])
+        .arg(url)
+        .stdin(Stdio::piped());
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            log::debug!("Unable to run curl: {error}");
+            return None;
+        }
+    };
+    let curl_config: String = headers
+        .iter()
+        .map(|header| {
+            let header = header.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("header = \"{header}\"\n")
+        })
+        .collect();
+    // Dropping stdin closes it, so curl doesn't wait for more of its config
+    child.stdin.take()?.write_all(curl_config.as_bytes()).ok()?;
+
+    let Some(output) = child
+        .controlled_with_output()
+        .time_limit(timeout)
+        .terminate_for_timeout()
+        .wait()
+        .ok()?
+    else {
+        log::debug!("Request to {url} timed out");
+        return None;
+    };
+    if !output.status.success() {
+        log::debug!(
+            "Request to {url} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    #[test]
+    fn parses_projects() {
+        assert_eq!(
+            parse_project("github.com", "starship/starship.git", "test", "", &[]),
+            Some(Project {
+                provider: Provider::GitHub,
+                api_url: "https://api.github.com".to_string(),
+                path: "starship/starship".to_string(),
+            })
+        );
+        let gitlab = parse_project(
+            "gitlab.example.com",
+            "/group/sub/project",
+            "test",
+            "gitlab",
+            &["gitlab.example.com"],
+        );
+        assert_eq!(
+            gitlab,
+            Some(Project {
+                provider: Provider::GitLab,
+                api_url: "https://gitlab.example.com/api/v4".to_string(),
+                path: "group/sub/project".to_string(),
+            })
+        );
+        assert_eq!(gitlab.unwrap().id(), "group%2Fsub%2Fproject");
+        assert_eq!(
+            parse_project(
+                "Git.Example.com",
+                "/org/repo",
+                "test",
+                "github",
+                &["git.example.com"]
+            )
+            .map(|p| p.api_url),
+            Some("https://git.example.com/api/v3".to_string())
+        );
+        assert_eq!(
+            parse_project(
+                "git.example.com",
+                "/org/repo",
+                "test",
+                "",
+                &["git.example.com"]
+            ),
+            None
+        );
+        assert_eq!(parse_project("github.com", "/", "test", "", &[]), None);
+    }
+
+    #[test]
+    fn lookalike_hosts_get_no_token() {
+        let mut context = default_context();
+        context
+            .env
+            .insert("GITHUB_TOKEN", "secret-github".to_string());
+        context
+            .env
+            .insert("GITLAB_TOKEN", "secret-gitlab".to_string());
+
+        for host in [
+            "github.evil.tld",
+            "mygitlab.attacker.io",
+            "github.com.evil.tld",
+        ] {
+            assert_eq!(parse_project(host, "/org/repo", "test", "", &[]), None);
+        }
+        // Self-hosted instances must be listed in `hosts`, even with a provider
+        assert_eq!(
+            parse_project("github.evil.tld", "/org/repo", "test", "github", &[]),
+            None
+        );
+        assert_eq!(
+            parse_project(
+                "git.evil.tld",
+                "/org/repo",
+                "test",
+                "gitlab",
+                &["git.example.com"]
+            ),
+            None
+        );
+
+        let project = parse_project("github.com", "/org/repo", "test", "", &[]).unwrap();
+        assert!(
+            project
+                .headers(&context)
+                .contains(&"Authorization: Bearer secret-github".to_string())
+        );
+        let project = parse_project("gitlab.com", "/org/repo", "test", "", &[]).unwrap();
+        assert_eq!(
+            project.headers(&context),
+            vec!["PRIVATE-TOKEN: secret-gitlab".to_string()]
+        );
+    }
+
+    #[test]
+    fn shows_cached_value_and_refreshes_expired_one() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context();
+        context
+            .env
+            .insert("STARSHIP_CACHE", dir.path().to_string_lossy().to_string());
+        let cache_path = dir.path().join("test.json");
+        let cached = |context: &Context| {
+            cached(context, "test", "test.json", "key", Duration::ZERO, || {
+                Some(Some(2))
+            })
+        };
+        let has_lock_file = || -> std::io::Result<bool> {
+            Ok(cache::refresh_lock_path(dir.path(), "test", "key").exists())
+        };
+
+        // Nothing is shown until the answer is fetched in the background
+        assert_eq!(cached(&context), None);
+        assert!(has_lock_file()?);
+        fs::remove_file(cache::refresh_lock_path(dir.path(), "test", "key"))?;
+
+        cache::save_entry(&cache_path, "key", &Some(1), Duration::from_secs(60));
+        assert_eq!(cached(&context), Some(1));
+        assert!(!has_lock_file()?);
+
+        // An expired answer is still shown while it is refreshed
+        cache::save_entry(&cache_path, "key", &Some(1), Duration::ZERO);
+        assert_eq!(cached(&context), Some(1));
+        assert!(has_lock_file()?);
+
+        // The refresh fetches the answer and removes the lock file
+        context.env.insert(REFRESH_VAR, "1".to_string());
+        assert_eq!(cached(&context), Some(2));
+        assert!(!has_lock_file()?);
+
+        dir.close()
+    }
+}
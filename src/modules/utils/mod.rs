@@ -6,6 +6,10 @@ pub mod directory_win;
 #[cfg(not(target_os = "windows"))]
 pub mod directory_nix;
 
+pub mod forge;
+
 pub mod path;
 
 pub mod truncate;
+
+pub mod version;
@@ -0,0 +1,35 @@
+use std::cmp::Ordering;
+
+/// Parses the leading numeric segments of a dotted version, e.g. `[8, 1]` for `8.1.x-dev`
+pub fn parse_segments(version: &str) -> Option<Vec<u64>> {
+    let segments: Vec<u64> = version
+        .trim()
+        .split('.')
+        .map_while(|segment| segment.parse().ok())
+        .collect();
+
+    (!segments.is_empty()).then_some(segments)
+}
+
+/// Compares versions segment by segment, with missing segments counting as `0`
+pub fn compare_segments(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    let segment = |v: &[u64], i: usize| v.get(i).copied().unwrap_or_default();
+    (0..len)
+        .map(|i| segment(a, i).cmp(&segment(b, i)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_partial_versions() {
+        assert_eq!(parse_segments("8.1.x-dev"), Some(vec![8, 1]));
+        assert_eq!(parse_segments("dev-main"), None);
+        assert_eq!(compare_segments(&[8, 1], &[8, 1, 0]), Ordering::Equal);
+        assert_eq!(compare_segments(&[8, 1, 2], &[8, 2]), Ordering::Less);
+    }
+}
@@ -1,12 +1,14 @@
 use std::borrow::Cow;
 use std::path::Path;
 
-use super::{Context, Module, ModuleConfig};
+use super::{Context, Module, ModuleConfig, Resource};
 
 use crate::configs::vcs::VcsConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::string_formatter::StringFormatterError;
 
+pub const RESOURCES: &[Resource] = &[Resource::Repo];
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("vcs");
     let config = VcsConfig::try_load(module.config);
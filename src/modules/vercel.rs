@@ -0,0 +1,125 @@
+use serde_json::Value;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::vercel::VercelConfig;
+use crate::formatter::StringFormatter;
+use crate::utils;
+
+/// Creates a module with the Vercel project and team the current directory is linked to with
+/// `vercel link`
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("vercel");
+    let config = VercelConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let root = context
+        .begin_ancestor_scan()
+        .set_folders(&[".vercel"])
+        .scan()?;
+    let project_path = root.join(".vercel").join("project.json");
+    let project: Value = match serde_json::from_str(&utils::read_file(&project_path).ok()?) {
+        Ok(project) => project,
+        Err(error) => {
+            log::warn!("Unable to parse {}: {error}", project_path.display());
+            return None;
+        }
+    };
+
+    // Older versions of the CLI only record the ID of the project
+    let name = project["projectName"]
+        .as_str()
+        .or_else(|| project["projectId"].as_str())?;
+    let org = project["orgId"]
+        .as_str()
+        .map(|org| config.org_aliases.get(org).copied().unwrap_or(org));
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "project" => Some(Ok(name)),
+                "project_id" => project["projectId"].as_str().map(Ok),
+                "org" => org.map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `vercel`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+
+    fn link_project(dir: &Path, project: &str) -> io::Result<()> {
+        fs::create_dir(dir.join(".vercel"))?;
+        fs::write(dir.join(".vercel").join("project.json"), project)
+    }
+
+    #[test]
+    fn folder_without_linked_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = ModuleRenderer::new("vercel").path(dir.path()).collect();
+        assert_eq!(actual, None);
+        dir.close()
+    }
+
+    #[test]
+    fn linked_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        link_project(
+            dir.path(),
+            r#"{"projectId":"prj_123","orgId":"team_456","projectName":"web"}"#,
+        )?;
+        fs::create_dir(dir.path().join("src"))?;
+
+        let actual = ModuleRenderer::new("vercel")
+            .path(dir.path().join("src"))
+            .config(toml::toml! {
+                [vercel.org_aliases]
+                team_456 = "acme"
+            })
+            .collect();
+        let expected = Some(format!("on {} ", Color::White.bold().paint("▲ web (acme)")));
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
+    #[test]
+    fn linked_project_without_name() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        link_project(dir.path(), r#"{"projectId":"prj_123","orgId":"team_456"}"#)?;
+
+        let actual = ModuleRenderer::new("vercel").path(dir.path()).collect();
+        let expected = Some(format!(
+            "on {} ",
+            Color::White.bold().paint("▲ prj_123 (team_456)")
+        ));
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+}
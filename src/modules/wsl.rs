@@ -0,0 +1,214 @@
+use std::path::Path;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::wsl::WslConfig;
+use crate::formatter::StringFormatter;
+use crate::utils::{context_path, read_file};
+
+/// The directory Windows drives are mounted in, unless `/etc/wsl.conf` sets another one
+const DEFAULT_AUTOMOUNT_ROOT: &str = "/mnt/";
+
+/// Returns the major version of WSL, from the release of the kernel it runs. WSL 1 reports
+/// a release such as `4.4.0-19041-Microsoft`, and WSL 2 one such as
+/// `5.15.153.1-microsoft-standard-WSL2`. Custom kernels are only supported by WSL 2.
+fn wsl_version(context: &Context) -> &'static str {
+    let release =
+        read_file(context_path(context, "/proc/sys/kernel/osrelease")).unwrap_or_default();
+    if release.contains("Microsoft") && !release.contains("WSL2") {
+        "1"
+    } else {
+        "2"
+    }
+}
+
+/// Returns the directory Windows drives are mounted in, from the `root` option of the
+/// `[automount]` section of `/etc/wsl.conf`
+fn automount_root(context: &Context) -> String {
+    let Ok(wsl_conf) = read_file(context_path(context, "/etc/wsl.conf")) else {
+        return DEFAULT_AUTOMOUNT_ROOT.into();
+    };
+
+    let mut section = "";
+    for line in wsl_conf.lines().map(str::trim) {
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            section = name.trim();
+        } else if section == "automount"
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "root"
+        {
+            let root = value.trim().trim_matches('"');
+            return format!("{}/", root.trim_end_matches('/'));
+        }
+    }
+    DEFAULT_AUTOMOUNT_ROOT.into()
+}
+
+/// Returns the Windows drive the directory is on, e.g. `C:` for `/mnt/c/Users`
+fn windows_drive(dir: &Path, automount_root: &str) -> Option<String> {
+    let relative = dir.strip_prefix(automount_root).ok()?;
+    let drive = relative.components().next()?.as_os_str().to_str()?;
+    let mut letters = drive.chars();
+    match (letters.next(), letters.next()) {
+        (Some(letter), None) if letter.is_ascii_alphabetic() => {
+            Some(format!("{}:", letter.to_ascii_uppercase()))
+        }
+        _ => None,
+    }
+}
+
+/// Creates a module with the WSL distribution and version
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("wsl");
+    let config = WslConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    // Set by WSL in every distribution it runs
+    let distro = context.get_env("WSL_DISTRO_NAME")?;
+    let drive = windows_drive(&context.current_dir, &automount_root(context));
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                "windows_path" => drive.as_ref().map(|_| config.windows_path_symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" if drive.is_some() => Some(Ok(config.windows_path_style)),
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "distro" => Some(Ok(distro.as_str())),
+                "version" => Some(Ok(wsl_version(context))),
+                "drive" => drive.as_deref().map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `wsl`:\n{error}");
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::path::Path;
+
+    use super::windows_drive;
+    use crate::test::ModuleRenderer;
+    use crate::utils;
+    use nu_ansi_term::Color;
+
+    #[test]
+    fn not_in_wsl() {
+        let actual = ModuleRenderer::new("wsl")
+            .config(toml::toml! {
+                [wsl]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn wsl2_distro() -> std::io::Result<()> {
+        let renderer = ModuleRenderer::new("wsl")
+            .config(toml::toml! {
+                [wsl]
+                disabled = false
+            })
+            .env("WSL_DISTRO_NAME", "Ubuntu")
+            .path("/home/user");
+
+        let osrelease = renderer.root_path().join("proc/sys/kernel/osrelease");
+        fs::create_dir_all(osrelease.parent().unwrap())?;
+        utils::write_file(&osrelease, "5.15.153.1-microsoft-standard-WSL2\n")?;
+
+        let actual = renderer.collect();
+        let expected = Some(format!(
+            "{} ",
+            Color::Blue.bold().paint("🐧 Ubuntu on WSL2")
+        ));
+
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn wsl1_on_windows_drive() -> std::io::Result<()> {
+        let renderer = ModuleRenderer::new("wsl")
+            .config(toml::toml! {
+                [wsl]
+                disabled = false
+                format = "[$distro WSL$version $drive$windows_path]($style)"
+            })
+            .env("WSL_DISTRO_NAME", "Debian")
+            .path("/mnt/c/Users");
+
+        let osrelease = renderer.root_path().join("proc/sys/kernel/osrelease");
+        fs::create_dir_all(osrelease.parent().unwrap())?;
+        utils::write_file(&osrelease, "4.4.0-19041-Microsoft\n")?;
+
+        let actual = renderer.collect();
+        let expected = Some(Color::Yellow.bold().paint("Debian WSL1 C:⚠ ").to_string());
+
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn automount_root_from_wsl_conf() -> std::io::Result<()> {
+        let renderer = ModuleRenderer::new("wsl")
+            .config(toml::toml! {
+                [wsl]
+                disabled = false
+                format = "$drive"
+            })
+            .env("WSL_DISTRO_NAME", "Ubuntu")
+            .path("/win/d/projects");
+
+        let wsl_conf = renderer.root_path().join("etc/wsl.conf");
+        fs::create_dir_all(wsl_conf.parent().unwrap())?;
+        utils::write_file(
+            &wsl_conf,
+            "[boot]\nsystemd=true\n\n[automount]\nroot = /win\n",
+        )?;
+
+        assert_eq!(renderer.collect(), Some("D:".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn windows_drives() {
+        assert_eq!(
+            windows_drive(Path::new("/mnt/c"), "/mnt/"),
+            Some("C:".to_string())
+        );
+        assert_eq!(
+            windows_drive(Path::new("/mnt/d/src/starship"), "/mnt/"),
+            Some("D:".to_string())
+        );
+        assert_eq!(windows_drive(Path::new("/mnt/wsl"), "/mnt/"), None);
+        assert_eq!(windows_drive(Path::new("/mnt"), "/mnt/"), None);
+        assert_eq!(windows_drive(Path::new("/home/user"), "/mnt/"), None);
+    }
+}
@@ -1,26 +1,35 @@
 use clap::{ValueEnum, builder::PossibleValue};
-use nu_ansi_term::AnsiStrings;
+use nu_ansi_term::{AnsiStrings, Color};
 use rayon::prelude::*;
 use regex::Regex;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::{Debug, Write as FmtWrite};
 use std::io::{self, Write};
 use std::path::PathBuf;
 use std::sync::OnceLock;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use terminal_size::terminal_size;
+use toml_edit::DocumentMut;
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthChar;
 
-use crate::configs::PROMPT_ORDER;
-use crate::context::{Context, Properties, Shell, Target};
+use crate::config::{ModuleConfig, color_to_string, style_attributes};
+use crate::configs::{FullConfig, PROMPT_ORDER, Profile, env_var};
+use crate::configure;
+use crate::context::{Context, Properties, Resource, Shell, Target};
 use crate::formatter::{StringFormatter, VariableHolder};
 use crate::module::ALL_MODULES;
 use crate::module::Module;
 use crate::modules;
 use crate::segment::Segment;
 use crate::shadow;
-use crate::utils::wrap_colorseq_for_shell;
+use crate::utils::async_modules;
+use crate::utils::background;
+use crate::utils::prompt_budget::PromptBudget;
+use crate::utils::prompt_width;
+use crate::utils::render;
+use crate::utils::timing::{self, Step};
+use crate::utils::{wrap_colorseq_for_shell, wrap_seq_for_shell};
 
 pub struct Grapheme<'a>(pub &'a str);
 
@@ -74,7 +83,11 @@ pub fn prompt(args: Properties, target: Target) {
     let stdout = io::stdout();
     let mut handle = stdout.lock();
 
-    write!(handle, "{}", get_prompt(&context)).unwrap();
+    if context.properties.phase == Some(2) {
+        write!(handle, "{}", render_async_modules(&context)).unwrap();
+    } else {
+        write!(handle, "{}", get_prompt(&context)).unwrap();
+    }
 }
 
 pub fn prompt_with_claude_code(args: Properties, target: Target) {
@@ -94,65 +107,37 @@ pub fn get_prompt(context: &Context) -> String {
     let config = &context.root_config;
     let mut buf = String::new();
 
-    match std::env::var_os("TERM") {
-        Some(term) if term == "dumb" => {
-            log::error!("Under a 'dumb' terminal (TERM=dumb).");
-            buf.push_str("Starship disabled due to TERM=dumb > ");
-            return buf;
-        }
-        _ => {}
-    }
-
     // A workaround for a fish bug (see #739,#279). Applying it to all shells
     // breaks things (see #808,#824,#834). Should only be printed in fish.
     if Shell::Fish == context.shell && context.target == Target::Main {
         buf.push_str("\x1b[J"); // An ASCII control code to clear screen
     }
 
-    let (formatter, modules) = load_formatter_and_modules(context);
-
-    let formatter = formatter.map_variables_to_segments(|module| {
-        // Make $all display all modules not explicitly referenced
-        if module == "all" {
-            Some(Ok(all_modules_uniq(&modules)
-                .par_iter()
-                .flat_map(|module| {
-                    handle_module(module, context, &modules)
-                        .into_iter()
-                        .flat_map(|module| module.segments)
-                        .collect::<Vec<Segment>>()
-                })
-                .collect::<Vec<_>>()))
-        } else if context.is_module_disabled_in_config(module) {
-            None
-        } else {
-            // Get segments from module
-            Some(Ok(handle_module(module, context, &modules)
-                .into_iter()
-                .flat_map(|module| module.segments)
-                .collect::<Vec<Segment>>()))
-        }
-    });
-
-    // Creates a root module and prints it.
-    let mut root_module = Module::new("Starship Root", "The root module", None);
-    root_module.set_segments(
-        formatter
-            .parse(None, Some(context))
-            .expect("Unexpected error returned in root format variables"),
-    );
-
-    let module_strings = root_module.ansi_strings_for_width(Some(context.width));
+    // Start the budget of the prompt, shared with the right prompt of bash
+    let budget = context.prompt_budget();
+    let output = render_prompt(context, &context.target);
     if config.add_newline && context.target != Target::Continuation {
         // continuation prompts normally do not include newlines, but they can
         writeln!(buf).unwrap();
     }
+    let prompt_marks = config.prompt_marks && context.target == Target::Main;
+    if prompt_marks {
+        buf.push_str(&prompt_mark(context, "A"));
+    }
     // AnsiStrings strips redundant ANSI color sequences, so apply it before modifying the ANSI
     // color sequences for this specific shell
-    let shell_wrapped_output =
-        wrap_colorseq_for_shell(AnsiStrings(&module_strings).to_string(), context.shell);
+    let shell_wrapped_output = wrap_colorseq_for_shell(output.clone(), context.shell);
     write!(buf, "{shell_wrapped_output}").unwrap();
 
+    if should_emulate_right_prompt(context) {
+        let right_output = render_prompt(context, &Target::Right).replace('\n', "");
+        buf = with_bash_right_prompt(&buf, &output, &right_output, context.width);
+    }
+
+    if prompt_marks {
+        buf.push_str(&prompt_mark(context, "B"));
+    }
+
     if context.target == Target::Right {
         // right prompts generally do not allow newlines
         buf = buf.replace('\n', "");
@@ -165,9 +150,401 @@ pub fn get_prompt(context: &Context) -> String {
         buf = buf.replace('\n', " \\n");
     }
 
+    if let Some(budget) = budget {
+        budget.save();
+    }
     buf
 }
 
+/// An OSC 133 (FinalTerm) semantic prompt mark, letting terminals find where prompts start and
+/// end, wrapped so the shell does not count it towards the width of the prompt
+fn prompt_mark(context: &Context, mark: &str) -> String {
+    wrap_seq_for_shell(
+        format!("\x1b]133;{mark}\x07"),
+        context.shell,
+        '\x1b',
+        '\x07',
+    )
+}
+
+/// Renders the format string of the target, without wrapping its ANSI color sequences
+fn render_prompt(context: &Context, target: &Target) -> String {
+    let mut root_module = Module::new("Starship Root", "The root module", None);
+    root_module.set_segments(render_segments(context, target));
+
+    let module_strings = root_module.ansi_strings_for_width(Some(context.width));
+    AnsiStrings(&module_strings).to_string()
+}
+
+/// Renders the segments of the format string of the target, each recording the module it was
+/// rendered by
+fn render_segments(context: &Context, target: &Target) -> Vec<Segment> {
+    let (formatter, modules) = load_formatter_and_modules(context, target);
+    let budget = context.prompt_budget();
+    let async_segments = async_modules::rendered_segments(context);
+
+    // Get segments from module, or its placeholder once the prompt is out of time
+    let module_segments = |module: &str| {
+        let mut segments = if async_modules::is_deferred(context, module) {
+            async_segments.get(module).cloned().unwrap_or_default()
+        } else if let Some(rendered) = budget.and_then(|budget| budget.rendered(module)) {
+            rendered
+        } else if let Some(kept) = context.kept_module_output(module) {
+            kept
+        } else if let Some(placeholder) =
+            budget.and_then(|budget| budget.placeholder(module, context))
+        {
+            placeholder
+        } else {
+            let segments = handle_module(module, context, &modules)
+                .into_iter()
+                .flat_map(|module| module.segments)
+                .collect::<Vec<Segment>>();
+            context.record_module_output(module, &segments);
+            match budget {
+                // The commands of a module finishing late were cut short, so its output may be
+                // incomplete
+                Some(budget) => budget.placeholder(module, context).unwrap_or_else(|| {
+                    budget.record(module, &segments);
+                    segments
+                }),
+                None => segments,
+            }
+        };
+        for segment in &mut segments {
+            segment.set_module(module);
+        }
+        segments
+    };
+
+    // Make $all display all modules not explicitly referenced
+    let variables: BTreeSet<String> = formatter.get_variables();
+    let all_modules = if variables.contains("all") {
+        all_modules_uniq(&modules)
+    } else {
+        Vec::new()
+    };
+    let scheduled = variables
+        .into_iter()
+        .filter(|module| module != "all" && !context.is_module_disabled_in_config(module))
+        .chain(all_modules.iter().cloned())
+        .collect();
+    // Once the prompt is out of time, the modules are replaced by placeholders instead of waiting
+    // for the resources they depend on
+    let prepare = |resource| {
+        if !budget.is_some_and(PromptBudget::is_spent) {
+            context.prepare(resource);
+        }
+    };
+    let computed = schedule_modules(scheduled, &prepare, &module_segments);
+
+    let formatter = formatter.map_variables_to_segments(|module| {
+        if module == "all" {
+            Some(Ok(all_modules
+                .iter()
+                .filter_map(|module| computed.get(module))
+                .flatten()
+                .cloned()
+                .collect()))
+        } else {
+            computed.get(module).cloned().map(Ok)
+        }
+    });
+    let segments = formatter
+        .parse(None, Some(context))
+        .expect("Unexpected error returned in root format variables");
+
+    // Put the symbol showing modules were skipped first
+    let mut root_segments = budget
+        .map(|budget| budget.symbol(context))
+        .unwrap_or_default();
+    root_segments.extend(segments);
+    root_segments = prompt_width::fit(context, root_segments);
+    let separators = &context.root_config.segment_separators;
+    let (separator, right) = match target {
+        Target::Right => (&separators.right, true),
+        _ => (&separators.left, false),
+    };
+    if !separator.is_empty() {
+        root_segments = insert_segment_separators(root_segments, separator, right);
+    }
+    if context.is_plain() {
+        root_segments.iter_mut().for_each(Segment::clear_style);
+    }
+    root_segments
+}
+
+/// Inserts `separator` between adjacent modules with different backgrounds. In the left prompt
+/// it is drawn in the background of the module before it and closes each line, while in the
+/// right prompt it is drawn in the background of the module after it and opens each line.
+fn insert_segment_separators(segments: Vec<Segment>, separator: &str, right: bool) -> Vec<Segment> {
+    let separator_between = |prev: Option<Color>, next: Option<Color>| {
+        let (fg, bg) = if right { (next?, prev) } else { (prev?, next) };
+        let style = bg.map_or_else(|| fg.normal(), |bg| fg.on(bg));
+        (prev != next).then(|| Segment::from_text(Some(style.into()), separator))
+    };
+
+    let mut result = Vec::with_capacity(segments.len());
+    // The module and background of the last segment with text on the current line
+    let mut prev: Option<(Option<String>, Option<Color>)> = None;
+    for segment in segments {
+        if matches!(segment, Segment::LineTerm) {
+            if let Some((_, prev_bg)) = prev.take() {
+                result.extend(separator_between(prev_bg, None).unwrap_or_default());
+            }
+            result.push(segment);
+            continue;
+        }
+        if !segment.value().is_empty() {
+            let module = segment.module().map(str::to_string);
+            let bg = segment.style().and_then(|style| style.background);
+            match &prev {
+                Some((prev_module, prev_bg)) if *prev_module != module => {
+                    result.extend(separator_between(*prev_bg, bg).unwrap_or_default());
+                }
+                None => result.extend(separator_between(None, bg).unwrap_or_default()),
+                Some(_) => {}
+            }
+            prev = Some((module, bg));
+        }
+        result.push(segment);
+    }
+    if let Some((_, prev_bg)) = prev {
+        result.extend(separator_between(prev_bg, None).unwrap_or_default());
+    }
+    result
+}
+
+/// Prints the prompt styled for a tmux status line
+pub fn prompt_tmux(args: Properties, target: Target) {
+    print!("{}", get_tmux_prompt(Context::new(args, target)));
+}
+
+/// Renders the prompt with tmux `#[...]` style directives in place of ANSI escape sequences
+pub fn get_tmux_prompt(mut context: Context) -> String {
+    // The output of modules must not be escaped for the shell
+    context.shell = Shell::Unknown;
+
+    let mut root_module = Module::new("Starship Root", "The root module", None);
+    root_module.set_segments(render_segments(&context, &context.target));
+    root_module
+        .ansi_strings_for_width(Some(context.width))
+        .iter()
+        .filter(|string| !string.as_str().is_empty())
+        .map(|string| {
+            // Status lines are a single line, and `#` starts a tmux format
+            let text = string.as_str().replace('\n', "").replace('#', "##");
+            format!("{}{text}", tmux_style(string.style_ref()))
+        })
+        .collect()
+}
+
+/// A tmux style directive for a style, resetting what it does not set
+fn tmux_style(style: &nu_ansi_term::Style) -> String {
+    let mut items = vec![
+        format!("fg={}", tmux_color(style.foreground)),
+        format!("bg={}", tmux_color(style.background)),
+        "none".to_string(),
+    ];
+    items.extend(style_attributes(style).into_iter().map(|attribute| {
+        match attribute {
+            "dimmed" => "dim",
+            "italic" => "italics",
+            "underline" => "underscore",
+            "inverted" => "reverse",
+            attribute => attribute,
+        }
+        .to_string()
+    }));
+    format!("#[{}]", items.join(","))
+}
+
+/// The name of a color in tmux styles
+fn tmux_color(color: Option<nu_ansi_term::Color>) -> String {
+    let Some(name) = color.and_then(color_to_string) else {
+        return "default".to_string();
+    };
+    if name.parse::<u8>().is_ok() {
+        return format!("colour{name}");
+    }
+    name.replace("purple", "magenta")
+        .replace("bright-", "bright")
+}
+
+/// The output format of `starship print`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RenderFormat {
+    /// A `<pre>` block of styled `<span>`s
+    Html,
+    /// An image of the prompt on a terminal background
+    Svg,
+}
+
+/// Prints the prompt as HTML or SVG, for screenshots in documentation
+pub fn print_rendered(args: Properties, target: Target, format: RenderFormat) {
+    println!(
+        "{}",
+        get_rendered_prompt(Context::new(args, target), format)
+    );
+}
+
+/// Renders the prompt as HTML or SVG instead of with ANSI escape sequences
+pub fn get_rendered_prompt(mut context: Context, format: RenderFormat) -> String {
+    // The output of modules must not be escaped for the shell
+    context.shell = Shell::Unknown;
+
+    let mut root_module = Module::new("Starship Root", "The root module", None);
+    root_module.set_segments(render_segments(&context, &context.target));
+    let strings = root_module.ansi_strings_for_width(Some(context.width));
+    match format {
+        RenderFormat::Html => render::html(&strings),
+        RenderFormat::Svg => render::svg(&strings),
+    }
+}
+
+/// Prints the segments of the prompt as JSON, for tools drawing them natively
+pub fn prompt_json(args: Properties, target: Target) {
+    let context = Context::new(args, target);
+    let segments: Vec<_> = render_segments(&context, &context.target)
+        .iter()
+        .map(|segment| segment_json(segment, segment.module()))
+        .collect();
+    println!("{}", serde_json::json!({ "segments": segments }));
+}
+
+/// Prints the segments of a module as JSON, for tools drawing them natively
+pub fn module_json(module_name: &str, args: Properties) {
+    let context = Context::new(args, Target::Main);
+    let segments: Vec<_> = modules::handle(module_name, &context)
+        .map(|module| module.segments)
+        .unwrap_or_default()
+        .iter()
+        .map(|segment| segment_json(segment, Some(module_name)))
+        .collect();
+    println!(
+        "{}",
+        serde_json::json!({ "name": module_name, "segments": segments })
+    );
+}
+
+/// A segment as JSON, with its style broken down into colors and attributes
+fn segment_json(segment: &Segment, module: Option<&str>) -> serde_json::Value {
+    let kind = match segment {
+        Segment::Text(_) => "text",
+        Segment::Fill(_) => "fill",
+        Segment::LineTerm => "line_term",
+    };
+    let style = segment.style().unwrap_or_default();
+    serde_json::json!({
+        "type": kind,
+        "module": module,
+        "text": segment.value(),
+        "style": {
+            "fg": style.foreground.and_then(color_to_string),
+            "bg": style.background.and_then(color_to_string),
+            "attrs": style_attributes(&style),
+        },
+    })
+}
+
+/// Renders the `async_modules` used by the left and right prompts, for phase 2 of a prompt
+/// rendered in two phases
+fn render_async_modules(context: &Context) -> String {
+    let (_, modules) = load_formatter_and_modules(context, &Target::Main);
+    let mut used = modules.clone();
+    if modules.contains("all") {
+        used.extend(all_modules_uniq(&modules));
+    }
+
+    let rendered = context
+        .root_config
+        .async_modules
+        .par_iter()
+        .filter(|module| used.contains(*module))
+        .map(|module| {
+            let segments = handle_module(module, context, &modules)
+                .into_iter()
+                .flat_map(|module| module.segments)
+                .collect();
+            (module.clone(), segments)
+        })
+        .collect();
+    async_modules::serialize_segments(&rendered)
+}
+
+/// Computes the segments of the modules in parallel. The modules are grouped by the resources
+/// shared between modules they declare, and each group is only started once its resources are
+/// ready, instead of holding up a thread per module while waiting for them. The resources of a
+/// group are computed alongside the other groups, so no module waits for a resource it doesn't
+/// use.
+fn schedule_modules(
+    modules: Vec<String>,
+    prepare: &(dyn Fn(Resource) + Sync),
+    compute: &(dyn Fn(&str) -> Vec<Segment> + Sync),
+) -> HashMap<String, Vec<Segment>> {
+    let mut groups: Vec<(&[Resource], Vec<String>)> = Vec::new();
+    for module in modules {
+        let resources = modules::resources(&module);
+        match groups.iter_mut().find(|(group, _)| *group == resources) {
+            Some((_, group)) => group.push(module),
+            None => groups.push((resources, vec![module])),
+        }
+    }
+
+    groups
+        .into_par_iter()
+        .flat_map(|(resources, modules)| {
+            resources.par_iter().for_each(|resource| prepare(*resource));
+            modules.into_par_iter().map(|module| {
+                let segments = compute(&module);
+                (module, segments)
+            })
+        })
+        .collect()
+}
+
+/// Bash has no right prompt of its own, so it is drawn as part of the left prompt.
+///
+/// The left prompt of ble.sh users is rendered with `STARSHIP_BASH_RIGHT_PROMPT=0`,
+/// since ble.sh supports right prompts natively.
+fn should_emulate_right_prompt(context: &Context) -> bool {
+    context.shell == Shell::Bash
+        && context.target == Target::Main
+        && context.root_config.bash_right_prompt
+        && !context.root_config.right_format.is_empty()
+        && context.width > 0
+        && context.get_env("STARSHIP_BASH_RIGHT_PROMPT").as_deref() != Some("0")
+}
+
+/// Inserts the right prompt at the beginning of the last line of a bash prompt.
+///
+/// The right prompt is drawn by saving the cursor position, moving to the column where
+/// the right prompt starts and restoring the cursor afterwards. All of it is wrapped in
+/// `\[` and `\]`, so bash doesn't count it towards the width of the prompt.
+/// The right prompt is left out if it doesn't fit next to the left prompt.
+fn with_bash_right_prompt(prompt: &str, left: &str, right: &str, width: usize) -> String {
+    let left_width = left
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .width_graphemes();
+    let right_width = right.width_graphemes();
+    if right_width == 0 || left_width + right_width >= width {
+        return prompt.to_string();
+    }
+
+    let column = width - right_width + 1;
+    let right_prompt = format!("\\[\x1b7\x1b[{column}G{right}\x1b8\\]");
+    match prompt.rfind('\n') {
+        Some(index) => format!(
+            "{}{right_prompt}{}",
+            &prompt[..=index],
+            &prompt[index + 1..]
+        ),
+        None => format!("{right_prompt}{prompt}"),
+    }
+}
+
 pub fn module(module_name: &str, args: Properties) {
     let context = Context::new(args, Target::Main);
     let module = get_module(module_name, &context).unwrap_or_default();
@@ -175,12 +552,41 @@ pub fn module(module_name: &str, args: Properties) {
 }
 
 pub fn get_module(module_name: &str, context: &Context) -> Option<String> {
-    modules::handle(module_name, context).map(|m| m.to_string())
+    let mut module = modules::handle(module_name, context)?;
+    if context.is_plain() {
+        module.segments.iter_mut().for_each(Segment::clear_style);
+    }
+    Some(module.to_string())
+}
+
+/// The output format of `starship timings`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TimingsFormat {
+    /// A table of the modules, slowest first
+    #[default]
+    Text,
+    /// The timings of the modules and their steps as JSON
+    Json,
+    /// A Chrome trace event file, which can be opened in Perfetto or `chrome://tracing`
+    Trace,
 }
 
-pub fn timings(args: Properties) {
+pub fn timings(args: Properties, format: TimingsFormat) {
+    if format != TimingsFormat::Text {
+        timing::enable();
+    }
     let context = Context::new(args, Target::Main);
+    let start = Instant::now();
+    let modules = compute_modules(&context);
 
+    match format {
+        TimingsFormat::Text => print_timings(&modules),
+        TimingsFormat::Json => println!("{:#}", timings_json(&modules)),
+        TimingsFormat::Trace => println!("{}", timings_trace(&modules, start)),
+    }
+}
+
+fn print_timings(modules: &[Module]) {
     struct ModuleTiming {
         name: String,
         name_len: usize,
@@ -189,7 +595,7 @@ pub fn timings(args: Properties) {
         duration_len: usize,
     }
 
-    let mut modules = compute_modules(&context)
+    let mut modules = modules
         .iter()
         .filter(|module| !module.is_empty() || module.duration.as_millis() > 0)
         .map(|module| ModuleTiming {
@@ -224,14 +630,106 @@ pub fn timings(args: Properties) {
     }
 }
 
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+/// The timings of the modules, with the time spent on each kind of step, in microseconds
+fn timings_json(modules: &[Module]) -> serde_json::Value {
+    let modules: Vec<_> = modules
+        .iter()
+        .filter_map(|module| {
+            let profile = module.profile.as_ref()?;
+            let total = |step| {
+                profile
+                    .steps
+                    .iter()
+                    .filter(|timing| timing.step == step)
+                    .map(|timing| timing.duration)
+                    .sum()
+            };
+            let steps: Vec<_> = profile
+                .steps
+                .iter()
+                .map(|timing| {
+                    serde_json::json!({
+                        "step": timing.step.name(),
+                        "start_us": micros(timing.start.duration_since(profile.start)),
+                        "duration_us": micros(timing.duration),
+                    })
+                })
+                .collect();
+
+            Some(serde_json::json!({
+                "name": module.get_name(),
+                "output": module.get_segments().join(""),
+                "duration_us": micros(profile.duration),
+                "detection_us": micros(total(Step::Detection)),
+                "command_us": micros(total(Step::Command)),
+                "formatting_us": micros(total(Step::Formatting)),
+                "steps": steps,
+            }))
+        })
+        .collect();
+
+    serde_json::json!({ "modules": modules })
+}
+
+/// The timings of the modules and their steps as Chrome trace events, relative to `start`
+fn timings_trace(modules: &[Module], start: Instant) -> serde_json::Value {
+    let event = |name: &str, category: &str, from: Instant, duration: Duration, thread, module| {
+        serde_json::json!({
+            "name": name,
+            "cat": category,
+            "ph": "X",
+            "ts": micros(from.saturating_duration_since(start)),
+            "dur": micros(duration),
+            "pid": 1,
+            "tid": thread,
+            "args": { "module": module },
+        })
+    };
+
+    let events: Vec<_> = modules
+        .iter()
+        .filter_map(|module| Some((module.get_name(), module.profile.as_ref()?)))
+        .flat_map(|(name, profile)| {
+            std::iter::once(event(
+                name,
+                "module",
+                profile.start,
+                profile.duration,
+                profile.thread,
+                name,
+            ))
+            .chain(profile.steps.iter().map(|timing| {
+                event(
+                    timing.step.name(),
+                    "step",
+                    timing.start,
+                    timing.duration,
+                    profile.thread,
+                    name,
+                )
+            }))
+        })
+        .collect();
+
+    serde_json::json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+}
+
 pub fn explain(args: Properties) {
     let context = Context::new(args, Target::Main);
+    let user_config = context.config.config.clone().unwrap_or_default();
+    let config = toml::Value::try_from(FullConfig::load(&user_config))
+        .unwrap_or_else(|_| toml::Value::Table(toml::Table::new()));
 
     struct ModuleInfo {
         value: String,
         value_len: usize,
         desc: String,
         duration: String,
+        sources: Vec<String>,
     }
 
     static DONT_PRINT: &[&str] = &["line_break"];
@@ -249,6 +747,7 @@ pub fn explain(args: Properties) {
                     + format_duration(&module.duration).width_graphemes(),
                 desc: module.get_description().clone(),
                 duration: format_duration(&module.duration),
+                sources: explain_sources(&module, &config, &user_config),
             }
         })
         .collect::<Vec<ModuleInfo>>();
@@ -310,19 +809,77 @@ pub fn explain(args: Properties) {
             println!();
         } else {
             println!(
-                " {}{}  -  {}",
+                " \"{}\" ({}){}  -  {}",
                 info.value,
+                info.duration,
                 " ".repeat(max_module_width - info.value_len),
                 info.desc,
             );
         }
+        for source in &info.sources {
+            println!("     {source}");
+        }
+    }
+}
+
+/// Describes where the output of a module comes from: the options shaping it, with whether
+/// they were set in the config, and the values of the variables in its format
+fn explain_sources(
+    module: &Module,
+    config: &toml::Value,
+    user_config: &toml::Table,
+) -> Vec<String> {
+    let name = module.get_name();
+    let mut sources = vec![format!("module: {name}")];
+
+    let path: Vec<&str> = name.split('.').collect();
+    let module_config = path.iter().try_fold(config, |value, key| value.get(key));
+    let user_config = path
+        .iter()
+        .try_fold(user_config, |table, key| table.get(*key)?.as_table());
+
+    let mut variables: Vec<(&str, String)> = Vec::new();
+    for segment in &module.segments {
+        match (segment.variable(), variables.last_mut()) {
+            (Some(variable), Some((last, value))) if *last == variable => {
+                value.push_str(segment.value());
+            }
+            (Some(variable), _) => variables.push((variable, segment.value().to_string())),
+            (None, _) => {}
+        }
+    }
+    variables.dedup();
+
+    let mut keys = vec!["format", "style"];
+    keys.extend(variables.iter().map(|(variable, _)| *variable));
+    let mut seen = BTreeSet::new();
+    for key in keys {
+        let Some(value) = module_config.and_then(|config| config.get(key)) else {
+            continue;
+        };
+        if !seen.insert(key) {
+            continue;
+        }
+        let origin = if user_config.is_some_and(|config| config.contains_key(key)) {
+            "from config"
+        } else {
+            "default"
+        };
+        sources.push(format!("{key} = {value} ({origin})"));
     }
+
+    sources.extend(
+        variables
+            .iter()
+            .map(|(variable, value)| format!("${variable} = \"{}\"", value.replace('\n', "\\n"))),
+    );
+    sources
 }
 
 fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
     let mut prompt_order: Vec<Module<'a>> = Vec::new();
 
-    let (_formatter, modules) = load_formatter_and_modules(context);
+    let (_formatter, modules) = load_formatter_and_modules(context, &context.target);
 
     for module in &modules {
         // Manually add all modules if `$all` is encountered
@@ -352,16 +909,33 @@ fn handle_module<'a>(
         if !context.is_module_disabled_in_config(module) {
             modules.extend(modules::handle(module, context));
         }
+    } else if let Some(group) = module
+        .strip_prefix("custom.")
+        .filter(|name| is_custom_group(context, name))
+    {
+        // Write out the custom modules of the group, except for those that are explicitly set
+        modules.extend(
+            custom_modules(context, Some(group), module_list)
+                .par_iter()
+                .flat_map(|module| modules::handle(module, context))
+                .collect::<Vec<Module>>(),
+        );
     } else if module.starts_with("custom.") || module.starts_with("env_var.") {
         // custom.<name> and env_var.<name> are special cases and handle disabled modules themselves
         modules.extend(modules::handle(module, context));
-    } else if matches!(module, "custom" | "env_var") {
+    } else if module == "custom" {
+        // Write out all custom modules, except for those that are explicitly set
+        modules.extend(
+            custom_modules(context, None, module_list)
+                .par_iter()
+                .flat_map(|module| modules::handle(module, context))
+                .collect::<Vec<Module>>(),
+        );
+    } else if module == "env_var" {
         // env var is a spacial case and may contain a top-level module definition
-        if module == "env_var" {
-            modules.extend(modules::handle(module, context));
-        }
+        modules.extend(modules::handle(module, context));
 
-        // Write out all custom modules, except for those that are explicitly set
+        // Write out all env_var modules, except for those that are explicitly set
         modules.extend(
             context
                 .config
@@ -373,7 +947,7 @@ fn handle_module<'a>(
                 .par_iter()
                 .filter_map(|(child, config)| {
                     // Some env var keys may be part of a top-level module definition
-                    if module == "env_var" && !config.is_table() {
+                    if !config.is_table() || env_var::TABLE_OPTIONS.contains(&child.as_str()) {
                         None
                     } else if should_add_implicit_module(module, child, config, module_list) {
                         Some(modules::handle(&format!("{module}.{child}"), context))
@@ -393,6 +967,62 @@ fn handle_module<'a>(
     modules
 }
 
+/// Whether `${custom.<name>}` refers to the custom modules with `group = "<name>"`, rather
+/// than to a custom module
+fn is_custom_group(context: &Context, name: &str) -> bool {
+    context
+        .config
+        .get_custom_modules()
+        .is_some_and(|custom_modules| {
+            !custom_modules.contains_key(name)
+                && custom_modules
+                    .values()
+                    .any(|config| custom_module_group(config) == Some(name))
+        })
+}
+
+/// Returns the custom modules `$custom` expands to, or `${custom.<group>}` with a group, in
+/// the order of `custom_order` and then of the config. Modules set explicitly in the format,
+/// by their name or by their group, are left out.
+fn custom_modules(
+    context: &Context,
+    group: Option<&str>,
+    module_list: &BTreeSet<String>,
+) -> Vec<String> {
+    let Some(custom_modules) = context.config.get_custom_modules() else {
+        return Vec::new();
+    };
+
+    let order = &context.root_config.custom_order;
+    let mut names: Vec<&String> = custom_modules.keys().collect();
+    names.sort_by_key(|name| {
+        order
+            .iter()
+            .position(|ordered| ordered == *name)
+            .unwrap_or(order.len())
+    });
+
+    names
+        .into_iter()
+        .filter(|name| {
+            let config = &custom_modules[name.as_str()];
+            let module_group = custom_module_group(config);
+            let in_group = match group {
+                Some(group) => module_group == Some(group),
+                None => module_group.is_none_or(|module_group| {
+                    !module_list.contains(&format!("custom.{module_group}"))
+                }),
+            };
+            in_group && should_add_implicit_module("custom", name, config, module_list)
+        })
+        .map(|name| format!("custom.{name}"))
+        .collect()
+}
+
+fn custom_module_group(config: &toml::Value) -> Option<&str> {
+    config.get("group").and_then(toml::Value::as_str)
+}
+
 fn should_add_implicit_module(
     parent_module: &str,
     child_module: &str,
@@ -437,12 +1067,27 @@ fn all_modules_uniq(module_list: &BTreeSet<String>) -> Vec<String> {
     prompt_order
 }
 
+/// The profile selected with `STARSHIP_PROFILE`, if any
+fn active_profile<'a>(context: &'a Context) -> Option<&'a Profile> {
+    let name = context
+        .get_env("STARSHIP_PROFILE")
+        .filter(|name| !name.is_empty())?;
+    let profile = context.root_config.profile(&name);
+    if profile.is_none() {
+        log::warn!("Profile {name:?} set by STARSHIP_PROFILE not found");
+    }
+    profile
+}
+
 /// Load the correct formatter for the context (ie left prompt or right prompt)
 /// and the list of all modules used in a format string
-fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>, BTreeSet<String>) {
+fn load_formatter_and_modules<'a>(
+    context: &'a Context,
+    target: &Target,
+) -> (StringFormatter<'a>, BTreeSet<String>) {
     let config = &context.root_config;
 
-    if context.target == Target::Continuation {
+    if *target == Target::Continuation {
         let cf = &config.continuation_prompt;
         let formatter = StringFormatter::new(cf);
         return match formatter {
@@ -457,15 +1102,27 @@ fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>,
         };
     }
 
-    let (left_format_str, right_format_str): (&str, &str) = match context.target {
-        Target::Main | Target::Right => (&config.format, &config.right_format),
-        Target::Profile(ref name) => {
-            if let Some(lf) = config
-                .user_profiles
-                .get(name)
-                .or_else(|| config.internal_profiles.get(name))
-            {
-                (lf, "")
+    // The profile selected with `STARSHIP_PROFILE` replaces the prompt, and the `narrow` profile
+    // replaces it in turn in narrow terminals
+    let mut layouts = vec![active_profile(context)];
+    if context.is_narrow() {
+        layouts.push(config.user_profiles.get("narrow"));
+    }
+    let (format, right_format) = layouts.into_iter().flatten().fold(
+        (config.format.as_str(), config.right_format.as_str()),
+        |(format, right_format), profile| {
+            (
+                profile.format().unwrap_or(format),
+                profile.right_format().unwrap_or(right_format),
+            )
+        },
+    );
+
+    let (left_format_str, right_format_str): (&str, &str) = match target {
+        Target::Main | Target::Right => (format, right_format),
+        Target::Profile(name) => {
+            if let Some(profile) = config.profile(name) {
+                (profile.format().unwrap_or(&config.format), "")
             } else {
                 log::error!("Profile {name:?} not found");
                 return (StringFormatter::raw(">"), BTreeSet::new());
@@ -478,7 +1135,7 @@ fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>,
     let rf = StringFormatter::new(right_format_str);
 
     if let Err(ref e) = lf {
-        let name = if let Target::Profile(ref profile_name) = context.target {
+        let name = if let Target::Profile(profile_name) = target {
             format!("profile.{profile_name}")
         } else {
             "format".to_string()
@@ -496,7 +1153,7 @@ fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>,
         .flat_map(VariableHolder::get_variables)
         .collect();
 
-    let main_formatter = match context.target {
+    let main_formatter = match target {
         Target::Main | Target::Profile(_) => lf,
         Target::Right => rf,
         Target::Continuation => unreachable!("Continuation prompt should have been handled above"),
@@ -508,9 +1165,18 @@ fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>,
     }
 }
 
+/// Prints whether the terminal background is light or dark, which the init scripts keep in
+/// `STARSHIP_BACKGROUND` for the rest of the session
+pub fn background() {
+    if let Some(background) = background::detect_for_session(&Context::default()) {
+        println!("{background}");
+    }
+}
+
 #[cfg(feature = "config-schema")]
 pub fn print_schema() {
-    let schema = schemars::schema_for!(crate::configs::FullConfig);
+    let mut schema = schemars::schema_for!(crate::configs::FullConfig);
+    crate::utils::redact::extend_schema(&mut schema);
     println!("{}", serde_json::to_string_pretty(&schema).unwrap());
 }
 
@@ -527,13 +1193,25 @@ impl ValueEnum for Preset {
     }
 }
 
-pub fn preset_command(name: Option<Preset>, output: Option<PathBuf>, list: bool) {
+pub fn preset_command(names: &[Preset], output: Option<PathBuf>, list: bool, apply: bool) {
     if list {
         println!("{}", preset_list());
         return;
     }
-    let variant = name.expect("name argument must be specified");
-    let content = shadow::get_preset_content(variant.0);
+    let preset = match compose_presets(names) {
+        Ok(preset) => preset,
+        Err(err) => {
+            eprintln!("Error composing presets: {err}");
+            std::process::exit(1);
+        }
+    };
+    if apply {
+        configure::apply_preset(&Context::default(), &preset);
+        return;
+    }
+
+    let content = preset.to_string();
+    let content = content.as_bytes();
     if let Some(output) = output {
         if let Err(err) = std::fs::write(output, content) {
             eprintln!("Error writing preset to file: {err}");
@@ -545,6 +1223,25 @@ pub fn preset_command(name: Option<Preset>, output: Option<PathBuf>, list: bool)
     }
 }
 
+/// Merges the presets in order, so that the options of later presets take precedence
+fn compose_presets(names: &[Preset]) -> Result<DocumentMut, String> {
+    let parse = |preset: &Preset| {
+        std::str::from_utf8(shadow::get_preset_content(preset.0))
+            .map_err(|err| err.to_string())?
+            .parse::<DocumentMut>()
+            .map_err(|err| format!("Unable to parse preset {}: {err}", preset.0))
+    };
+
+    let (first, rest) = names
+        .split_first()
+        .ok_or("name argument must be specified")?;
+    let mut composed = parse(first)?;
+    for preset in rest {
+        configure::merge_edits(composed.as_table_mut(), parse(preset)?.as_table());
+    }
+    Ok(composed)
+}
+
 fn preset_list() -> String {
     Preset::value_variants()
         .iter()
@@ -592,48 +1289,205 @@ mod test {
     }
 
     #[test]
-    fn prompt_with_all() -> io::Result<()> {
+    fn degraded_prompt() {
         let mut context = default_context().set_config(toml::toml! {
                 add_newline = false
-                right_format= "$directory$line_break"
-                format="$all"
+                format = "$character"
+                command_timeout = 0
+                prompt_timeout_strategy = "degrade"
+                prompt_timeout_symbol = "!"
                 [character]
-                format=">"
+                format = ">"
         });
-        context.env.insert("HOME", NULL_DEVICE.to_string());
-        let dir = tempfile::tempdir().unwrap();
-        context.current_dir = dir.path().to_path_buf();
+        context.target = Target::Main;
 
-        let expected = String::from(">");
-        let actual = get_prompt(&context);
-        assert_eq!(expected, actual);
-        dir.close()
+        // Modules past the deadline are skipped, with nothing cached to show in their place
+        assert_eq!(get_prompt(&context), "!");
     }
 
     #[test]
-    fn rprompt_with_all() -> io::Result<()> {
+    #[cfg(not(windows))]
+    fn late_module_is_cut_at_the_deadline() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
         let mut context = default_context().set_config(toml::toml! {
-            format= "$directory$line_break"
-            right_format="$all"
-            [character]
-            format=">"
+                add_newline = false
+                format = "${custom.slow}$character"
+                command_timeout = 200
+                prompt_timeout_strategy = "degrade"
+                prompt_timeout_symbol = "!"
+                [custom.slow]
+                command = "sleep 5; echo slow"
+                when = true
+                shell = ["sh"]
+                [character]
+                format = ">"
         });
-        context.env.insert("HOME", NULL_DEVICE.to_string());
-        let dir = tempfile::tempdir().unwrap();
+        context
+            .env
+            .insert("STARSHIP_CACHE", dir.path().to_string_lossy().to_string());
         context.current_dir = dir.path().to_path_buf();
+        context.target = Target::Main;
 
-        context.target = Target::Right;
-
-        let expected = String::from(">");
-        let actual = get_prompt(&context);
-        assert_eq!(expected, actual);
+        // The command is stopped at the deadline, and the module is shown as skipped
+        let start = Instant::now();
+        assert_eq!(get_prompt(&context), "!>");
+        assert!(start.elapsed() < Duration::from_secs(2));
         dir.close()
     }
 
     #[test]
-    fn custom_prompt() {
+    fn segment_separators() {
         let mut context = default_context().set_config(toml::toml! {
-            add_newline = false
+                format = "${env_var.a}${env_var.b}${env_var.c}"
+                segment_separators = { left = ">", right = "<" }
+                [env_var.a]
+                format = "[$env_value](bg:red)"
+                [env_var.b]
+                format = "[$env_value](bg:blue)"
+                [env_var.c]
+                format = "[$env_value](bg:blue)"
+        });
+        context.env.insert("a", "a".to_string());
+        context.env.insert("b", "b".to_string());
+        context.env.insert("c", "c".to_string());
+
+        let segments = render_segments(&context, &Target::Main);
+        let rendered: Vec<_> = segments
+            .iter()
+            .map(|segment| (segment.value(), segment.style()))
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                ("a", Some(nu_ansi_term::Style::new().on(Color::Red))),
+                (">", Some(Color::Red.on(Color::Blue))),
+                ("b", Some(nu_ansi_term::Style::new().on(Color::Blue))),
+                ("c", Some(nu_ansi_term::Style::new().on(Color::Blue))),
+                (">", Some(Color::Blue.normal())),
+            ]
+        );
+    }
+
+    #[test]
+    fn right_segment_separators() {
+        let mut context = default_context().set_config(toml::toml! {
+                right_format = "$env_var"
+                segment_separators = { left = ">", right = "<" }
+                [env_var.a]
+                format = "[$env_value](bg:red)"
+                [env_var.b]
+                format = "$env_value"
+        });
+        context.env.insert("a", "a".to_string());
+        context.env.insert("b", "b".to_string());
+
+        let segments = render_segments(&context, &Target::Right);
+        let values: Vec<_> = segments.iter().map(Segment::value).collect();
+        assert_eq!(values, vec!["<", "a", "b"]);
+        assert_eq!(segments[0].style(), Some(Color::Red.normal()));
+    }
+
+    #[test]
+    fn schedule_modules_computes_each_module_once() {
+        let context = default_context();
+        let computed = std::sync::Mutex::new(Vec::new());
+        let modules = vec![
+            "character".to_string(),
+            "git_branch".to_string(),
+            "rust".to_string(),
+        ];
+
+        let segments =
+            schedule_modules(modules, &|resource| context.prepare(resource), &|module| {
+                computed.lock().unwrap().push(module.to_string());
+                Segment::from_text(None, module)
+            });
+
+        let mut computed = computed.into_inner().unwrap();
+        computed.sort();
+        assert_eq!(computed, vec!["character", "git_branch", "rust"]);
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments["rust"][0].value(), "rust");
+    }
+
+    #[test]
+    fn schedule_modules_does_not_wait_for_undeclared_resources() {
+        let (repo_sender, repo_receiver) = std::sync::mpsc::channel();
+        let repo_receiver = std::sync::Mutex::new(repo_receiver);
+        let modules = vec!["git_branch".to_string(), "rust".to_string()];
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        // The repo is only found once `rust` is computed, which would time out if it waited
+        let segments = pool.install(|| {
+            schedule_modules(
+                modules,
+                &|resource| {
+                    if resource == Resource::Repo {
+                        repo_receiver
+                            .lock()
+                            .unwrap()
+                            .recv_timeout(std::time::Duration::from_secs(5))
+                            .expect("rust waited for the repo");
+                    }
+                },
+                &|module| {
+                    if module == "rust" {
+                        repo_sender.send(()).unwrap();
+                    }
+                    Segment::from_text(None, module)
+                },
+            )
+        });
+
+        assert_eq!(segments.len(), 2);
+    }
+
+    #[test]
+    fn prompt_with_all() -> io::Result<()> {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline = false
+                right_format= "$directory$line_break"
+                format="$all"
+                [character]
+                format=">"
+        });
+        context.env.insert("HOME", NULL_DEVICE.to_string());
+        let dir = tempfile::tempdir().unwrap();
+        context.current_dir = dir.path().to_path_buf();
+
+        let expected = String::from(">");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn rprompt_with_all() -> io::Result<()> {
+        let mut context = default_context().set_config(toml::toml! {
+            format= "$directory$line_break"
+            right_format="$all"
+            [character]
+            format=">"
+        });
+        context.env.insert("HOME", NULL_DEVICE.to_string());
+        let dir = tempfile::tempdir().unwrap();
+        context.current_dir = dir.path().to_path_buf();
+
+        context.target = Target::Right;
+
+        let expected = String::from(">");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn custom_prompt() {
+        let mut context = default_context().set_config(toml::toml! {
+            add_newline = false
             [profiles]
             test="0_0$character"
             [character]
@@ -646,6 +1500,103 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn profile_from_env() {
+        let mut context = default_context().set_config(toml::toml! {
+            add_newline = false
+            format = "full$character"
+            right_format = "right"
+            [profiles]
+            short = "short$character"
+            [profiles.minimal]
+            format = "$character"
+            right_format = ""
+            [character]
+            format = ">>"
+        });
+        context.target = Target::Main;
+        context
+            .env
+            .insert("STARSHIP_PROFILE", "minimal".to_string());
+        assert_eq!(get_prompt(&context), ">>");
+
+        context.target = Target::Right;
+        assert_eq!(get_prompt(&context), "");
+
+        // Profiles only made of a format keep the right prompt
+        context.env.insert("STARSHIP_PROFILE", "short".to_string());
+        assert_eq!(get_prompt(&context), "right");
+
+        context
+            .env
+            .insert("STARSHIP_PROFILE", "missing".to_string());
+        context.target = Target::Main;
+        assert_eq!(get_prompt(&context), "full>>");
+    }
+
+    #[test]
+    fn segments_as_json() {
+        let context = default_context().set_config(toml::toml! {
+            format = "> $character"
+            [character]
+            format = "[x](bold fg:#1e1e2e)"
+        });
+
+        let segments: Vec<_> = render_segments(&context, &Target::Main)
+            .iter()
+            .map(|segment| segment_json(segment, segment.module()))
+            .collect();
+        assert_eq!(
+            serde_json::Value::from(segments),
+            serde_json::json!([
+                {
+                    "type": "text",
+                    "module": null,
+                    "text": "> ",
+                    "style": { "fg": null, "bg": null, "attrs": [] },
+                },
+                {
+                    "type": "text",
+                    "module": "character",
+                    "text": "x",
+                    "style": { "fg": "#1e1e2e", "bg": null, "attrs": ["bold"] },
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn tmux_prompt() {
+        let mut context = default_context().set_config(toml::toml! {
+            format = "#1 $character"
+            [character]
+            format = "[x](bold fg:purple bg:214)[y](underline #1e1e2e)"
+        });
+        context.shell = Shell::Zsh;
+
+        assert_eq!(
+            get_tmux_prompt(context),
+            "#[fg=default,bg=default,none]##1 \
+             #[fg=magenta,bg=colour214,none,bold]x\
+             #[fg=#1e1e2e,bg=default,none,underscore]y"
+        );
+    }
+
+    #[test]
+    fn rendered_prompt() {
+        let mut context = default_context().set_config(toml::toml! {
+            format = "<$character>"
+            [character]
+            format = "[&](bold red)"
+        });
+        context.shell = Shell::Bash;
+
+        let html = get_rendered_prompt(context, RenderFormat::Html);
+        assert!(html.contains(r#"<span style="color: #c5c8c6">&lt;</span>"#));
+        assert!(html.contains(r#"<span style="color: #cd0000; font-weight: bold">&amp;</span>"#));
+        assert!(!html.contains('\x1b'));
+    }
+
     #[test]
     fn custom_prompt_fallback() {
         let mut context = default_context().set_config(toml::toml! {
@@ -674,6 +1625,357 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn format_lines() {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=false
+                format="ignored"
+                format_lines=["first", "second", "> "]
+                right_format_lines=["", "right", "last"]
+        });
+        assert_eq!(context.root_config.format, "first\nsecond${fill}right\n> ");
+
+        context.target = Target::Main;
+        let actual = get_prompt(&context);
+        assert!(actual.starts_with("first\nsecond"));
+        assert!(actual.ends_with("right\n> "));
+
+        context.target = Target::Right;
+        let actual = get_prompt(&context);
+        assert_eq!(String::from("last"), actual);
+    }
+
+    #[test]
+    fn bash_right_prompt() {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=false
+                format="left\n> "
+                right_format="right"
+        });
+        context.target = Target::Main;
+        context.shell = Shell::Bash;
+        context.width = 20;
+
+        let expected = String::from("left\n\\[\x1b7\x1b[16Gright\x1b8\\]> ");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn prompt_marks() {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=true
+                format="> "
+                prompt_marks=true
+        });
+        context.target = Target::Main;
+        context.shell = Shell::Bash;
+        assert_eq!(
+            get_prompt(&context),
+            "\n\\[\x1b]133;A\x07\\]> \\[\x1b]133;B\x07\\]"
+        );
+
+        context.shell = Shell::Zsh;
+        assert_eq!(
+            get_prompt(&context),
+            "\n%{\x1b]133;A\x07%}> %{\x1b]133;B\x07%}"
+        );
+
+        context.target = Target::Right;
+        assert_eq!(get_prompt(&context), "");
+    }
+
+    #[test]
+    fn plain_prompt() {
+        let mut context = default_context().set_config(toml::toml! {
+            add_newline = false
+            format = "[a](bold red)$fill$character"
+            [fill]
+            symbol = "-"
+            style = "blue"
+            [character]
+            format = "[b](green)"
+        });
+        context.width = 6;
+        assert_ne!(get_prompt(&context), "a----b");
+
+        context.env.insert("TERM", "dumb".to_string());
+        assert_eq!(get_prompt(&context), "a----b");
+
+        context.env.insert("TERM", "xterm".to_string());
+        context.env.insert("NO_COLOR", String::new());
+        assert_ne!(get_prompt(&context), "a----b");
+
+        context.env.insert("NO_COLOR", "1".to_string());
+        assert_eq!(get_prompt(&context), "a----b");
+        assert_eq!(get_module("character", &context), Some("b".to_string()));
+    }
+
+    #[test]
+    fn force_plain() {
+        let context = default_context().set_config(toml::toml! {
+            add_newline = false
+            format = "[a](bold red) $character"
+            force_plain = true
+            [character]
+            format = "[b](green)"
+        });
+        assert_eq!(get_prompt(&context), "a b");
+    }
+
+    #[test]
+    fn bash_right_prompt_shares_the_budget() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=false
+                format="$character"
+                right_format="$status"
+                force_plain=true
+                command_timeout=0
+                prompt_timeout_strategy="degrade"
+                prompt_timeout_symbol="!"
+        });
+        context
+            .env
+            .insert("STARSHIP_CACHE", dir.path().to_string_lossy().to_string());
+        context.target = Target::Main;
+        context.shell = Shell::Bash;
+        context.width = 20;
+
+        // Both modules are skipped, but the symbol is only shown once
+        assert_eq!(get_prompt(&context), "!");
+        dir.close()
+    }
+
+    #[test]
+    fn bash_right_prompt_too_wide() {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=false
+                format="a long left prompt "
+                right_format="right"
+        });
+        context.target = Target::Main;
+        context.shell = Shell::Bash;
+        context.width = 20;
+
+        let expected = String::from("a long left prompt ");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn bash_right_prompt_disabled() {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=false
+                format="> "
+                right_format="right"
+                bash_right_prompt=false
+        });
+        context.target = Target::Main;
+        context.shell = Shell::Bash;
+        context.width = 20;
+
+        let expected = String::from("> ");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn fill_limits() {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=false
+                format="a$fill b"
+                [fill]
+                style=""
+                symbol="-="
+                max_width=4
+        });
+        context.target = Target::Main;
+        context.width = 20;
+
+        let expected = String::from("a-=-= b");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn fill_collapses_below_min_width() {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=false
+                format="a long left prompt$fill b"
+                [fill]
+                style=""
+                min_width=3
+        });
+        context.target = Target::Main;
+        context.width = 22;
+
+        let expected = String::from("a long left prompt b");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn narrow_profile() {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=false
+                format="wide"
+                width_threshold=40
+                [profiles]
+                narrow="narrow"
+        });
+        context.target = Target::Main;
+
+        context.width = 80;
+        assert_eq!(String::from("wide"), get_prompt(&context));
+
+        context.width = 30;
+        assert_eq!(String::from("narrow"), get_prompt(&context));
+    }
+
+    #[test]
+    fn module_format_narrow() {
+        let mut context = default_context().set_config(toml::toml! {
+                add_newline=false
+                format="$character"
+                width_threshold=40
+                [character]
+                format="wide"
+                format_narrow="narrow"
+        });
+        context.target = Target::Main;
+
+        context.width = 80;
+        assert_eq!(String::from("wide"), get_prompt(&context));
+
+        context.width = 30;
+        assert_eq!(String::from("narrow"), get_prompt(&context));
+    }
+
+    #[test]
+    fn explain_sources_of_module() {
+        let context = default_context().set_config(toml::toml! {
+            [character]
+            format = "$symbol "
+            success_symbol = "[>](bold green)"
+        });
+        let user_config = context.config.config.clone().unwrap();
+        let config = toml::Value::try_from(FullConfig::load(&user_config)).unwrap();
+        let module = modules::handle("character", &context).unwrap();
+
+        assert_eq!(
+            explain_sources(&module, &config, &user_config),
+            vec![
+                "module: character",
+                "format = \"$symbol \" (from config)",
+                "$symbol = \">\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn explain_sources_of_default_options() {
+        let context = default_context().set_config(toml::toml! {
+            [git_branch]
+            symbol = "B "
+        });
+        let user_config = context.config.config.clone().unwrap();
+        let config = toml::Value::try_from(FullConfig::load(&user_config)).unwrap();
+        let mut module = context.new_module("git_branch");
+        module.set_segments(
+            StringFormatter::new("on [$symbol](red)")
+                .unwrap()
+                .map_meta(|variable, _| (variable == "symbol").then_some("B "))
+                .parse(None, Some(&context))
+                .unwrap(),
+        );
+
+        let sources = explain_sources(&module, &config, &user_config);
+        assert_eq!(sources[0], "module: git_branch");
+        assert!(sources[1].starts_with("format = \"on [$symbol$branch"));
+        assert!(sources[1].ends_with(" (default)"));
+        assert_eq!(sources[2], "style = \"bold vcs\" (default)");
+        assert_eq!(sources[3], "symbol = \"B \" (from config)");
+        assert_eq!(sources[4], "$symbol = \"B \"");
+    }
+
+    fn profiled_module<'a>(context: &'a Context, start: Instant) -> Module<'a> {
+        let mut module = context.new_module("nodejs");
+        module.set_segments(Segment::from_text(None, "v20"));
+        module.profile = Some(timing::ModuleProfile {
+            start: start + Duration::from_millis(1),
+            duration: Duration::from_millis(5),
+            thread: 2,
+            steps: vec![
+                timing::StepTiming {
+                    step: Step::Detection,
+                    start: start + Duration::from_millis(1),
+                    duration: Duration::from_millis(1),
+                },
+                timing::StepTiming {
+                    step: Step::Command,
+                    start: start + Duration::from_millis(2),
+                    duration: Duration::from_millis(3),
+                },
+            ],
+        });
+        module
+    }
+
+    #[test]
+    fn timings_as_json() {
+        let context = default_context();
+        let module = profiled_module(&context, Instant::now());
+
+        assert_eq!(
+            timings_json(&[module]),
+            serde_json::json!({
+                "modules": [{
+                    "name": "nodejs",
+                    "output": "v20",
+                    "duration_us": 5000,
+                    "detection_us": 1000,
+                    "command_us": 3000,
+                    "formatting_us": 0,
+                    "steps": [
+                        { "step": "detection", "start_us": 0, "duration_us": 1000 },
+                        { "step": "command", "start_us": 1000, "duration_us": 3000 },
+                    ],
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn timings_as_trace() {
+        let context = default_context();
+        let start = Instant::now();
+        let modules = [
+            profiled_module(&context, start),
+            context.new_module("unprofiled"),
+        ];
+
+        let trace = timings_trace(&modules, start);
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0],
+            serde_json::json!({
+                "name": "nodejs",
+                "cat": "module",
+                "ph": "X",
+                "ts": 1000,
+                "dur": 5000,
+                "pid": 1,
+                "tid": 2,
+                "args": { "module": "nodejs" },
+            })
+        );
+        assert_eq!(events[2]["name"], "command");
+        assert_eq!(events[2]["ts"], 2000);
+        assert_eq!(events[2]["args"]["module"], "nodejs");
+    }
+
     #[test]
     fn preset_list_returns_one_or_more_items() {
         assert!(preset_list().lines().count() > 0);
@@ -681,9 +1983,9 @@ mod test {
 
     #[test]
     fn preset_command_does_not_panic_on_correct_inputs() {
-        preset_command(None, None, true);
+        preset_command(&[], None, true, false);
         for v in Preset::value_variants() {
-            preset_command(Some(v.clone()), None, false);
+            preset_command(std::slice::from_ref(v), None, false, false);
         }
     }
 
@@ -691,7 +1993,12 @@ mod test {
     fn preset_command_output_to_file() -> std::io::Result<()> {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("preset.toml");
-        preset_command(Some(Preset("nerd-font-symbols")), Some(path.clone()), false);
+        preset_command(
+            &[Preset("nerd-font-symbols")],
+            Some(path.clone()),
+            false,
+            false,
+        );
 
         let actual = utils::read_file(&path)?;
         let expected = include_str!("../docs/public/presets/toml/nerd-font-symbols.toml");
@@ -700,6 +2007,25 @@ mod test {
         dir.close()
     }
 
+    #[test]
+    fn compose_presets_merges_in_order() {
+        let composed =
+            compose_presets(&[Preset("nerd-font-symbols"), Preset("pure-preset")]).unwrap();
+        let config: toml::Table = toml::from_str(&composed.to_string()).unwrap();
+
+        let pure: toml::Table =
+            toml::from_slice(shadow::get_preset_content("pure-preset")).unwrap();
+        let nerd_font: toml::Table =
+            toml::from_slice(shadow::get_preset_content("nerd-font-symbols")).unwrap();
+        assert_eq!(config["format"], pure["format"]);
+        assert_eq!(config["git_branch"]["style"], pure["git_branch"]["style"]);
+        assert_eq!(
+            config["git_branch"]["symbol"],
+            nerd_font["git_branch"]["symbol"]
+        );
+        assert_eq!(config["aws"], nerd_font["aws"]);
+    }
+
     #[test]
     #[cfg(feature = "config-schema")]
     fn print_schema_does_not_panic() {
@@ -747,6 +2073,27 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn env_expands_without_table_options() {
+        let mut context = default_context().set_config(toml::toml! {
+                format="$env_var"
+                [env_var]
+                format="$env_value"
+                variable = "a"
+                [env_var.value_aliases]
+                a = "z"
+                [env_var.b]
+                format="$env_value"
+        });
+        context.env.insert("a", "a".to_string());
+        context.env.insert("b", "b".to_string());
+        context.env.insert("value_aliases", "v".to_string());
+
+        let expected = String::from("\nzb");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn custom_mixed() -> std::io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -814,6 +2161,61 @@ mod test {
         dir.close()
     }
 
+    #[test]
+    fn custom_ordered() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context().set_config(toml::toml! {
+                format="$custom"
+                custom_order = ["c", "missing", "a"]
+                [custom.a]
+                when=true
+                format="a"
+                [custom.b]
+                when=true
+                format="b"
+                [custom.c]
+                when=true
+                format="c"
+        });
+        context.current_dir = dir.path().to_path_buf();
+
+        let expected = String::from("\ncab");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn custom_groups() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context().set_config(toml::toml! {
+                format="$custom|${custom.cloud}|${custom.d}"
+                custom_order = ["c"]
+                [custom.a]
+                when=true
+                format="a"
+                [custom.b]
+                when=true
+                format="b"
+                group="cloud"
+                [custom.c]
+                when=true
+                format="c"
+                group="cloud"
+                [custom.d]
+                when=true
+                format="d"
+                group="d"
+        });
+        context.current_dir = dir.path().to_path_buf();
+
+        // A module named like a group is shown instead of the group
+        let expected = String::from("\na|cb|d");
+        let actual = get_prompt(&context);
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
     #[test]
     fn custom_missing() -> std::io::Result<()> {
         let dir = tempfile::tempdir()?;
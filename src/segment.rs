@@ -1,8 +1,9 @@
 use crate::{
-    config::Style,
+    config::{Style, parse_style_string},
     print::{Grapheme, UnicodeWidthGraphemes},
 };
 use nu_ansi_term::{AnsiString, Style as AnsiStyle};
+use serde::{Deserialize, Serialize};
 use unicode_segmentation::UnicodeSegmentation;
 
 /// Type that holds text with an associated style
@@ -13,6 +14,12 @@ pub struct TextSegment {
 
     /// The string value of the current segment.
     value: String,
+
+    /// The name of the format variable the segment was rendered from, if any.
+    variable: Option<String>,
+
+    /// The name of the module the segment was rendered by, once it is part of a prompt.
+    module: Option<String>,
 }
 
 impl TextSegment {
@@ -33,21 +40,34 @@ pub struct FillSegment {
 
     /// The string value of the current segment.
     value: String,
+
+    /// The width below which the segment collapses to an empty string.
+    min_width: usize,
+
+    /// The width the segment is limited to, or 0 for no limit.
+    max_width: usize,
 }
 
 impl FillSegment {
     // Returns the AnsiString of the segment value, not including its prefix and suffix
     pub fn ansi_string(&self, width: Option<usize>, prev: Option<&AnsiStyle>) -> AnsiString<'_> {
         let s = match width {
-            Some(w) => self
-                .value
-                .graphemes(true)
-                .cycle()
-                .scan(0usize, |len, g| {
-                    *len += Grapheme(g).width();
-                    if *len <= w { Some(g) } else { None }
-                })
-                .collect::<String>(),
+            Some(w) if w < self.min_width => String::new(),
+            Some(w) => {
+                let w = if self.max_width > 0 {
+                    w.min(self.max_width)
+                } else {
+                    w
+                };
+                self.value
+                    .graphemes(true)
+                    .cycle()
+                    .scan(0usize, |len, g| {
+                        *len += Grapheme(g).width();
+                        if *len <= w { Some(g) } else { None }
+                    })
+                    .collect::<String>()
+            }
             None => String::from(&self.value),
         };
         match self.style {
@@ -79,11 +99,29 @@ mod fill_seg_tests {
             let f = FillSegment {
                 value: String::from(*text),
                 style: Some(style.into()),
+                min_width: 0,
+                max_width: 0,
             };
             let actual = f.ansi_string(Some(width), None);
             assert_eq!(style.paint(*expected), actual);
         }
     }
+
+    #[test]
+    fn ansi_string_width_limits() {
+        let style = Color::Blue.bold();
+        let f = FillSegment {
+            value: String::from("─ "),
+            style: Some(style.into()),
+            min_width: 4,
+            max_width: 6,
+        };
+
+        assert_eq!(style.paint(""), f.ansi_string(Some(3), None));
+        assert_eq!(style.paint("─ ─ "), f.ansi_string(Some(4), None));
+        assert_eq!(style.paint("─ ─ ─ "), f.ansi_string(Some(10), None));
+        assert_eq!(style.paint("─ "), f.ansi_string(None, None));
+    }
 }
 
 /// A segment is a styled text chunk ready for printing.
@@ -108,6 +146,8 @@ impl Segment {
             segs.push(Self::Text(TextSegment {
                 value: String::from(s),
                 style,
+                variable: None,
+                module: None,
             }));
         });
         segs
@@ -115,12 +155,28 @@ impl Segment {
 
     /// Creates a new fill segment
     pub fn fill<T>(style: Option<Style>, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::fill_with_limits(style, value, 0, 0)
+    }
+
+    /// Creates a new fill segment that collapses below `min_width` and never grows past
+    /// `max_width` (unless it is 0)
+    pub fn fill_with_limits<T>(
+        style: Option<Style>,
+        value: T,
+        min_width: usize,
+        max_width: usize,
+    ) -> Self
     where
         T: Into<String>,
     {
         Self::Fill(FillSegment {
             style,
             value: value.into(),
+            min_width,
+            max_width,
         })
     }
 
@@ -148,6 +204,69 @@ impl Segment {
         }
     }
 
+    /// Gives the segment the background of `style`, unless it has a background of its own
+    pub fn set_background_if_empty(&mut self, style: Style) {
+        let segment_style = match self {
+            Self::Fill(fs) => &mut fs.style,
+            Self::Text(ts) => &mut ts.style,
+            Self::LineTerm => return,
+        };
+        *segment_style = Some(
+            segment_style
+                .unwrap_or_default()
+                .with_background_if_empty(&style),
+        );
+    }
+
+    /// Removes the style of the segment, keeping its text
+    pub fn clear_style(&mut self) {
+        match self {
+            Self::Fill(fs) => fs.style = None,
+            Self::Text(ts) => ts.style = None,
+            Self::LineTerm => {}
+        }
+    }
+
+    /// Returns the name of the format variable the segment was rendered from, if any
+    pub fn variable(&self) -> Option<&str> {
+        match self {
+            Self::Text(ts) => ts.variable.as_deref(),
+            Self::Fill(_) | Self::LineTerm => None,
+        }
+    }
+
+    /// Records the format variable the segment was rendered from, unless it was already
+    /// rendered from a variable nested inside of it
+    pub fn set_variable_if_empty(&mut self, name: &str) {
+        if let Self::Text(ts) = self
+            && ts.variable.is_none()
+        {
+            ts.variable = Some(name.to_string());
+        }
+    }
+
+    /// Returns the name of the module the segment was rendered by, if it was recorded
+    pub fn module(&self) -> Option<&str> {
+        match self {
+            Self::Text(ts) => ts.module.as_deref(),
+            Self::Fill(_) | Self::LineTerm => None,
+        }
+    }
+
+    /// Records the module the segment was rendered by
+    pub fn set_module(&mut self, name: &str) {
+        if let Self::Text(ts) = self {
+            ts.module = Some(name.to_string());
+        }
+    }
+
+    /// Replaces the text of a text segment with the result of `f`
+    pub fn map_text(&mut self, f: impl FnOnce(&str) -> String) {
+        if let Self::Text(ts) = self {
+            ts.value = f(&ts.value);
+        }
+    }
+
     pub fn value(&self) -> &str {
         match self {
             Self::Fill(fs) => &fs.value,
@@ -174,5 +293,63 @@ impl Segment {
     }
 }
 
+/// A segment in a form that can be passed on to another starship process, with its style as a
+/// style string
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SerializedSegment {
+    Text {
+        value: String,
+        style: Option<String>,
+    },
+    Fill {
+        value: String,
+        style: Option<String>,
+        min_width: usize,
+        max_width: usize,
+    },
+    LineTerm,
+}
+
+impl From<&Segment> for SerializedSegment {
+    fn from(segment: &Segment) -> Self {
+        match segment {
+            Segment::Text(ts) => Self::Text {
+                value: ts.value.clone(),
+                style: ts.style.map(|style| style.to_style_string()),
+            },
+            Segment::Fill(fs) => Self::Fill {
+                value: fs.value.clone(),
+                style: fs.style.map(|style| style.to_style_string()),
+                min_width: fs.min_width,
+                max_width: fs.max_width,
+            },
+            Segment::LineTerm => Self::LineTerm,
+        }
+    }
+}
+
+impl From<SerializedSegment> for Segment {
+    fn from(segment: SerializedSegment) -> Self {
+        let parse =
+            |style: Option<String>| style.and_then(|style| parse_style_string(&style, None));
+        match segment {
+            SerializedSegment::Text { value, style } => Self::Text(TextSegment {
+                value,
+                style: parse(style),
+                variable: None,
+                module: None,
+            }),
+            SerializedSegment::Fill {
+                value,
+                style,
+                min_width,
+                max_width,
+            } => Self::fill_with_limits(parse(style), value, min_width, max_width),
+            SerializedSegment::LineTerm => Self::LineTerm,
+        }
+    }
+}
+
 const LINE_TERMINATOR: char = '\n';
 const LINE_TERMINATOR_STRING: &str = "\n";
@@ -124,11 +124,24 @@ impl<'a> ModuleRenderer<'a> {
         self
     }
 
+    pub fn job_names(mut self, names: &[&str]) -> Self {
+        self.context.properties.job_names = names.iter().map(ToString::to_string).collect();
+        self
+    }
+
     pub fn cmd_duration(mut self, duration: u64) -> Self {
         self.context.properties.cmd_duration = Some(duration.to_string());
         self
     }
 
+    pub fn last_command<T>(mut self, command: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.context.properties.last_command = Some(command.into());
+        self
+    }
+
     pub fn keymap<T>(mut self, keymap: T) -> Self
     where
         T: Into<String>,
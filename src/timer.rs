@@ -0,0 +1,183 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+
+/// The file in the state directory holding the running timer
+pub const STATE_FILE: &str = "timer.json";
+
+/// A timer started by `starship timer start`
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Timer {
+    pub started_at: SystemTime,
+    pub duration: Duration,
+    pub label: Option<String>,
+}
+
+impl Timer {
+    /// The time left until the timer expires, or `None` once it has
+    pub fn remaining(&self) -> Option<Duration> {
+        let elapsed = self.started_at.elapsed().unwrap_or_default();
+        self.duration
+            .checked_sub(elapsed)
+            .filter(|left| !left.is_zero())
+    }
+
+    /// How long ago the timer expired, or `None` while it is running
+    pub fn expired_for(&self) -> Option<Duration> {
+        let elapsed = self.started_at.elapsed().unwrap_or_default();
+        elapsed.checked_sub(self.duration)
+    }
+}
+
+/// Returns the directory starship keeps its state in, `STARSHIP_STATE`,
+/// `$XDG_STATE_HOME/starship` or `~/.local/state/starship`
+pub fn state_dir(context: &Context) -> Option<PathBuf> {
+    context
+        .get_env_os("STARSHIP_STATE")
+        .map(PathBuf::from)
+        .or_else(|| {
+            context
+                .get_env_os("XDG_STATE_HOME")
+                .map(|dir| PathBuf::from(dir).join("starship"))
+        })
+        .or_else(|| {
+            context
+                .get_home()
+                .map(|home| home.join(".local").join("state").join("starship"))
+        })
+}
+
+/// Returns the timer started last, whether it is running or expired
+pub fn load(context: &Context) -> Option<Timer> {
+    let path = state_dir(context)?.join(STATE_FILE);
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|error| log::debug!("Unable to read the timer state: {error}"))
+        .ok()
+}
+
+/// Parses the length of a timer, such as `25m`, `1h30m` or `90s`. A number without a unit is a
+/// number of minutes.
+fn parse_duration(duration: &str) -> Option<Duration> {
+    let duration = match duration.trim().parse::<u64>() {
+        Ok(minutes) => Duration::from_secs(minutes.checked_mul(60)?),
+        Err(_) => duration
+            .trim()
+            .parse::<jiff::SignedDuration>()
+            .ok()?
+            .try_into()
+            .ok()?,
+    };
+    (!duration.is_zero()).then_some(duration)
+}
+
+/// Starts a timer of the given length, replacing the running one
+pub fn start(context: &Context, duration: &str, label: Option<String>) {
+    let Some(length) = parse_duration(duration) else {
+        eprintln!("Invalid timer length `{duration}`, expected a length such as `25m` or `1h30m`");
+        process::exit(1);
+    };
+    let Some(dir) = state_dir(context) else {
+        eprintln!("Unable to find the state directory");
+        process::exit(1);
+    };
+
+    let timer = Timer {
+        started_at: SystemTime::now(),
+        duration: length,
+        label,
+    };
+    let saved = fs::create_dir_all(&dir)
+        .map_err(|error| error.to_string())
+        .and_then(|()| serde_json::to_string(&timer).map_err(|error| error.to_string()))
+        .and_then(|content| {
+            fs::write(dir.join(STATE_FILE), content).map_err(|error| error.to_string())
+        });
+    match saved {
+        Ok(()) => println!(
+            "Started a timer for {duration:#}",
+            duration = jiff::SignedDuration::try_from(length).unwrap_or_default()
+        ),
+        Err(error) => {
+            eprintln!("Unable to save the timer to {}: {error}", dir.display());
+            process::exit(1);
+        }
+    }
+}
+
+/// Stops the running timer, or removes the expired one
+pub fn stop(context: &Context) {
+    let Some(path) = state_dir(context).map(|dir| dir.join(STATE_FILE)) else {
+        eprintln!("Unable to find the state directory");
+        process::exit(1);
+    };
+    match fs::remove_file(&path) {
+        Ok(()) => println!("Stopped the timer"),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            println!("No timer is running");
+        }
+        Err(error) => {
+            eprintln!("Unable to remove {}: {error}", path.display());
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("25m"), Some(Duration::from_secs(25 * 60)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(90 * 60)));
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("5"), Some(Duration::from_secs(5 * 60)));
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("-5m"), None);
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    #[test]
+    fn remaining_and_expired() {
+        let timer = Timer {
+            started_at: SystemTime::now() - Duration::from_secs(60),
+            duration: Duration::from_secs(300),
+            label: None,
+        };
+        assert!(timer.remaining().unwrap() <= Duration::from_secs(240));
+        assert_eq!(timer.expired_for(), None);
+
+        let timer = Timer {
+            duration: Duration::from_secs(30),
+            ..timer
+        };
+        assert_eq!(timer.remaining(), None);
+        assert!(timer.expired_for().unwrap() >= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn starts_and_stops() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context();
+        context.env.insert(
+            "STARSHIP_STATE",
+            dir.path().join("state").to_string_lossy().to_string(),
+        );
+
+        start(&context, "25m", Some("focus".to_string()));
+        let timer = load(&context).unwrap();
+        assert_eq!(timer.duration, Duration::from_secs(25 * 60));
+        assert_eq!(timer.label.as_deref(), Some("focus"));
+
+        stop(&context);
+        assert_eq!(load(&context), None);
+        dir.close()
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use crate::context::Context;
+use crate::segment::{Segment, SerializedSegment};
+
+/// The environment variable the shell passes the modules rendered in phase 2 in, as JSON
+const SEGMENTS_VAR: &str = "STARSHIP_ASYNC_SEGMENTS";
+
+/// Whether the module is left out of the prompt until it is rendered in phase 2
+pub fn is_deferred(context: &Context, module: &str) -> bool {
+    context.properties.phase == Some(1)
+        && context
+            .root_config
+            .async_modules
+            .iter()
+            .any(|name| name == module)
+}
+
+/// Returns the segments of the modules rendered in phase 2, once the shell has received them
+pub fn rendered_segments(context: &Context) -> HashMap<String, Vec<Segment>> {
+    let Some(json) = context
+        .get_env(SEGMENTS_VAR)
+        .filter(|json| !json.is_empty())
+    else {
+        return HashMap::new();
+    };
+
+    match serde_json::from_str::<HashMap<String, Vec<SerializedSegment>>>(&json) {
+        Ok(modules) => modules
+            .into_iter()
+            .map(|(name, segments)| (name, segments.into_iter().map(Segment::from).collect()))
+            .collect(),
+        Err(error) => {
+            log::warn!("Invalid {SEGMENTS_VAR}: {error}");
+            HashMap::new()
+        }
+    }
+}
+
+/// Serializes the segments of the modules rendered in phase 2, to be passed back to
+/// starship by the shell
+pub fn serialize_segments(modules: &HashMap<String, Vec<Segment>>) -> String {
+    let modules: HashMap<&str, Vec<SerializedSegment>> = modules
+        .iter()
+        .map(|(name, segments)| {
+            let segments = segments.iter().map(SerializedSegment::from).collect();
+            (name.as_str(), segments)
+        })
+        .collect();
+    serde_json::to_string(&modules).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_style_string;
+    use crate::test::default_context;
+
+    #[test]
+    fn deferred_in_phase_one() {
+        let mut context = default_context().set_config(toml::toml! {
+            async_modules = ["git_status"]
+        });
+        assert!(!is_deferred(&context, "git_status"));
+
+        context.properties.phase = Some(1);
+        assert!(is_deferred(&context, "git_status"));
+        assert!(!is_deferred(&context, "directory"));
+    }
+
+    #[test]
+    fn segments_round_trip() {
+        let style = parse_style_string("bold fg:red", None);
+        let mut modules = HashMap::new();
+        modules.insert(
+            "git_status".to_string(),
+            Segment::from_text(style, "[!]\nnext"),
+        );
+
+        let mut context = default_context();
+        context
+            .env
+            .insert(SEGMENTS_VAR, serialize_segments(&modules));
+        let rendered = rendered_segments(&context);
+
+        let segments = &rendered["git_status"];
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].value(), "[!]");
+        assert_eq!(
+            segments[0].style(),
+            style.map(|style| style.to_ansi_style(None))
+        );
+        assert_eq!(segments[2].value(), "next");
+    }
+}
@@ -0,0 +1,288 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::context::Context;
+
+/// How long to wait for the terminal to answer the background color query. Terminals answer the
+/// query that follows it right away, so this is only reached if the terminal doesn't answer at all.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The brightness of the terminal background
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Background {
+    Light,
+    Dark,
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Light => write!(f, "light"),
+            Self::Dark => write!(f, "dark"),
+        }
+    }
+}
+
+/// Detects the terminal background from, in order:
+/// - the `STARSHIP_BACKGROUND` environment variable (`light` or `dark`), which the init scripts
+///   set once per session from `starship background`
+/// - the `COLORFGBG` environment variable set by some terminals
+pub fn detect(context: &Context) -> Option<Background> {
+    match context
+        .get_env("STARSHIP_BACKGROUND")
+        .as_deref()
+        .map(str::trim)
+    {
+        Some("light") => return Some(Background::Light),
+        Some("dark") => return Some(Background::Dark),
+        // Left empty by the init scripts if the background is unknown
+        Some("") | None => {}
+        Some(value) => log::warn!("Invalid value of STARSHIP_BACKGROUND: {value:?}"),
+    }
+
+    context
+        .get_env("COLORFGBG")
+        .and_then(|value| parse_colorfgbg(&value))
+}
+
+/// Detects the terminal background for `starship background`, which the init scripts run once
+/// per session. Unless `detect` finds it, this asks the terminal for its background color, which
+/// is only done if `palette_light` or `palette_dark` is set.
+pub fn detect_for_session(context: &Context) -> Option<Background> {
+    let config = &context.root_config;
+    detect(context).or_else(|| {
+        if config.palette_light.is_none() && config.palette_dark.is_none() {
+            return None;
+        }
+        query_background(QUERY_TIMEOUT)
+            .as_deref()
+            .and_then(parse_osc11_response)
+    })
+}
+
+/// Parses `COLORFGBG`, which is made up of the ANSI colors of the foreground and background,
+/// e.g. `15;0` for white on black
+fn parse_colorfgbg(value: &str) -> Option<Background> {
+    let background = value.rsplit(';').next()?.parse::<u8>().ok()?;
+    match background {
+        0..=6 | 8 => Some(Background::Dark),
+        7 | 9..=15 => Some(Background::Light),
+        _ => None,
+    }
+}
+
+/// Parses the answer to an OSC 11 query, e.g. `\x1b]11;rgb:ffff/ffff/ffff\x1b\\`
+fn parse_osc11_response(response: &str) -> Option<Background> {
+    let (_, color) = response.split_once("rgb:")?;
+    let color = color.trim_end_matches(['\x07', '\x1b', '\\']);
+
+    let mut channels = color.split('/').map(|channel| {
+        let max = 16_f64.powi(i32::try_from(channel.len()).ok()?) - 1.0;
+        let value = u16::from_str_radix(channel, 16).ok()?;
+        Some(f64::from(value) / max)
+    });
+    let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    Some(if luminance > 0.5 {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+/// The input read while waiting for the answers to the queries, split into the answers and the
+/// rest, such as keys typed meanwhile
+#[cfg(any(unix, test))]
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Replies {
+    /// The answer to the OSC 11 background color query
+    background: Option<String>,
+    /// Whether the answer to the DA1 query, which terminals answer after the OSC 11 query, was read
+    done: bool,
+    /// The input that isn't part of an answer
+    rest: Vec<u8>,
+}
+
+/// Splits the answers to the OSC 11 and DA1 queries from the rest of the input. Answers that are
+/// still incomplete are dropped as well, so they are never taken for typed keys.
+#[cfg(any(unix, test))]
+fn split_replies(input: &[u8]) -> Replies {
+    let mut replies = Replies::default();
+    let mut i = 0;
+    while i < input.len() {
+        let remaining = &input[i..];
+        if remaining.starts_with(b"\x1b]11;") {
+            // The answer is terminated by either BEL or ST
+            let end = remaining
+                .iter()
+                .enumerate()
+                .find_map(|(j, byte)| match byte {
+                    b'\x07' => Some(j + 1),
+                    b'\\' if remaining[j - 1] == b'\x1b' => Some(j + 1),
+                    _ => None,
+                });
+            let Some(end) = end else {
+                break;
+            };
+            replies.background = String::from_utf8(remaining[..end].to_vec()).ok();
+            i += end;
+        } else if remaining.starts_with(b"\x1b[?") {
+            let parameters = remaining[3..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit() || **byte == b';')
+                .count();
+            match remaining.get(3 + parameters) {
+                Some(b'c') => {
+                    replies.done = true;
+                    i += 4 + parameters;
+                }
+                Some(_) => {
+                    replies.rest.push(remaining[0]);
+                    i += 1;
+                }
+                None => break,
+            }
+        } else {
+            replies.rest.push(remaining[0]);
+            i += 1;
+        }
+    }
+    replies
+}
+
+/// Asks the terminal for its background color, returning its raw answer. The query is followed
+/// by a DA1 query, which every terminal answers, so terminals that don't support it don't have to
+/// be waited for. Keys typed while waiting are put back into the input of the terminal.
+#[cfg(all(unix, not(test)))]
+fn query_background(timeout: Duration) -> Option<String> {
+    use nix::poll::{PollFd, PollFlags, PollTimeout, poll};
+    use nix::sys::termios::{SetArg, cfmakeraw, tcgetattr, tcsetattr};
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::fd::{AsFd, AsRawFd};
+    use std::time::Instant;
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let original = tcgetattr(&tty).ok()?;
+    let mut raw = original.clone();
+    cfmakeraw(&mut raw);
+    tcsetattr(&tty, SetArg::TCSANOW, &raw).ok()?;
+
+    let start = Instant::now();
+    let mut input = Vec::new();
+    if tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").is_ok() {
+        while !split_replies(&input).done {
+            let Some(remaining) = timeout.checked_sub(start.elapsed()) else {
+                log::debug!("Timed out waiting for the terminal background color");
+                break;
+            };
+            let mut fds = [PollFd::new(tty.as_fd(), PollFlags::POLLIN)];
+            let timeout = PollTimeout::try_from(remaining).unwrap_or(PollTimeout::ZERO);
+            if !matches!(poll(&mut fds, timeout), Ok(n) if n > 0) {
+                break;
+            }
+
+            let mut buf = [0; 64];
+            match tty.read(&mut buf) {
+                Ok(n) if n > 0 => input.extend_from_slice(&buf[..n]),
+                _ => break,
+            }
+        }
+    }
+
+    if let Err(error) = tcsetattr(&tty, SetArg::TCSANOW, &original) {
+        log::warn!("Unable to restore the terminal mode: {error}");
+    }
+
+    let replies = split_replies(&input);
+    for byte in &replies.rest {
+        // SAFETY: TIOCSTI reads a single byte from the pointer, which is valid for the call
+        let result = unsafe {
+            nix::libc::ioctl(
+                tty.as_raw_fd(),
+                nix::libc::TIOCSTI,
+                std::ptr::from_ref(byte),
+            )
+        };
+        if result != 0 {
+            log::debug!("Unable to put back the input read while querying the terminal");
+            break;
+        }
+    }
+    replies.background
+}
+
+#[cfg(any(not(unix), test))]
+fn query_background(_timeout: Duration) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colorfgbg() {
+        assert_eq!(parse_colorfgbg("15;0"), Some(Background::Dark));
+        assert_eq!(parse_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(parse_colorfgbg("0;default;7"), Some(Background::Light));
+        assert_eq!(parse_colorfgbg("15;default"), None);
+    }
+
+    #[test]
+    fn osc11_response() {
+        assert_eq!(
+            parse_osc11_response("\x1b]11;rgb:ffff/ffff/ffff\x1b\\"),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            parse_osc11_response("\x1b]11;rgb:1e/1e/2e\x07"),
+            Some(Background::Dark)
+        );
+        assert_eq!(parse_osc11_response("\x1b]11;rgb:ffff/ffff\x07"), None);
+        assert_eq!(parse_osc11_response(""), None);
+    }
+
+    #[test]
+    fn replies_and_typed_keys() {
+        assert_eq!(
+            split_replies(b"ls\x1b]11;rgb:ffff/ffff/ffff\x1b\\\x1b[?62;22c -l"),
+            Replies {
+                background: Some("\x1b]11;rgb:ffff/ffff/ffff\x1b\\".to_string()),
+                done: true,
+                rest: b"ls -l".to_vec(),
+            }
+        );
+        // Terminals that don't support OSC 11 only answer the DA1 query
+        assert_eq!(
+            split_replies(b"\x1b[?1;2cx"),
+            Replies {
+                background: None,
+                done: true,
+                rest: b"x".to_vec(),
+            }
+        );
+        // Incomplete answers are not taken for typed keys, but other escape sequences are
+        assert_eq!(
+            split_replies(b"\x1b[A\x1b]11;rgb:1e1e/1e"),
+            Replies {
+                background: None,
+                done: false,
+                rest: b"\x1b[A".to_vec(),
+            }
+        );
+        assert_eq!(
+            split_replies(b"\x1b]11;rgb:1e/1e/2e\x07"),
+            Replies {
+                background: Some("\x1b]11;rgb:1e/1e/2e\x07".to_string()),
+                done: false,
+                rest: Vec::new(),
+            }
+        );
+    }
+}
@@ -42,6 +42,30 @@ impl<'a> Env<'a> {
         env::var_os(key.as_ref())
     }
 
+    // Retrieves the environment variables whose name starts with `prefix`, sorted by name
+    #[cfg(test)]
+    pub fn get_env_with_prefix(&self, prefix: &str) -> Vec<(String, String)> {
+        let mut vars: Vec<_> = self
+            .env
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| ((*key).to_string(), value.clone()))
+            .collect();
+        vars.sort();
+        vars
+    }
+
+    #[cfg(not(test))]
+    pub fn get_env_with_prefix(&self, prefix: &str) -> Vec<(String, String)> {
+        // `env::vars` panics on variables that aren't valid unicode, so they are skipped instead
+        let mut vars: Vec<_> = env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+            .filter(|(key, _)| key.starts_with(prefix))
+            .collect();
+        vars.sort();
+        vars
+    }
+
     #[cfg(test)]
     pub fn insert(&mut self, k: &'a str, v: String) -> Option<String> {
         self.env.insert(k, v)
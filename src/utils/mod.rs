@@ -1,6 +1,13 @@
+pub mod async_modules;
+pub mod background;
 pub mod env;
+pub mod prompt_budget;
+pub mod prompt_width;
+pub mod redact;
+pub mod render;
 pub mod serde;
 pub mod statusline;
+pub mod timing;
 
 use process_control::{ChildExt, Control};
 use std::ffi::OsStr;
@@ -316,6 +323,10 @@ Elixir 1.10 (compiled with Erlang/OTP 22)\n",
             stdout: String::from("gleam 1.0.0\n"),
             stderr: String::default(),
         }),
+        "godot --version" => Some(CommandOutput {
+            stdout: String::from("4.2.1.stable.official.b09f793f5\n"),
+            stderr: String::default(),
+        }),
         "go version" => Some(CommandOutput {
             stdout: String::from("go version go1.12.1 linux/amd64\n"),
             stderr: String::default(),
@@ -599,9 +610,11 @@ pub fn wrap_seq_for_shell(
 ) -> String {
     let (beg, end) = match shell {
         // \[ and \]
-        Shell::Bash => ("\u{5c}\u{5b}", "\u{5c}\u{5d}"),
+        Shell::Bash | Shell::Osh => ("\u{5c}\u{5b}", "\u{5c}\u{5d}"),
         // %{ and %}
         Shell::Tcsh | Shell::Zsh => ("\u{25}\u{7b}", "\u{25}\u{7d}"),
+        // \001 and \002, since the prompt of YSH isn't expanded
+        Shell::Ysh => ("\u{1}", "\u{2}"),
         _ => return ansi,
     };
 
@@ -636,6 +649,7 @@ fn internal_exec_cmd<T: AsRef<OsStr> + Debug, U: AsRef<OsStr> + Debug>(
 }
 
 pub fn exec_timeout(cmd: &mut Command, time_limit: Duration) -> Option<CommandOutput> {
+    let _step = timing::step(timing::Step::Command);
     let start = Instant::now();
     let process = match cmd.spawn() {
         Ok(process) => process,
@@ -1010,6 +1024,15 @@ mod tests {
         assert_eq!(&bresult3, "\\[OH NO\\]");
         assert_eq!(&bresult4, "herpaderp");
         assert_eq!(&bresult5, "");
+
+        let yresult0 = wrap_seq_for_shell(test0.to_string(), Shell::Ysh, '\x1b', 'm');
+        let yresult4 = wrap_seq_for_shell(test4.to_string(), Shell::Ysh, '\x1b', 'm');
+
+        assert_eq!(
+            &yresult0,
+            "\u{1}\x1b2m\u{2}hellomynamekeyes\u{1}\x1b2m\u{2}"
+        );
+        assert_eq!(&yresult4, "herpaderp");
     }
 
     #[test]
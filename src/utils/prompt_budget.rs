@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+use crate::context::Context;
+use crate::formatter::StringFormatter;
+use crate::segment::Segment;
+
+/// The file in the cache directory holding the output of the modules of the last prompt
+pub const CACHE_FILE: &str = "prompt_cache.json";
+
+/// The output of the modules of the last prompt in a directory, shown in place of the modules
+/// that could not be computed in time
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+struct ModuleCache {
+    dir: PathBuf,
+    modules: HashMap<String, String>,
+}
+
+/// Limits the time spent computing a prompt to `command_timeout`, with the `degrade` prompt
+/// timeout strategy. The commands run by modules are stopped at the deadline, and modules that
+/// are not computed by then are replaced by a placeholder showing their output from the last
+/// prompt, and a symbol is added to the prompt.
+///
+/// A single budget is shared by all the prompts rendered with a context, such as the left and
+/// right prompts of bash.
+pub struct PromptBudget {
+    deadline: Instant,
+    cache_path: Option<PathBuf>,
+    cache: ModuleCache,
+    rendered: Mutex<HashMap<String, Vec<Segment>>>,
+    skipped: Mutex<Vec<String>>,
+    symbol_shown: AtomicBool,
+    saved: AtomicBool,
+}
+
+impl PromptBudget {
+    pub fn new(context: &Context) -> Option<Self> {
+        let config = &context.root_config;
+        match config.prompt_timeout_strategy.as_str() {
+            "wait" => return None,
+            "degrade" => {}
+            strategy => {
+                log::warn!(
+                    "Unknown prompt_timeout_strategy {strategy:?}, expected \"wait\" or \"degrade\""
+                );
+                return None;
+            }
+        }
+
+        let cache_path = cache::cache_dir(context).map(|dir| dir.join(CACHE_FILE));
+        let cache = cache_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<ModuleCache>(&content).ok())
+            .filter(|cache| cache.dir == context.current_dir)
+            .unwrap_or_else(|| ModuleCache {
+                dir: context.current_dir.clone(),
+                modules: HashMap::new(),
+            });
+
+        Some(Self {
+            deadline: Instant::now() + Duration::from_millis(config.command_timeout),
+            cache_path,
+            cache,
+            rendered: Mutex::new(HashMap::new()),
+            skipped: Mutex::new(Vec::new()),
+            symbol_shown: AtomicBool::new(false),
+            saved: AtomicBool::new(false),
+        })
+    }
+
+    /// The time left to compute the prompt, which limits how long the commands of modules run
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the prompt is out of time
+    pub fn is_spent(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// Returns the segments of a module computed for this prompt, if it was computed already
+    pub fn rendered(&self, module: &str) -> Option<Vec<Segment>> {
+        self.rendered.lock().get(module).cloned()
+    }
+
+    /// Returns the placeholder of a module once the budget is spent, instead of computing it
+    pub fn placeholder(&self, module: &str, context: &Context) -> Option<Vec<Segment>> {
+        if !self.is_spent() {
+            return None;
+        }
+
+        log::debug!("Skipping module {module:?}, the prompt took longer than command_timeout");
+        self.skipped.lock().push(module.to_string());
+        let Some(cached) = self.cache.modules.get(module) else {
+            return Some(Vec::new());
+        };
+
+        let format = &context.root_config.prompt_timeout_placeholder;
+        Some(render(format, context, |variable| match variable {
+            "cached" => Some(cached.trim_end()),
+            "name" => Some(module),
+            _ => None,
+        }))
+    }
+
+    /// Records the segments of a module, to be shown if it can not be computed in time next time
+    pub fn record(&self, module: &str, segments: &[Segment]) {
+        self.rendered
+            .lock()
+            .insert(module.to_string(), segments.to_vec());
+    }
+
+    /// The symbol showing that modules were skipped, if any were and it wasn't shown yet
+    pub fn symbol(&self, context: &Context) -> Vec<Segment> {
+        if self.skipped.lock().is_empty() || self.symbol_shown.swap(true, Ordering::Relaxed) {
+            return Vec::new();
+        }
+        log::warn!(
+            "The prompt took longer than command_timeout, skipped modules: {}",
+            self.skipped.lock().join(", ")
+        );
+        render(&context.root_config.prompt_timeout_symbol, context, |_| {
+            None
+        })
+    }
+
+    /// Saves the output of the modules for the next prompt, once per prompt and only if it
+    /// changed
+    pub fn save(&self) {
+        if self.saved.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let Some(path) = &self.cache_path else {
+            return;
+        };
+
+        let mut cache = self.cache.clone();
+        for (module, segments) in self.rendered.lock().iter() {
+            let output: String = segments.iter().map(Segment::value).collect();
+            if output.trim().is_empty() {
+                cache.modules.remove(module);
+            } else {
+                cache.modules.insert(module.clone(), output);
+            }
+        }
+        if cache == self.cache {
+            return;
+        }
+
+        let saved = serde_json::to_string(&cache)
+            .map_err(|error| error.to_string())
+            .and_then(|content| fs::write(path, content).map_err(|error| error.to_string()));
+        if let Err(error) = saved {
+            log::debug!("Unable to save the module cache to {path:?}: {error}");
+        }
+    }
+}
+
+fn render<'a>(
+    format: &str,
+    context: &Context,
+    variables: impl Fn(&str) -> Option<&'a str> + Sync,
+) -> Vec<Segment> {
+    let parsed = StringFormatter::new(format).and_then(|formatter| {
+        formatter
+            .map(|variable| variables(variable).map(Ok))
+            .parse(None, Some(context))
+    });
+    match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error parsing {format:?}: {error}");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+    use nu_ansi_term::Style;
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(PromptBudget::new(&default_context()).is_none());
+    }
+
+    #[test]
+    fn commands_run_until_the_deadline() {
+        let context = default_context().set_config(toml::toml! {
+            prompt_timeout_strategy = "degrade"
+            command_timeout = 10_000
+        });
+        assert_eq!(context.command_time_limit(), Duration::from_secs(10));
+
+        // Once the budget is started, commands only get the time left
+        let budget = context.prompt_budget().unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(context.command_time_limit() <= Duration::from_millis(9950));
+        assert!(context.command_time_limit() >= budget.remaining());
+    }
+
+    #[test]
+    fn placeholders_show_cached_output() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context().set_config(toml::toml! {
+            prompt_timeout_strategy = "degrade"
+            command_timeout = 0
+        });
+        context
+            .env
+            .insert("STARSHIP_CACHE", dir.path().to_string_lossy().to_string());
+        context.current_dir = dir.path().to_path_buf();
+
+        // Nothing is cached before the first prompt
+        let budget = PromptBudget::new(&context).unwrap();
+        assert_eq!(
+            budget
+                .placeholder("git_branch", &context)
+                .map(|segments| segments.len()),
+            Some(0)
+        );
+        budget.record("directory", &Segment::from_text(None, "~/src "));
+        budget.record("rust", &Segment::from_text(None, ""));
+        budget.save();
+        let symbol: String = budget.symbol(&context).iter().map(Segment::value).collect();
+        assert_eq!(symbol, "⧗ ");
+        // The symbol is shown once per prompt
+        assert!(budget.symbol(&context).is_empty());
+
+        let budget = PromptBudget::new(&context).unwrap();
+        let placeholder = budget.placeholder("directory", &context).unwrap();
+        let placeholder: String = placeholder
+            .iter()
+            .map(|segment| segment.ansi_string(None).to_string())
+            .collect();
+        assert_eq!(
+            placeholder,
+            format!("{} ", Style::new().dimmed().paint("~/src"))
+        );
+        assert_eq!(
+            budget
+                .placeholder("rust", &context)
+                .map(|segments| segments.len()),
+            Some(0)
+        );
+
+        dir.close()
+    }
+
+    #[test]
+    fn cache_is_only_saved_when_changed() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context().set_config(toml::toml! {
+            prompt_timeout_strategy = "degrade"
+        });
+        context
+            .env
+            .insert("STARSHIP_CACHE", dir.path().to_string_lossy().to_string());
+        context.current_dir = dir.path().to_path_buf();
+        let cache_path = dir.path().join(CACHE_FILE);
+
+        let budget = PromptBudget::new(&context).unwrap();
+        budget.record("directory", &Segment::from_text(None, "~/src "));
+        budget.save();
+        let saved = fs::read_to_string(&cache_path)?;
+
+        // Rewritten with the same content in another layout, which is kept if nothing changed
+        let reformatted =
+            serde_json::to_string_pretty(&serde_json::from_str::<ModuleCache>(&saved)?)?;
+        fs::write(&cache_path, &reformatted)?;
+        let budget = PromptBudget::new(&context).unwrap();
+        budget.record("directory", &Segment::from_text(None, "~/src "));
+        budget.save();
+        assert_eq!(fs::read_to_string(&cache_path)?, reformatted);
+
+        let budget = PromptBudget::new(&context).unwrap();
+        budget.record("directory", &Segment::from_text(None, "~/work "));
+        budget.save();
+        assert_ne!(fs::read_to_string(&cache_path)?, reformatted);
+        dir.close()
+    }
+}
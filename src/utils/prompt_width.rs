@@ -0,0 +1,168 @@
+use std::cmp::Reverse;
+
+use crate::context::Context;
+use crate::segment::Segment;
+
+/// The key of a module table making the module shortened or left out when a line of the
+/// prompt is wider than `max_prompt_width`. Modules with a lower priority go first.
+pub const PRIORITY_KEY: &str = "priority";
+
+/// Shortens the lines of the prompt wider than `max_prompt_width`, or the terminal if it is
+/// narrower. Modules with a `priority` are collapsed to their `$symbol` and then left out one
+/// at a time, from the lowest priority up and, among the same priority, from the right.
+pub fn fit(context: &Context, segments: Vec<Segment>) -> Vec<Segment> {
+    let max_width = match context.root_config.max_prompt_width {
+        0 => return segments,
+        max_width if context.width > 0 => max_width.min(context.width),
+        max_width => max_width,
+    };
+
+    let mut lines: Vec<Vec<Segment>> = vec![Vec::new()];
+    for segment in segments {
+        let line_end = matches!(segment, Segment::LineTerm);
+        lines.last_mut().unwrap().push(segment);
+        if line_end {
+            lines.push(Vec::new());
+        }
+    }
+    for line in &mut lines {
+        fit_line(context, line, max_width);
+    }
+    lines.into_iter().flatten().collect()
+}
+
+fn fit_line(context: &Context, line: &mut Vec<Segment>, max_width: usize) {
+    if line_width(line) <= max_width {
+        return;
+    }
+
+    let mut modules: Vec<(i64, usize, String)> = Vec::new();
+    for (position, segment) in line.iter().enumerate() {
+        let Some(module) = segment.module() else {
+            continue;
+        };
+        if modules.iter().any(|(_, _, name)| name == module) {
+            continue;
+        }
+        if let Some(priority) = priority(context, module) {
+            modules.push((priority, position, module.to_string()));
+        }
+    }
+    modules.sort_by_key(|(priority, position, _)| (*priority, Reverse(*position)));
+
+    for (_, _, module) in modules {
+        line.retain(|segment| {
+            segment.module() != Some(module.as_str()) || segment.variable() == Some("symbol")
+        });
+        if line_width(line) <= max_width {
+            return;
+        }
+        line.retain(|segment| segment.module() != Some(module.as_str()));
+        if line_width(line) <= max_width {
+            return;
+        }
+    }
+}
+
+/// The width of a line, not counting `$fill` which shrinks to fit
+fn line_width(line: &[Segment]) -> usize {
+    line.iter()
+        .filter(|segment| !matches!(segment, Segment::Fill(_)))
+        .map(Segment::width_graphemes)
+        .sum()
+}
+
+fn priority(context: &Context, name: &str) -> Option<i64> {
+    let path: Vec<&str> = name.split('.').collect();
+    let value = context
+        .module_config_source()
+        .get_config(&path)?
+        .get(PRIORITY_KEY)?;
+    let priority = value.as_integer();
+    if priority.is_none() {
+        log::warn!("`{name}.{PRIORITY_KEY}` should be an integer, found {value}");
+    }
+    priority
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    fn module_segments(module: &str, symbol: &str, value: &str) -> Vec<Segment> {
+        let mut segments = Segment::from_text(None, symbol);
+        segments[0].set_variable_if_empty("symbol");
+        segments.extend(Segment::from_text(None, value));
+        for segment in &mut segments {
+            segment.set_module(module);
+        }
+        segments
+    }
+
+    fn rendered(segments: &[Segment]) -> String {
+        segments.iter().map(Segment::value).collect()
+    }
+
+    fn prompt() -> Vec<Segment> {
+        let mut segments = module_segments("kubernetes", "k:", "long-context ");
+        segments.extend(module_segments("git_branch", "b:", "feature "));
+        segments.extend(module_segments("directory", "", "~/src "));
+        segments
+    }
+
+    #[test]
+    fn keeps_prompt_that_fits() {
+        let mut context = default_context().set_config(toml::toml! {
+            max_prompt_width = 40
+            [kubernetes]
+            priority = 1
+        });
+        context.width = 80;
+        assert_eq!(
+            rendered(&fit(&context, prompt())),
+            "k:long-context b:feature ~/src "
+        );
+    }
+
+    #[test]
+    fn collapses_lowest_priority_first() {
+        let mut context = default_context().set_config(toml::toml! {
+            max_prompt_width = 25
+            [kubernetes]
+            priority = 1
+            [git_branch]
+            priority = 2
+        });
+        context.width = 80;
+        assert_eq!(rendered(&fit(&context, prompt())), "k:b:feature ~/src ");
+    }
+
+    #[test]
+    fn drops_modules_to_fit_terminal() {
+        let mut context = default_context().set_config(toml::toml! {
+            max_prompt_width = 100
+            [kubernetes]
+            priority = 1
+            [git_branch]
+            priority = 1
+        });
+        context.width = 6;
+        // Modules further right go first, and modules without a priority are always kept
+        assert_eq!(rendered(&fit(&context, prompt())), "~/src ");
+    }
+
+    #[test]
+    fn fits_each_line() {
+        let mut context = default_context().set_config(toml::toml! {
+            max_prompt_width = 10
+            [kubernetes]
+            priority = 1
+        });
+        context.width = 80;
+        let mut segments = module_segments("kubernetes", "k:", "long-context ");
+        segments.push(Segment::LineTerm);
+        segments.extend(module_segments("kubernetes", "k:", "ctx"));
+        assert_eq!(rendered(&fit(&context, segments)), "k:\nk:ctx");
+    }
+}
@@ -0,0 +1,214 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use sha1::{Digest, Sha1};
+
+use crate::context::Context;
+
+/// The key of a module table listing the variables hidden when `redact.enabled` is set
+pub const VARIABLES_KEY: &str = "redact_variables";
+
+/// The variables hidden by default, for modules without `redact_variables`
+const DEFAULT_VARIABLES: &[(&str, &[&str])] = &[
+    ("aws", &["profile"]),
+    ("azure", &["subscription", "username"]),
+    ("cmd_duration", &["command"]),
+    ("gcloud", &["account", "project"]),
+    ("hostname", &["hostname"]),
+    ("kubernetes", &["context", "cluster", "user"]),
+    ("localip", &["localip", "localipv4", "localipv6"]),
+    ("network", &["ssid"]),
+    ("openstack", &["cloud", "project"]),
+    ("username", &["user", "real_user"]),
+];
+
+thread_local! {
+    /// How the module being computed on this thread hides its variables
+    static REDACTION: RefCell<Option<Arc<Redaction>>> = const { RefCell::new(None) };
+}
+
+/// The variables a module hides and what they are replaced with
+#[derive(Debug)]
+pub struct Redaction {
+    variables: Vec<String>,
+    placeholder: String,
+    fake_names: bool,
+}
+
+/// Hides the variables of a module while it is computed, until it is dropped
+pub struct RedactionGuard {
+    previous: Option<Arc<Redaction>>,
+}
+
+impl Redaction {
+    /// The text shown in place of `value`, if the variable `name` is hidden. Empty values
+    /// are kept, so the same text groups are shown as without redaction.
+    pub fn replace(&self, name: &str, value: &str) -> Option<String> {
+        if value.is_empty() || !self.variables.iter().any(|variable| variable == name) {
+            return None;
+        }
+
+        if self.fake_names {
+            // The same value always gets the same name, so values can still be told apart
+            let hash = crate::utils::encode_to_hex(&Sha1::digest(value.as_bytes()));
+            Some(format!("{name}-{}", &hash[..4]))
+        } else {
+            Some(self.placeholder.clone())
+        }
+    }
+}
+
+/// Starts hiding the variables of the module `name` on this thread, if `redact.enabled` is set
+pub fn enter(context: &Context, name: &str) -> RedactionGuard {
+    let redaction = context
+        .root_config
+        .redact
+        .enabled
+        .then(|| Arc::new(redaction(context, name)));
+    RedactionGuard {
+        previous: REDACTION.replace(redaction),
+    }
+}
+
+/// How the module being computed on this thread hides its variables, if it does
+pub fn current() -> Option<Arc<Redaction>> {
+    REDACTION.with_borrow(Clone::clone)
+}
+
+impl Drop for RedactionGuard {
+    fn drop(&mut self) {
+        REDACTION.set(self.previous.take());
+    }
+}
+
+/// Adds `redact_variables`, `priority` and `format_narrow`, which every module accepts, to the
+/// schema of the table of every module
+#[cfg(feature = "config-schema")]
+pub fn extend_schema(schema: &mut schemars::Schema) {
+    use serde_json::Value;
+
+    let definitions: Vec<String> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| {
+            crate::module::ALL_MODULES.contains(&name.as_str())
+                || name.as_str() == "custom"
+                || name.as_str() == "env_var"
+        })
+        .filter_map(|(_, property)| {
+            // `custom` and `env_var` are tables of modules
+            let reference = property
+                .get("$ref")
+                .or_else(|| property.get("additionalProperties")?.get("$ref"))?;
+            Some(reference.as_str()?.strip_prefix("#/$defs/")?.to_string())
+        })
+        .collect();
+
+    let Some(defs) = schema.get_mut("$defs").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for definition in definitions {
+        if let Some(properties) = defs
+            .get_mut(&definition)
+            .and_then(|definition| definition.get_mut("properties"))
+            .and_then(Value::as_object_mut)
+        {
+            properties.insert(
+                VARIABLES_KEY.to_string(),
+                serde_json::json!({ "type": "array", "items": { "type": "string" } }),
+            );
+            properties.insert(
+                crate::utils::prompt_width::PRIORITY_KEY.to_string(),
+                serde_json::json!({ "type": "integer", "format": "int64" }),
+            );
+            properties.insert(
+                crate::config::NARROW_FORMAT_KEY.to_string(),
+                serde_json::json!({ "type": "string" }),
+            );
+        }
+    }
+}
+
+fn redaction(context: &Context, name: &str) -> Redaction {
+    let path: Vec<&str> = name.split('.').collect();
+    let configured = context
+        .module_config_source()
+        .get_config(&path)
+        .and_then(|config| config.get(VARIABLES_KEY));
+
+    let variables = match configured {
+        Some(toml::Value::Array(variables)) => variables
+            .iter()
+            .filter_map(|variable| variable.as_str().map(ToString::to_string))
+            .collect(),
+        Some(value) => {
+            log::warn!("`{name}.{VARIABLES_KEY}` should be an array of strings, found {value}");
+            Vec::new()
+        }
+        None => DEFAULT_VARIABLES
+            .iter()
+            .find(|(module, _)| *module == name)
+            .map(|(_, variables)| variables.iter().map(ToString::to_string).collect())
+            .unwrap_or_default(),
+    };
+
+    let config = &context.root_config.redact;
+    Redaction {
+        variables,
+        placeholder: config.placeholder.clone(),
+        fake_names: config.fake_names,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    #[test]
+    fn disabled_by_default() {
+        let context = default_context();
+        let _guard = enter(&context, "hostname");
+        assert!(current().is_none());
+    }
+
+    #[test]
+    fn restores_previous_module() {
+        let context = default_context().set_config(toml::toml! {
+            [redact]
+            enabled = true
+        });
+        let outer = enter(&context, "hostname");
+        {
+            let _inner = enter(&context, "directory");
+            assert_eq!(current().unwrap().replace("hostname", "box"), None);
+        }
+        assert_eq!(
+            current().unwrap().replace("hostname", "box").as_deref(),
+            Some("•••")
+        );
+        drop(outer);
+        assert!(current().is_none());
+    }
+
+    #[test]
+    fn fake_names_are_stable() {
+        let context = default_context().set_config(toml::toml! {
+            [redact]
+            enabled = true
+            fake_names = true
+            [aws]
+            redact_variables = ["profile", "region"]
+        });
+        let _guard = enter(&context, "aws");
+        let redaction = current().unwrap();
+        let first = redaction.replace("profile", "prod").unwrap();
+        assert!(first.starts_with("profile-"));
+        assert_eq!(redaction.replace("profile", "prod").unwrap(), first);
+        assert_ne!(redaction.replace("profile", "dev").unwrap(), first);
+        assert_eq!(redaction.replace("profile", ""), None);
+        assert!(redaction.replace("region", "eu-west-1").is_some());
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt::Write;
+
+use nu_ansi_term::{AnsiString, Color, Style};
+use unicode_width::UnicodeWidthStr;
+
+/// The colors of the text and background when the prompt doesn't set them
+const FOREGROUND: Rgb = (0xc5, 0xc8, 0xc6);
+const BACKGROUND: Rgb = (0x1d, 0x1f, 0x21);
+
+/// The colors of the 16 ANSI colors, as in xterm
+const ANSI_COLORS: [Rgb; 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+/// The size of the text and of each character cell of SVG output, in pixels
+const FONT_SIZE: f64 = 14.0;
+const CELL_WIDTH: f64 = 8.4;
+const LINE_HEIGHT: f64 = 20.0;
+const PADDING: f64 = 10.0;
+
+type Rgb = (u8, u8, u8);
+
+/// Renders styled strings as an HTML `<pre>` block with inline styles
+pub fn html(strings: &[AnsiString]) -> String {
+    let mut html = format!(
+        r#"<pre class="starship-prompt" style="background-color: {}; color: {}; padding: 1em;">"#,
+        hex(BACKGROUND),
+        hex(FOREGROUND)
+    );
+    for (index, line) in lines(strings).iter().enumerate() {
+        if index > 0 {
+            html.push('\n');
+        }
+        for (text, style) in line {
+            let (fg, bg) = colors(style);
+            let mut css = format!("color: {}", hex(fg));
+            if let Some(bg) = bg {
+                write!(css, "; background-color: {}", hex(bg)).unwrap();
+            }
+            for declaration in text_decorations(style) {
+                write!(css, "; {declaration}").unwrap();
+            }
+            write!(html, r#"<span style="{css}">{}</span>"#, escape(text)).unwrap();
+        }
+    }
+    html.push_str("</pre>");
+    html
+}
+
+/// Renders styled strings as an SVG image of a terminal showing them
+pub fn svg(strings: &[AnsiString]) -> String {
+    let lines = lines(strings);
+    let columns = lines
+        .iter()
+        .map(|line| line.iter().map(|(text, _)| text.width()).sum::<usize>())
+        .max()
+        .unwrap_or(0);
+    let width = pixels(columns as f64 * CELL_WIDTH + 2.0 * PADDING);
+    let height = lines.len() as f64 * LINE_HEIGHT + 2.0 * PADDING;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="monospace" font-size="{FONT_SIZE}">"#
+    );
+    write!(
+        svg,
+        r#"<rect width="100%" height="100%" fill="{}"/>"#,
+        hex(BACKGROUND)
+    )
+    .unwrap();
+
+    for (index, line) in lines.iter().enumerate() {
+        let top = PADDING + index as f64 * LINE_HEIGHT;
+        let baseline = top + LINE_HEIGHT * 0.75;
+        let mut column = 0;
+        let mut backgrounds = String::new();
+        let mut spans = String::new();
+        for (text, style) in line {
+            let x = pixels(PADDING + column as f64 * CELL_WIDTH);
+            let (fg, bg) = colors(style);
+            if let Some(bg) = bg {
+                write!(
+                    backgrounds,
+                    r#"<rect x="{x}" y="{top}" width="{}" height="{LINE_HEIGHT}" fill="{}"/>"#,
+                    pixels(text.width() as f64 * CELL_WIDTH),
+                    hex(bg)
+                )
+                .unwrap();
+            }
+            let mut attributes = format!(r#"x="{x}" fill="{}""#, hex(fg));
+            if style.is_bold {
+                attributes.push_str(r#" font-weight="bold""#);
+            }
+            if style.is_italic {
+                attributes.push_str(r#" font-style="italic""#);
+            }
+            if style.is_dimmed {
+                attributes.push_str(r#" opacity="0.5""#);
+            }
+            if style.is_hidden {
+                attributes.push_str(r#" visibility="hidden""#);
+            }
+            match (style.is_underline, style.is_strikethrough) {
+                (true, true) => attributes.push_str(r#" text-decoration="underline line-through""#),
+                (true, false) => attributes.push_str(r#" text-decoration="underline""#),
+                (false, true) => attributes.push_str(r#" text-decoration="line-through""#),
+                (false, false) => {}
+            }
+            write!(spans, "<tspan {attributes}>{}</tspan>", escape(text)).unwrap();
+            column += text.width();
+        }
+        write!(
+            svg,
+            r#"{backgrounds}<text y="{baseline}" xml:space="preserve">{spans}</text>"#
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Splits styled strings into lines of styled runs of text, leaving out empty runs
+fn lines<'a>(strings: &'a [AnsiString<'a>]) -> Vec<Vec<(&'a str, Style)>> {
+    let mut lines = vec![Vec::new()];
+    for string in strings {
+        for (index, text) in string.as_str().split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Vec::new());
+            }
+            if !text.is_empty() {
+                lines.last_mut().unwrap().push((text, *string.style_ref()));
+            }
+        }
+    }
+    lines
+}
+
+/// The colors of the text and of its background, if it has one
+fn colors(style: &Style) -> (Rgb, Option<Rgb>) {
+    let fg = style.foreground.and_then(rgb);
+    let bg = style.background.and_then(rgb);
+    if style.is_reverse {
+        (bg.unwrap_or(BACKGROUND), Some(fg.unwrap_or(FOREGROUND)))
+    } else {
+        (fg.unwrap_or(FOREGROUND), bg)
+    }
+}
+
+/// The CSS declarations for the attributes of a style other than its colors
+fn text_decorations(style: &Style) -> Vec<&'static str> {
+    let mut declarations = Vec::new();
+    if style.is_bold {
+        declarations.push("font-weight: bold");
+    }
+    if style.is_italic {
+        declarations.push("font-style: italic");
+    }
+    if style.is_dimmed {
+        declarations.push("opacity: 0.5");
+    }
+    if style.is_hidden {
+        declarations.push("visibility: hidden");
+    }
+    match (style.is_underline, style.is_strikethrough) {
+        (true, true) => declarations.push("text-decoration: underline line-through"),
+        (true, false) => declarations.push("text-decoration: underline"),
+        (false, true) => declarations.push("text-decoration: line-through"),
+        (false, false) => {}
+    }
+    declarations
+}
+
+/// The RGB value of a color, or `None` for the default color of the terminal
+fn rgb(color: Color) -> Option<Rgb> {
+    let index = match color {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Purple | Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::White => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightPurple | Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::LightGray => 15,
+        Color::Fixed(index) => index,
+        Color::Rgb(r, g, b) => return Some((r, g, b)),
+        Color::Default => return None,
+    };
+    Some(fixed_rgb(index))
+}
+
+/// The RGB value of a color of the 256 color palette, as in xterm
+fn fixed_rgb(index: u8) -> Rgb {
+    match index {
+        0..=15 => ANSI_COLORS[index as usize],
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        232..=255 => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// Rounds a length to a tenth of a pixel, to leave out floating point noise
+fn pixels(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn hex((r, g, b): Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_colors() {
+        assert_eq!(fixed_rgb(1), (0xcd, 0x00, 0x00));
+        assert_eq!(fixed_rgb(16), (0, 0, 0));
+        assert_eq!(fixed_rgb(214), (0xff, 0xaf, 0x00));
+        assert_eq!(fixed_rgb(244), (0x80, 0x80, 0x80));
+    }
+
+    #[test]
+    fn html_output() {
+        let strings = [
+            Color::Cyan.bold().paint("~/<src>"),
+            Style::new().paint("\n"),
+            Color::Rgb(1, 2, 3).reverse().paint("❯"),
+        ];
+        assert_eq!(
+            html(&strings),
+            "<pre class=\"starship-prompt\" style=\"background-color: #1d1f21; color: #c5c8c6; padding: 1em;\">\
+             <span style=\"color: #00cdcd; font-weight: bold\">~/&lt;src&gt;</span>\n\
+             <span style=\"color: #1d1f21; background-color: #010203\">❯</span></pre>"
+        );
+    }
+
+    #[test]
+    fn svg_size() {
+        let strings = [Color::Red.paint("ab"), Style::new().paint("\nabcd")];
+        let svg = svg(&strings);
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="53.6" height="60""#)
+        );
+        assert!(svg.contains(r##"<tspan x="10" fill="#cd0000">ab</tspan>"##));
+        assert!(svg.ends_with("</svg>"));
+    }
+}
@@ -1,4 +1,6 @@
+use crate::config::NARROW_FORMAT_KEY;
 use crate::module::ALL_MODULES;
+use crate::utils::{prompt_width, redact};
 use serde::de::{
     Deserializer, Error, IntoDeserializer, Visitor,
     value::{Error as ValueError, MapDeserializer, SeqDeserializer},
@@ -181,6 +183,16 @@ impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
             return visitor.visit_none();
         }
 
+        // Every module may list the variables it hides when `redact.enabled` is set, give its
+        // priority when the prompt is wider than `max_prompt_width` and its format in narrow
+        // terminals
+        if self.current_key == Some(redact::VARIABLES_KEY)
+            || self.current_key == Some(prompt_width::PRIORITY_KEY)
+            || self.current_key == Some(NARROW_FORMAT_KEY)
+        {
+            return visitor.visit_none();
+        }
+
         if !self.error_on_ignored {
             return visitor.visit_none();
         }
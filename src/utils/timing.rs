@@ -0,0 +1,152 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// The steps of the module being computed on this thread
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// A kind of work done while computing a module
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Looking for files, folders and repositories
+    Detection,
+    /// Running external commands
+    Command,
+    /// Rendering format strings
+    Formatting,
+}
+
+impl Step {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Detection => "detection",
+            Self::Command => "command",
+            Self::Formatting => "formatting",
+        }
+    }
+}
+
+/// A step of a module, with when it started and how long it took
+#[derive(Clone, Debug)]
+pub struct StepTiming {
+    pub step: Step,
+    pub start: Instant,
+    pub duration: Duration,
+}
+
+/// How long a module took to compute, on which thread, and the steps it was made up of
+#[derive(Clone, Debug)]
+pub struct ModuleProfile {
+    pub start: Instant,
+    pub duration: Duration,
+    /// The index of the thread, 0 for the main thread and 1 onwards for the worker threads
+    pub thread: usize,
+    pub steps: Vec<StepTiming>,
+}
+
+struct Recording {
+    steps: Vec<StepTiming>,
+    depth: usize,
+}
+
+/// Records the time until it is dropped as a step of the module being profiled
+pub struct StepGuard {
+    step: Step,
+    start: Instant,
+    outermost: bool,
+}
+
+/// Enables recording the steps of modules, which is only done for `starship timings`
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Runs `compute`, the computation of a module, recording its steps if enabled
+pub fn profile_module<T>(compute: impl FnOnce() -> T) -> (T, Option<ModuleProfile>) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return (compute(), None);
+    }
+
+    let previous = RECORDING.replace(Some(Recording {
+        steps: Vec::new(),
+        depth: 0,
+    }));
+    let start = Instant::now();
+    let result = compute();
+    let duration = start.elapsed();
+    let recording = RECORDING.replace(previous);
+
+    let profile = ModuleProfile {
+        start,
+        duration,
+        thread: rayon::current_thread_index().map_or(0, |index| index + 1),
+        steps: recording
+            .map(|recording| recording.steps)
+            .unwrap_or_default(),
+    };
+    (result, Some(profile))
+}
+
+/// Starts a step of the module being profiled on this thread, if any. Steps nested in other
+/// steps count as part of the outer step, e.g. a command run while rendering a format string.
+pub fn step(step: Step) -> Option<StepGuard> {
+    let outermost = RECORDING.with_borrow_mut(|recording| {
+        let recording = recording.as_mut()?;
+        recording.depth += 1;
+        Some(recording.depth == 1)
+    })?;
+
+    Some(StepGuard {
+        step,
+        start: Instant::now(),
+        outermost,
+    })
+}
+
+impl Drop for StepGuard {
+    fn drop(&mut self) {
+        let duration = self.start.elapsed();
+        RECORDING.with_borrow_mut(|recording| {
+            let Some(recording) = recording else {
+                return;
+            };
+            recording.depth = recording.depth.saturating_sub(1);
+            if self.outermost {
+                recording.steps.push(StepTiming {
+                    step: self.step,
+                    start: self.start,
+                    duration,
+                });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_outermost_steps() {
+        enable();
+        let ((), profile) = profile_module(|| {
+            let detection = step(Step::Detection);
+            drop(detection);
+
+            let _formatting = step(Step::Formatting);
+            let _command = step(Step::Command);
+        });
+
+        let steps: Vec<_> = profile.unwrap().steps.iter().map(|s| s.step).collect();
+        assert_eq!(steps, vec![Step::Detection, Step::Formatting]);
+    }
+
+    #[test]
+    fn ignores_steps_outside_of_modules() {
+        assert!(step(Step::Command).is_none());
+    }
+}